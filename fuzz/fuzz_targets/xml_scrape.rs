@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use link_scraper::config::ScrapeConfig;
+use link_scraper::formats::xml;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = xml::scrape(data);
+    let strict = ScrapeConfig {
+        strict: true,
+        ..ScrapeConfig::default()
+    };
+    let _ = xml::scrape_with_config(data, &strict);
+});
@@ -1,25 +1,106 @@
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
-use crate::helpers::find_urls;
-use infer::Type;
+use crate::config::ScrapeConfig;
+use crate::format_info::{
+    CHM_MIME_TYPES, CLIPBOARD_MIME_TYPES, DICOM_MIME_TYPES, FONT_MIME_TYPES, GEO_MIME_TYPES,
+    IMAGE_MIME_TYPES, LNK_MIME_TYPES, MHTML_MIME_TYPES, ODF_MIME_TYPES, OOXML_MIME_TYPES,
+    OPML_MIME_TYPES, PDF_MIME_TYPES, PLAINTEXT_MIME_TYPES, PLIST_MIME_TYPES, RTF_MIME_TYPES,
+    SVG_MIME_TYPES, TORRENT_MIME_TYPES, XML_MIME_TYPES,
+};
+use crate::helpers::{find_matches, normalize_url, AsUrl, MatchKind};
+use crate::links::LinkId;
+use infer::{Infer, Type};
+use once_cell::sync::Lazy;
 use std::fmt::{Display, Formatter};
 use std::io::{read_to_string, BufRead, BufReader, Read, Seek};
 use thiserror::Error;
 
 /// Guesses the file-type and scrapes links from the file.
-pub fn scrape<R>(mut reader: R) -> Result<Vec<Link>, LinkScrapingError>
+pub fn scrape<R>(reader: R) -> Result<Vec<Link>, LinkScrapingError>
 where
     R: BufRead + Seek,
 {
-    fn infer_and_scrape<R>(mut reader: R) -> Result<Vec<Link>, LinkScrapingError>
+    scrape_configured(reader, &ScrapeConfig::default())
+}
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<Link>, LinkScrapingError>);
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<Link>, LinkScrapingError>);
+
+/// Like [`scrape`], but only returns the links accepted by the given [`ScrapeConfig`].
+///
+/// Inputs exceeding [`ScrapeConfig::max_input_bytes`] are rejected before their type is even guessed.
+pub fn scrape_with_config<R>(
+    mut reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<Link>, LinkScrapingError>
+where
+    R: BufRead + Seek,
+{
+    config.check_stream_size::<_, LinkScrapingError>(&mut reader)?;
+    Ok(scrape_configured(reader, config)?
+        .into_iter()
+        .filter(|link| config.accepts(link.url()))
+        .filter(|link| !config.structured_only || link.is_structured())
+        .collect())
+}
+
+/// Like [`scrape`], but only returns the links whose url is not in `seen` yet, e.g. for incremental crawls.
+///
+/// Urls are compared by [`normalize_url`], so `seen` has to contain normalized urls.
+/// Of several links with the same new url only the first one is returned.
+pub fn scrape_new<R>(
+    reader: R,
+    seen: &std::collections::HashSet<String>,
+) -> Result<Vec<Link>, LinkScrapingError>
+where
+    R: BufRead + Seek,
+{
+    let mut found = std::collections::HashSet::new();
+    Ok(scrape(reader)?
+        .into_iter()
+        .filter(|link| {
+            let url = normalize_url(link.url());
+            !seen.contains(&url) && found.insert(url)
+        })
+        .collect())
+}
+
+/// Like [`scrape_new`], but also adds the normalized urls of the returned links to `seen`.
+pub fn scrape_new_into<R>(
+    reader: R,
+    seen: &mut std::collections::HashSet<String>,
+) -> Result<Vec<Link>, LinkScrapingError>
+where
+    R: BufRead + Seek,
+{
+    let links = scrape_new(reader, seen)?;
+    seen.extend(links.iter().map(|link| normalize_url(link.url())));
+    Ok(links)
+}
+
+/// Guesses the file-type and passes the config on to the scrapers supporting it (OOXML and ODF).
+fn scrape_configured<R>(
+    mut reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<Link>, LinkScrapingError>
+where
+    R: BufRead + Seek,
+{
+    fn infer_and_scrape<R>(
+        mut reader: R,
+        config: &ScrapeConfig,
+    ) -> Result<Vec<Link>, LinkScrapingError>
     where
         R: BufRead + Seek,
     {
-        if let Some(file_type) = infer::get(reader.fill_buf()?) {
-            scrape_from_buffer(reader, file_type)
+        if let Some(file_type) = INFER.get(reader.fill_buf()?) {
+            scrape_from_buffer(reader, file_type, config)
         } else {
-            Ok(find_urls(&read_to_string(reader)?)
+            let content = read_to_string(reader)?;
+            Ok(find_matches(&content, config)
                 .iter()
-                .map(|link| Link::StringLink(link.as_str().to_string()))
+                .map(|link| match link.kind {
+                    MatchKind::Url => Link::StringLink(link.as_str(&content).to_string()),
+                    MatchKind::Email => Link::EmailLink(link.as_str(&content).to_string()),
+                })
                 .collect())
         }
     }
@@ -32,18 +113,671 @@ where
         // Therefore we have to make sure,that we grab at least this amount of data when
         // processing it.
         1..8192 => {
-            if let Some(found) = infer::get(buf) {
-                scrape_from_buffer(reader, found)
+            if let Some(found) = INFER.get(buf) {
+                scrape_from_buffer(reader, found, config)
             } else {
-                infer_and_scrape(BufReader::with_capacity(8192, reader))
+                infer_and_scrape(BufReader::with_capacity(8192, reader), config)
             }
         }
         // If we have 8192 bytes or more, we can just use the existing buffer.
-        _ => infer_and_scrape(reader),
+        _ => infer_and_scrape(reader, config),
     }
 }
-gen_scrape_from_slice!(scrape(Read) -> Result<Vec<Link>, LinkScrapingError>);
-gen_scrape_from_file!(scrape(Read) -> Result<Vec<Link>, LinkScrapingError>);
+
+/// Like [`scrape_from_file`], but also reports whether the extension of the file disagrees with its content,
+/// e.g. a `.txt` that really is a zip-archive or a `.jpg` that really is a PDF.
+///
+/// The scraper is still chosen by the content, the extension is only compared against it.
+pub fn scrape_from_path<P>(path: P) -> Result<PathScrapeResult, LinkScrapingError>
+where
+    P: AsRef<std::path::Path>,
+{
+    let path = path.as_ref();
+    let mut reader = BufReader::with_capacity(8192, std::fs::File::open(path)?);
+    let head = reader.fill_buf()?;
+    let content_type_mismatch = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| {
+            content_type_mismatch(extension, head)
+                .map(|detected| (extension.to_string(), detected.to_string()))
+        });
+    Ok(PathScrapeResult {
+        links: scrape(reader)?,
+        content_type_mismatch,
+    })
+}
+
+/// Links scraped by [`scrape_from_path`] together with what the file's extension claims.
+#[derive(Debug, Clone)]
+pub struct PathScrapeResult {
+    pub links: Vec<Link>,
+    /// The declared extension and the detected mime-type, if the content does not match the extension.
+    ///
+    /// Content that is not recognized counts as `text/plain`. Extensions this crate does not know are never reported.
+    pub content_type_mismatch: Option<(String, String)>,
+}
+
+/// Returns the mime-type detected from the first bytes of a file, if it contradicts the file's `extension`.
+///
+/// Textual content only contradicts extensions of binary formats, since XML, HTML and the like are
+/// often not recognized and fall back to `text/plain`.
+fn content_type_mismatch(extension: &str, head: &[u8]) -> Option<&'static str> {
+    fn is_textual(mime: &str) -> bool {
+        mime.starts_with("text/") || mime.contains("xml") || mime.ends_with("json")
+    }
+
+    let extension = extension.to_ascii_lowercase();
+    let expected = crate::supported_formats()
+        .into_iter()
+        .filter(|format| format.extensions.contains(&extension.as_str()))
+        .flat_map(|format| format.mime_types.iter().copied())
+        .collect::<Vec<_>>();
+    let detected = INFER.get(head);
+    let mime = detected.map_or("text/plain", |found| found.mime_type());
+    let is_consistent = expected.is_empty()
+        || expected.contains(&mime)
+        || detected.is_some_and(|found| found.extension() == extension)
+        || (is_textual(mime) && expected.iter().any(|expected| is_textual(expected)));
+    (!is_consistent).then_some(mime)
+}
+
+/// Options for [`scrape_and_check`].
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub struct LinkCheckOptions {
+    /// How many requests may be in flight at the same time
+    pub concurrency: usize,
+    /// Time after which a request counts as failed
+    pub timeout: std::time::Duration,
+    /// Sends no requests for links whose scheme is neither `http` nor `https`
+    pub skip_non_http: bool,
+}
+
+#[cfg(feature = "http")]
+impl Default for LinkCheckOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            timeout: std::time::Duration::from_secs(10),
+            skip_non_http: true,
+        }
+    }
+}
+
+/// Like [`scrape`], but also sends a `HEAD`-request to every link to check whether it is still alive.
+///
+/// Returns the links in their original order, each with the HTTP status code of its response.
+/// The status is [`None`] if the request failed, timed out or was skipped because of [`LinkCheckOptions::skip_non_http`].
+///
+/// Has to be called from within a tokio runtime.
+#[cfg(feature = "http")]
+pub async fn scrape_and_check<R>(
+    reader: R,
+    options: &LinkCheckOptions,
+) -> Result<Vec<(Link, Option<u16>)>, LinkScrapingError>
+where
+    R: BufRead + Seek,
+{
+    use std::sync::Arc;
+
+    let links = scrape(reader)?;
+    let client = reqwest::Client::builder()
+        .timeout(options.timeout)
+        .build()?;
+    let permits = Arc::new(tokio::sync::Semaphore::new(options.concurrency.max(1)));
+    let mut requests = tokio::task::JoinSet::new();
+    for (index, link) in links.iter().enumerate() {
+        let url = link.url().to_string();
+        let is_http = url::Url::parse(&url)
+            .is_ok_and(|url| url.scheme() == "http" || url.scheme() == "https");
+        if options.skip_non_http && !is_http {
+            continue;
+        }
+
+        let client = client.clone();
+        let permits = permits.clone();
+        requests.spawn(async move {
+            let _permit = permits.acquire_owned().await.ok()?;
+            let response = client.head(url).send().await.ok()?;
+            Some((index, response.status().as_u16()))
+        });
+    }
+
+    let mut statuses = vec![None; links.len()];
+    while let Some(result) = requests.join_next().await {
+        if let Ok(Some((index, status))) = result {
+            statuses[index] = Some(status);
+        }
+    }
+    Ok(links.into_iter().zip(statuses).collect())
+}
+
+/// Scrapes the entries of a zip-archive one after another, e.g. an archive of uploaded documents.
+///
+/// Only the entry being scraped is held in memory, and stopping the iteration stops the unzipping as well.
+/// Yields the name of every file-entry together with its links in the order of the archive,
+/// directories are skipped. Every entry is scraped like [`scrape_from_slice`] would, so an entry of an unknown type
+/// yields its error without ending the iteration.
+///
+/// Fails right away if the reader does not contain a zip-archive. Tar-archives are not supported.
+/// # Example
+/// ```no_run
+/// use link_scraper::any_format_scraper::scrape_archive_iter;
+///
+/// let archive = std::fs::File::open("uploads.zip").unwrap();
+/// for (entry, links) in scrape_archive_iter(archive).unwrap().take(100) {
+///     println!("{}: {:?}", entry, links.map(|links| links.len()));
+/// }
+/// ```
+#[cfg(feature = "archive")]
+pub fn scrape_archive_iter<R>(
+    reader: R,
+) -> Result<impl Iterator<Item = (String, Result<Vec<Link>, LinkScrapingError>)>, LinkScrapingError>
+where
+    R: Read + Seek,
+{
+    let mut archive = zip::ZipArchive::new(reader)?;
+    Ok((0..archive.len()).filter_map(move |index| {
+        let name = archive.name_for_index(index)?.to_string();
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) if entry.is_dir() => return None,
+            Ok(entry) => entry,
+            Err(e) => return Some((name, Err(e.into()))),
+        };
+        let mut bytes = Vec::new();
+        if let Err(e) = entry.read_to_end(&mut bytes) {
+            return Some((name, Err(e.into())));
+        }
+        Some((name, scrape_from_slice(bytes)))
+    }))
+}
+
+/// Serves repeated submissions of the same bytes from a cache instead of scraping them again, see [`scrape_from_slice`].
+///
+/// Inputs are identified by their length and a 128-bit hash of their content. The hash is keyed randomly
+/// for every cache, so inputs with colliding hashes can not be crafted. Once `capacity` results are cached,
+/// the least recently used one is dropped. Failed scrapes are not cached.
+///
+/// The cache can be shared between threads, the scraping itself happens outside of its lock.
+/// # Example
+/// ```
+/// use link_scraper::any_format_scraper::CachingScraper;
+///
+/// let scraper = CachingScraper::new(64);
+/// let first = scraper.scrape("see https://cache.test.com").unwrap();
+/// let second = scraper.scrape("see https://cache.test.com").unwrap();
+/// assert_eq!(first.len(), second.len());
+/// assert_eq!((scraper.hits(), scraper.misses()), (1, 1));
+/// ```
+#[cfg(feature = "cache")]
+pub struct CachingScraper {
+    capacity: usize,
+    hash_keys: [std::collections::hash_map::RandomState; 2],
+    cache: std::sync::Mutex<ScrapeCache>,
+    hits: std::sync::atomic::AtomicUsize,
+    misses: std::sync::atomic::AtomicUsize,
+}
+
+/// The length of an input and two independent hashes of its content
+#[cfg(feature = "cache")]
+type CacheKey = (usize, u64, u64);
+
+#[cfg(feature = "cache")]
+#[derive(Default)]
+struct ScrapeCache {
+    /// The links of every cached input, together with the time they were last used
+    entries: std::collections::HashMap<CacheKey, (Vec<Link>, u64)>,
+    time: u64,
+}
+
+#[cfg(feature = "cache")]
+impl CachingScraper {
+    /// Creates an empty cache holding the results of up to `capacity` inputs, `0` disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            hash_keys: Default::default(),
+            cache: Default::default(),
+            hits: Default::default(),
+            misses: Default::default(),
+        }
+    }
+
+    /// Like [`scrape_from_slice`], but returns the cached links if the same bytes were scraped before.
+    pub fn scrape<T>(&self, bytes: T) -> Result<Vec<Link>, LinkScrapingError>
+    where
+        T: AsRef<[u8]>,
+    {
+        use std::hash::BuildHasher;
+        use std::sync::atomic::Ordering;
+
+        let bytes = bytes.as_ref();
+        let [first, second] = &self.hash_keys;
+        let key = (bytes.len(), first.hash_one(bytes), second.hash_one(bytes));
+        {
+            let mut cache = self.lock();
+            cache.time += 1;
+            let time = cache.time;
+            if let Some((links, last_used)) = cache.entries.get_mut(&key) {
+                *last_used = time;
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(links.clone());
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let links = scrape_from_slice(bytes)?;
+        if self.capacity > 0 {
+            let mut cache = self.lock();
+            if cache.entries.len() >= self.capacity && !cache.entries.contains_key(&key) {
+                let least_recently_used = cache
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, (_, last_used))| *last_used)
+                    .map(|(key, _)| *key);
+                if let Some(least_recently_used) = least_recently_used {
+                    cache.entries.remove(&least_recently_used);
+                }
+            }
+            let time = cache.time;
+            cache.entries.insert(key, (links.clone(), time));
+        }
+        Ok(links)
+    }
+
+    /// How many calls of [`CachingScraper::scrape`] were served from the cache
+    pub fn hits(&self) -> usize {
+        self.hits.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// How many calls of [`CachingScraper::scrape`] had to scrape their input, including failed ones
+    pub fn misses(&self) -> usize {
+        self.misses.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// How many results are cached right now
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether no results are cached right now
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A panic while holding the lock can not leave the cache in an inconsistent state, so poisoning is ignored.
+    fn lock(&self) -> std::sync::MutexGuard<'_, ScrapeCache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Returns an id for every link that is unique within the links of a document, see [`Link::id`].
+///
+/// Links sharing an id are numbered in the order they were found, so the ids stay stable
+/// as long as the document and the order of its links do not change.
+/// # Example
+/// ```
+/// use link_scraper::any_format_scraper::{link_ids, scrape_from_slice};
+///
+/// let links = scrape_from_slice("https://id.test.com").unwrap();
+/// let rescraped = scrape_from_slice("https://id.test.com").unwrap();
+/// assert_eq!(link_ids(&links), link_ids(&rescraped));
+/// ```
+pub fn link_ids(links: &[Link]) -> Vec<LinkId> {
+    let mut occurrences = std::collections::HashMap::<LinkId, usize>::new();
+    links
+        .iter()
+        .map(|link| {
+            let id = link.id();
+            let occurrence = occurrences.entry(id).or_default();
+            *occurrence += 1;
+            if *occurrence == 1 {
+                id
+            } else {
+                LinkId::from_parts([id.to_string().as_str(), &occurrence.to_string()])
+            }
+        })
+        .collect()
+}
+
+/// Scrapes many inputs while keeping at most `threshold` urls in memory, see [`scrape_from_slice`].
+///
+/// Only the urls are kept, the kinds and locations of the scraped [`Link`]s are dropped.
+/// Once more urls are buffered, they are written to an anonymous temporary file that is removed when
+/// it is dropped, one per line with backslashes and line-breaks escaped. Once more than
+/// [`ExternalSortScraper::max_open_files`] files were written, they are merged into a single one.
+/// [`ExternalSortScraper::finish`] returns a lazy iterator over all urls, either in the order they were
+/// scraped or sorted by merging the individually sorted files.
+/// # Example
+/// ```
+/// use link_scraper::any_format_scraper::ExternalSortScraper;
+///
+/// let mut scraper = ExternalSortScraper::new(1).deduplicated(true);
+/// scraper.scrape("https://b.spill.test.com https://a.spill.test.com").unwrap();
+/// scraper.scrape("see https://b.spill.test.com").unwrap();
+/// let urls = scraper.finish().unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(urls, ["https://a.spill.test.com", "https://b.spill.test.com"]);
+/// ```
+#[cfg(feature = "spill")]
+pub struct ExternalSortScraper {
+    threshold: usize,
+    sorted: bool,
+    deduplicated: bool,
+    max_open_files: usize,
+    buffer: Vec<String>,
+    spilled: Vec<std::fs::File>,
+}
+
+#[cfg(feature = "spill")]
+impl ExternalSortScraper {
+    /// Creates a scraper that spills its urls to disk once more than `threshold` are held in memory.
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            sorted: false,
+            deduplicated: false,
+            max_open_files: 64,
+            buffer: vec![],
+            spilled: vec![],
+        }
+    }
+
+    /// Whether [`ExternalSortScraper::finish`] returns the urls sorted instead of in the order they were scraped.
+    pub fn sorted(mut self, sorted: bool) -> Self {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Whether repeated urls are dropped. Duplicates are found while merging, so this implies sorted urls.
+    pub fn deduplicated(mut self, deduplicated: bool) -> Self {
+        self.deduplicated = deduplicated;
+        self
+    }
+
+    /// How many spilled files are kept open before they are merged into one, at least 2. Defaults to 64.
+    pub fn max_open_files(mut self, max_open_files: usize) -> Self {
+        self.max_open_files = max_open_files.max(2);
+        self
+    }
+
+    /// Scrapes the bytes like [`scrape_from_slice`], keeps their urls and returns how many links were found.
+    pub fn scrape<T>(&mut self, bytes: T) -> Result<usize, LinkScrapingError>
+    where
+        T: AsRef<[u8]>,
+    {
+        let links = scrape_from_slice(bytes)?;
+        self.buffer
+            .extend(links.iter().map(|link| link.url().to_string()));
+        if self.buffer.len() > self.threshold {
+            self.spill()?;
+        }
+        Ok(links.len())
+    }
+
+    /// How many temporary files holding spilled urls are currently open
+    pub fn spilled_files(&self) -> usize {
+        self.spilled.len()
+    }
+
+    /// Returns an iterator over all scraped urls, reading the spilled ones back from disk as needed.
+    pub fn finish(mut self) -> Result<SpilledUrls, LinkScrapingError> {
+        self.prepare_buffer();
+        let mut runs = self
+            .spilled
+            .into_iter()
+            .map(|file| UrlRun::File(BufReader::new(file).lines()))
+            .collect::<Vec<_>>();
+        runs.push(UrlRun::Memory(self.buffer.into_iter()));
+        Ok(SpilledUrls::new(
+            runs,
+            self.sorted || self.deduplicated,
+            self.deduplicated,
+        )?)
+    }
+
+    fn spill(&mut self) -> std::io::Result<()> {
+        self.prepare_buffer();
+        let file = write_run(self.buffer.drain(..).map(Ok))?;
+        self.spilled.push(file);
+        if self.spilled.len() > self.max_open_files {
+            let runs = self
+                .spilled
+                .drain(..)
+                .map(|file| UrlRun::File(BufReader::new(file).lines()))
+                .collect();
+            let merged =
+                SpilledUrls::new(runs, self.sorted || self.deduplicated, self.deduplicated)?;
+            self.spilled.push(write_run(merged)?);
+        }
+        Ok(())
+    }
+
+    /// Every run has to be sorted on its own to be merged later.
+    fn prepare_buffer(&mut self) {
+        if self.sorted || self.deduplicated {
+            self.buffer.sort_unstable();
+        }
+        if self.deduplicated {
+            self.buffer.dedup();
+        }
+    }
+}
+
+/// The urls of an [`ExternalSortScraper`], reading errors of the spilled files are returned as they occur.
+#[cfg(feature = "spill")]
+pub struct SpilledUrls {
+    /// The spilled files followed by the urls that were still held in memory
+    runs: Vec<UrlRun>,
+    /// The smallest url not yet returned of every run, only used when merging
+    heads: Option<std::collections::BinaryHeap<std::cmp::Reverse<(String, usize)>>>,
+    deduplicated: bool,
+    last: Option<String>,
+}
+
+#[cfg(feature = "spill")]
+impl SpilledUrls {
+    /// With `merge` every run has to be sorted already.
+    fn new(mut runs: Vec<UrlRun>, merge: bool, deduplicated: bool) -> std::io::Result<Self> {
+        let heads = if merge {
+            let mut heads = std::collections::BinaryHeap::new();
+            for (index, run) in runs.iter_mut().enumerate() {
+                if let Some(url) = run.next().transpose()? {
+                    heads.push(std::cmp::Reverse((url, index)));
+                }
+            }
+            Some(heads)
+        } else {
+            None
+        };
+        Ok(Self {
+            runs,
+            heads,
+            deduplicated,
+            last: None,
+        })
+    }
+}
+
+#[cfg(feature = "spill")]
+impl Iterator for SpilledUrls {
+    type Item = std::io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(heads) = &mut self.heads else {
+            while let Some(run) = self.runs.first_mut() {
+                match run.next() {
+                    Some(url) => return Some(url),
+                    None => drop(self.runs.remove(0)),
+                }
+            }
+            return None;
+        };
+
+        loop {
+            // The next url of a run is read before its head is taken, so a failed read loses nothing
+            let std::cmp::Reverse((_, index)) = heads.peek()?;
+            let index = *index;
+            let next = match self.runs[index].next().transpose() {
+                Ok(next) => next,
+                Err(e) => return Some(Err(e)),
+            };
+            let std::cmp::Reverse((url, _)) = heads.pop()?;
+            if let Some(next) = next {
+                heads.push(std::cmp::Reverse((next, index)));
+            }
+            if self.deduplicated {
+                if self.last.as_ref() == Some(&url) {
+                    continue;
+                }
+                self.last = Some(url.clone());
+            }
+            return Some(Ok(url));
+        }
+    }
+}
+
+#[cfg(feature = "spill")]
+enum UrlRun {
+    Memory(std::vec::IntoIter<String>),
+    File(std::io::Lines<BufReader<std::fs::File>>),
+}
+
+#[cfg(feature = "spill")]
+impl Iterator for UrlRun {
+    type Item = std::io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            UrlRun::Memory(urls) => urls.next().map(Ok),
+            UrlRun::File(lines) => lines
+                .next()
+                .map(|line| line.map(|line| unescape_line(&line))),
+        }
+    }
+}
+
+/// Writes the urls to an anonymous temporary file and returns it rewound to its start.
+#[cfg(feature = "spill")]
+fn write_run(
+    urls: impl Iterator<Item = std::io::Result<String>>,
+) -> std::io::Result<std::fs::File> {
+    use std::io::Write;
+
+    let mut writer = std::io::BufWriter::new(tempfile::tempfile()?);
+    for url in urls {
+        writeln!(writer, "{}", escape_line(&url?))?;
+    }
+    let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.rewind()?;
+    Ok(file)
+}
+
+#[cfg(feature = "spill")]
+fn escape_line(url: &str) -> String {
+    url.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(feature = "spill")]
+fn unescape_line(line: &str) -> String {
+    let mut url = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            url.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => url.push('\n'),
+            Some('r') => url.push('\r'),
+            Some(c) => url.push(c),
+            None => break,
+        }
+    }
+    url
+}
+
+/// [`infer`] extended by matchers for formats it does not recognize on its own.
+pub(crate) static INFER: Lazy<Infer> = Lazy::new(|| {
+    let mut infer = Infer::new();
+    infer.add("application/x-ms-shortcut", "lnk", is_lnk);
+    infer.add("text/x-opml", "opml", is_opml);
+    infer.add("application/vnd.google-earth.kml+xml", "kml", is_kml);
+    infer.add("application/gpx+xml", "gpx", is_gpx);
+    infer.add("application/x-plist", "plist", is_plist);
+    infer.add("application/vnd.ms-htmlhelp", "chm", is_chm);
+    infer.add("text/x-cf-html", "html", is_cf_html);
+    infer.add("application/x-bittorrent", "torrent", is_torrent);
+    infer.add("multipart/related", "mht", is_mhtml);
+    infer
+});
+
+fn is_lnk(buf: &[u8]) -> bool {
+    buf.starts_with(&[
+        0x4C, 0x00, 0x00, 0x00, 0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x46,
+    ])
+}
+
+fn is_chm(buf: &[u8]) -> bool {
+    buf.starts_with(b"ITSF")
+}
+
+/// A torrent is a bencoded dictionary, its keys are sorted, so `announce` usually comes first.
+/// Trackerless torrents may start with any other key, but always contain the `info`-dictionary.
+fn is_torrent(buf: &[u8]) -> bool {
+    buf.starts_with(b"d8:announce")
+        || (buf.starts_with(b"d")
+            && buf.get(1).is_some_and(u8::is_ascii_digit)
+            && buf[..buf.len().min(4096)]
+                .windows(7)
+                .any(|window| window == b"4:infod"))
+}
+
+/// MHTML starts with the headers of a MIME-message, one of them declaring it as `multipart/related`.
+fn is_mhtml(buf: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&buf[..buf.len().min(4096)]).to_ascii_lowercase();
+    let first_line = head.lines().next().unwrap_or_default();
+    first_line.split_once(':').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && name
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+    }) && head.contains("multipart/related")
+}
+
+/// Windows clipboard HTML starts with a `Version:`-line followed by the offsets of the HTML.
+fn is_cf_html(buf: &[u8]) -> bool {
+    buf.starts_with(b"Version:")
+        && String::from_utf8_lossy(&buf[..buf.len().min(256)]).contains("StartHTML:")
+}
+
+/// Only the first bytes are available, so this just checks for an `<opml`-tag near the beginning.
+fn is_opml(buf: &[u8]) -> bool {
+    starts_with_xml_element(buf, "<opml")
+}
+
+fn is_kml(buf: &[u8]) -> bool {
+    starts_with_xml_element(buf, "<kml")
+}
+
+fn is_gpx(buf: &[u8]) -> bool {
+    starts_with_xml_element(buf, "<gpx")
+}
+
+fn is_plist(buf: &[u8]) -> bool {
+    buf.starts_with(b"bplist00") || starts_with_xml_element(buf, "<plist")
+}
+
+fn starts_with_xml_element(buf: &[u8], tag: &str) -> bool {
+    let start = String::from_utf8_lossy(&buf[..buf.len().min(1024)]);
+    let start = start.trim_start_matches('\u{feff}').trim_start();
+    start.starts_with(tag) || (start.starts_with("<?xml") && start.contains(tag))
+}
 
 #[derive(Error, Debug)]
 pub enum LinkScrapingError {
@@ -62,6 +796,10 @@ pub enum LinkScrapingError {
     #[error(transparent)]
     OdtScrapingError(#[from] crate::formats::odf::OdfScrapingError),
 
+    #[cfg(feature = "iwork")]
+    #[error(transparent)]
+    IworkScrapingError(#[from] crate::formats::iwork::IworkScrapingError),
+
     #[cfg(feature = "pdf")]
     #[error(transparent)]
     PdfScrapingError(#[from] crate::formats::pdf::PdfScrapingError),
@@ -82,6 +820,57 @@ pub enum LinkScrapingError {
     #[error(transparent)]
     ImageScrapingError(#[from] crate::formats::image::ImageScrapingError),
 
+    #[cfg(feature = "dicom")]
+    #[error(transparent)]
+    DicomScrapingError(#[from] crate::formats::dicom::DicomScrapingError),
+
+    #[cfg(feature = "font")]
+    #[error(transparent)]
+    FontScrapingError(#[from] crate::formats::font::FontScrapingError),
+
+    #[cfg(feature = "lnk")]
+    #[error(transparent)]
+    LnkScrapingError(#[from] crate::formats::lnk::LnkScrapingError),
+
+    #[cfg(feature = "opml")]
+    #[error(transparent)]
+    OpmlScrapingError(#[from] crate::formats::xml::opml::OpmlScrapingError),
+
+    #[cfg(feature = "geo")]
+    #[error(transparent)]
+    GeoScrapingError(#[from] crate::formats::geo::GeoScrapingError),
+
+    #[cfg(feature = "plist")]
+    #[error(transparent)]
+    PlistScrapingError(#[from] crate::formats::plist::PlistScrapingError),
+
+    #[cfg(feature = "chm")]
+    #[error(transparent)]
+    ChmScrapingError(#[from] crate::formats::chm::ChmScrapingError),
+
+    #[cfg(feature = "clipboard")]
+    #[error(transparent)]
+    ClipboardScrapingError(#[from] crate::formats::clipboard::ClipboardScrapingError),
+
+    #[cfg(feature = "torrent")]
+    #[error(transparent)]
+    TorrentScrapingError(#[from] crate::formats::torrent::TorrentScrapingError),
+
+    #[cfg(feature = "mhtml")]
+    #[error(transparent)]
+    MhtmlScrapingError(#[from] crate::formats::mhtml::MhtmlScrapingError),
+
+    #[error(transparent)]
+    InputTooLargeError(#[from] crate::config::InputTooLargeError),
+
+    #[cfg(feature = "http")]
+    #[error(transparent)]
+    HttpError(#[from] reqwest::Error),
+
+    #[cfg(feature = "archive")]
+    #[error(transparent)]
+    ZipError(#[from] zip::result::ZipError),
+
     #[error("Required feature is not enabled")]
     FeatureNotEnabledError(String),
 
@@ -95,10 +884,14 @@ pub enum LinkScrapingError {
 #[derive(Debug, Clone)]
 pub enum Link {
     StringLink(String),
+    /// An email-address found in a file of unknown type. Only returned with [`ScrapeConfig::include_emails`]
+    EmailLink(String),
     #[cfg(feature = "plaintext")]
     TextFileLink(crate::formats::plaintext::TextFileLink),
     #[cfg(feature = "odf")]
     OdfLink(crate::formats::odf::OdfLink),
+    #[cfg(feature = "iwork")]
+    IworkLink(crate::formats::iwork::IworkLink),
     #[cfg(feature = "pdf")]
     PdfLink(crate::formats::pdf::PdfLink),
     #[cfg(feature = "ooxml")]
@@ -111,6 +904,337 @@ pub enum Link {
     SvgLink(crate::formats::xml::svg::SvgLink),
     #[cfg(feature = "image")]
     ImageLink(crate::formats::image::ImageLink),
+    #[cfg(feature = "dicom")]
+    DicomLink(crate::formats::dicom::DicomLink),
+    #[cfg(feature = "font")]
+    FontLink(crate::formats::font::FontLink),
+    #[cfg(feature = "lnk")]
+    LnkLink(crate::formats::lnk::LnkLink),
+    #[cfg(feature = "opml")]
+    OpmlLink(crate::formats::xml::opml::OpmlLink),
+    #[cfg(feature = "geo")]
+    GeoLink(crate::formats::geo::GeoLink),
+    #[cfg(feature = "plist")]
+    PlistLink(crate::formats::plist::PlistLink),
+    #[cfg(feature = "chm")]
+    ChmLink(crate::formats::chm::ChmLink),
+    #[cfg(feature = "clipboard")]
+    ClipboardLink(crate::formats::clipboard::ClipboardLink),
+    #[cfg(feature = "torrent")]
+    TorrentLink(crate::formats::torrent::TorrentLink),
+    #[cfg(feature = "mhtml")]
+    MhtmlLink(crate::formats::mhtml::MhtmlLink),
+}
+
+impl Link {
+    /// Returns whether this link was explicitly authored (e.g. a hyperlink or an attribute),
+    /// rather than found somewhere in the text.
+    ///
+    /// See [`ScrapeConfig::structured_only`].
+    pub fn is_structured(&self) -> bool {
+        match self {
+            #[cfg(feature = "ooxml")]
+            Link::OoxmlLink(link) => matches!(
+                link.kind,
+                crate::formats::ooxml::OoxmlLinkKind::Hyperlink
+                    | crate::formats::ooxml::OoxmlLinkKind::ExternalMedia
+                    | crate::formats::ooxml::OoxmlLinkKind::ExternalReference
+            ),
+            #[cfg(feature = "odf")]
+            Link::OdfLink(link) => link.kind == crate::formats::odf::OdfLinkKind::Hyperlink,
+            #[cfg(feature = "iwork")]
+            Link::IworkLink(link) => link.kind == crate::formats::iwork::IworkLinkKind::Hyperlink,
+            #[cfg(feature = "pdf")]
+            Link::PdfLink(link) => matches!(
+                link.kind,
+                crate::formats::pdf::PdfLinkKind::Hyperlink
+                    | crate::formats::pdf::PdfLinkKind::Action { .. }
+            ),
+            #[cfg(feature = "xml")]
+            Link::XmlLink(link) => matches!(
+                link.kind,
+                crate::formats::xml::XmlLinkKind::Attribute(_)
+                    | crate::formats::xml::XmlLinkKind::MetaRefresh(_)
+            ),
+            #[cfg(feature = "svg")]
+            Link::SvgLink(link) => {
+                matches!(
+                    link.kind,
+                    crate::formats::xml::svg::SvgLinkKind::Attribute(_)
+                )
+            }
+            #[cfg(feature = "font")]
+            Link::FontLink(link) => crate::formats::font::URL_NAME_IDS.contains(&link.name_id),
+            #[cfg(feature = "lnk")]
+            Link::LnkLink(link) => link.field == "target",
+            #[cfg(feature = "opml")]
+            Link::OpmlLink(_) => true,
+            #[cfg(feature = "torrent")]
+            Link::TorrentLink(_) => true,
+            #[cfg(feature = "mhtml")]
+            Link::MhtmlLink(link) => !matches!(
+                link.html_kind,
+                Some(
+                    crate::formats::html::HtmlLinkKind::Comment
+                        | crate::formats::html::HtmlLinkKind::PlainText
+                )
+            ),
+            #[cfg(feature = "geo")]
+            Link::GeoLink(link) => {
+                link.kind != crate::formats::geo::GeoLinkKind::PlacemarkDescription
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether this link was matched as an url or as an email-address.
+    ///
+    /// Email-addresses are only returned with [`ScrapeConfig::include_emails`].
+    pub fn match_kind(&self) -> MatchKind {
+        match self {
+            Link::EmailLink(_) => MatchKind::Email,
+            #[cfg(feature = "plaintext")]
+            Link::TextFileLink(link) => link.kind,
+            _ => MatchKind::Url,
+        }
+    }
+
+    /// Renders the url together with the kind and location of the link, uniformly across all formats.
+    ///
+    /// Example: `https://link.example.com [Hyperlink @ word/_rels/document.xml.rels:2:1]`
+    pub fn to_detail_string(&self) -> String {
+        match self.kind_and_location() {
+            Some((kind, Some(location))) => format!("{} [{} @ {}]", self.url(), kind, location),
+            Some((kind, None)) => format!("{} [{}]", self.url(), kind),
+            None => self.url().to_string(),
+        }
+    }
+
+    /// Returns a deterministic id of the url, kind and location of the link, see [`LinkId`].
+    ///
+    /// Re-scraping an unchanged document returns the same ids. Identical links at the same location
+    /// (or of formats without locations) share their id, use [`link_ids`] to tell them apart.
+    ///
+    /// The kind and location are hashed as [`Link::to_detail_string`] renders them, which may change
+    /// between versions of this crate, so ids should not be compared across versions.
+    pub fn id(&self) -> LinkId {
+        let (kind, location) = self.kind_and_location().unwrap_or_default();
+        LinkId::from_parts([
+            self.variant_name(),
+            &kind,
+            location.as_deref().unwrap_or_default(),
+            self.url(),
+        ])
+    }
+
+    /// Names the format and the format-specific kind of the link.
+    ///
+    /// Example: `OoxmlLink(Hyperlink)`
+    pub fn kind_name(&self) -> String {
+        match self.kind_and_location() {
+            Some((kind, _)) => format!("{}({})", self.variant_name(), kind),
+            None => self.variant_name().to_string(),
+        }
+    }
+
+    fn variant_name(&self) -> &'static str {
+        match self {
+            Link::StringLink(_) => "StringLink",
+            Link::EmailLink(_) => "EmailLink",
+            #[cfg(feature = "plaintext")]
+            Link::TextFileLink(_) => "TextFileLink",
+            #[cfg(feature = "ooxml")]
+            Link::OoxmlLink(_) => "OoxmlLink",
+            #[cfg(feature = "odf")]
+            Link::OdfLink(_) => "OdfLink",
+            #[cfg(feature = "iwork")]
+            Link::IworkLink(_) => "IworkLink",
+            #[cfg(feature = "pdf")]
+            Link::PdfLink(_) => "PdfLink",
+            #[cfg(feature = "rtf")]
+            Link::RtfLink(_) => "RtfLink",
+            #[cfg(feature = "xml")]
+            Link::XmlLink(_) => "XmlLink",
+            #[cfg(feature = "svg")]
+            Link::SvgLink(_) => "SvgLink",
+            #[cfg(feature = "image")]
+            Link::ImageLink(_) => "ImageLink",
+            #[cfg(feature = "dicom")]
+            Link::DicomLink(_) => "DicomLink",
+            #[cfg(feature = "font")]
+            Link::FontLink(_) => "FontLink",
+            #[cfg(feature = "lnk")]
+            Link::LnkLink(_) => "LnkLink",
+            #[cfg(feature = "opml")]
+            Link::OpmlLink(_) => "OpmlLink",
+            #[cfg(feature = "geo")]
+            Link::GeoLink(_) => "GeoLink",
+            #[cfg(feature = "plist")]
+            Link::PlistLink(_) => "PlistLink",
+            #[cfg(feature = "chm")]
+            Link::ChmLink(_) => "ChmLink",
+            #[cfg(feature = "clipboard")]
+            Link::ClipboardLink(_) => "ClipboardLink",
+            #[cfg(feature = "torrent")]
+            Link::TorrentLink(_) => "TorrentLink",
+            #[cfg(feature = "mhtml")]
+            Link::MhtmlLink(_) => "MhtmlLink",
+        }
+    }
+
+    pub(crate) fn kind_and_location(&self) -> Option<(String, Option<String>)> {
+        #[cfg(any(
+            feature = "plaintext",
+            feature = "ooxml",
+            feature = "odf",
+            feature = "iwork",
+            feature = "pdf",
+            feature = "rtf",
+            feature = "xml",
+            feature = "svg",
+            feature = "image",
+            feature = "dicom",
+            feature = "font",
+            feature = "lnk",
+            feature = "opml",
+            feature = "geo",
+            feature = "plist",
+            feature = "chm",
+            feature = "clipboard",
+            feature = "torrent",
+            feature = "mhtml"
+        ))]
+        fn detail(
+            kind: impl Display,
+            location: Option<String>,
+        ) -> Option<(String, Option<String>)> {
+            Some((kind.to_string(), location))
+        }
+
+        match self {
+            Link::StringLink(_) | Link::EmailLink(_) => None,
+            #[cfg(feature = "plaintext")]
+            Link::TextFileLink(link) => detail(
+                "PlainText",
+                Some(format!("{}:{}", link.location.line, link.location.pos)),
+            ),
+            #[cfg(feature = "ooxml")]
+            Link::OoxmlLink(link) => detail(
+                format!("{:?}", link.kind),
+                Some(format!("{}:{}", link.location.file, link.location.position)),
+            ),
+            #[cfg(feature = "odf")]
+            Link::OdfLink(link) => detail(
+                format!("{:?}", link.kind),
+                Some(format!("{}:{}", link.location.file, link.location.position)),
+            ),
+            #[cfg(feature = "iwork")]
+            Link::IworkLink(link) => detail(format!("{:?}", link.kind), Some(link.file.clone())),
+            #[cfg(feature = "pdf")]
+            Link::PdfLink(link) => detail(
+                format!("{:?}", link.kind),
+                Some(format!("page {}", link.location.page)),
+            ),
+            #[cfg(feature = "rtf")]
+            Link::RtfLink(_) => detail("PlainText", None),
+            #[cfg(feature = "xml")]
+            Link::XmlLink(link) => {
+                use crate::formats::xml::XmlLinkKind;
+                let kind = match &link.kind {
+                    XmlLinkKind::Attribute(attribute) => format!("Attribute({})", attribute.name),
+                    XmlLinkKind::Comment => "Comment".to_string(),
+                    XmlLinkKind::PlainText(_) => "PlainText".to_string(),
+                    XmlLinkKind::CData(_) => "CData".to_string(),
+                    XmlLinkKind::NameSpace(namespace) => format!("NameSpace({})", namespace),
+                    XmlLinkKind::MetaRefresh(_) => "MetaRefresh".to_string(),
+                };
+                detail(kind, Some(link.location.to_string()))
+            }
+            #[cfg(feature = "svg")]
+            Link::SvgLink(link) => {
+                use crate::formats::xml::svg::SvgLinkKind;
+                let kind = match &link.kind {
+                    SvgLinkKind::Attribute(attribute) => format!("Attribute({})", attribute.name),
+                    SvgLinkKind::NameSpace(namespace) => format!("NameSpace({})", namespace),
+                    kind => format!("{:?}", kind),
+                };
+                detail(kind, Some(link.location.to_string()))
+            }
+            #[cfg(feature = "image")]
+            Link::ImageLink(link) => detail(format!("{:?}", link.source), None),
+            #[cfg(feature = "dicom")]
+            Link::DicomLink(link) => detail("Element", Some(link.tag.clone())),
+            #[cfg(feature = "font")]
+            Link::FontLink(link) => detail("NameRecord", Some(link.name_id.to_string())),
+            #[cfg(feature = "lnk")]
+            Link::LnkLink(link) => detail(&link.field, None),
+            #[cfg(feature = "opml")]
+            Link::OpmlLink(link) => detail(format!("{:?}", link.kind), None),
+            #[cfg(feature = "geo")]
+            Link::GeoLink(link) => detail(format!("{:?}", link.kind), link.feature_name.clone()),
+            #[cfg(feature = "plist")]
+            Link::PlistLink(link) => detail("Value", Some(link.key_path.clone())),
+            #[cfg(feature = "chm")]
+            Link::ChmLink(link) => detail("Topic", Some(link.topic.clone())),
+            #[cfg(feature = "clipboard")]
+            Link::ClipboardLink(link) => detail(format!("{:?}", link.kind), None),
+            #[cfg(feature = "torrent")]
+            Link::TorrentLink(link) => detail(
+                format!("{:?}", link.kind),
+                link.tier.map(|tier| format!("tier {}", tier)),
+            ),
+            #[cfg(feature = "mhtml")]
+            Link::MhtmlLink(link) => {
+                detail(format!("{:?}", link.source), link.part_location.clone())
+            }
+        }
+    }
+}
+
+impl AsUrl for Link {
+    fn url(&self) -> &str {
+        match self {
+            Link::StringLink(link) | Link::EmailLink(link) => link,
+            #[cfg(feature = "plaintext")]
+            Link::TextFileLink(link) => &link.url,
+            #[cfg(feature = "ooxml")]
+            Link::OoxmlLink(link) => &link.url,
+            #[cfg(feature = "odf")]
+            Link::OdfLink(link) => &link.url,
+            #[cfg(feature = "iwork")]
+            Link::IworkLink(link) => &link.url,
+            #[cfg(feature = "pdf")]
+            Link::PdfLink(link) => &link.url,
+            #[cfg(feature = "rtf")]
+            Link::RtfLink(link) => &link.url,
+            #[cfg(feature = "xml")]
+            Link::XmlLink(link) => &link.url,
+            #[cfg(feature = "svg")]
+            Link::SvgLink(link) => &link.url,
+            #[cfg(feature = "image")]
+            Link::ImageLink(link) => &link.url,
+            #[cfg(feature = "dicom")]
+            Link::DicomLink(link) => &link.url,
+            #[cfg(feature = "font")]
+            Link::FontLink(link) => &link.url,
+            #[cfg(feature = "lnk")]
+            Link::LnkLink(link) => &link.url,
+            #[cfg(feature = "opml")]
+            Link::OpmlLink(link) => &link.url,
+            #[cfg(feature = "geo")]
+            Link::GeoLink(link) => &link.url,
+            #[cfg(feature = "plist")]
+            Link::PlistLink(link) => &link.url,
+            #[cfg(feature = "chm")]
+            Link::ChmLink(link) => &link.url,
+            #[cfg(feature = "clipboard")]
+            Link::ClipboardLink(link) => &link.url,
+            #[cfg(feature = "torrent")]
+            Link::TorrentLink(link) => &link.url,
+            #[cfg(feature = "mhtml")]
+            Link::MhtmlLink(link) => &link.url,
+        }
+    }
 }
 
 impl Display for Link {
@@ -119,6 +1243,9 @@ impl Display for Link {
             Link::StringLink(link) => {
                 write!(f, "StringLink({})", link)
             }
+            Link::EmailLink(link) => {
+                write!(f, "EmailLink({})", link)
+            }
             #[cfg(feature = "plaintext")]
             Link::TextFileLink(link) => {
                 write!(f, "TextFileLink({})", link)
@@ -131,6 +1258,10 @@ impl Display for Link {
             Link::OdfLink(link) => {
                 write!(f, "OdfLink({})", link)
             }
+            #[cfg(feature = "iwork")]
+            Link::IworkLink(link) => {
+                write!(f, "IworkLink({})", link)
+            }
             #[cfg(feature = "pdf")]
             Link::PdfLink(link) => {
                 write!(f, "PdfLink({})", link)
@@ -151,58 +1282,106 @@ impl Display for Link {
             Link::ImageLink(link) => {
                 write!(f, "ImageLink({})", link)
             }
+            #[cfg(feature = "dicom")]
+            Link::DicomLink(link) => {
+                write!(f, "DicomLink({})", link)
+            }
+            #[cfg(feature = "font")]
+            Link::FontLink(link) => {
+                write!(f, "FontLink({})", link)
+            }
+            #[cfg(feature = "lnk")]
+            Link::LnkLink(link) => {
+                write!(f, "LnkLink({})", link)
+            }
+            #[cfg(feature = "opml")]
+            Link::OpmlLink(link) => {
+                write!(f, "OpmlLink({})", link)
+            }
+            #[cfg(feature = "geo")]
+            Link::GeoLink(link) => {
+                write!(f, "GeoLink({})", link)
+            }
+            #[cfg(feature = "plist")]
+            Link::PlistLink(link) => {
+                write!(f, "PlistLink({})", link)
+            }
+            #[cfg(feature = "chm")]
+            Link::ChmLink(link) => {
+                write!(f, "ChmLink({})", link)
+            }
+            #[cfg(feature = "clipboard")]
+            Link::ClipboardLink(link) => {
+                write!(f, "ClipboardLink({})", link)
+            }
+            #[cfg(feature = "torrent")]
+            Link::TorrentLink(link) => {
+                write!(f, "TorrentLink({})", link)
+            }
+            #[cfg(feature = "mhtml")]
+            Link::MhtmlLink(link) => {
+                write!(f, "MhtmlLink({})", link)
+            }
         }
     }
 }
 
-fn scrape_from_buffer<R>(mut reader: R, file_type: Type) -> Result<Vec<Link>, LinkScrapingError>
+fn scrape_from_buffer<R>(
+    reader: R,
+    file_type: Type,
+    config: &ScrapeConfig,
+) -> Result<Vec<Link>, LinkScrapingError>
 where
     R: BufRead + Seek,
 {
-    match file_type.mime_type() {
-        "text/plain" | "text/csv" | "text/css" | "application/json" => Ok(try_text_file(reader)?),
-
-        "application/vnd.oasis.opendocument.text"
-        | "application/vnd.oasis.opendocument.spreadsheet"
-        | "application/vnd.oasis.opendocument.template"
-        | "application/vnd.oasis.opendocument.presentation" => Ok(try_odf(reader)?),
+    scrape_mime_type(reader, file_type.mime_type(), config)
+}
 
-        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
-        | "application/vnd.openxmlformats-officedocument.spreadsheetml.template"
-        | "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
-        | "application/vnd.openxmlformats-officedocument.wordprocessingml.template"
-        | "application/vnd.openxmlformats-officedocument.presentationml.presentation"
-        | "application/vnd.openxmlformats-officedocument.presentationml.template" => {
-            Ok(try_ooxml(reader)?)
-        }
-        "application/vnd.openxmlformats-officedocument.presentationml.slideshow" => {
-            Ok(try_ooxml(reader)?)
-        }
+/// Scrapes the stream with the scraper registered for the given mime-type, without guessing it from the content.
+pub(crate) fn scrape_mime_type<R>(
+    mut reader: R,
+    mime_type: &str,
+    config: &ScrapeConfig,
+) -> Result<Vec<Link>, LinkScrapingError>
+where
+    R: BufRead + Seek,
+{
+    match mime_type {
+        mime if PLAINTEXT_MIME_TYPES.contains(&mime) => Ok(try_text_file(reader, config)?),
+        mime if ODF_MIME_TYPES.contains(&mime) => Ok(try_odf(reader, config)?),
+        mime if OOXML_MIME_TYPES.contains(&mime) => Ok(try_ooxml(reader, config)?),
 
         "application/zip" => {
             let mut bytes = Vec::new();
             reader.read_to_end(&mut bytes)?;
-            try_zip(bytes)
+            try_zip(bytes, config)
         }
-        "application/pdf" => {
+        mime if PDF_MIME_TYPES.contains(&mime) => {
             let mut bytes = Vec::new();
             reader.read_to_end(&mut bytes)?;
             Ok(try_pdf(bytes)?)
         }
-        "application/rtf" => {
+        mime if RTF_MIME_TYPES.contains(&mime) => {
             let mut s = String::new();
             reader.read_to_string(&mut s)?;
             Ok(try_rtf(s)?)
         }
-        "image/svg+xml" => Ok(try_svg(reader)?),
-        "text/xml" | "text/html" => Ok(try_xml(reader)?),
-
-        "image/jpeg" | "image/png" | "image/tiff" | "image/webp" | "image/heic" | "image/heif" => {
-            Ok(try_image(reader)?)
-        }
+        mime if SVG_MIME_TYPES.contains(&mime) => Ok(try_svg(reader, config)?),
+        mime if XML_MIME_TYPES.contains(&mime) => Ok(try_xml(reader, config)?),
+        mime if IMAGE_MIME_TYPES.contains(&mime) => Ok(try_image(reader)?),
+        mime if DICOM_MIME_TYPES.contains(&mime) => Ok(try_dicom(reader)?),
+        mime if FONT_MIME_TYPES.contains(&mime) => Ok(try_font(reader)?),
+        mime if LNK_MIME_TYPES.contains(&mime) => Ok(try_lnk(reader)?),
+        mime if OPML_MIME_TYPES.contains(&mime) => Ok(try_opml(reader)?),
+        mime if GEO_MIME_TYPES.contains(&mime) => Ok(try_geo(reader)?),
+        mime if PLIST_MIME_TYPES.contains(&mime) => Ok(try_plist(reader)?),
+        mime if CHM_MIME_TYPES.contains(&mime) => Ok(try_chm(reader)?),
+        mime if CLIPBOARD_MIME_TYPES.contains(&mime) => Ok(try_clipboard(reader)?),
+        mime if TORRENT_MIME_TYPES.contains(&mime) => Ok(try_torrent(reader)?),
+        mime if MHTML_MIME_TYPES.contains(&mime) => Ok(try_mhtml(reader)?),
 
         _ => Err(LinkScrapingError::FileTypeNotImplemented(
-            file_type.mime_type().to_string(),
+            mime_type.to_string(),
         )),
     }
 }
@@ -221,53 +1400,142 @@ macro_rules! gen_try_format {
     }
 }
 
-gen_try_format!(
-    try_text_file(impl BufRead),
-    "plaintext",
-    plaintext,
-    TextFileLink => scrape
-);
-gen_try_format!(try_ooxml(impl Read + Seek), "ooxml", ooxml, OoxmlLink => scrape);
-gen_try_format!(try_odf(impl Read + Seek), "odf", odf, OdfLink => scrape);
 gen_try_format!(try_pdf(impl AsRef<[u8]>), "pdf", pdf, PdfLink => scrape_from_slice);
 gen_try_format!(try_rtf(impl AsRef<str>), "rtf", rtf, RtfLink => scrape_from_string);
-gen_try_format!(try_xml(impl Read), "xml", xml, XmlLink => scrape);
 gen_try_format!(try_image(impl BufRead + Seek), "image", image, ImageLink => scrape);
+gen_try_format!(try_dicom(impl Read), "dicom", dicom, DicomLink => scrape);
+gen_try_format!(try_font(impl Read + Seek), "font", font, FontLink => scrape);
+gen_try_format!(try_lnk(impl Read), "lnk", lnk, LnkLink => scrape);
+gen_try_format!(try_chm(impl Read + Seek), "chm", chm, ChmLink => scrape);
+gen_try_format!(try_geo(impl Read), "geo", geo, GeoLink => scrape);
+gen_try_format!(try_plist(impl Read + Seek), "plist", plist, PlistLink => scrape);
+gen_try_format!(try_clipboard(impl Read), "clipboard", clipboard, ClipboardLink => scrape);
+gen_try_format!(try_torrent(impl Read), "torrent", torrent, TorrentLink => scrape);
+gen_try_format!(try_mhtml(impl BufRead), "mhtml", mhtml, MhtmlLink => scrape);
 
-#[cfg(feature = "svg")]
-fn try_svg(reader: impl Read) -> Result<Vec<Link>, LinkScrapingError> {
-    Ok(crate::formats::xml::svg::scrape(reader)?
+#[cfg(feature = "plaintext")]
+fn try_text_file(
+    reader: impl BufRead,
+    config: &ScrapeConfig,
+) -> Result<Vec<Link>, LinkScrapingError> {
+    Ok(
+        crate::formats::plaintext::scrape_with_config(reader, config)?
+            .into_iter()
+            .map(Link::TextFileLink)
+            .collect(),
+    )
+}
+#[cfg(not(feature = "plaintext"))]
+fn try_text_file(_: impl BufRead, _: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
+    Err(LinkScrapingError::FeatureNotEnabledError("Detected plaintext-file but the corresponding feature is not enabled. Please enable it in your dependencies.".to_string()))
+}
+
+#[cfg(feature = "ooxml")]
+fn try_ooxml(
+    reader: impl Read + Seek,
+    config: &ScrapeConfig,
+) -> Result<Vec<Link>, LinkScrapingError> {
+    Ok(crate::formats::ooxml::scrape_with_config(reader, config)?
         .into_iter()
-        .map(|link| Link::SvgLink(link))
+        .map(Link::OoxmlLink)
         .collect())
 }
+#[cfg(not(feature = "ooxml"))]
+fn try_ooxml(_: impl Read + Seek, _: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
+    Err(LinkScrapingError::FeatureNotEnabledError("Detected ooxml-file but the corresponding feature is not enabled. Please enable it in your dependencies.".to_string()))
+}
+
+#[cfg(feature = "odf")]
+fn try_odf(
+    reader: impl Read + Seek,
+    config: &ScrapeConfig,
+) -> Result<Vec<Link>, LinkScrapingError> {
+    Ok(crate::formats::odf::scrape_with_config(reader, config)?
+        .into_iter()
+        .map(Link::OdfLink)
+        .collect())
+}
+#[cfg(not(feature = "odf"))]
+fn try_odf(_: impl Read + Seek, _: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
+    Err(LinkScrapingError::FeatureNotEnabledError("Detected odf-file but the corresponding feature is not enabled. Please enable it in your dependencies.".to_string()))
+}
+
+/// iWork documents have no mime type of their own, they are only tried as a fallback for zip-files.
+#[cfg(feature = "iwork")]
+fn try_iwork(reader: impl Read + Seek) -> Result<Vec<Link>, LinkScrapingError> {
+    Ok(crate::formats::iwork::scrape(reader)?
+        .into_iter()
+        .map(Link::IworkLink)
+        .collect())
+}
+
+#[cfg(feature = "xml")]
+fn try_xml(reader: impl Read, config: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
+    Ok(crate::formats::xml::scrape_with_config(reader, config)?
+        .into_iter()
+        .map(Link::XmlLink)
+        .collect())
+}
+#[cfg(not(feature = "xml"))]
+fn try_xml(_: impl Read, _: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
+    Err(LinkScrapingError::FeatureNotEnabledError("Detected xml-file but the corresponding feature is not enabled. Please enable it in your dependencies.".to_string()))
+}
+
+#[cfg(feature = "svg")]
+fn try_svg(reader: impl Read, config: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
+    Ok(
+        crate::formats::xml::svg::scrape_with_config(reader, config)?
+            .into_iter()
+            .map(Link::SvgLink)
+            .collect(),
+    )
+}
 #[cfg(not(feature = "svg"))]
-fn try_svg(_: impl Read) -> Result<Vec<Link>, LinkScrapingError> {
+fn try_svg(_: impl Read, _: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
     return Err(LinkScrapingError::FeatureNotEnabledError(format!("Detected svg-file but the corresponding feature is not enabled. Please enable it in your dependencies.")));
 }
 
+#[cfg(feature = "opml")]
+fn try_opml(reader: impl Read) -> Result<Vec<Link>, LinkScrapingError> {
+    Ok(crate::formats::xml::opml::scrape(reader)?
+        .into_iter()
+        .map(Link::OpmlLink)
+        .collect())
+}
+#[cfg(not(feature = "opml"))]
+fn try_opml(_: impl Read) -> Result<Vec<Link>, LinkScrapingError> {
+    Err(LinkScrapingError::FeatureNotEnabledError("Detected opml-file but the corresponding feature is not enabled. Please enable it in your dependencies.".to_string()))
+}
+
 cfg_if::cfg_if! {
-    if #[cfg(any(feature = "ooxml", feature = "odf"))] {
-        fn try_zip(bytes: impl AsRef<[u8]>) -> Result<Vec<Link>, LinkScrapingError> {
+    if #[cfg(any(feature = "ooxml", feature = "odf", feature = "iwork"))] {
+        // iWork documents ignore the config
+        #[cfg_attr(not(any(feature = "ooxml", feature = "odf")), allow(unused_variables))]
+        fn try_zip(bytes: impl AsRef<[u8]>, config: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
             #[cfg(feature = "ooxml")] {
-                let ooxml_result = try_ooxml(std::io::Cursor::new(bytes.as_ref())).map_err(|e| LinkScrapingError::from(e));
+                let ooxml_result = try_ooxml(std::io::Cursor::new(bytes.as_ref()), config);
                 if let Ok(res) = ooxml_result { return Ok(res); }
             }
 
             #[cfg(feature = "odf")] {
-                let odf_result = try_odf(std::io::Cursor::new(bytes.as_ref())).map_err(|e| LinkScrapingError::from(e));
+                let odf_result = try_odf(std::io::Cursor::new(bytes.as_ref()), config);
                 if let Ok(res) = odf_result { return Ok(res); }
             }
 
-            #[cfg(all(feature = "ooxml", feature = "odf"))] {
+            #[cfg(feature = "iwork")] {
+                let iwork_result = try_iwork(std::io::Cursor::new(bytes.as_ref()));
+                if let Ok(res) = iwork_result { return Ok(res); }
+            }
+
+            #[cfg(all(feature = "ooxml", feature = "odf", feature = "iwork"))] {
                 return Err(LinkScrapingError::FileTypeNotImplemented("Detected zip-file but the corresponding type is not supported!".to_string()));
             }
-            #[cfg(not(all(feature = "ooxml", feature = "odf")))] {
+            #[cfg(not(all(feature = "ooxml", feature = "odf", feature = "iwork")))] {
                 return Err(LinkScrapingError::FeatureNotEnabledError("Detected zip-file but the corresponding feature is not enabled. Please enable it in your dependencies.".to_string()));
             }
         }
     } else {
-        fn try_zip(_: impl AsRef<[u8]>) -> Result<Vec<Link>, LinkScrapingError> {
+        fn try_zip(_: impl AsRef<[u8]>, _: &ScrapeConfig) -> Result<Vec<Link>, LinkScrapingError> {
             Err(LinkScrapingError::FeatureNotEnabledError("Detected zip-file but the corresponding feature is not enabled. Please enable it in your dependencies.".to_string()))
         }
     }
@@ -286,11 +1554,23 @@ mod tests {
     const TEST_ODS: &[u8] = include_bytes!("../test_files/odf/ods_test.ods");
     const TEST_ODP: &[u8] = include_bytes!("../test_files/odf/odp_test.odp");
     const TEST_OTT: &[u8] = include_bytes!("../test_files/odf/ott_test.ott");
+    const TEST_PAGES: &[u8] = include_bytes!("../test_files/iwork/pages_test.pages");
     const TEST_PDF: &[u8] = include_bytes!("../test_files/pdf/pdf_test.pdf");
     const TEST_RTF: &[u8] = include_bytes!("../test_files/rtf/rtf_test.rtf");
     const TEST_XML: &[u8] = include_bytes!("../test_files/xml/xml_test.xml");
     const TEST_SVG: &[u8] = include_bytes!("../test_files/xml/svg_test.svg");
     const TEST_JPG: &[u8] = include_bytes!("../test_files/images/exif_test.jpg");
+    const TEST_DICOM: &[u8] = include_bytes!("../test_files/dicom/dicom_test.dcm");
+    const TEST_LNK: &[u8] = include_bytes!("../test_files/lnk/lnk_test.lnk");
+    const TEST_FONT: &[u8] = include_bytes!("../test_files/font/font_test.woff2");
+    const TEST_OPML: &[u8] = include_bytes!("../test_files/opml/opml_test.opml");
+    const TEST_KML: &[u8] = include_bytes!("../test_files/geo/kml_test.kml");
+    const TEST_GPX: &[u8] = include_bytes!("../test_files/geo/gpx_test.gpx");
+    const TEST_PLIST: &[u8] = include_bytes!("../test_files/plist/binary_test.plist");
+    const TEST_CHM: &[u8] = include_bytes!("../test_files/chm/chm_test.chm");
+    const TEST_CF_HTML: &[u8] = include_bytes!("../test_files/clipboard/cf_html_test.txt");
+    const TEST_TORRENT: &[u8] = include_bytes!("../test_files/torrent/torrent_test.torrent");
+    const TEST_MHTML: &[u8] = include_bytes!("../test_files/mhtml/mhtml_test.mht");
 
     macro_rules! is_active {
         ($name: literal) => {{
@@ -326,10 +1606,390 @@ mod tests {
         scrape(TEST_ODS, is_active!("odf"));
         scrape(TEST_OTT, is_active!("odf"));
         scrape(TEST_ODP, is_active!("odf"));
+        scrape(TEST_PAGES, is_active!("iwork"));
         scrape(TEST_PDF, is_active!("pdf"));
         scrape(TEST_RTF, is_active!("rtf"));
         scrape(TEST_XML, is_active!("xml"));
         scrape(TEST_SVG, is_active!("svg"));
         scrape(TEST_JPG, is_active!("image"));
+        scrape(TEST_DICOM, is_active!("dicom"));
+        scrape(TEST_LNK, is_active!("lnk"));
+        scrape(TEST_FONT, is_active!("font"));
+        scrape(TEST_OPML, is_active!("opml"));
+        scrape(TEST_KML, is_active!("geo"));
+        scrape(TEST_GPX, is_active!("geo"));
+        scrape(TEST_PLIST, is_active!("plist"));
+        scrape(TEST_CHM, is_active!("chm"));
+        scrape(TEST_CF_HTML, is_active!("clipboard"));
+        scrape(TEST_TORRENT, is_active!("torrent"));
+        scrape(TEST_MHTML, is_active!("mhtml"));
+    }
+
+    #[test]
+    fn include_emails_test() {
+        let config = ScrapeConfig {
+            include_emails: true,
+            ..ScrapeConfig::default()
+        };
+        let text = include_bytes!("../test_files/plaintext/emails_test.txt");
+        let links = scrape_with_config(std::io::Cursor::new(text), &config).unwrap();
+        assert_eq!(
+            links
+                .iter()
+                .map(|link| (link.url(), link.match_kind()))
+                .collect_vec(),
+            vec![
+                ("https://website.test.com/contact", MatchKind::Url),
+                ("support@email.test.com", MatchKind::Email),
+                ("https://other.test.com", MatchKind::Url),
+            ]
+        );
+
+        let links = scrape_from_slice(text).unwrap();
+        assert!(links.iter().all(|link| link.match_kind() == MatchKind::Url));
+    }
+
+    #[test]
+    fn drop_private_hosts_test() {
+        let config = ScrapeConfig {
+            drop_private_hosts: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(
+            std::io::Cursor::new(b"http://127.0.0.1/ http://localhost/ https://example.com/"),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            links.iter().map(|link| link.url()).collect_vec(),
+            vec!["https://example.com/"]
+        );
+    }
+
+    #[test]
+    fn content_type_mismatch_test() {
+        assert_eq!(
+            content_type_mismatch("txt", TEST_PDF),
+            Some("application/pdf")
+        );
+        assert_eq!(
+            content_type_mismatch("JPG", TEST_PDF),
+            Some("application/pdf")
+        );
+        assert_eq!(content_type_mismatch("pdf", TEST_PDF), None);
+        assert_eq!(content_type_mismatch("jpeg", TEST_JPG), None);
+        assert!(content_type_mismatch("txt", TEST_DOCX).is_some());
+        assert_eq!(
+            content_type_mismatch("pdf", b"https://test.com/"),
+            Some("text/plain")
+        );
+        assert_eq!(
+            content_type_mismatch("xml", b"<a href='https://test.com/'/>"),
+            None
+        );
+        assert_eq!(content_type_mismatch("unknown", TEST_PDF), None);
+    }
+
+    #[test]
+    fn scrape_from_path_test() {
+        let dir = std::env::temp_dir().join("link_scraper_scrape_from_path_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let text = dir.join("text.pdf");
+        std::fs::write(&text, "see https://text.test.com").unwrap();
+        let matching = dir.join("text.txt");
+        std::fs::write(&matching, "see https://text.test.com").unwrap();
+
+        let result = scrape_from_path(&text).unwrap();
+        assert_eq!(
+            result.content_type_mismatch,
+            Some(("pdf".to_string(), "text/plain".to_string()))
+        );
+        assert_eq!(
+            result.links.iter().map(|link| link.url()).collect_vec(),
+            vec!["https://text.test.com"]
+        );
+        assert!(scrape_from_path(&matching)
+            .unwrap()
+            .content_type_mismatch
+            .is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn scrape_from_path_mismatch_test() {
+        let dir = std::env::temp_dir().join("link_scraper_scrape_from_path_pdf_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("file.txt");
+        std::fs::write(&path, TEST_PDF).unwrap();
+
+        let result = scrape_from_path(&path).unwrap();
+        println!("{:?}", result);
+        assert_eq!(
+            result.content_type_mismatch,
+            Some(("txt".to_string(), "application/pdf".to_string()))
+        );
+        assert!(!result.links.is_empty());
+        assert!(result
+            .links
+            .iter()
+            .all(|link| matches!(link, Link::PdfLink(_))));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "ooxml")]
+    #[test]
+    fn detail_string_test() {
+        let links = scrape_from_slice(TEST_DOCX).unwrap();
+        let hyperlink = links
+            .iter()
+            .find(|link| link.url() == "https://hyperlink.test.com/")
+            .unwrap();
+        let detail = hyperlink.to_detail_string();
+        println!("{}", detail);
+        assert!(detail.starts_with("https://hyperlink.test.com/ [Hyperlink @ "));
+        assert!(detail.contains("word/_rels/document.xml.rels:"));
+    }
+
+    #[cfg(feature = "ooxml")]
+    #[test]
+    fn group_by_kind_name_test() {
+        let links = scrape_from_slice(TEST_DOCX).unwrap();
+        let groups = crate::helpers::group_by_kind(links, Link::kind_name);
+        println!("{:?}", groups.keys());
+        assert!(groups["OoxmlLink(Hyperlink)"]
+            .iter()
+            .any(|link| link.url() == "https://hyperlink.test.com/"));
+        assert!(groups["OoxmlLink(Comment)"]
+            .iter()
+            .any(|link| link.url() == "https://comment.test.com"));
+        assert!(groups["OoxmlLink(PlainText)"]
+            .iter()
+            .any(|link| link.url() == "https://plaintext.test.com"));
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn scrape_and_check_test() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/alive"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/dead"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let text = format!(
+            "{0}/alive ftp://files.test.com/report {0}/dead",
+            server.uri()
+        );
+        let checked = scrape_and_check(
+            std::io::Cursor::new(text.as_bytes()),
+            &LinkCheckOptions::default(),
+        )
+        .await
+        .unwrap();
+        let statuses = checked
+            .iter()
+            .map(|(link, status)| (link.url().replace(&server.uri(), ""), *status))
+            .collect_vec();
+        assert_eq!(
+            statuses,
+            vec![
+                ("/alive".to_string(), Some(200)),
+                ("ftp://files.test.com/report".to_string(), None),
+                ("/dead".to_string(), Some(404)),
+            ]
+        );
+    }
+
+    #[test]
+    fn structured_only_test() {
+        let config = ScrapeConfig {
+            structured_only: true,
+            ..ScrapeConfig::default()
+        };
+        let links =
+            scrape_with_config(std::io::Cursor::new(b"https://plaintext.test.com"), &config)
+                .unwrap();
+        assert!(links.is_empty());
+    }
+
+    #[test]
+    fn scrape_new_test() {
+        let document = "https://a.test.com https://NEW.test.com/x https://Seen.test.com/p https://new.test.com/x";
+        let mut seen = ["https://a.test.com", "https://seen.test.com/p"]
+            .map(str::to_string)
+            .into();
+        let new = scrape_new(std::io::Cursor::new(document), &seen).unwrap();
+        assert_eq!(
+            new.iter().map(|link| link.url()).collect::<Vec<_>>(),
+            ["https://NEW.test.com/x"]
+        );
+        assert_eq!(seen.len(), 2);
+
+        scrape_new_into(std::io::Cursor::new(document), &mut seen).unwrap();
+        assert!(seen.contains("https://new.test.com/x"));
+        assert!(scrape_new(std::io::Cursor::new(document), &seen)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "ooxml")]
+    fn link_ids_test() {
+        let links = scrape_from_slice(TEST_DOCX).unwrap();
+        let rescraped = scrape_from_slice(TEST_DOCX).unwrap();
+        let ids = link_ids(&links);
+        assert_eq!(ids, link_ids(&rescraped));
+        assert_eq!(ids.iter().unique().count(), links.len());
+
+        // Only the numbering tells identical links without a location apart
+        let strings = vec![Link::StringLink("https://id.test.com".to_string()); 2];
+        assert_eq!(strings[0].id(), strings[1].id());
+        let ids = link_ids(&strings);
+        assert_eq!(ids[0], strings[0].id());
+        assert_ne!(ids[0], ids[1]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "cache", feature = "ooxml", feature = "odf"))]
+    fn caching_scraper_test() {
+        let scraper = CachingScraper::new(1);
+        let first = scraper.scrape(TEST_DOCX).unwrap();
+        let second = scraper.scrape(TEST_DOCX).unwrap();
+        assert!(!first.is_empty());
+        assert_eq!(
+            first.iter().map(|link| link.url()).collect::<Vec<_>>(),
+            second.iter().map(|link| link.url()).collect::<Vec<_>>()
+        );
+        assert_eq!((scraper.hits(), scraper.misses()), (1, 1));
+
+        // Only one result fits, so the docx is dropped for the odt
+        scraper.scrape(TEST_ODT).unwrap();
+        scraper.scrape(TEST_DOCX).unwrap();
+        assert_eq!((scraper.hits(), scraper.misses()), (1, 3));
+        assert_eq!(scraper.len(), 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "spill", feature = "ooxml", feature = "odf"))]
+    fn external_sort_scraper_test() {
+        let inputs = [TEST_DOCX, TEST_ODT, TEST_ODS, TEST_DOCX];
+        let expected = inputs
+            .iter()
+            .flat_map(|input| scrape_from_slice(input).unwrap())
+            .map(|link| link.url().to_string())
+            .collect::<Vec<_>>();
+        assert!(expected.len() > 9);
+
+        let mut scraper = ExternalSortScraper::new(3);
+        for input in inputs {
+            scraper.scrape(input).unwrap();
+        }
+        assert!(scraper.spilled_files() > 1);
+        let urls = scraper
+            .finish()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(urls, expected);
+
+        let mut scraper = ExternalSortScraper::new(3).deduplicated(true);
+        for input in inputs {
+            scraper.scrape(input).unwrap();
+        }
+        let urls = scraper
+            .finish()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut deduplicated = expected.clone();
+        deduplicated.sort_unstable();
+        deduplicated.dedup();
+        assert_eq!(urls, deduplicated);
+
+        for sorted in [false, true] {
+            let mut scraper = ExternalSortScraper::new(1).max_open_files(2).sorted(sorted);
+            for input in inputs {
+                scraper.scrape(input).unwrap();
+            }
+            assert!(scraper.spilled_files() <= 2);
+            let urls = scraper
+                .finish()
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let mut expected = expected.clone();
+            if sorted {
+                expected.sort_unstable();
+            }
+            assert_eq!(urls, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn escape_line_test() {
+        for url in ["https://a.com/\\n", "https://b.com/\n\r\\"] {
+            assert!(!escape_line(url).contains('\n'));
+            assert_eq!(unescape_line(&escape_line(url)), url);
+        }
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "archive",
+        feature = "ooxml",
+        feature = "odf",
+        feature = "plaintext"
+    ))]
+    fn scrape_archive_iter_test() {
+        use std::io::Write;
+
+        let mut buffer = std::io::Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("documents/", options).unwrap();
+        for (name, content) in [
+            ("documents/report.docx", TEST_DOCX),
+            (
+                "documents/notes.txt",
+                b"see https://archive.test.com".as_slice(),
+            ),
+            ("table.ods", TEST_ODS),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let entries = scrape_archive_iter(std::io::Cursor::new(buffer.into_inner()))
+            .unwrap()
+            .map(|(name, links)| (name, links.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["documents/report.docx", "documents/notes.txt", "table.ods"]
+        );
+        assert!(entries[0]
+            .1
+            .iter()
+            .any(|link| link.url() == "https://hyperlink.test.com/"));
+        assert_eq!(entries[1].1[0].url(), "https://archive.test.com");
+        assert!(entries[2]
+            .1
+            .iter()
+            .any(|link| link.url() == "https://hyperlink.test.com/"));
     }
 }
@@ -0,0 +1,216 @@
+use crate::helpers::{is_public_url, is_schema_url, single_label_host, RedactPolicy, UrlMatcher};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Options that adjust which links the scrapers return.
+///
+/// All options are disabled by default, so `ScrapeConfig::default()` behaves like the plain `scrape`-functions.
+#[derive(Debug, Clone, Default)]
+pub struct ScrapeConfig {
+    /// Drops links whose host is `localhost` or a private, loopback or link-local ip-address.
+    ///
+    /// See [`is_public_url`] for details.
+    pub drop_private_hosts: bool,
+
+    /// Reconstructs urls that were wrapped onto the next line. Only used by the plaintext scraper.
+    ///
+    /// See [`crate::formats::plaintext::scrape_with_config`] for details.
+    pub join_wrapped_lines: bool,
+
+    /// Keeps only explicitly authored links (e.g. hyperlinks in OOXML and ODF, `href`-attributes in XML),
+    /// skipping urls that were merely found somewhere in the text.
+    ///
+    /// Formats without structured links (e.g. plaintext) return no links at all.
+    pub structured_only: bool,
+
+    /// Skips archive-entries that cannot be read or parsed instead of failing the whole archive.
+    /// Only used by the zip-based scrapers (OOXML and ODF).
+    ///
+    /// Without it, entries with malformed xml are still scraped up to the malformed part, but not reported.
+    /// The skipped entries are reported by e.g. [`crate::formats::ooxml::scrape_with_warnings`].
+    pub skip_corrupt_entries: bool,
+
+    /// Also scrapes the comment of zip-archives and of their entries. Only used by the zip-based scrapers (OOXML and ODF).
+    pub scrape_zip_comments: bool,
+
+    /// Rejects inputs larger than this amount of bytes with an [`InputTooLargeError`] before parsing them.
+    ///
+    /// Scrapers reading from a [`Seek`](std::io::Seek) check the remaining length of the stream up front,
+    /// the plaintext scraper stops as soon as it has read more than the limit.
+    pub max_input_bytes: Option<usize>,
+
+    /// Attaches the sentence containing each link as its `sentence`.
+    /// Only used by the scrapers that decode text (plaintext, OOXML and ODF).
+    ///
+    /// See [`crate::helpers::sentence_around`] for how sentences are segmented.
+    pub with_sentence: bool,
+
+    /// Also returns the targets of internal relationships, resolved to their path inside the package.
+    /// Only used by the OOXML scraper.
+    ///
+    /// See [`crate::formats::ooxml::OoxmlLinkKind::InternalReference`].
+    pub internal_references: bool,
+
+    /// Removes soft hyphens and zero-width characters from the text before searching it for urls,
+    /// so urls broken up by them are found as a whole. Only used by the plaintext, OOXML and ODF scrapers.
+    ///
+    /// See [`crate::helpers::strip_invisible_chars`].
+    pub strip_invisible_chars: bool,
+
+    /// Which branch of an OOXML `mc:AlternateContent`-block is scraped. Only used by the OOXML scraper.
+    ///
+    /// Both branches usually contain the same content, so scraping both would return every link twice.
+    pub alternate_content: AlternateContentBranch,
+
+    /// Returns the first malformed xml-event or zip-entry as an error instead of silently returning
+    /// the links found so far. Only used by the XML, OOXML and ODF scrapers.
+    ///
+    /// The zip-based scrapers then fail on the first malformed entry, even if [`ScrapeConfig::skip_corrupt_entries`]
+    /// is enabled.
+    pub strict: bool,
+
+    /// How many levels of embedded objects (e.g. a spreadsheet inside a document) are scraped as well.
+    /// Only used by the OOXML and ODF scrapers with the `embedded_objects` feature.
+    ///
+    /// See [`crate::formats::ooxml::OoxmlLinkKind::EmbeddedObject`].
+    pub embedded_object_depth: usize,
+
+    /// Repairs urls whose UTF-8 was decoded as Latin-1 or Windows-1252 somewhere along the way (`Ã¼ber` instead of `über`).
+    /// Only used by the plaintext, OOXML and ODF scrapers with the `encoding` feature.
+    ///
+    /// See [`crate::helpers::repair_mojibake`] for when an url counts as double-encoded.
+    pub repair_mojibake: bool,
+
+    /// Keeps namespace- and schema-urls like `http://www.w3.org/2000/svg`, which are part of the document's structure
+    /// rather than its content. Used by the XML- and SVG-scrapers and every scraper filtering with [`ScrapeConfig::accepts`].
+    ///
+    /// See [`is_schema_url`] for which urls count as schema-urls.
+    pub include_schema_urls: bool,
+
+    /// Also returns email-addresses found in the text, tagged with [`crate::helpers::MatchKind::Email`].
+    /// Only used by the plaintext scraper and the plaintext-fallback of the any_format scraper.
+    pub include_emails: bool,
+
+    /// Also returns links jumping to another place inside the document, e.g. to a named destination.
+    /// Only used by the PDF scraper.
+    ///
+    /// See [`crate::formats::pdf::PdfLinkKind::InternalDestination`].
+    pub internal_destinations: bool,
+
+    /// Replaces linkify for finding urls in text, see [`UrlMatcher`].
+    /// Only used by the plaintext scraper and the plaintext-fallback of the any_format scraper.
+    pub url_matcher: Option<Arc<dyn UrlMatcher>>,
+
+    /// Visits the entries of zip-archives sorted by their name instead of in the order they are stored in,
+    /// so archives with the same entries always return their links in the same order.
+    /// Only used by the zip-based scrapers (OOXML and ODF).
+    ///
+    /// The links of a single entry are always returned in the order they appear in.
+    pub deterministic: bool,
+
+    /// Collects the first characters of the main text as a preview while scraping, `0` disables it.
+    /// Only used by the zip-based scrapers (OOXML and ODF), which return it as [`crate::formats::ooxml::ZipScrapeResult::preview`].
+    /// The PDF-scraper offers `scrape_with_preview` instead, like the other two.
+    ///
+    /// The preview is taken from `word/document.xml`, the slides or the shared strings of OOXML-files
+    /// and from `content.xml` of ODF-files. Whitespace is collapsed into single spaces.
+    pub preview_length: usize,
+
+    /// Decodes QR-codes in up to this many images embedded into a document, `0` disables it.
+    /// Only used with the `qr` feature by the OOXML-, ODF- and PDF-scrapers.
+    ///
+    /// Only PNG-images of OOXML- and ODF-files and uncompressed or Flate-compressed images of PDFs are decoded,
+    /// each with at most 4096 × 4096 pixels.
+    pub qr_code_images: usize,
+
+    /// Redacts the returned urls with the given policy, e.g. to log them without leaking tokens.
+    /// Urls are filtered before they are redacted. Only used by the plaintext-, OOXML-, ODF- and PDF-scrapers.
+    ///
+    /// See [`crate::helpers::redact_url`].
+    pub redact: Option<RedactPolicy>,
+
+    /// Drops links whose host is a single label without a top-level domain, like `http://foo`,
+    /// which OCR and loose text often produce. Used by every scraper filtering with [`ScrapeConfig::accepts`].
+    ///
+    /// See [`single_label_host`] for which hosts count as single-label.
+    pub require_valid_tld: bool,
+
+    /// Keeps `localhost` even though [`ScrapeConfig::require_valid_tld`] drops single-label hosts.
+    pub allow_localhost: bool,
+
+    /// Returns at most this many links and stops scraping once more were found.
+    /// Only used by the zip-based scrapers (OOXML and ODF), which report whether links were dropped
+    /// as [`crate::formats::ooxml::ZipScrapeResult::truncated`].
+    ///
+    /// Archives are scraped entry by entry, so the links of the remaining entries are never extracted.
+    /// Links rejected by [`ScrapeConfig::accepts`] do not count towards the limit.
+    pub max_links: Option<usize>,
+}
+
+/// A branch of an OOXML `mc:AlternateContent`-block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlternateContentBranch {
+    /// The first `mc:Choice`, which is what current Office versions display.
+    /// Falls back to `mc:Fallback` if there is no `mc:Choice`
+    #[default]
+    Choice,
+    /// The `mc:Fallback`, which is what older Office versions display
+    Fallback,
+}
+
+impl ScrapeConfig {
+    /// Returns whether the given url passes all filters of this config.
+    pub fn accepts(&self, url: &str) -> bool {
+        (!self.drop_private_hosts || is_public_url(url))
+            && (self.include_schema_urls || !is_schema_url(url))
+            && (!self.require_valid_tld || self.accepts_single_label_host(url))
+    }
+
+    fn accepts_single_label_host(&self, url: &str) -> bool {
+        match single_label_host(url) {
+            Some(host) => self.allow_localhost && host == "localhost",
+            None => true,
+        }
+    }
+
+    /// Fails if `size` exceeds [`ScrapeConfig::max_input_bytes`].
+    pub fn check_input_size(&self, size: u64) -> Result<(), InputTooLargeError> {
+        match self.max_input_bytes {
+            Some(limit) if size > limit as u64 => Err(InputTooLargeError { size, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the remaining length of a stream without consuming it.
+    #[cfg(any(feature = "ooxml", feature = "odf", feature = "any_format"))]
+    pub(crate) fn check_stream_size<R, E>(&self, reader: &mut R) -> Result<(), E>
+    where
+        R: std::io::Seek,
+        E: From<std::io::Error> + From<InputTooLargeError>,
+    {
+        use std::io::SeekFrom;
+
+        if self.max_input_bytes.is_none() {
+            return Ok(());
+        }
+        let start = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(self.check_input_size(end.saturating_sub(start))?)
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Input of at least {size} bytes exceeds the limit of {limit} bytes")]
+pub struct InputTooLargeError {
+    pub size: u64,
+    pub limit: usize,
+}
+
+/// A document was aborted because parsing it would have taken unreasonable time or memory,
+/// e.g. because of entities expanding exponentially ("billion laughs").
+#[derive(Error, Debug)]
+#[error("Document exceeds the resource limit of the parser: {limit}")]
+pub struct ResourceLimitExceededError {
+    pub limit: String,
+}
@@ -0,0 +1,192 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Any error of the format-specific scrapers, for code that mixes several formats.
+///
+/// Every scraping-error converts into it, so results of different formats can be combined with `?`.
+/// The original error is kept as the [`Error::source`] and can be retrieved with [`ScrapeError::downcast_ref`].
+/// # Example
+/// ```
+/// use link_scraper::error::ScrapeError;
+/// use link_scraper::formats::plaintext;
+/// use link_scraper::helpers::AsUrl;
+///
+/// fn urls(text: &str) -> Result<Vec<String>, ScrapeError> {
+///     let links = plaintext::scrape_from_slice(text)?;
+///     Ok(links.iter().map(|link| link.url().to_string()).collect())
+/// }
+/// assert_eq!(urls("see https://error.test.com").unwrap(), ["https://error.test.com"]);
+/// ```
+#[derive(Debug)]
+pub struct ScrapeError {
+    format: &'static str,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl ScrapeError {
+    /// The name of the format that failed to be scraped, like the module-names of [`crate::formats`] (e.g. `ooxml`).
+    ///
+    /// Errors of [`crate::any_format_scraper`] are named `any_format`, plain io-errors `io`.
+    pub fn format(&self) -> &'static str {
+        self.format
+    }
+
+    /// Returns the original error if it is of the given type.
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: Error + 'static,
+    {
+        self.source.downcast_ref()
+    }
+
+    /// Returns the original error.
+    pub fn into_inner(self) -> Box<dyn Error + Send + Sync> {
+        self.source
+    }
+}
+
+impl Display for ScrapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to scrape {}: {}", self.format, self.source)
+    }
+}
+
+impl Error for ScrapeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Implements [`From`] for the errors of the given formats, each behind their feature.
+macro_rules! impl_from_errors {
+    ($($(#[$cfg:meta])? $format:literal => $error:ty),* $(,)?) => {
+        $(
+            $(#[$cfg])?
+            impl From<$error> for ScrapeError {
+                fn from(error: $error) -> Self {
+                    ScrapeError {
+                        format: $format,
+                        source: Box::new(error),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_errors!(
+    "io" => std::io::Error,
+    "io" => crate::config::InputTooLargeError,
+    "io" => crate::config::ResourceLimitExceededError,
+    #[cfg(feature = "any_format")]
+    "any_format" => crate::any_format_scraper::LinkScrapingError,
+    #[cfg(feature = "apk")]
+    "apk" => crate::formats::apk::ApkScrapingError,
+    #[cfg(feature = "asciidoc")]
+    "asciidoc" => crate::formats::asciidoc::AsciidocScrapingError,
+    #[cfg(feature = "chm")]
+    "chm" => crate::formats::chm::ChmScrapingError,
+    #[cfg(feature = "clipboard")]
+    "clipboard" => crate::formats::clipboard::ClipboardScrapingError,
+    #[cfg(feature = "css")]
+    "css" => crate::formats::css::CssScrapingError,
+    #[cfg(feature = "dicom")]
+    "dicom" => crate::formats::dicom::DicomScrapingError,
+    #[cfg(feature = "fixed_width")]
+    "fixed_width" => crate::formats::fixed_width::FixedWidthScrapingError,
+    #[cfg(feature = "font")]
+    "font" => crate::formats::font::FontScrapingError,
+    #[cfg(feature = "geo")]
+    "geo" => crate::formats::geo::GeoScrapingError,
+    #[cfg(feature = "html")]
+    "html" => crate::formats::html::HtmlScrapingError,
+    #[cfg(feature = "crawler")]
+    "http_response" => crate::formats::http_response::HttpResponseScrapingError,
+    #[cfg(feature = "image")]
+    "image" => crate::formats::image::ImageScrapingError,
+    #[cfg(feature = "ini")]
+    "ini" => crate::formats::ini::IniScrapingError,
+    #[cfg(feature = "iwork")]
+    "iwork" => crate::formats::iwork::IworkScrapingError,
+    #[cfg(feature = "json")]
+    "json" => crate::formats::json::JsonScrapingError,
+    #[cfg(feature = "latex")]
+    "latex" => crate::formats::latex::LatexScrapingError,
+    #[cfg(feature = "lnk")]
+    "lnk" => crate::formats::lnk::LnkScrapingError,
+    #[cfg(feature = "mhtml")]
+    "mhtml" => crate::formats::mhtml::MhtmlScrapingError,
+    #[cfg(feature = "odf")]
+    "odf" => crate::formats::odf::OdfScrapingError,
+    #[cfg(feature = "ooxml")]
+    "ooxml" => crate::formats::ooxml::OoxmlScrapingError,
+    #[cfg(feature = "pdf")]
+    "pdf" => crate::formats::pdf::PdfScrapingError,
+    #[cfg(feature = "plaintext")]
+    "plaintext" => crate::formats::plaintext::TextFileScrapingError,
+    #[cfg(feature = "plist")]
+    "plist" => crate::formats::plist::PlistScrapingError,
+    #[cfg(feature = "proto")]
+    "proto" => crate::formats::proto::ProtoScrapingError,
+    #[cfg(feature = "references")]
+    "references" => crate::formats::references::ReferencesScrapingError,
+    #[cfg(feature = "crawler")]
+    "robots" => crate::formats::robots::RobotsScrapingError,
+    #[cfg(feature = "rst")]
+    "rst" => crate::formats::rst::RstScrapingError,
+    #[cfg(feature = "rtf")]
+    "rtf" => crate::formats::rtf::RtfScrapingError,
+    #[cfg(feature = "shell")]
+    "shell" => crate::formats::shell::ShellScrapingError,
+    #[cfg(feature = "source")]
+    "source_code" => crate::formats::source_code::SourceCodeScrapingError,
+    #[cfg(feature = "torrent")]
+    "torrent" => crate::formats::torrent::TorrentScrapingError,
+    #[cfg(feature = "warc")]
+    "warc" => crate::formats::warc::WarcScrapingError,
+    #[cfg(any(feature = "xml", feature = "xlink"))]
+    "xml" => crate::formats::xml::XmlScrapingError,
+    #[cfg(any(feature = "xml", feature = "xlink"))]
+    "svg" => crate::formats::xml::svg::SvgScrapingError,
+    #[cfg(feature = "opml")]
+    "opml" => crate::formats::xml::opml::OpmlScrapingError,
+    #[cfg(feature = "xlink")]
+    "xlink" => crate::formats::xml::xlink::XLinkFormatError,
+);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(all(feature = "plaintext", feature = "ooxml"))]
+    fn mixed_formats_test() {
+        use super::ScrapeError;
+        use crate::formats::{ooxml, plaintext};
+        use crate::helpers::AsUrl;
+        use std::error::Error;
+
+        fn urls(text: &[u8], document: &[u8]) -> Result<Vec<String>, ScrapeError> {
+            let mut urls = plaintext::scrape_from_slice(text)?
+                .iter()
+                .map(|link| link.url().to_string())
+                .collect::<Vec<_>>();
+            urls.extend(
+                ooxml::scrape_from_slice(document)?
+                    .iter()
+                    .map(|link| link.url().to_string()),
+            );
+            Ok(urls)
+        }
+
+        let docx = include_bytes!("../test_files/ooxml/docx_test.docx");
+        let found = urls(b"see https://error.test.com", docx).unwrap();
+        assert_eq!(found[0], "https://error.test.com");
+        assert!(found.contains(&"https://hyperlink.test.com/".to_string()));
+
+        let error = urls(b"https://error.test.com", b"no zip-archive").unwrap_err();
+        assert_eq!(error.format(), "ooxml");
+        assert!(error
+            .downcast_ref::<ooxml::OoxmlScrapingError>()
+            .is_some_and(|error| matches!(error, ooxml::OoxmlScrapingError::ZipError(_))));
+        assert!(error.source().is_some());
+    }
+}
@@ -0,0 +1,157 @@
+/// A format this crate knows about and whether it was compiled in.
+///
+/// See [`supported_formats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatInfo {
+    /// The name of the feature enabling the format
+    pub name: &'static str,
+    /// The mime-types the [`crate::any_format_scraper`] dispatches to this format.
+    /// Empty for formats that have to be scraped with their own module.
+    pub mime_types: &'static [&'static str],
+    /// The usual file-extensions of the format
+    pub extensions: &'static [&'static str],
+    /// Whether the feature of the format is enabled in this build
+    pub enabled: bool,
+}
+
+pub(crate) const PLAINTEXT_MIME_TYPES: &[&str] =
+    &["text/plain", "text/csv", "text/css", "application/json"];
+pub(crate) const PDF_MIME_TYPES: &[&str] = &["application/pdf"];
+pub(crate) const XML_MIME_TYPES: &[&str] = &["text/xml", "text/html"];
+pub(crate) const SVG_MIME_TYPES: &[&str] = &["image/svg+xml"];
+pub(crate) const OPML_MIME_TYPES: &[&str] = &["text/x-opml"];
+pub(crate) const GEO_MIME_TYPES: &[&str] = &[
+    "application/vnd.google-earth.kml+xml",
+    "application/gpx+xml",
+];
+pub(crate) const OOXML_MIME_TYPES: &[&str] = &[
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.template",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.template",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.openxmlformats-officedocument.presentationml.template",
+    "application/vnd.openxmlformats-officedocument.presentationml.slideshow",
+];
+pub(crate) const ODF_MIME_TYPES: &[&str] = &[
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.spreadsheet",
+    "application/vnd.oasis.opendocument.template",
+    "application/vnd.oasis.opendocument.presentation",
+];
+pub(crate) const RTF_MIME_TYPES: &[&str] = &["application/rtf"];
+pub(crate) const IMAGE_MIME_TYPES: &[&str] = &[
+    "image/jpeg",
+    "image/png",
+    "image/tiff",
+    "image/webp",
+    "image/heic",
+    "image/heif",
+];
+pub(crate) const DICOM_MIME_TYPES: &[&str] = &["application/dicom"];
+pub(crate) const PLIST_MIME_TYPES: &[&str] = &["application/x-plist"];
+pub(crate) const LNK_MIME_TYPES: &[&str] = &["application/x-ms-shortcut"];
+pub(crate) const CHM_MIME_TYPES: &[&str] = &["application/vnd.ms-htmlhelp"];
+pub(crate) const CLIPBOARD_MIME_TYPES: &[&str] = &["text/x-cf-html"];
+pub(crate) const TORRENT_MIME_TYPES: &[&str] = &["application/x-bittorrent"];
+pub(crate) const MHTML_MIME_TYPES: &[&str] = &["multipart/related", "message/rfc822"];
+pub(crate) const FONT_MIME_TYPES: &[&str] = &[
+    "font/ttf",
+    "font/otf",
+    "font/woff",
+    "font/woff2",
+    "application/font-sfnt",
+    "application/font-woff",
+];
+
+/// Lists every format this crate supports, together with whether it is enabled in this build.
+/// # Example
+/// ```
+/// let pdf = link_scraper::supported_formats()
+///     .into_iter()
+///     .find(|format| format.name == "pdf")
+///     .unwrap();
+/// if !pdf.enabled {
+///     println!("PDF-support was not compiled in");
+/// }
+/// ```
+pub fn supported_formats() -> Vec<FormatInfo> {
+    macro_rules! format_info {
+        ($name:literal, $mime_types:expr, [$($extension:literal),*]) => {
+            FormatInfo {
+                name: $name,
+                mime_types: $mime_types,
+                extensions: &[$($extension),*],
+                enabled: cfg!(feature = $name),
+            }
+        };
+    }
+
+    vec![
+        format_info!("plaintext", PLAINTEXT_MIME_TYPES, ["txt", "csv", "css"]),
+        format_info!("pdf", PDF_MIME_TYPES, ["pdf"]),
+        format_info!("xml", XML_MIME_TYPES, ["xml", "html", "htm", "xhtml"]),
+        format_info!("xlink", &[], []),
+        format_info!("svg", SVG_MIME_TYPES, ["svg"]),
+        format_info!("opml", OPML_MIME_TYPES, ["opml"]),
+        format_info!("geo", GEO_MIME_TYPES, ["kml", "gpx"]),
+        format_info!(
+            "ooxml",
+            OOXML_MIME_TYPES,
+            ["docx", "dotx", "xlsx", "xltx", "pptx", "potx", "ppsx"]
+        ),
+        format_info!("odf", ODF_MIME_TYPES, ["odt", "ods", "odp", "ott"]),
+        format_info!("rtf", RTF_MIME_TYPES, ["rtf"]),
+        format_info!(
+            "image",
+            IMAGE_MIME_TYPES,
+            ["jpg", "jpeg", "png", "tif", "tiff", "webp", "heic", "heif"]
+        ),
+        format_info!("source", &[], []),
+        format_info!("proto", &[], ["proto", "textproto"]),
+        format_info!("fixed_width", &[], []),
+        format_info!("dicom", DICOM_MIME_TYPES, ["dcm"]),
+        format_info!("json", &[], ["json", "jsonl", "ndjson"]),
+        format_info!("plist", PLIST_MIME_TYPES, ["plist"]),
+        format_info!("lnk", LNK_MIME_TYPES, ["lnk"]),
+        format_info!("iwork", &[], ["pages", "numbers", "key"]),
+        format_info!("apk", &[], ["apk", "ipa"]),
+        format_info!("shell", &[], ["sh", "bash", "service", "desktop"]),
+        format_info!("crawler", &[], []),
+        format_info!("latex", &[], ["tex", "bib"]),
+        format_info!("asciidoc", &[], ["adoc", "asciidoc"]),
+        format_info!("rst", &[], ["rst"]),
+        format_info!("html", &[], ["html", "htm"]),
+        format_info!("chm", CHM_MIME_TYPES, ["chm"]),
+        format_info!("clipboard", CLIPBOARD_MIME_TYPES, []),
+        format_info!("torrent", TORRENT_MIME_TYPES, ["torrent"]),
+        format_info!("warc", &[], ["warc"]),
+        format_info!("mhtml", MHTML_MIME_TYPES, ["mht", "mhtml"]),
+        format_info!("css", &[], ["css"]),
+        format_info!("ini", &[], ["ini", "cfg", "properties"]),
+        format_info!("references", &[], ["ris", "enw", "bib"]),
+        format_info!(
+            "font",
+            FONT_MIME_TYPES,
+            ["ttf", "otf", "ttc", "woff", "woff2"]
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supported_formats_test() {
+        let formats = supported_formats();
+        let plaintext = formats.iter().find(|it| it.name == "plaintext").unwrap();
+        assert!(plaintext.mime_types.contains(&"text/plain"));
+        assert_eq!(plaintext.enabled, cfg!(feature = "plaintext"));
+
+        let pdf = formats.iter().find(|it| it.name == "pdf").unwrap();
+        assert_eq!(pdf.enabled, cfg!(feature = "pdf"));
+        let ooxml = formats.iter().find(|it| it.name == "ooxml").unwrap();
+        assert_eq!(ooxml.enabled, cfg!(feature = "ooxml"));
+    }
+}
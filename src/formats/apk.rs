@@ -0,0 +1,383 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::io::{Cursor, Read, Seek};
+use thiserror::Error;
+use zip::result::ZipError;
+
+/// Scrapes links from Android app packages (.apk) and iOS app packages (.ipa), e.g. hardcoded API-endpoints.
+///
+/// Of an APK the binary XML of the `AndroidManifest.xml` and the string values of the `resources.arsc`
+/// (the compiled `res/values`) are searched for urls. The `<data>`-elements of intent-filters are joined
+/// into the deep links they accept, e.g. `https://example.com/open` for
+/// `<data android:scheme="https" android:host="example.com" android:pathPrefix="/open"/>`.
+/// Of an IPA every `Info.plist` is scraped with [`crate::formats::plist`].
+/// Compiled code (`classes.dex`, Mach-O binaries) is not scraped.
+///
+/// Fails with [`ApkScrapingError::NotAnAppPackage`] for zip-archives containing none of these files.
+pub fn scrape<R>(reader: R) -> Result<Vec<ApkLink>, ApkScrapingError>
+where
+    R: Read + Seek,
+{
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut links: Vec<ApkLink> = vec![];
+    let mut is_app_package = false;
+    for file_name in archive
+        .file_names()
+        .map(|name| name.to_owned())
+        .collect_vec()
+    {
+        let source = match file_name.rsplit('/').next().unwrap_or_default() {
+            _ if file_name == MANIFEST => ApkLinkSource::Manifest,
+            _ if file_name == RESOURCE_TABLE => ApkLinkSource::Resources,
+            INFO_PLIST if file_name.starts_with(IPA_PAYLOAD) => ApkLinkSource::Plist,
+            _ => continue,
+        };
+        is_app_package = true;
+        let mut bytes = Vec::new();
+        archive.by_name(&file_name)?.read_to_end(&mut bytes)?;
+        let urls = match source {
+            ApkLinkSource::Manifest => manifest_urls(&bytes)
+                .ok_or_else(|| ApkScrapingError::InvalidBinaryXmlError(file_name.clone()))?,
+            ApkLinkSource::Resources => resource_urls(&bytes)
+                .ok_or_else(|| ApkScrapingError::InvalidBinaryXmlError(file_name.clone()))?,
+            ApkLinkSource::Plist => crate::formats::plist::scrape(Cursor::new(bytes))?
+                .into_iter()
+                .map(|link| link.url)
+                .collect(),
+        };
+        links.extend(urls.into_iter().map(|url| ApkLink {
+            url,
+            file: file_name.clone(),
+            source,
+        }));
+    }
+
+    if !is_app_package {
+        return Err(ApkScrapingError::NotAnAppPackage);
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<ApkLink>, ApkScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<ApkLink>, ApkScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum ApkScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    ZipError(#[from] ZipError),
+    #[error(transparent)]
+    PlistScrapingError(#[from] crate::formats::plist::PlistScrapingError),
+    #[error("The binary XML {0} is malformed.")]
+    InvalidBinaryXmlError(String),
+    #[error("The zip-archive is neither an APK nor an IPA.")]
+    NotAnAppPackage,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApkLink {
+    pub url: String,
+    /// Path of the archive-entry containing the link
+    pub file: String,
+    pub source: ApkLinkSource,
+}
+
+impl Display for ApkLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for ApkLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApkLinkSource {
+    /// The link is an attribute-value or a deep link of the `AndroidManifest.xml`
+    Manifest,
+    /// The link is a string-resource inside the `resources.arsc`
+    Resources,
+    /// The link is a string-value of an `Info.plist` of an IPA
+    Plist,
+}
+
+const MANIFEST: &str = "AndroidManifest.xml";
+const RESOURCE_TABLE: &str = "resources.arsc";
+const INFO_PLIST: &str = "Info.plist";
+const IPA_PAYLOAD: &str = "Payload/";
+
+const RES_STRING_POOL_TYPE: u16 = 0x0001;
+const RES_TABLE_TYPE: u16 = 0x0002;
+const RES_XML_TYPE: u16 = 0x0003;
+const RES_XML_START_ELEMENT_TYPE: u16 = 0x0102;
+/// The data-type of an attribute whose typed value is an index into the string pool
+const TYPE_STRING: u8 = 0x03;
+const UTF8_FLAG: u32 = 1 << 8;
+const NO_INDEX: u32 = u32::MAX;
+
+/// Attributes of a manifest's `<data>`-element that make up a deep link, the path-variants in order of preference
+const DEEP_LINK_PATHS: [&str; 3] = ["path", "pathPrefix", "pathPattern"];
+
+/// A chunk of Android's binary resource formats: a type, the size of its header and its size including the header.
+struct Chunk<'a> {
+    kind: u16,
+    header_size: usize,
+    bytes: &'a [u8],
+}
+
+impl<'a> Chunk<'a> {
+    fn at(bytes: &'a [u8], offset: usize) -> Option<Self> {
+        let kind = read_u16(bytes, offset)?;
+        let header_size = read_u16(bytes, offset + 2)? as usize;
+        let size = read_u32(bytes, offset + 4)? as usize;
+        if size < 8 || header_size < 8 || header_size > size {
+            return None;
+        }
+        Some(Chunk {
+            kind,
+            header_size,
+            bytes: bytes.get(offset..offset.checked_add(size)?)?,
+        })
+    }
+
+    /// The chunks following the header, which stops at the first malformed one.
+    fn children(&self) -> impl Iterator<Item = Chunk<'a>> {
+        let bytes = self.bytes;
+        let mut offset = self.header_size;
+        std::iter::from_fn(move || {
+            let chunk = Chunk::at(bytes, offset)?;
+            offset += chunk.bytes.len();
+            Some(chunk)
+        })
+    }
+
+    /// Decodes a string pool, returns [`None`] if any string lies outside of the chunk.
+    fn strings(&self) -> Option<Vec<String>> {
+        let count = read_u32(self.bytes, 8)? as usize;
+        let is_utf8 = read_u32(self.bytes, 16)? & UTF8_FLAG != 0;
+        let strings_start = read_u32(self.bytes, 20)? as usize;
+        (0..count)
+            .map(|index| {
+                let offset = read_u32(self.bytes, self.header_size + index * 4)? as usize;
+                let start = strings_start.checked_add(offset)?;
+                if is_utf8 {
+                    utf8_string(self.bytes, start)
+                } else {
+                    utf16_string(self.bytes, start)
+                }
+            })
+            .collect()
+    }
+}
+
+/// UTF-8 strings are prefixed with their length in UTF-16 code units and in bytes, each taking one or two bytes.
+fn utf8_string(bytes: &[u8], offset: usize) -> Option<String> {
+    let length_size = |offset: usize| -> Option<(usize, usize)> {
+        let first = *bytes.get(offset)? as usize;
+        if first & 0x80 == 0 {
+            Some((first, 1))
+        } else {
+            Some((((first & 0x7f) << 8) | *bytes.get(offset + 1)? as usize, 2))
+        }
+    };
+    let (_, skip) = length_size(offset)?;
+    let (length, skip_bytes) = length_size(offset + skip)?;
+    let start = offset + skip + skip_bytes;
+    Some(String::from_utf8_lossy(bytes.get(start..start + length)?).to_string())
+}
+
+/// UTF-16 strings are prefixed with their length in code units, taking one or two `u16`s.
+fn utf16_string(bytes: &[u8], offset: usize) -> Option<String> {
+    let first = read_u16(bytes, offset)? as usize;
+    let (length, start) = if first & 0x8000 == 0 {
+        (first, offset + 2)
+    } else {
+        let second = read_u16(bytes, offset + 2)? as usize;
+        (((first & 0x7fff) << 16) | second, offset + 4)
+    };
+    let units = bytes
+        .get(start..start.checked_add(length.checked_mul(2)?)?)?
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect_vec();
+    Some(String::from_utf16_lossy(&units))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+/// Finds the urls in the attribute-values of a binary XML and joins the deep links of its `<data>`-elements.
+fn manifest_urls(bytes: &[u8]) -> Option<Vec<String>> {
+    let document = Chunk::at(bytes, 0).filter(|chunk| chunk.kind == RES_XML_TYPE)?;
+    let strings = document
+        .children()
+        .find(|chunk| chunk.kind == RES_STRING_POOL_TYPE)?
+        .strings()?;
+    let string = |index: u32| strings.get(index as usize).map(String::as_str);
+
+    let mut urls = vec![];
+    for element in document
+        .children()
+        .filter(|chunk| chunk.kind == RES_XML_START_ELEMENT_TYPE)
+    {
+        let extension = element.header_size;
+        let name = string(read_u32(element.bytes, extension + 4)?).unwrap_or_default();
+        let attribute_start = read_u16(element.bytes, extension + 8)? as usize;
+        let attribute_size = read_u16(element.bytes, extension + 10)? as usize;
+        let attribute_count = read_u16(element.bytes, extension + 12)? as usize;
+
+        let mut attributes = vec![];
+        for index in 0..attribute_count {
+            let offset = extension + attribute_start + index * attribute_size;
+            let attribute_name = string(read_u32(element.bytes, offset + 4)?).unwrap_or_default();
+            let raw_value = read_u32(element.bytes, offset + 8)?;
+            let data_type = *element.bytes.get(offset + 15)?;
+            let data = read_u32(element.bytes, offset + 16)?;
+            let value = if raw_value != NO_INDEX {
+                string(raw_value)
+            } else if data_type == TYPE_STRING {
+                string(data)
+            } else {
+                None
+            };
+            if let Some(value) = value {
+                urls.extend(
+                    find_urls(value)
+                        .iter()
+                        .map(|link| link.as_str().to_string()),
+                );
+                attributes.push((attribute_name, value));
+            }
+        }
+        if name == "data" {
+            urls.extend(deep_link(&attributes));
+        }
+    }
+    Some(urls)
+}
+
+/// Joins the `scheme`, `host`, `port` and path of an intent-filter's `<data>`-element.
+fn deep_link(attributes: &[(&str, &str)]) -> Option<String> {
+    let attribute = |name: &str| {
+        attributes
+            .iter()
+            .find(|(attribute, _)| *attribute == name)
+            .map(|(_, value)| *value)
+    };
+    let scheme = attribute("scheme")?;
+    let host = attribute("host")?;
+    let port = attribute("port").map_or(String::new(), |port| format!(":{}", port));
+    let path = DEEP_LINK_PATHS
+        .into_iter()
+        .find_map(attribute)
+        .unwrap_or_default();
+    Some(format!("{}://{}{}{}", scheme, host, port, path))
+}
+
+/// Finds the urls in the global string pool of a resource table, which holds the values of all string-resources.
+fn resource_urls(bytes: &[u8]) -> Option<Vec<String>> {
+    let table = Chunk::at(bytes, 0).filter(|chunk| chunk.kind == RES_TABLE_TYPE)?;
+    let strings = table
+        .children()
+        .find(|chunk| chunk.kind == RES_STRING_POOL_TYPE)?
+        .strings()?;
+    Some(
+        strings
+            .iter()
+            .flat_map(|string| find_urls(string))
+            .map(|link| link.as_str().to_string())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_APK: &[u8] = include_bytes!("../../test_files/apk/apk_test.apk");
+    const TEST_IPA: &[u8] = include_bytes!("../../test_files/apk/ipa_test.ipa");
+
+    fn link(url: &str, file: &str, source: ApkLinkSource) -> ApkLink {
+        ApkLink {
+            url: url.to_string(),
+            file: file.to_string(),
+            source,
+        }
+    }
+
+    #[test]
+    fn scrape_apk_test() {
+        let links = scrape_from_slice(TEST_APK).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                link(
+                    "https://deeplink.apk.test.com/open",
+                    MANIFEST,
+                    ApkLinkSource::Manifest
+                ),
+                link(
+                    "https://endpoint.apk.test.com/api",
+                    MANIFEST,
+                    ApkLinkSource::Manifest
+                ),
+                link(
+                    "https://api.apk.test.com/v1",
+                    RESOURCE_TABLE,
+                    ApkLinkSource::Resources
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_ipa_test() {
+        let links = scrape_from_slice(TEST_IPA).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![link(
+                "https://api.ipa.test.com",
+                "Payload/Test.app/Info.plist",
+                ApkLinkSource::Plist
+            )]
+        );
+    }
+
+    #[test]
+    fn truncated_string_pool_test() {
+        let mut pool = vec![];
+        for value in [RES_STRING_POOL_TYPE, 28] {
+            pool.extend(value.to_le_bytes());
+        }
+        // Claims a string behind the end of the chunk
+        for value in [32u32, 1, 0, 0, 28, 0, 100] {
+            pool.extend(value.to_le_bytes());
+        }
+        assert!(Chunk::at(&pool, 0).unwrap().strings().is_none());
+    }
+
+    #[test]
+    fn not_an_app_package_test() {
+        const TEST_DOCX: &[u8] = include_bytes!("../../test_files/ooxml/docx_test.docx");
+        assert!(matches!(
+            scrape_from_slice(TEST_DOCX),
+            Err(ApkScrapingError::NotAnAppPackage)
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes links from AsciiDoc-sources.
+///
+/// The targets of link-macros (`link:https://link.example.com[text]`), urls followed by a link text
+/// (`https://link.example.com[text]`) and urls defined by attribute-entries (`:name: https://link.example.com`)
+/// are returned without the surrounding markup. Links in the rest of the text are found as usual.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<AsciidocLink>, AsciidocScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(scrape_from_string(content))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<AsciidocLink>, AsciidocScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<AsciidocLink>, AsciidocScrapingError>);
+
+/// Like [`scrape`] but takes the source as a string.
+pub fn scrape_from_string<S>(content: S) -> Vec<AsciidocLink>
+where
+    S: AsRef<str>,
+{
+    let mut links: Vec<AsciidocLink> = vec![];
+    for line in content.as_ref().lines() {
+        if let Some((name, value)) = attribute_entry(line) {
+            if let Some(url) = find_urls(value).first() {
+                links.push(AsciidocLink {
+                    url: url.as_str().to_string(),
+                    kind: AsciidocLinkKind::AttributeEntry,
+                    text: Some(name.to_string()),
+                });
+            }
+            continue;
+        }
+
+        // The line without the link-macros, which are blanked out to keep the positions intact
+        let mut text = line.to_string();
+        let mut search = 0;
+        while let Some(found) = line[search..].find(LINK_MACRO) {
+            let start = search + found;
+            let target_start = start + LINK_MACRO.len();
+            search = target_start;
+            let is_word_start = start == 0 || !line.as_bytes()[start - 1].is_ascii_alphanumeric();
+            let target_end = line[target_start..]
+                .find(|c: char| c == '[' || c.is_whitespace())
+                .map_or(line.len(), |end| target_start + end);
+            if !is_word_start || target_end == target_start {
+                continue;
+            }
+            let Some((label, end)) = bracketed(line, target_end) else {
+                continue;
+            };
+            links.push(AsciidocLink {
+                url: line[target_start..target_end].to_string(),
+                kind: AsciidocLinkKind::LinkMacro,
+                text: label,
+            });
+            text.replace_range(start..end, &" ".repeat(end - start));
+            search = end;
+        }
+
+        for link in find_urls(&text) {
+            // The link text may or may not have been taken as part of the url
+            let url_end = link
+                .as_str()
+                .find('[')
+                .map_or(link.end(), |end| link.start() + end);
+            let url = &line[link.start()..url_end];
+            links.push(match bracketed(line, url_end) {
+                Some((label, _)) => AsciidocLink {
+                    url: url.to_string(),
+                    kind: AsciidocLinkKind::InlineLink,
+                    text: label,
+                },
+                None => AsciidocLink {
+                    url: url.to_string(),
+                    kind: AsciidocLinkKind::PlainText,
+                    text: None,
+                },
+            });
+        }
+    }
+    links
+}
+
+#[derive(Error, Debug)]
+pub enum AsciidocScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsciidocLink {
+    pub url: String,
+    pub kind: AsciidocLinkKind,
+    /// The link text inside the brackets, or the name of the attribute-entry defining the url
+    pub text: Option<String>,
+}
+
+impl Display for AsciidocLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for AsciidocLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AsciidocLinkKind {
+    /// The link is the target of a link-macro <br/>
+    /// Example: `link:https://link.example.com[Example]`
+    LinkMacro,
+    /// The link is an url followed by its link text <br/>
+    /// Example: `https://link.example.com[Example]`
+    InlineLink,
+    /// The link is defined by an attribute-entry and referenced as `{name}` in the document <br/>
+    /// Example: `:example-url: https://link.example.com`
+    AttributeEntry,
+    /// The link is inside the text without any markup <br/>
+    /// Example: `See https://link.example.com`
+    PlainText,
+}
+
+const LINK_MACRO: &str = "link:";
+
+/// Splits an attribute-entry like `:name: value` into its name and value.
+fn attribute_entry(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.strip_prefix(':')?.split_once(':')?;
+    let is_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    is_name.then_some((name, value.trim()))
+}
+
+/// Reads the `[...]` starting at `open`.
+///
+/// Returns the trimmed content of the brackets ([`None`] if it is empty) and the position after the closing bracket.
+fn bracketed(line: &str, open: usize) -> Option<(Option<String>, usize)> {
+    if !line[open..].starts_with('[') {
+        return None;
+    }
+    let close = open + line[open..].find(']')?;
+    let label = line[open + 1..close].trim();
+    Some((
+        Some(label.to_string()).filter(|label| !label.is_empty()),
+        close + 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_ASCIIDOC: &[u8] = include_bytes!("../../test_files/asciidoc/asciidoc_test.adoc");
+
+    #[test]
+    fn scrape_asciidoc_test() {
+        let links = scrape_from_slice(TEST_ASCIIDOC).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&AsciidocLink {
+            url: "https://macro.test.com/docs".to_string(),
+            kind: AsciidocLinkKind::LinkMacro,
+            text: Some("The docs".to_string())
+        }));
+        assert!(links.contains(&AsciidocLink {
+            url: "https://inline.test.com/guide".to_string(),
+            kind: AsciidocLinkKind::InlineLink,
+            text: Some("Guide".to_string())
+        }));
+        assert!(links.contains(&AsciidocLink {
+            url: "https://attribute.test.com".to_string(),
+            kind: AsciidocLinkKind::AttributeEntry,
+            text: Some("repo-url".to_string())
+        }));
+        assert!(links.contains(&AsciidocLink {
+            url: "https://plaintext.test.com".to_string(),
+            kind: AsciidocLinkKind::PlainText,
+            text: None
+        }));
+        assert_eq!(links.len(), 4);
+    }
+
+    #[test]
+    fn trailing_markup_is_not_part_of_url_test() {
+        let links = scrape_from_string(
+            "See https://inline.test.com/a[A], link:https://macro.test.com/b[B].",
+        );
+        assert_eq!(
+            links.iter().map(|it| it.url.as_str()).collect::<Vec<_>>(),
+            ["https://macro.test.com/b", "https://inline.test.com/a"]
+        );
+    }
+}
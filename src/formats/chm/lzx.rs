@@ -0,0 +1,424 @@
+//! Decoder for the LZX-compression used by the `MSCompressed` section of CHM-files.
+//!
+//! Follows the layout of the original LZX-format (as used in CAB and CHM), not LZX DELTA.
+
+const NUM_CHARS: usize = 256;
+const NUM_PRIMARY_LENGTHS: usize = 7;
+const NUM_SECONDARY_LENGTHS: usize = 249;
+const MIN_MATCH: usize = 2;
+const FRAME_SIZE: usize = 32768;
+const PRETREE_SYMBOLS: usize = 20;
+const ALIGNED_SYMBOLS: usize = 8;
+
+const BLOCKTYPE_VERBATIM: u32 = 1;
+const BLOCKTYPE_ALIGNED: u32 = 2;
+const BLOCKTYPE_UNCOMPRESSED: u32 = 3;
+
+/// Decompresses `length` bytes from a stream starting at a reset point.
+pub(super) fn decompress(
+    input: &[u8],
+    window_bits: u32,
+    length: usize,
+) -> Result<Vec<u8>, &'static str> {
+    if !(15..=21).contains(&window_bits) {
+        return Err("unsupported window size");
+    }
+    let position_slots = match window_bits {
+        21 => 50,
+        20 => 42,
+        bits => bits as usize * 2,
+    };
+    Decoder::new(input, NUM_CHARS + position_slots * 8).decompress(length)
+}
+
+/// Reads 16-bit little-endian words, whose bits are consumed starting at the most significant one.
+struct BitReader<'a> {
+    input: &'a [u8],
+    position: usize,
+    buffer: u64,
+    bits_left: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn ensure(&mut self, bits: u32) {
+        while self.bits_left < bits {
+            // Reading past the end yields zeros, the amount of output bounds the decoding anyway.
+            let low = *self.input.get(self.position).unwrap_or(&0) as u64;
+            let high = *self.input.get(self.position + 1).unwrap_or(&0) as u64;
+            self.position += 2;
+            self.buffer |= ((high << 8) | low) << (64 - 16 - self.bits_left);
+            self.bits_left += 16;
+        }
+    }
+
+    fn read(&mut self, bits: u32) -> u32 {
+        if bits == 0 {
+            return 0;
+        }
+        self.ensure(bits);
+        let value = (self.buffer >> (64 - bits)) as u32;
+        self.buffer <<= bits;
+        self.bits_left -= bits;
+        value
+    }
+
+    /// Discards the remaining bits of the current word. Reads a whole word if there are none.
+    fn align_to_bytes(&mut self) {
+        if self.bits_left == 0 {
+            self.ensure(16);
+        }
+        self.buffer = 0;
+        self.bits_left = 0;
+    }
+
+    /// Every frame starts at a word boundary.
+    fn align_to_word(&mut self) {
+        let bits = self.bits_left & 15;
+        self.buffer <<= bits;
+        self.bits_left -= bits;
+    }
+
+    fn read_raw(&mut self, length: usize) -> Result<&'a [u8], &'static str> {
+        let bytes = self
+            .input
+            .get(self.position..self.position + length)
+            .ok_or("unexpected end of input")?;
+        self.position += length;
+        Ok(bytes)
+    }
+}
+
+/// A canonical huffman-code, whose codes are read starting at their most significant bit.
+struct HuffmanTree {
+    counts: [u16; 17],
+    symbols: Vec<u16>,
+}
+
+impl HuffmanTree {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 17];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        // Symbols sorted by code length, ties broken by symbol
+        let mut symbols: Vec<u16> = (0..lengths.len() as u16)
+            .filter(|&symbol| lengths[symbol as usize] != 0)
+            .collect();
+        symbols.sort_by_key(|&symbol| lengths[symbol as usize]);
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<usize, &'static str> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for length in 1..=16 {
+            code |= bits.read(1) as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize] as usize);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid huffman code")
+    }
+}
+
+struct Decoder<'a> {
+    bits: BitReader<'a>,
+    /// The decoded data before the intel E8 translation
+    window: Vec<u8>,
+    extra_bits: [u32; 51],
+    position_base: [usize; 51],
+    main_lengths: Vec<u8>,
+    length_lengths: Vec<u8>,
+    main_tree: HuffmanTree,
+    length_tree: HuffmanTree,
+    aligned_tree: HuffmanTree,
+    repeated_offsets: [usize; 3],
+    intel_started: bool,
+    intel_file_size: i32,
+    block_type: u32,
+    block_length: usize,
+    block_remaining: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(input: &'a [u8], main_elements: usize) -> Self {
+        let mut extra_bits = [0u32; 51];
+        let mut bits = 0;
+        for slot in (0..51).step_by(2) {
+            extra_bits[slot] = bits;
+            if slot + 1 < 51 {
+                extra_bits[slot + 1] = bits;
+            }
+            if slot != 0 && bits < 17 {
+                bits += 1;
+            }
+        }
+        let mut position_base = [0usize; 51];
+        let mut base = 0;
+        for slot in 0..51 {
+            position_base[slot] = base;
+            base += 1 << extra_bits[slot];
+        }
+
+        Self {
+            bits: BitReader {
+                input,
+                position: 0,
+                buffer: 0,
+                bits_left: 0,
+            },
+            window: vec![],
+            extra_bits,
+            position_base,
+            main_lengths: vec![0; main_elements],
+            length_lengths: vec![0; NUM_SECONDARY_LENGTHS + 1],
+            main_tree: HuffmanTree::new(&[]),
+            length_tree: HuffmanTree::new(&[]),
+            aligned_tree: HuffmanTree::new(&[]),
+            repeated_offsets: [1, 1, 1],
+            intel_started: false,
+            intel_file_size: 0,
+            block_type: 0,
+            block_length: 0,
+            block_remaining: 0,
+        }
+    }
+
+    fn decompress(mut self, length: usize) -> Result<Vec<u8>, &'static str> {
+        if self.bits.read(1) == 1 {
+            let high = self.bits.read(16) as i32;
+            let low = self.bits.read(16) as i32;
+            self.intel_file_size = (high << 16) | low;
+        }
+
+        let mut output = Vec::with_capacity(length.min(self.bits.input.len() * 16));
+        let mut frame = 0;
+        while output.len() < length {
+            let frame_start = frame * FRAME_SIZE;
+            let frame_end = (frame_start + FRAME_SIZE).min(length);
+            while self.window.len() < frame_end {
+                if self.block_remaining == 0 {
+                    self.read_block_header()?;
+                    continue;
+                }
+                if self.block_type == BLOCKTYPE_UNCOMPRESSED {
+                    let amount = self.block_remaining.min(frame_end - self.window.len());
+                    let bytes = self.bits.read_raw(amount)?;
+                    self.window.extend_from_slice(bytes);
+                    self.block_remaining -= amount;
+                } else {
+                    let produced = self.decode_symbol()?;
+                    self.block_remaining = self
+                        .block_remaining
+                        .checked_sub(produced)
+                        .ok_or("match overruns block")?;
+                }
+            }
+            self.bits.align_to_word();
+
+            let mut data = self.window[frame_start..frame_end].to_vec();
+            if self.intel_started && self.intel_file_size != 0 && frame < 32768 {
+                translate_intel_calls(&mut data, frame_start as i32, self.intel_file_size);
+            }
+            output.append(&mut data);
+            frame += 1;
+        }
+        Ok(output)
+    }
+
+    fn read_block_header(&mut self) -> Result<(), &'static str> {
+        // Uncompressed blocks of odd length are padded with a single byte
+        if self.block_type == BLOCKTYPE_UNCOMPRESSED && self.block_length & 1 == 1 {
+            self.bits.read_raw(1)?;
+        }
+
+        self.block_type = self.bits.read(3);
+        let high = self.bits.read(16) as usize;
+        let low = self.bits.read(8) as usize;
+        self.block_length = (high << 8) | low;
+        self.block_remaining = self.block_length;
+
+        match self.block_type {
+            BLOCKTYPE_VERBATIM | BLOCKTYPE_ALIGNED => {
+                if self.block_type == BLOCKTYPE_ALIGNED {
+                    let lengths: Vec<u8> = (0..ALIGNED_SYMBOLS)
+                        .map(|_| self.bits.read(3) as u8)
+                        .collect();
+                    self.aligned_tree = HuffmanTree::new(&lengths);
+                }
+
+                let mut main_lengths = std::mem::take(&mut self.main_lengths);
+                self.read_lengths(&mut main_lengths, 0, NUM_CHARS)?;
+                let main_elements = main_lengths.len();
+                self.read_lengths(&mut main_lengths, NUM_CHARS, main_elements)?;
+                if main_lengths[0xE8] != 0 {
+                    self.intel_started = true;
+                }
+                self.main_tree = HuffmanTree::new(&main_lengths);
+                self.main_lengths = main_lengths;
+
+                let mut length_lengths = std::mem::take(&mut self.length_lengths);
+                self.read_lengths(&mut length_lengths, 0, NUM_SECONDARY_LENGTHS)?;
+                self.length_tree = HuffmanTree::new(&length_lengths);
+                self.length_lengths = length_lengths;
+            }
+            BLOCKTYPE_UNCOMPRESSED => {
+                self.intel_started = true;
+                self.bits.align_to_bytes();
+                let header = self.bits.read_raw(12)?;
+                for (offset, bytes) in self.repeated_offsets.iter_mut().zip(header.chunks(4)) {
+                    *offset = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+                }
+            }
+            _ => return Err("invalid block type"),
+        }
+        Ok(())
+    }
+
+    /// Reads the code lengths of `lengths[first..last]`, which are stored as deltas to the previous block
+    /// and encoded with a pre-tree.
+    fn read_lengths(
+        &mut self,
+        lengths: &mut [u8],
+        first: usize,
+        last: usize,
+    ) -> Result<(), &'static str> {
+        let pretree_lengths: Vec<u8> = (0..PRETREE_SYMBOLS)
+            .map(|_| self.bits.read(4) as u8)
+            .collect();
+        let pretree = HuffmanTree::new(&pretree_lengths);
+
+        let fill = |lengths: &mut [u8], start: usize, run: usize, value: u8| {
+            let end = (start + run).min(lengths.len());
+            lengths[start..end].fill(value);
+            start + run
+        };
+        // Only the symbols up to 16 are deltas, a run of 19 must not be followed by another run
+        let delta = |length: u8, delta: usize| match delta {
+            0..=16 => Ok(((length as usize + 17 - delta) % 17) as u8),
+            _ => Err("invalid pre-tree symbol"),
+        };
+
+        let mut x = first;
+        while x < last {
+            match pretree.decode(&mut self.bits)? {
+                17 => {
+                    let run = self.bits.read(4) as usize + 4;
+                    x = fill(lengths, x, run, 0);
+                }
+                18 => {
+                    let run = self.bits.read(5) as usize + 20;
+                    x = fill(lengths, x, run, 0);
+                }
+                19 => {
+                    let run = self.bits.read(1) as usize + 4;
+                    let z = pretree.decode(&mut self.bits)?;
+                    let value = delta(lengths[x], z)?;
+                    x = fill(lengths, x, run, value);
+                }
+                z => {
+                    lengths[x] = delta(lengths[x], z)?;
+                    x += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes a literal or a match and returns the amount of bytes it produced.
+    fn decode_symbol(&mut self) -> Result<usize, &'static str> {
+        let main_element = self.main_tree.decode(&mut self.bits)?;
+        if main_element < NUM_CHARS {
+            self.window.push(main_element as u8);
+            return Ok(1);
+        }
+
+        let main_element = main_element - NUM_CHARS;
+        let mut match_length = main_element & NUM_PRIMARY_LENGTHS;
+        if match_length == NUM_PRIMARY_LENGTHS {
+            match_length += self.length_tree.decode(&mut self.bits)?;
+        }
+        match_length += MIN_MATCH;
+
+        let slot = main_element >> 3;
+        let match_offset = match slot {
+            0 => self.repeated_offsets[0],
+            1 => {
+                self.repeated_offsets.swap(0, 1);
+                self.repeated_offsets[0]
+            }
+            2 => {
+                self.repeated_offsets.swap(0, 2);
+                self.repeated_offsets[0]
+            }
+            _ => {
+                let offset = self.read_match_offset(slot)?;
+                self.repeated_offsets =
+                    [offset, self.repeated_offsets[0], self.repeated_offsets[1]];
+                offset
+            }
+        };
+
+        if match_offset == 0 || match_offset > self.window.len() {
+            return Err("match offset beyond start of stream");
+        }
+        let start = self.window.len() - match_offset;
+        for i in 0..match_length {
+            self.window.push(self.window[start + i]);
+        }
+        Ok(match_length)
+    }
+
+    fn read_match_offset(&mut self, slot: usize) -> Result<usize, &'static str> {
+        let extra = *self.extra_bits.get(slot).ok_or("invalid position slot")?;
+        let base = self.position_base[slot] - 2;
+        if self.block_type != BLOCKTYPE_ALIGNED {
+            return Ok(base + self.bits.read(extra) as usize);
+        }
+
+        // In aligned blocks the lowest three bits are huffman-encoded
+        Ok(match extra {
+            0 => base,
+            1..=2 => base + self.bits.read(extra) as usize,
+            3 => base + self.aligned_tree.decode(&mut self.bits)?,
+            _ => {
+                let verbatim = (self.bits.read(extra - 3) as usize) << 3;
+                base + verbatim + self.aligned_tree.decode(&mut self.bits)?
+            }
+        })
+    }
+}
+
+/// Reverts the translation of relative to absolute call-targets of x86 `CALL`-instructions (`0xE8`).
+fn translate_intel_calls(data: &mut [u8], frame_start: i32, file_size: i32) {
+    if data.len() <= 10 {
+        return;
+    }
+    let end = data.len() - 10;
+    let mut i = 0;
+    let mut current_position = frame_start;
+    while i < end {
+        if data[i] != 0xE8 {
+            i += 1;
+            current_position += 1;
+            continue;
+        }
+        let absolute = i32::from_le_bytes([data[i + 1], data[i + 2], data[i + 3], data[i + 4]]);
+        if absolute >= -current_position && absolute < file_size {
+            let relative = if absolute >= 0 {
+                absolute - current_position
+            } else {
+                absolute + file_size
+            };
+            data[i + 1..i + 5].copy_from_slice(&relative.to_le_bytes());
+        }
+        i += 5;
+        current_position += 5;
+    }
+}
@@ -0,0 +1,334 @@
+use crate::formats::html::links_in_html;
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Seek};
+use thiserror::Error;
+
+mod lzx;
+
+/// Scrapes links from the HTML-topics of a compiled HTML help file (.chm).
+///
+/// Collects the values of `href`- and `src`-attributes (including relative links to other topics)
+/// as well as urls in the text of the topics.
+///
+/// Reads the whole stream before processing the contents.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<ChmLink>, ChmScrapingError>
+where
+    R: Read + Seek,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let archive = ChmArchive::parse(&bytes)?;
+
+    let mut links = vec![];
+    for entry in archive.entries.iter().filter(|entry| is_topic(&entry.name)) {
+        let content = archive.read(entry)?;
+        links.extend(
+            links_in_html(&String::from_utf8_lossy(content))
+                .into_iter()
+                .map(|url| ChmLink {
+                    url,
+                    topic: entry.name.clone(),
+                }),
+        );
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<ChmLink>, ChmScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<ChmLink>, ChmScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum ChmScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Given file was not a CHM-file.")]
+    NotAChmError,
+    #[error("The CHM-structure ended unexpectedly.")]
+    UnexpectedEndError,
+    #[error("The compressed content could not be decompressed: {0}")]
+    LzxError(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChmLink {
+    pub url: String,
+    /// The path of the topic containing the link inside the archive. Example: `/index.htm`
+    pub topic: String,
+}
+
+impl Display for ChmLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for ChmLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+const ITSF_SIGNATURE: &[u8] = b"ITSF";
+const ITSP_SIGNATURE: &[u8] = b"ITSP";
+const PMGL_SIGNATURE: &[u8] = b"PMGL";
+const PMGL_HEADER_LENGTH: usize = 0x14;
+const COMPRESSED_CONTENT: &str = "::DataSpace/Storage/MSCompressed/Content";
+const CONTROL_DATA: &str = "::DataSpace/Storage/MSCompressed/ControlData";
+const RESET_TABLE: &str = "::DataSpace/Storage/MSCompressed/Transform/\
+{7FC28940-9D31-11D0-9B27-00A0C91E9C7C}/InstanceData/ResetTable";
+/// Sizes in version 2 of the control data are given in multiples of this
+const LZX_FRAME_SIZE: usize = 0x8000;
+
+fn is_topic(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with(".htm") || name.ends_with(".html")
+}
+
+struct DirectoryEntry {
+    name: String,
+    /// 0 is stored uncompressed, 1 is the LZX-compressed `MSCompressed`-section
+    section: usize,
+    offset: usize,
+    length: usize,
+}
+
+struct ChmArchive<'a> {
+    bytes: &'a [u8],
+    content_offset: usize,
+    entries: Vec<DirectoryEntry>,
+    /// The decompressed `MSCompressed`-section, if any entry is stored there
+    compressed_section: Option<Vec<u8>>,
+}
+
+impl<'a> ChmArchive<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, ChmScrapingError> {
+        if !bytes.starts_with(ITSF_SIGNATURE) {
+            return Err(ChmScrapingError::NotAChmError);
+        }
+        let version = read_u32(bytes, 0x04)?;
+        let directory_offset = read_u64(bytes, 0x48)?;
+        let directory_length = read_u64(bytes, 0x50)?;
+        // Version 2 headers do not store the content offset, the content follows the directory.
+        let content_offset = if version >= 3 {
+            read_u64(bytes, 0x58)?
+        } else {
+            directory_offset + directory_length
+        };
+
+        let directory = slice(bytes, directory_offset, directory_length)?;
+        if !directory.starts_with(ITSP_SIGNATURE) {
+            return Err(ChmScrapingError::NotAChmError);
+        }
+        let header_length = read_u32(directory, 0x08)?;
+        let chunk_size = read_u32(directory, 0x10)?;
+        let chunk_count = read_u32(directory, 0x2C)?;
+
+        let mut entries = vec![];
+        for index in 0..chunk_count {
+            let chunk = slice(directory, header_length + index * chunk_size, chunk_size)?;
+            // Index-chunks (PMGI) only speed up lookups, every entry is listed in a PMGL-chunk.
+            if chunk.starts_with(PMGL_SIGNATURE) {
+                entries.append(&mut parse_listing_chunk(chunk)?);
+            }
+        }
+
+        let mut archive = Self {
+            bytes,
+            content_offset,
+            entries,
+            compressed_section: None,
+        };
+        if archive.entries.iter().any(|entry| entry.section == 1) {
+            archive.compressed_section = Some(archive.decompress_section()?);
+        }
+        Ok(archive)
+    }
+
+    fn find(&self, name: &str) -> Result<&'a [u8], ChmScrapingError> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .ok_or(ChmScrapingError::LzxError("missing compression metadata"))?;
+        slice(self.bytes, self.content_offset + entry.offset, entry.length)
+    }
+
+    fn read(&self, entry: &DirectoryEntry) -> Result<&[u8], ChmScrapingError> {
+        match (entry.section, &self.compressed_section) {
+            (0, _) => slice(self.bytes, self.content_offset + entry.offset, entry.length),
+            (_, Some(section)) => slice(section, entry.offset, entry.length),
+            (_, None) => Err(ChmScrapingError::UnexpectedEndError),
+        }
+    }
+
+    /// Decompresses the whole `MSCompressed`-section.
+    ///
+    /// The LZX-state is reset in fixed intervals, whose positions in the compressed stream
+    /// are listed in the reset table.
+    fn decompress_section(&self) -> Result<Vec<u8>, ChmScrapingError> {
+        let content = self.find(COMPRESSED_CONTENT)?;
+        let control_data = self.find(CONTROL_DATA)?;
+        let reset_table = self.find(RESET_TABLE)?;
+
+        if control_data.get(4..8) != Some(b"LZXC") {
+            return Err(ChmScrapingError::LzxError("invalid control data"));
+        }
+        let mut reset_interval = read_u32(control_data, 0x0C)?;
+        let mut window_size = read_u32(control_data, 0x10)?;
+        if read_u32(control_data, 0x08)? == 2 {
+            reset_interval *= LZX_FRAME_SIZE;
+            window_size *= LZX_FRAME_SIZE;
+        }
+        if !window_size.is_power_of_two() || reset_interval % LZX_FRAME_SIZE != 0 {
+            return Err(ChmScrapingError::LzxError("invalid control data"));
+        }
+
+        let entry_count = read_u32(reset_table, 0x04)?;
+        let entry_size = read_u32(reset_table, 0x08)?;
+        let table_offset = read_u32(reset_table, 0x0C)?;
+        let uncompressed_length = read_u64(reset_table, 0x10)?;
+        let frame_size = read_u64(reset_table, 0x20)?;
+        if frame_size != LZX_FRAME_SIZE || reset_interval == 0 {
+            return Err(ChmScrapingError::LzxError("invalid reset table"));
+        }
+
+        let mut section = vec![];
+        for start in (0..uncompressed_length).step_by(reset_interval) {
+            let frame = start / LZX_FRAME_SIZE;
+            if frame >= entry_count {
+                return Err(ChmScrapingError::LzxError("invalid reset table"));
+            }
+            let compressed_start = read_u64(reset_table, table_offset + frame * entry_size)?;
+            let input = content
+                .get(compressed_start..)
+                .ok_or(ChmScrapingError::UnexpectedEndError)?;
+            let length = reset_interval.min(uncompressed_length - start);
+            section.append(
+                &mut lzx::decompress(input, window_size.trailing_zeros(), length)
+                    .map_err(ChmScrapingError::LzxError)?,
+            );
+        }
+        Ok(section)
+    }
+}
+
+/// A listing chunk contains entries of the form `name-length name section offset length`,
+/// all numbers being ENCINTs. The end of the chunk is reserved for a quick-reference area.
+fn parse_listing_chunk(chunk: &[u8]) -> Result<Vec<DirectoryEntry>, ChmScrapingError> {
+    let free_space = read_u32(chunk, 0x04)?;
+    let end = chunk
+        .len()
+        .checked_sub(free_space)
+        .ok_or(ChmScrapingError::UnexpectedEndError)?;
+
+    let mut entries = vec![];
+    let mut position = PMGL_HEADER_LENGTH;
+    while position < end {
+        let name_length = read_encint(chunk, &mut position)?;
+        let name = slice(chunk, position, name_length)?;
+        position += name_length;
+        entries.push(DirectoryEntry {
+            name: String::from_utf8_lossy(name).to_string(),
+            section: read_encint(chunk, &mut position)?,
+            offset: read_encint(chunk, &mut position)?,
+            length: read_encint(chunk, &mut position)?,
+        });
+    }
+    Ok(entries)
+}
+
+/// Variable-length integer of 7-bit groups, most significant first. The high bit marks continuation.
+fn read_encint(bytes: &[u8], position: &mut usize) -> Result<usize, ChmScrapingError> {
+    let mut value = 0usize;
+    loop {
+        let byte = *bytes
+            .get(*position)
+            .ok_or(ChmScrapingError::UnexpectedEndError)?;
+        *position += 1;
+        value = value
+            .checked_mul(128)
+            .ok_or(ChmScrapingError::UnexpectedEndError)?
+            | (byte & 0x7F) as usize;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+fn slice(bytes: &[u8], offset: usize, length: usize) -> Result<&[u8], ChmScrapingError> {
+    offset
+        .checked_add(length)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or(ChmScrapingError::UnexpectedEndError)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<usize, ChmScrapingError> {
+    let bytes = slice(bytes, offset, 4)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<usize, ChmScrapingError> {
+    let bytes = slice(bytes, offset, 8)?;
+    usize::try_from(u64::from_le_bytes(bytes.try_into().unwrap()))
+        .map_err(|_| ChmScrapingError::UnexpectedEndError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CHM: &[u8] = include_bytes!("../../../test_files/chm/chm_test.chm");
+    const NOT_A_CHM: &[u8] = include_bytes!("../../../test_files/xml/xml_test.xml");
+
+    #[test]
+    fn scrape_chm_test() {
+        let links = scrape_from_slice(TEST_CHM).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&ChmLink {
+            url: "page2.htm".to_string(),
+            topic: "/index.htm".to_string()
+        }));
+        assert!(links.contains(&ChmLink {
+            url: "https://uncompressed.chm.test.com".to_string(),
+            topic: "/index.htm".to_string()
+        }));
+    }
+
+    #[test]
+    fn scrape_compressed_topics_test() {
+        let links = scrape_from_slice(TEST_CHM).unwrap();
+        assert!(links.contains(&ChmLink {
+            url: "index.htm#top".to_string(),
+            topic: "/page2.htm".to_string()
+        }));
+        assert!(links.contains(&ChmLink {
+            url: "https://verbatim.chm.test.com/aaaaa/abcabc".to_string(),
+            topic: "/page2.htm".to_string()
+        }));
+        assert!(links.contains(&ChmLink {
+            url: "images/logo.gif".to_string(),
+            topic: "/page3.htm".to_string()
+        }));
+    }
+
+    #[test]
+    fn fail_on_invalid_pretree_symbol_test() {
+        // A verbatim block whose pre-tree only codes symbol 19, so the run of 19 is followed by another 19
+        let corrupt_block = [
+            0x00, 0x10, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+        ];
+        assert_eq!(
+            lzx::decompress(&corrupt_block, 16, 1),
+            Err("invalid pre-tree symbol")
+        );
+    }
+
+    #[test]
+    fn fail_on_non_chm_test() {
+        assert!(matches!(
+            scrape_from_slice(NOT_A_CHM),
+            Err(ChmScrapingError::NotAChmError)
+        ));
+    }
+}
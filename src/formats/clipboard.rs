@@ -0,0 +1,179 @@
+use crate::formats::html::links_in_html;
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+
+/// Scrapes links from HTML copied to the Windows clipboard (`CF_HTML`).
+///
+/// The `SourceURL` of the header is returned as [`ClipboardLinkKind::SourceUrl`].
+/// Of the HTML only the fragment between `StartFragment` and `EndFragment` is scraped,
+/// which is what the user actually copied.
+/// Input without a `CF_HTML`-header is scraped as HTML as a whole.
+///
+/// Reads the whole stream before processing the contents.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<ClipboardLink>, ClipboardScrapingError>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let header = CfHtmlHeader::parse(&bytes);
+    let mut links: Vec<ClipboardLink> = header
+        .source_url
+        .iter()
+        .map(|url| ClipboardLink {
+            url: url.clone(),
+            kind: ClipboardLinkKind::SourceUrl,
+        })
+        .collect();
+    let fragment = String::from_utf8_lossy(header.fragment(&bytes));
+    links.extend(
+        links_in_html(&fragment)
+            .into_iter()
+            .map(|url| ClipboardLink {
+                url,
+                kind: ClipboardLinkKind::Fragment,
+            }),
+    );
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<ClipboardLink>, ClipboardScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<ClipboardLink>, ClipboardScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum ClipboardScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardLink {
+    pub url: String,
+    pub kind: ClipboardLinkKind,
+}
+
+impl Display for ClipboardLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for ClipboardLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardLinkKind {
+    /// The page the HTML was copied from, taken from the `SourceURL` of the header
+    SourceUrl,
+    /// The link is an attribute-value or part of the text of the copied fragment
+    Fragment,
+}
+
+fn is_cf_html(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"Version:")
+        && String::from_utf8_lossy(&bytes[..bytes.len().min(256)]).contains("StartHTML:")
+}
+
+/// The `Key:Value`-lines preceding the HTML. Offsets are byte-offsets into the whole clipboard-data.
+#[derive(Default)]
+struct CfHtmlHeader {
+    /// Where the header ends, `0` if there is none
+    length: usize,
+    start_html: Option<usize>,
+    end_html: Option<usize>,
+    start_fragment: Option<usize>,
+    end_fragment: Option<usize>,
+    source_url: Option<String>,
+}
+
+impl CfHtmlHeader {
+    fn parse(bytes: &[u8]) -> Self {
+        let mut header = Self::default();
+        if !is_cf_html(bytes) {
+            return header;
+        }
+
+        for line in bytes.split_inclusive(|&byte| byte == b'\n') {
+            let text = String::from_utf8_lossy(line);
+            let Some((key, value)) = text.trim_end().split_once(':') else {
+                break;
+            };
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric()) {
+                break;
+            }
+            header.length += line.len();
+
+            let offset = value.trim().parse::<usize>().ok();
+            match key {
+                "StartHTML" => header.start_html = offset,
+                "EndHTML" => header.end_html = offset,
+                "StartFragment" => header.start_fragment = offset,
+                "EndFragment" => header.end_fragment = offset,
+                "SourceURL" => header.source_url = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+        header
+    }
+
+    /// The copied fragment, falling back to the whole HTML if the fragment-offsets are missing or invalid.
+    fn fragment<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        let range = |start: Option<usize>, end: Option<usize>| {
+            let (start, end) = (start?, end?);
+            (self.length <= start && start <= end).then_some(())?;
+            bytes.get(start..end)
+        };
+        range(self.start_fragment, self.end_fragment)
+            .or_else(|| range(self.start_html, self.end_html))
+            .unwrap_or(&bytes[self.length..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CF_HTML: &[u8] = include_bytes!("../../test_files/clipboard/cf_html_test.txt");
+
+    #[test]
+    fn scrape_cf_html_test() {
+        let links = scrape_from_slice(TEST_CF_HTML).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                ClipboardLink {
+                    url: "https://source.clipboard.test.com/article".to_string(),
+                    kind: ClipboardLinkKind::SourceUrl
+                },
+                ClipboardLink {
+                    url: "https://fragment.clipboard.test.com/".to_string(),
+                    kind: ClipboardLinkKind::Fragment
+                },
+                ClipboardLink {
+                    url: "https://text.clipboard.test.com".to_string(),
+                    kind: ClipboardLinkKind::Fragment
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_without_header_test() {
+        let links =
+            scrape_from_slice("<p><a href=\"https://plain.test.com\">plain</a></p>").unwrap();
+        assert_eq!(
+            links,
+            vec![ClipboardLink {
+                url: "https://plain.test.com".to_string(),
+                kind: ClipboardLinkKind::Fragment
+            }]
+        );
+    }
+}
@@ -1,8 +1,13 @@
-use crate::helpers::find_urls;
+use crate::config::{InputTooLargeError, ResourceLimitExceededError, ScrapeConfig};
+use crate::formats::limited_xml_reader::LimitedXmlReader;
+use crate::helpers::{find_urls, push_preview, strip_invisible_chars, AsUrl};
 use itertools::Itertools;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
 use std::error::Error;
 use std::io::{Read, Seek};
-use zip::read::ZipFile;
+use std::sync::Arc;
+use xml::reader::XmlEvent;
 use zip::result::ZipError;
 
 /// Scrapes all links from a given compressed file.
@@ -37,25 +42,388 @@ where
     Ok(links)
 }
 
-/// Takes a reader for some zipped bytes and tries to extract some data from it.
-pub(crate) fn unified_unzip_scrape<R, T, E, F>(reader: R, extractor: F) -> Result<Vec<T>, E>
+/// Concatenates the text of all entries accepted by `is_text_entry`, in the order they are stored in the archive.
+///
+/// Every paragraph (`<w:p>`, `<a:p>`, `<text:p>`, `<text:h>`) ends with a line break.
+/// Tabs, line breaks and ODF's `<text:s/>`-spaces are kept, all other tags are dropped.
+pub(crate) fn extract_text<R, E, F>(reader: R, is_text_entry: F) -> Result<String, E>
 where
     R: Read + Seek,
-    E: Error + From<std::io::Error> + From<ZipError>,
-    F: Fn(ZipFile<'_>, &str, &mut Vec<T>) -> Result<(), E>,
+    E: From<ZipError> + From<xml::reader::Error> + From<ResourceLimitExceededError>,
+    F: Fn(&str) -> bool,
 {
     let mut archive = zip::ZipArchive::new(reader)?;
-    let mut links: Vec<T> = vec![];
+    let mut text = String::new();
     for file_name in archive
         .file_names()
+        .filter(|name| is_text_entry(name))
         .map(|name| name.to_owned())
         .collect_vec()
     {
-        let content = archive.by_name(&file_name)?;
-        if content.size() == 0 {
-            continue;
+        let mut parser = LimitedXmlReader::new(archive.by_name(&file_name)?);
+        loop {
+            match parser.next_or_end::<E>(true)? {
+                XmlEvent::Characters(chars) | XmlEvent::Whitespace(chars) => text.push_str(&chars),
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => match name.local_name.as_str() {
+                    "tab" => text.push('\t'),
+                    "br" | "line-break" => text.push('\n'),
+                    "s" => {
+                        let count = attributes
+                            .iter()
+                            .find(|attribute| attribute.name.local_name == "c")
+                            .and_then(|attribute| attribute.value.parse().ok())
+                            .unwrap_or(1);
+                        text.push_str(&" ".repeat(count))
+                    }
+                    _ => {}
+                },
+                XmlEvent::EndElement { name } if matches!(name.local_name.as_str(), "p" | "h") => {
+                    text.push('\n')
+                }
+                XmlEvent::EndDocument => break,
+                _ => {}
+            }
         }
-        extractor(content, &file_name, &mut links)?;
     }
-    Ok(links)
+    Ok(text)
+}
+
+/// Groups links by the archive-entry they were found in, keeping their order inside each entry.
+pub(crate) fn group_by_entry<T, F>(links: Vec<T>, entry: F) -> BTreeMap<String, Vec<T>>
+where
+    F: Fn(&T) -> &str,
+{
+    let mut groups: BTreeMap<String, Vec<T>> = BTreeMap::new();
+    for link in links {
+        groups
+            .entry(entry(&link).to_string())
+            .or_default()
+            .push(link);
+    }
+    groups
+}
+
+/// Links scraped from a zip-archive together with the entries that had to be skipped.
+#[derive(Debug, Clone)]
+pub struct ZipScrapeResult<T> {
+    pub links: Vec<T>,
+    /// Only filled if [`crate::config::ScrapeConfig::skip_corrupt_entries`] is enabled.
+    pub warnings: Vec<ZipEntryWarning>,
+    /// The beginning of the main text. Only filled if [`crate::config::ScrapeConfig::preview_length`] is set.
+    pub preview: String,
+    /// Whether links were dropped because there were more than [`crate::config::ScrapeConfig::max_links`].
+    ///
+    /// `false` means `links` is complete, even if it contains exactly `max_links` links.
+    pub truncated: bool,
+}
+
+/// An archive-entry that could not be read or parsed.
+///
+/// Links found in the entry before the error occurred are still returned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZipEntryWarning {
+    pub entry: String,
+    pub message: String,
+}
+
+/// Where inside the zip-structure a comment was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZipCommentSource {
+    /// The comment of the whole archive
+    ArchiveComment,
+    /// The comment of a single archive-entry
+    EntryComment,
+}
+
+/// The url stored in the links of an archive, either owned by every link
+/// or an [`Arc<str>`] shared between all links with an identical url.
+pub(crate) trait ArchiveUrl: AsRef<str> + From<String> {
+    /// Creates the url, reusing an identical one of the `interned` urls if possible.
+    fn intern(url: &str, interned: &mut HashSet<Arc<str>>) -> Self;
+}
+
+impl ArchiveUrl for String {
+    fn intern(url: &str, _: &mut HashSet<Arc<str>>) -> Self {
+        url.to_string()
+    }
+}
+
+impl ArchiveUrl for Arc<str> {
+    fn intern(url: &str, interned: &mut HashSet<Arc<str>>) -> Self {
+        if let Some(existing) = interned.get(url) {
+            return existing.clone();
+        }
+        let url: Arc<str> = Arc::from(url);
+        interned.insert(url.clone());
+        url
+    }
+}
+
+/// Collects the links of an archive.
+pub(crate) struct LinkCollector<T> {
+    pub(crate) links: Vec<T>,
+    /// The sequence of the next link, which keeps counting when links are dropped again by [`Self::retain_accepted`]
+    sequence: usize,
+    /// Only filled if the links store their urls as [`Arc<str>`]
+    interned: HashSet<Arc<str>>,
+    strip_invisible_chars: bool,
+    /// Whether an entry ends at its first malformed xml-event instead of failing, which is only needed
+    /// by [`ScrapeConfig::strict`] and by [`ScrapeConfig::skip_corrupt_entries`] to record it as a warning
+    lenient_xml: bool,
+    preview: String,
+    preview_length: usize,
+    /// The number of images whose QR-codes may still be decoded
+    #[cfg(feature = "qr")]
+    qr_code_images: usize,
+    #[cfg(feature = "encoding")]
+    repair_mojibake: bool,
+}
+
+impl<T> LinkCollector<T> {
+    fn new(config: &ScrapeConfig) -> Self {
+        Self {
+            links: vec![],
+            sequence: 0,
+            interned: HashSet::new(),
+            strip_invisible_chars: config.strip_invisible_chars,
+            lenient_xml: !(config.skip_corrupt_entries || config.strict),
+            preview: String::new(),
+            preview_length: config.preview_length,
+            #[cfg(feature = "qr")]
+            qr_code_images: config.qr_code_images,
+            #[cfg(feature = "encoding")]
+            repair_mojibake: config.repair_mojibake,
+        }
+    }
+
+    /// Prepares a text to be searched for urls, see [`ScrapeConfig::strip_invisible_chars`].
+    pub(crate) fn clean<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if self.strip_invisible_chars {
+            strip_invisible_chars(text)
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Returns the [`ScrapeConfig`]-independent sequence of the next link and advances it.
+    pub(crate) fn next_sequence(&mut self) -> usize {
+        self.sequence += 1;
+        self.sequence - 1
+    }
+
+    /// Whether the xml of an entry is read with [`LimitedXmlReader::next_or_end`] leniently.
+    pub(crate) fn lenient_xml(&self) -> bool {
+        self.lenient_xml
+    }
+
+    /// Adds text of the main content to the preview, see [`ScrapeConfig::preview_length`].
+    pub(crate) fn push_preview(&mut self, text: &str) {
+        push_preview(&mut self.preview, text, self.preview_length);
+    }
+
+    /// Decodes the QR-code of an embedded PNG-image and returns the urls of its payload,
+    /// unless [`ScrapeConfig::qr_code_images`] images were decoded already.
+    #[cfg(feature = "qr")]
+    pub(crate) fn qr_code_urls<U: ArchiveUrl>(
+        &mut self,
+        reader: &mut dyn Read,
+    ) -> std::io::Result<Vec<U>> {
+        if self.qr_code_images == 0 {
+            return Ok(vec![]);
+        }
+        self.qr_code_images -= 1;
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let Some(payload) = crate::formats::qr::decode_png(&bytes) else {
+            return Ok(vec![]);
+        };
+        Ok(find_urls(&payload)
+            .iter()
+            .map(|link| self.url(link.as_str()))
+            .collect())
+    }
+
+    /// Returns the url to store in a link.
+    pub(crate) fn url<U: ArchiveUrl>(&mut self, url: &str) -> U {
+        #[cfg(feature = "encoding")]
+        let repaired = self
+            .repair_mojibake
+            .then(|| crate::helpers::repair_mojibake(url))
+            .flatten();
+        #[cfg(feature = "encoding")]
+        let url = repaired.as_deref().unwrap_or(url);
+
+        U::intern(url, &mut self.interned)
+    }
+}
+
+impl<T: AsUrl> LinkCollector<T> {
+    /// Drops the links collected since the first `collected` ones that [`ScrapeConfig::accepts`] rejects,
+    /// so they do not count towards [`ScrapeConfig::max_links`].
+    fn retain_accepted(&mut self, collected: usize, config: &ScrapeConfig) {
+        let mut index = 0;
+        self.links.retain(|link| {
+            index += 1;
+            index <= collected || config.accepts(link.url())
+        });
+    }
+}
+
+/// Link-types that can be created from a url found in a zip-comment.
+pub(crate) trait FromZipComment {
+    type Url: ArchiveUrl;
+
+    /// `entry` is empty for [`ZipCommentSource::ArchiveComment`].
+    fn from_zip_comment(
+        url: Self::Url,
+        source: ZipCommentSource,
+        entry: &str,
+        sequence: usize,
+    ) -> Self;
+}
+
+/// Takes a reader for some zipped bytes and tries to extract some data from it.
+///
+/// With [`ScrapeConfig::skip_corrupt_entries`] an entry failing to open or to be extracted is recorded as a warning
+/// instead of aborting the whole archive, unless [`ScrapeConfig::strict`] is enabled as well.
+/// With [`ScrapeConfig::scrape_zip_comments`] the comments of every entry and of the archive itself are scraped as well.
+/// With [`ScrapeConfig::deterministic`] the entries are visited in the order of their names.
+///
+/// `on_entry` is called with the name, index and total number of every entry before it is visited,
+/// including empty entries and directories.
+#[cfg(feature = "odf")]
+pub(crate) fn unified_unzip_scrape<R, T, E, F>(
+    reader: R,
+    config: &ScrapeConfig,
+    extractor: F,
+    on_entry: &mut dyn FnMut(&str, usize, usize),
+) -> Result<ZipScrapeResult<T>, E>
+where
+    R: Read + Seek,
+    T: FromZipComment + AsUrl,
+    E: Error + From<std::io::Error> + From<ZipError> + From<InputTooLargeError>,
+    F: Fn(&mut dyn Read, &str, &mut LinkCollector<T>) -> Result<(), E>,
+{
+    Ok(unified_unzip_scrape_with_unfiltered(reader, config, false, extractor, on_entry)?.0)
+}
+
+/// Like [`unified_unzip_scrape`], but with `collect_unfiltered` it also returns what [`scrape_unfiltered`] would,
+/// without traversing the archive a second time.
+///
+/// Every entry is buffered before it is passed to the `extractor` then, since both need to read it.
+pub(crate) fn unified_unzip_scrape_with_unfiltered<R, T, E, F>(
+    mut reader: R,
+    config: &ScrapeConfig,
+    collect_unfiltered: bool,
+    extractor: F,
+    on_entry: &mut dyn FnMut(&str, usize, usize),
+) -> Result<(ZipScrapeResult<T>, Vec<String>), E>
+where
+    R: Read + Seek,
+    T: FromZipComment + AsUrl,
+    E: Error + From<std::io::Error> + From<ZipError> + From<InputTooLargeError>,
+    F: Fn(&mut dyn Read, &str, &mut LinkCollector<T>) -> Result<(), E>,
+{
+    config.check_stream_size::<_, E>(&mut reader)?;
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut links: LinkCollector<T> = LinkCollector::new(config);
+    let mut warnings: Vec<ZipEntryWarning> = vec![];
+    let mut unfiltered: Vec<String> = vec![];
+    let mut file_names = archive
+        .file_names()
+        .map(|name| name.to_owned())
+        .collect_vec();
+    if config.deterministic {
+        file_names.sort();
+    }
+    let total = file_names.len();
+    for (index, file_name) in file_names.into_iter().enumerate() {
+        on_entry(&file_name, index, total);
+        let collected = links.links.len();
+        let mut comment = String::new();
+        let result = match archive.by_name(&file_name) {
+            Ok(mut content) => {
+                if config.scrape_zip_comments {
+                    comment = content.comment().to_string();
+                }
+                if content.size() == 0 {
+                    Ok(())
+                } else if collect_unfiltered {
+                    let mut bytes = Vec::new();
+                    match content.read_to_end(&mut bytes) {
+                        Ok(_) => {
+                            // Like `scrape_unfiltered`, entries that are not valid UTF-8 are skipped
+                            if let Ok(text) = std::str::from_utf8(&bytes) {
+                                find_urls(text)
+                                    .iter()
+                                    .for_each(|link| unfiltered.push(link.as_str().to_string()));
+                            }
+                            extractor(&mut bytes.as_slice(), &file_name, &mut links)
+                        }
+                        Err(e) => Err(E::from(e)),
+                    }
+                } else {
+                    extractor(&mut content, &file_name, &mut links)
+                }
+            }
+            Err(e) => Err(E::from(e)),
+        };
+        scrape_comment(
+            &comment,
+            ZipCommentSource::EntryComment,
+            &file_name,
+            &mut links,
+        );
+        match result {
+            Err(e) if config.skip_corrupt_entries && !config.strict => {
+                warnings.push(ZipEntryWarning {
+                    entry: file_name,
+                    message: e.to_string(),
+                })
+            }
+            result => result?,
+        }
+        links.retain_accepted(collected, config);
+        if config.max_links.is_some_and(|max| links.links.len() > max) {
+            break;
+        }
+    }
+    if config.scrape_zip_comments {
+        let comment = String::from_utf8_lossy(archive.comment()).to_string();
+        let collected = links.links.len();
+        scrape_comment(&comment, ZipCommentSource::ArchiveComment, "", &mut links);
+        links.retain_accepted(collected, config);
+    }
+    let truncated = config.max_links.is_some_and(|max| links.links.len() > max);
+    if let Some(max) = config.max_links {
+        links.links.truncate(max);
+    }
+    Ok((
+        ZipScrapeResult {
+            preview: links.preview.trim_end().to_string(),
+            links: links.links,
+            warnings,
+            truncated,
+        },
+        unfiltered,
+    ))
+}
+
+fn scrape_comment<T>(
+    comment: &str,
+    source: ZipCommentSource,
+    entry: &str,
+    collector: &mut LinkCollector<T>,
+) where
+    T: FromZipComment,
+{
+    find_urls(comment).iter().for_each(|link| {
+        let url = collector.url(link.as_str());
+        let sequence = collector.next_sequence();
+        collector
+            .links
+            .push(T::from_zip_comment(url, source, entry, sequence))
+    });
 }
@@ -0,0 +1,286 @@
+use crate::formats::includes::resolve_includes;
+pub use crate::formats::includes::IncludedLink;
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes the `@import`-rules and `url()`-references of a stylesheet.
+///
+/// Urls are returned as written, usually relative to the stylesheet. Comments and `data:`-urls are skipped.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<CssLink>, CssScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = vec![];
+    reader.read_to_end(&mut content)?;
+    Ok(scrape_from_string(String::from_utf8_lossy(&content)))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<CssLink>, CssScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<CssLink>, CssScrapingError>);
+
+/// Like [`scrape`] but takes the stylesheet as a string.
+pub fn scrape_from_string<S>(content: S) -> Vec<CssLink>
+where
+    S: AsRef<str>,
+{
+    let css = strip_comments(content.as_ref());
+    // Lowercasing ASCII keeps the byte-offsets intact
+    let lowercase = css.to_ascii_lowercase();
+    let mut links = vec![];
+    let mut position = 0;
+    while position < css.len() {
+        let import = lowercase[position..].find("@import").map(|i| position + i);
+        let url = lowercase[position..].find("url(").map(|i| position + i);
+        let (start, kind) = match (import, url) {
+            (Some(import), url) if url.is_none_or(|url| import < url) => {
+                (import, CssLinkKind::Import)
+            }
+            (_, Some(url)) => (url, CssLinkKind::Url),
+            _ => break,
+        };
+
+        let reference = match kind {
+            CssLinkKind::Import => {
+                let rest = &css[start + "@import".len()..];
+                let skipped = rest.len() - rest.trim_start().len();
+                let rest = &rest[skipped..];
+                let value = if rest
+                    .get(..4)
+                    .is_some_and(|it| it.eq_ignore_ascii_case("url("))
+                {
+                    url_function(&rest[4..]).map(|(value, length)| (value, length + 4))
+                } else {
+                    quoted(rest)
+                };
+                value.map(|(value, length)| (value, "@import".len() + skipped + length))
+            }
+            // `url(` as the end of another function-name, like `myurl(`, is no reference
+            CssLinkKind::Url
+                if css[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '-' || c == '_') =>
+            {
+                None
+            }
+            CssLinkKind::Url => url_function(&css[start + 4..])
+                .map(|(value, length)| (value, "url(".len() + length)),
+        };
+        let Some((value, length)) = reference else {
+            position = start + 1;
+            continue;
+        };
+        position = start + length;
+        let value = value.trim();
+        if !value.is_empty()
+            && !value
+                .get(..5)
+                .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+        {
+            links.push(CssLink {
+                url: value.to_string(),
+                kind,
+            });
+        }
+    }
+    links
+}
+
+/// Like [`scrape`], but also follows the `@import`-rules up to `max_depth` stylesheets deep.
+///
+/// `fetch` is called with the url of every imported stylesheet and returns its content, or `None` to skip it.
+/// Imports are resolved against the url of the importing stylesheet if that is absolute,
+/// the imports of the scraped stylesheet itself are passed as written.
+/// Every url is fetched at most once, so import-cycles end.
+///
+/// # Example
+/// ```
+/// use link_scraper::formats::css::scrape_with_imports;
+///
+/// let css = r#"@import "https://css.example.com/theme.css";"#;
+/// let links = scrape_with_imports(css.as_bytes(), 4, |url| {
+///     (url == "https://css.example.com/theme.css").then(|| b"a { background: url(bg.png) }".to_vec())
+/// })
+/// .unwrap();
+/// assert_eq!(links[1].link.url, "bg.png");
+/// assert_eq!(links[1].depth, 1);
+/// assert_eq!(links[1].chain, vec!["https://css.example.com/theme.css"]);
+/// ```
+pub fn scrape_with_imports<R, F>(
+    mut reader: R,
+    max_depth: usize,
+    fetch: F,
+) -> Result<Vec<IncludedLink<CssLink>>, CssScrapingError>
+where
+    R: BufRead,
+    F: Fn(&str) -> Option<Vec<u8>>,
+{
+    let mut content = vec![];
+    reader.read_to_end(&mut content)?;
+    resolve_includes(&content, max_depth, &fetch, &|stylesheet| {
+        let links = scrape_from_string(String::from_utf8_lossy(stylesheet));
+        let imports = links
+            .iter()
+            .filter(|link| link.kind == CssLinkKind::Import)
+            .map(|link| link.url.clone())
+            .collect();
+        Ok::<_, CssScrapingError>((links, imports))
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum CssScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssLink {
+    pub url: String,
+    pub kind: CssLinkKind,
+}
+
+impl Display for CssLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for CssLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CssLinkKind {
+    /// The link is the stylesheet of an `@import`-rule <br/>
+    /// Example: `@import url("https://link.example.com/theme.css");`
+    Import,
+    /// The link is any other `url()`-reference, e.g. of a background-image or a font <br/>
+    /// Example: `body { background: url(https://link.example.com/bg.png) }`
+    Url,
+}
+
+/// Replaces `/* comments */` by a space. An unterminated comment reaches to the end.
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        stripped.push(' ');
+        rest = rest[start + 2..]
+            .find("*/")
+            .map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// Parses the argument of an `url(` up to its closing parenthesis,
+/// returning it (without quotes) and the length up to and including the parenthesis.
+fn url_function(rest: &str) -> Option<(&str, usize)> {
+    let skipped = rest.len() - rest.trim_start().len();
+    let (value, length) = match quoted(&rest[skipped..]) {
+        Some((value, length)) => (value, skipped + length),
+        None => {
+            let end = rest.find(')')?;
+            return Some((&rest[..end], end + 1));
+        }
+    };
+    let end = rest[length..].find(')')?;
+    Some((value, length + end + 1))
+}
+
+/// Parses a string in single or double quotes, returning its content and its length including the quotes.
+fn quoted(rest: &str) -> Option<(&str, usize)> {
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let end = rest[1..].find(quote)? + 1;
+    Some((&rest[1..end], end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CSS: &[u8] = include_bytes!("../../test_files/css/css_test.css");
+
+    fn link(url: &str, kind: CssLinkKind) -> CssLink {
+        CssLink {
+            url: url.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn scrape_css_test() {
+        let links = scrape_from_slice(TEST_CSS).unwrap();
+        println!("{:?}", links);
+        use CssLinkKind::{Import, Url};
+        assert_eq!(
+            links,
+            vec![
+                link("https://css.test.com/styles/child.css", Import),
+                link("https://fonts.css.test.com/font.css", Import),
+                link("https://img.css.test.com/bg.png", Url),
+                link("fonts/test.woff2", Url),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_with_imports_test() {
+        let fetch = |url: &str| match url {
+            "https://css.test.com/styles/child.css" => Some(
+                b"@import url(grandchild.css);\n.logo { background-image: url('logo.png') }"
+                    .to_vec(),
+            ),
+            "https://css.test.com/styles/grandchild.css" => {
+                // Cycles are only followed once
+                Some(b"@import 'https://css.test.com/styles/child.css';".to_vec())
+            }
+            _ => None,
+        };
+
+        let links = scrape_with_imports(TEST_CSS, 1, fetch).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .filter(|it| it.depth == 0)
+            .all(|it| it.chain.is_empty()));
+        let child_links = links
+            .iter()
+            .filter(|it| it.depth == 1)
+            .map(|it| it.link.url.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(child_links, vec!["grandchild.css", "logo.png"]);
+        assert!(links
+            .iter()
+            .filter(|it| it.depth == 1)
+            .all(|it| it.chain == vec!["https://css.test.com/styles/child.css"]));
+
+        let links = scrape_with_imports(TEST_CSS, 5, fetch).unwrap();
+        let grandchild_link = links.iter().find(|it| it.depth == 2).unwrap();
+        assert_eq!(
+            grandchild_link.chain,
+            vec![
+                "https://css.test.com/styles/child.css",
+                "https://css.test.com/styles/grandchild.css"
+            ]
+        );
+        assert_eq!(links.iter().map(|it| it.depth).max(), Some(2));
+    }
+
+    #[test]
+    fn url_function_test() {
+        assert_eq!(url_function(" 'a b.png' )x"), Some(("a b.png", 12)));
+        assert_eq!(url_function("a.png)"), Some(("a.png", 6)));
+        assert_eq!(url_function("\"a.png\""), None);
+        assert!(scrape_from_string("a { background: myurl(https://a.com) }").is_empty());
+    }
+}
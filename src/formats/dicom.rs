@@ -0,0 +1,302 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+
+/// Scrapes links from all string-valued elements of a DICOM file's data set (including nested sequences).
+///
+/// Pixel data and everything following it is not inspected.
+///
+/// Reads the whole stream before processing the contents.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<DicomLink>, DicomScrapingError>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.get(PREAMBLE_LENGTH..PREAMBLE_LENGTH + MAGIC.len()) != Some(MAGIC) {
+        return Err(DicomScrapingError::NotADicomError);
+    }
+
+    // The file meta information is always encoded as explicit VR little endian.
+    let mut meta = DataSetReader::new(&bytes[PREAMBLE_LENGTH + MAGIC.len()..], true, true);
+    let mut links = vec![];
+    let mut transfer_syntax = String::new();
+    while meta.peek_group() == Some(META_GROUP) {
+        let element = meta.next_element()?;
+        if element.tag == TRANSFER_SYNTAX_TAG {
+            transfer_syntax = element_text(&element).unwrap_or_default();
+        }
+        collect_links(&element, true, &mut links);
+    }
+
+    let remaining = meta.remaining();
+    let inflated;
+    let (data_set, explicit_vr, little_endian) = match transfer_syntax.as_str() {
+        IMPLICIT_VR_LITTLE_ENDIAN => (remaining, false, true),
+        EXPLICIT_VR_BIG_ENDIAN => (remaining, true, false),
+        DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN => {
+            let mut buffer = Vec::new();
+            flate2::read::DeflateDecoder::new(remaining).read_to_end(&mut buffer)?;
+            inflated = buffer;
+            (inflated.as_slice(), true, true)
+        }
+        // Every other transfer syntax only changes how the pixel data is encoded.
+        _ => (remaining, true, true),
+    };
+
+    let mut reader = DataSetReader::new(data_set, explicit_vr, little_endian);
+    while reader.peek_group().is_some() {
+        let element = reader.next_element()?;
+        if element.tag == PIXEL_DATA_TAG {
+            break;
+        }
+        collect_links(&element, explicit_vr, &mut links);
+    }
+
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<DicomLink>, DicomScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<DicomLink>, DicomScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum DicomScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Given file was not a DICOM-file.")]
+    NotADicomError,
+    #[error("The data set ended in the middle of an element.")]
+    UnexpectedEndError,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DicomLink {
+    pub url: String,
+    /// The tag of the element containing the link. Formatted as `(gggg,eeee)`
+    pub tag: String,
+}
+
+impl Display for DicomLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for DicomLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+const PREAMBLE_LENGTH: usize = 128;
+const MAGIC: &[u8] = b"DICM";
+const META_GROUP: u16 = 0x0002;
+const ITEM_GROUP: u16 = 0xFFFE;
+const UNDEFINED_LENGTH: u32 = 0xFFFF_FFFF;
+const TRANSFER_SYNTAX_TAG: (u16, u16) = (0x0002, 0x0010);
+const PIXEL_DATA_TAG: (u16, u16) = (0x7FE0, 0x0010);
+
+const IMPLICIT_VR_LITTLE_ENDIAN: &str = "1.2.840.10008.1.2";
+const EXPLICIT_VR_BIG_ENDIAN: &str = "1.2.840.10008.1.2.2";
+const DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN: &str = "1.2.840.10008.1.2.1.99";
+
+/// Value representations that contain text
+const STRING_VRS: [&[u8; 2]; 17] = [
+    b"AE", b"AS", b"CS", b"DA", b"DS", b"DT", b"IS", b"LO", b"LT", b"PN", b"SH", b"ST", b"TM",
+    b"UC", b"UI", b"UR", b"UT",
+];
+
+/// Value representations that are followed by two reserved bytes and a 32-bit length
+const LONG_VRS: [&[u8; 2]; 13] = [
+    b"OB", b"OD", b"OF", b"OL", b"OV", b"OW", b"SQ", b"SV", b"UC", b"UN", b"UR", b"UT", b"UV",
+];
+
+struct DataElement<'a> {
+    tag: (u16, u16),
+    vr: Option<[u8; 2]>,
+    /// [`None`] for sequences, items and delimiters, whose content is read as regular elements.
+    value: Option<&'a [u8]>,
+}
+
+/// Reads data elements one after another.
+///
+/// Sequences and items are not skipped but entered, so nested elements are returned as if they were top-level.
+struct DataSetReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    explicit_vr: bool,
+    little_endian: bool,
+}
+
+impl<'a> DataSetReader<'a> {
+    fn new(bytes: &'a [u8], explicit_vr: bool, little_endian: bool) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            explicit_vr,
+            little_endian,
+        }
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.position..]
+    }
+
+    fn peek_group(&self) -> Option<u16> {
+        let bytes = self.bytes.get(self.position..self.position + 2)?;
+        Some(self.u16_from([bytes[0], bytes[1]]))
+    }
+
+    fn next_element(&mut self) -> Result<DataElement<'a>, DicomScrapingError> {
+        let tag = (self.read_u16()?, self.read_u16()?);
+        if tag.0 == ITEM_GROUP {
+            self.read_u32()?;
+            return Ok(DataElement {
+                tag,
+                vr: None,
+                value: None,
+            });
+        }
+
+        let vr = if self.explicit_vr {
+            let vr = self.read_bytes(2)?;
+            Some([vr[0], vr[1]])
+        } else {
+            None
+        };
+        let length = match &vr {
+            Some(vr) if LONG_VRS.contains(&vr) => {
+                self.read_bytes(2)?;
+                self.read_u32()?
+            }
+            Some(_) => self.read_u16()? as u32,
+            None => self.read_u32()?,
+        };
+
+        let is_sequence =
+            vr.as_ref() == Some(b"SQ") || (vr.is_none() && self.starts_with_item(length));
+        if length == UNDEFINED_LENGTH || is_sequence {
+            return Ok(DataElement {
+                tag,
+                vr,
+                value: None,
+            });
+        }
+
+        Ok(DataElement {
+            tag,
+            vr,
+            value: Some(self.read_bytes(length as usize)?),
+        })
+    }
+
+    /// In implicit VR data sets, sequences can only be recognized by their first item.
+    fn starts_with_item(&self, length: u32) -> bool {
+        length >= 8
+            && self
+                .bytes
+                .get(self.position..self.position + 4)
+                .is_some_and(|bytes| {
+                    self.u16_from([bytes[0], bytes[1]]) == ITEM_GROUP
+                        && self.u16_from([bytes[2], bytes[3]]) == 0xE000
+                })
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], DicomScrapingError> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + length)
+            .ok_or(DicomScrapingError::UnexpectedEndError)?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, DicomScrapingError> {
+        let bytes = self.read_bytes(2)?;
+        Ok(self.u16_from([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DicomScrapingError> {
+        let bytes = self.read_bytes(4)?;
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn u16_from(&self, bytes: [u8; 2]) -> u16 {
+        if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        }
+    }
+}
+
+/// Returns the textual value of an element. Values are padded with spaces or nulls to an even length.
+fn element_text(element: &DataElement) -> Option<String> {
+    let value = element.value?;
+    Some(
+        String::from_utf8_lossy(value)
+            .trim_end_matches(['\0', ' '])
+            .to_string(),
+    )
+}
+
+/// Without explicit VRs there is no way to tell strings from binary data,
+/// so every value that is valid UTF-8 is scanned.
+fn collect_links(element: &DataElement, explicit_vr: bool, links: &mut Vec<DicomLink>) {
+    let is_text = match &element.vr {
+        Some(vr) if explicit_vr => STRING_VRS.contains(&vr),
+        _ => element
+            .value
+            .is_some_and(|value| std::str::from_utf8(value).is_ok()),
+    };
+    if !is_text {
+        return;
+    }
+    let Some(text) = element_text(element) else {
+        return;
+    };
+
+    find_urls(&text).iter().for_each(|link| {
+        links.push(DicomLink {
+            url: link.as_str().to_string(),
+            tag: format!("({:04X},{:04X})", element.tag.0, element.tag.1),
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DICOM: &[u8] = include_bytes!("../../test_files/dicom/dicom_test.dcm");
+    const NOT_A_DICOM: &[u8] = include_bytes!("../../test_files/xml/xml_test.xml");
+
+    #[test]
+    fn scrape_dicom_test() {
+        let links = scrape_from_slice(TEST_DICOM).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&DicomLink {
+            url: "https://dicom.test.com/studies/1".to_string(),
+            tag: "(0008,1190)".to_string()
+        }));
+        assert!(links.contains(&DicomLink {
+            url: "https://nested.dicom.test.com".to_string(),
+            tag: "(0018,1030)".to_string()
+        }));
+    }
+
+    #[test]
+    fn fail_on_non_dicom_test() {
+        assert!(matches!(
+            scrape_from_slice(NOT_A_DICOM),
+            Err(DicomScrapingError::NotADicomError)
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+use crate::any_format_scraper::{LinkScrapingError, INFER};
+use crate::config::ScrapeConfig;
+use crate::helpers::AsUrl;
+use std::io::{Cursor, Read};
+
+/// Scrapes an object embedded into an OOXML- or ODF-document through the any-format scraper.
+///
+/// OLE-objects are unwrapped first, if they contain an OOXML-package (`Package`-stream).
+/// Objects of unknown or unsupported types are returned without any links.
+/// Objects embedded into the object itself are scraped until [`ScrapeConfig::embedded_object_depth`] is exhausted.
+pub(crate) fn scrape_embedded_object<R>(
+    mut reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<String>, LinkScrapingError>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let bytes = unwrap_ole_package(bytes)?;
+    if INFER.get(&bytes).is_none() {
+        return Ok(vec![]);
+    }
+
+    let nested_config = ScrapeConfig {
+        embedded_object_depth: config.embedded_object_depth.saturating_sub(1),
+        ..config.clone()
+    };
+    match crate::any_format_scraper::scrape_with_config(Cursor::new(bytes), &nested_config) {
+        Ok(links) => Ok(links.iter().map(|link| link.url().to_string()).collect()),
+        Err(
+            LinkScrapingError::FileTypeNotImplemented(_)
+            | LinkScrapingError::FeatureNotEnabledError(_),
+        ) => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+const CFB_SIGNATURE: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Office stores embedded OOXML-documents in the `Package`-stream of an OLE compound file.
+/// Other compound files are returned as they are.
+fn unwrap_ole_package(bytes: Vec<u8>) -> Result<Vec<u8>, LinkScrapingError> {
+    if !bytes.starts_with(CFB_SIGNATURE) {
+        return Ok(bytes);
+    }
+    let mut compound_file = cfb::CompoundFile::open(Cursor::new(&bytes))?;
+    if !compound_file.is_stream("/Package") {
+        return Ok(bytes);
+    }
+    let mut package = Vec::new();
+    compound_file
+        .open_stream("/Package")?
+        .read_to_end(&mut package)?;
+    Ok(package)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const TEST_XLSX: &[u8] = include_bytes!("../../test_files/ooxml/xlsx_test.xlsx");
+
+    #[test]
+    fn unwrap_ole_package_test() {
+        let mut compound_file = cfb::CompoundFile::create(Cursor::new(vec![])).unwrap();
+        compound_file
+            .create_stream("/Package")
+            .unwrap()
+            .write_all(TEST_XLSX)
+            .unwrap();
+        compound_file.flush().unwrap();
+        let ole_object = compound_file.into_inner().into_inner();
+
+        assert_eq!(unwrap_ole_package(ole_object.clone()).unwrap(), TEST_XLSX);
+        let links =
+            scrape_embedded_object(ole_object.as_slice(), &ScrapeConfig::default()).unwrap();
+        assert!(!links.is_empty());
+        assert_eq!(
+            links,
+            scrape_embedded_object(TEST_XLSX, &ScrapeConfig::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn skip_unknown_object_test() {
+        let links = scrape_embedded_object(&[0u8, 1, 2, 3][..], &ScrapeConfig::default()).unwrap();
+        assert!(links.is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::links::HasLocation;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::ops::Range;
+use thiserror::Error;
+
+/// Scrapes links from fixed-width text, whose columns sit at the given character-ranges of every line.
+///
+/// Each column is scraped on its own, so text of adjacent columns cannot bleed into a url.
+/// Columns reaching past the end of a line are cut off at the end of the line.
+pub fn scrape<R>(
+    reader: R,
+    columns: &[Range<usize>],
+) -> Result<Vec<FixedWidthLink>, FixedWidthScrapingError>
+where
+    R: BufRead,
+{
+    let mut links = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let chars: Vec<char> = line.chars().collect();
+        for (column_index, column) in columns.iter().enumerate() {
+            let start = column.start.min(chars.len());
+            let end = column.end.clamp(start, chars.len());
+            let content: String = chars[start..end].iter().collect();
+            find_urls(&content).iter().for_each(|link| {
+                links.push(FixedWidthLink {
+                    url: link.as_str().to_string(),
+                    line: index + 1,
+                    column_index,
+                })
+            });
+        }
+    }
+    Ok(links)
+}
+
+/// Convenience function, that uses [`scrape`] to scrape links from a buffer.
+pub fn scrape_from_slice<T>(
+    buffer: T,
+    columns: &[Range<usize>],
+) -> Result<Vec<FixedWidthLink>, FixedWidthScrapingError>
+where
+    T: AsRef<[u8]>,
+{
+    scrape(buffer.as_ref(), columns)
+}
+
+/// Convenience function, that reads a file and uses [`scrape`] to scrape links from its content.
+pub fn scrape_from_file<P>(
+    path: P,
+    columns: &[Range<usize>],
+) -> Result<Vec<FixedWidthLink>, FixedWidthScrapingError>
+where
+    P: AsRef<std::path::Path>,
+{
+    scrape(std::io::BufReader::new(std::fs::File::open(path)?), columns)
+}
+
+#[derive(Error, Debug)]
+pub enum FixedWidthScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedWidthLink {
+    pub url: String,
+    /// 1-based line the link is on
+    pub line: usize,
+    /// Index of the column inside the given column-ranges
+    pub column_index: usize,
+}
+
+impl Display for FixedWidthLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for FixedWidthLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl HasLocation for FixedWidthLink {
+    type Location = (usize, usize);
+
+    fn location(&self) -> Self::Location {
+        (self.line, self.column_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_FIXED_WIDTH: &[u8] =
+        include_bytes!("../../test_files/fixed_width/fixed_width_test.txt");
+    const COLUMNS: [Range<usize>; 2] = [0..12, 12..44];
+
+    #[test]
+    fn scrape_fixed_width_test() {
+        let links = scrape_from_slice(TEST_FIXED_WIDTH, &COLUMNS).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                FixedWidthLink {
+                    url: "https://first.test.com".to_string(),
+                    line: 2,
+                    column_index: 1
+                },
+                FixedWidthLink {
+                    url: "https://second.test.com/path".to_string(),
+                    line: 3,
+                    column_index: 1
+                },
+                FixedWidthLink {
+                    url: "https://short.test.com".to_string(),
+                    line: 5,
+                    column_index: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_do_not_bleed_test() {
+        let links = scrape_from_slice(
+            "ACC-0001https://x.test.com/pathCLOSED",
+            &[0..8, 8..31, 31..37],
+        )
+        .unwrap();
+        assert_eq!(
+            links,
+            vec![FixedWidthLink {
+                url: "https://x.test.com/path".to_string(),
+                line: 1,
+                column_index: 1
+            }]
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Seek};
+use thiserror::Error;
+use ttf_parser::name::{Name, Table};
+use ttf_parser::{PlatformId, RawFace, Tag};
+
+/// The name-ids whose records hold an url: `11` vendor url, `12` designer url and `14` license url.
+pub const URL_NAME_IDS: [u16; 3] = [11, 12, 14];
+
+/// Scrapes links from the `name`-table of TrueType- and OpenType-fonts, font collections, WOFF and WOFF2.
+///
+/// The records listed in [`URL_NAME_IDS`] are returned as a whole if they do not contain an url with a scheme,
+/// all other records (e.g. `0` copyright notice or `13` license description) are searched for urls.
+/// A record repeated for several platforms or languages is only returned once.
+///
+/// Reads the whole stream before processing the contents.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<FontLink>, FontScrapingError>
+where
+    R: Read + Seek,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let mut links: Vec<FontLink> = vec![];
+    for data in name_tables(&bytes)? {
+        let table = Table::parse(&data)
+            .ok_or_else(|| FontScrapingError::InvalidFontError("Invalid name-table".to_string()))?;
+        for name in table.names {
+            let Some(value) = decode_name(&name) else {
+                continue;
+            };
+            let mut urls = find_urls(&value)
+                .iter()
+                .map(|link| link.as_str().to_string())
+                .collect::<Vec<_>>();
+            if urls.is_empty() && URL_NAME_IDS.contains(&name.name_id) && !value.trim().is_empty() {
+                urls.push(value.trim().to_string());
+            }
+            for url in urls {
+                let link = FontLink {
+                    url,
+                    name_id: name.name_id,
+                };
+                if !links.contains(&link) {
+                    links.push(link);
+                }
+            }
+        }
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<FontLink>, FontScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<FontLink>, FontScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum FontScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid font: {0}")]
+    InvalidFontError(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontLink {
+    pub url: String,
+    /// The id of the name-record containing the link, e.g. `11` for the vendor url. See [`URL_NAME_IDS`].
+    pub name_id: u16,
+}
+
+impl Display for FontLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for FontLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+const NAME_TAG: &[u8; 4] = b"name";
+
+/// Returns the (decompressed) `name`-tables of all fonts in the file.
+fn name_tables(bytes: &[u8]) -> Result<Vec<Vec<u8>>, FontScrapingError> {
+    match bytes.get(..4) {
+        Some(b"wOFF") => woff_name_tables(bytes),
+        Some(b"wOF2") => woff2_name_tables(bytes),
+        _ => {
+            let count = ttf_parser::fonts_in_collection(bytes).unwrap_or(1);
+            let mut tables = vec![];
+            for index in 0..count {
+                let face = RawFace::parse(bytes, index)
+                    .map_err(|e| FontScrapingError::InvalidFontError(e.to_string()))?;
+                if let Some(table) = face.table(Tag::from_bytes(NAME_TAG)) {
+                    tables.push(table.to_vec());
+                }
+            }
+            Ok(tables)
+        }
+    }
+}
+
+/// WOFF compresses every table on its own with zlib, unless that would not make it smaller.
+fn woff_name_tables(bytes: &[u8]) -> Result<Vec<Vec<u8>>, FontScrapingError> {
+    let mut header = FontReader::new(bytes, 12);
+    let table_count = header.u16()?;
+    let mut directory = FontReader::new(bytes, 44);
+    let mut tables = vec![];
+    for _ in 0..table_count {
+        let tag = directory.bytes(4)?;
+        let offset = directory.u32()? as usize;
+        let compressed_length = directory.u32()? as usize;
+        let original_length = directory.u32()? as usize;
+        directory.bytes(4)?;
+        if tag != NAME_TAG {
+            continue;
+        }
+
+        let data = FontReader::new(bytes, offset).bytes(compressed_length)?;
+        if compressed_length < original_length {
+            let mut table = Vec::with_capacity(original_length);
+            flate2::read::ZlibDecoder::new(data)
+                .take(original_length as u64)
+                .read_to_end(&mut table)?;
+            tables.push(table);
+        } else {
+            tables.push(data.to_vec());
+        }
+    }
+    Ok(tables)
+}
+
+/// The tags WOFF2 refers to by their index in the table directory.
+const WOFF2_KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+/// WOFF2 compresses all tables together into a single brotli-stream following the table directory.
+fn woff2_name_tables(bytes: &[u8]) -> Result<Vec<Vec<u8>>, FontScrapingError> {
+    let mut header = FontReader::new(bytes, 4);
+    let flavor = header.bytes(4)?;
+    header.u32()?;
+    let table_count = header.u16()?;
+    header.bytes(6)?;
+    let compressed_length = header.u32()? as usize;
+
+    let mut directory = FontReader::new(bytes, 48);
+    // Start and length of every `name`-table inside the decompressed stream
+    let mut names: Vec<(usize, usize)> = vec![];
+    let mut stream_length = 0;
+    for _ in 0..table_count {
+        let flags = directory.u8()?;
+        let tag: &[u8] = match flags & 0x3f {
+            0x3f => directory.bytes(4)?,
+            index => WOFF2_KNOWN_TAGS[index as usize],
+        };
+        let transform = flags >> 6;
+        let mut length = directory.base_128()? as usize;
+        // `glyf` and `loca` are transformed unless the transform-version is 3, all other tables only if it is not 0
+        let is_transformed = if tag == b"glyf" || tag == b"loca" {
+            transform != 3
+        } else {
+            transform != 0
+        };
+        if is_transformed {
+            length = directory.base_128()? as usize;
+        }
+        if tag == NAME_TAG {
+            names.push((stream_length, length));
+        }
+        stream_length += length;
+    }
+    if flavor == b"ttcf" {
+        skip_woff2_collection_directory(&mut directory)?;
+    }
+
+    let compressed = directory.bytes(compressed_length)?;
+    let mut stream = Vec::with_capacity(stream_length);
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        .take(stream_length as u64)
+        .read_to_end(&mut stream)?;
+    names
+        .into_iter()
+        .map(|(start, length)| {
+            stream
+                .get(start..start + length)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| {
+                    FontScrapingError::InvalidFontError(
+                        "The compressed tables are truncated".to_string(),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// The fonts of a collection only refer to the tables of the table directory, so their directory is just skipped.
+fn skip_woff2_collection_directory(directory: &mut FontReader) -> Result<(), FontScrapingError> {
+    directory.u32()?;
+    for _ in 0..directory.u255()? {
+        let table_count = directory.u255()?;
+        directory.u32()?;
+        for _ in 0..table_count {
+            directory.u255()?;
+        }
+    }
+    Ok(())
+}
+
+/// Decodes the UTF-16 records of the Unicode- and Windows-platform and the ASCII-part of Macintosh-records.
+fn decode_name(name: &Name) -> Option<String> {
+    if name.is_unicode() {
+        name.to_string()
+    } else if name.platform_id == PlatformId::Macintosh && name.name.is_ascii() {
+        Some(String::from_utf8_lossy(name.name).to_string())
+    } else {
+        None
+    }
+}
+
+/// Reads the big-endian values of a font-file.
+struct FontReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> FontReader<'a> {
+    fn new(bytes: &'a [u8], position: usize) -> Self {
+        Self { bytes, position }
+    }
+
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], FontScrapingError> {
+        let bytes = self
+            .position
+            .checked_add(length)
+            .and_then(|end| self.bytes.get(self.position..end))
+            .ok_or_else(|| {
+                FontScrapingError::InvalidFontError("Unexpected end of file".to_string())
+            })?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, FontScrapingError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FontScrapingError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, FontScrapingError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// WOFF2's `UIntBase128`: up to five bytes of seven bits each, the highest bit marks that more bytes follow.
+    fn base_128(&mut self) -> Result<u32, FontScrapingError> {
+        let mut value: u32 = 0;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            if (i == 0 && byte == 0x80) || value & 0xfe00_0000 != 0 {
+                return Err(FontScrapingError::InvalidFontError(
+                    "Invalid UIntBase128".to_string(),
+                ));
+            }
+            value = (value << 7) | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(FontScrapingError::InvalidFontError(
+            "Invalid UIntBase128".to_string(),
+        ))
+    }
+
+    /// WOFF2's `255UInt16`: a single byte for small values, the codes 253 to 255 announce larger ones.
+    fn u255(&mut self) -> Result<u16, FontScrapingError> {
+        Ok(match self.u8()? {
+            253 => self.u16()?,
+            254 => self.u8()? as u16 + 506,
+            255 => self.u8()? as u16 + 253,
+            code => code as u16,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_TTF: &[u8] = include_bytes!("../../test_files/font/font_test.ttf");
+    const TEST_WOFF: &[u8] = include_bytes!("../../test_files/font/font_test.woff");
+    const TEST_WOFF2: &[u8] = include_bytes!("../../test_files/font/font_test.woff2");
+
+    #[test]
+    fn scrape_font_test() {
+        for font in [TEST_TTF, TEST_WOFF, TEST_WOFF2] {
+            let links = scrape_from_slice(font).unwrap();
+            println!("{:?}", links);
+            // The records of the Macintosh-platform come first
+            assert_eq!(
+                links,
+                vec![
+                    FontLink {
+                        url: "https://copyright.font.test.com".to_string(),
+                        name_id: 0,
+                    },
+                    FontLink {
+                        url: "license.font.test.com/ofl".to_string(),
+                        name_id: 14,
+                    },
+                    FontLink {
+                        url: "https://vendor.font.test.com".to_string(),
+                        name_id: 11,
+                    },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_font_test() {
+        assert!(matches!(
+            scrape_from_slice(b"wOF2\x00\x01"),
+            Err(FontScrapingError::InvalidFontError(_))
+        ));
+    }
+}
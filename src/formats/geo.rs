@@ -0,0 +1,244 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+use xml::reader::XmlEvent;
+use xml::EventReader;
+
+/// Scrapes the links of a GPX- or KML-file and attributes them to the feature they belong to.
+///
+/// Elements are matched by their local name, so both formats (and all of their versions) are handled alike.
+pub fn scrape<R>(reader: R) -> Result<Vec<GeoLink>, GeoScrapingError>
+where
+    R: Read,
+{
+    let mut collector: Vec<GeoLink> = vec![];
+    let mut features: Vec<Feature> = vec![];
+    // Local name and text of every open element
+    let mut elements: Vec<(String, String)> = vec![];
+    let mut parser = EventReader::new(reader);
+    loop {
+        match parser.next()? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                if FEATURE_ELEMENTS.contains(&name.local_name.as_str()) {
+                    features.push(Feature {
+                        name: None,
+                        first_link: collector.len(),
+                    });
+                }
+                if name.local_name == "link" {
+                    let href = attributes
+                        .iter()
+                        .find(|attribute| attribute.name.local_name == "href");
+                    if let Some(href) = href.filter(|href| !href.value.trim().is_empty()) {
+                        collector.push(GeoLink::new(href.value.trim(), GeoLinkKind::WaypointLink));
+                    }
+                }
+                elements.push((name.local_name, String::new()));
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                if let Some((_, content)) = elements.last_mut() {
+                    content.push_str(&text);
+                }
+            }
+            XmlEvent::EndElement { .. } => {
+                let Some((element, text)) = elements.pop() else {
+                    continue;
+                };
+                let parent = elements.last().map(|(name, _)| name.as_str());
+                let grandparent = elements
+                    .len()
+                    .checked_sub(2)
+                    .map(|index| elements[index].0.as_str());
+                let text = text.trim();
+
+                match (element.as_str(), parent) {
+                    ("name", _) if !text.is_empty() => {
+                        if let Some(feature) = features.last_mut() {
+                            feature.name.get_or_insert_with(|| text.to_string());
+                        }
+                    }
+                    ("href", Some("Icon")) if !text.is_empty() => {
+                        collector.push(GeoLink::new(text, GeoLinkKind::Icon))
+                    }
+                    ("href", Some("Link" | "Url"))
+                        if grandparent == Some("NetworkLink") && !text.is_empty() =>
+                    {
+                        collector.push(GeoLink::new(text, GeoLinkKind::NetworkLink))
+                    }
+                    // GPX 1.0 has the link of a waypoint as text
+                    ("url", _) if !text.is_empty() => {
+                        collector.push(GeoLink::new(text, GeoLinkKind::WaypointLink))
+                    }
+                    ("description" | "desc" | "cmt", _) => {
+                        find_urls(text).iter().for_each(|link| {
+                            collector.push(GeoLink::new(
+                                link.as_str(),
+                                GeoLinkKind::PlacemarkDescription,
+                            ))
+                        })
+                    }
+                    (element, _) if FEATURE_ELEMENTS.contains(&element) => {
+                        if let Some(feature) = features.pop() {
+                            feature.attribute(&mut collector);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+    Ok(collector)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<GeoLink>, GeoScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<GeoLink>, GeoScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum GeoScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    XmlReaderError(#[from] xml::reader::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoLink {
+    pub url: String,
+    /// The `<name>` of the innermost named feature (placemark, folder, waypoint, track, ...) containing the link
+    pub feature_name: Option<String>,
+    pub kind: GeoLinkKind,
+}
+
+impl GeoLink {
+    fn new(url: &str, kind: GeoLinkKind) -> Self {
+        GeoLink {
+            url: url.to_string(),
+            feature_name: None,
+            kind,
+        }
+    }
+}
+
+impl Display for GeoLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for GeoLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeoLinkKind {
+    /// The link of a GPX waypoint, route or track, or the `<atom:link>` of a KML feature <br/>
+    /// Example: `<wpt><link href="https://link.example.com"/></wpt>`
+    WaypointLink,
+    /// The link is inside the description or comment of a KML placemark or GPX waypoint <br/>
+    /// Example: `<description>See https://link.example.com</description>`
+    PlacemarkDescription,
+    /// A KML document that is loaded into this one <br/>
+    /// Example: `<NetworkLink><Link><href>https://link.example.com/overlay.kml</href></Link></NetworkLink>`
+    NetworkLink,
+    /// The image of a KML overlay or icon style <br/>
+    /// Example: `<GroundOverlay><Icon><href>https://link.example.com/map.png</href></Icon></GroundOverlay>`
+    Icon,
+}
+
+/// KML-features and GPX-elements that have a `<name>`
+const FEATURE_ELEMENTS: [&str; 13] = [
+    "Document",
+    "Folder",
+    "Placemark",
+    "NetworkLink",
+    "GroundOverlay",
+    "ScreenOverlay",
+    "PhotoOverlay",
+    "metadata",
+    "wpt",
+    "rte",
+    "rtept",
+    "trk",
+    "trkpt",
+];
+
+struct Feature {
+    name: Option<String>,
+    /// Index of the first link found inside this feature
+    first_link: usize,
+}
+
+impl Feature {
+    /// Links of nested features without a name of their own are attributed to this one.
+    fn attribute(self, collector: &mut [GeoLink]) {
+        let Some(name) = self.name else {
+            return;
+        };
+        collector[self.first_link..]
+            .iter_mut()
+            .filter(|link| link.feature_name.is_none())
+            .for_each(|link| link.feature_name = Some(name.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KML: &[u8] = include_bytes!("../../test_files/geo/kml_test.kml");
+    const TEST_GPX: &[u8] = include_bytes!("../../test_files/geo/gpx_test.gpx");
+
+    #[test]
+    fn scrape_kml_test() {
+        let links = scrape_from_slice(TEST_KML).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                GeoLink {
+                    url: "https://description.kml.test.com/summit".to_string(),
+                    feature_name: Some("Summit".to_string()),
+                    kind: GeoLinkKind::PlacemarkDescription
+                },
+                GeoLink {
+                    url: "https://network.kml.test.com/trails.kml".to_string(),
+                    feature_name: Some("Trail Overlay".to_string()),
+                    kind: GeoLinkKind::NetworkLink
+                },
+                GeoLink {
+                    url: "https://overlay.kml.test.com/map.png".to_string(),
+                    feature_name: Some("Hiking Map".to_string()),
+                    kind: GeoLinkKind::Icon
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_gpx_test() {
+        let links = scrape_from_slice(TEST_GPX).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&GeoLink {
+            url: "https://waypoint.gpx.test.com/hut".to_string(),
+            feature_name: Some("Mountain Hut".to_string()),
+            kind: GeoLinkKind::WaypointLink
+        }));
+        assert!(links.contains(&GeoLink {
+            url: "https://desc.gpx.test.com".to_string(),
+            feature_name: Some("Mountain Hut".to_string()),
+            kind: GeoLinkKind::PlacemarkDescription
+        }));
+        assert!(links.contains(&GeoLink {
+            url: "https://track.gpx.test.com".to_string(),
+            feature_name: Some("Ridge Track".to_string()),
+            kind: GeoLinkKind::WaypointLink
+        }));
+    }
+}
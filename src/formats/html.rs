@@ -0,0 +1,326 @@
+//! A tolerant HTML-tokenizer for HTML that is rarely well-formed XML (e.g. CHM-topics and clipboard fragments).
+//!
+//! Use [`crate::formats::xml`] for XHTML and other well-formed documents.
+
+use crate::helpers::{csp_sources, find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+
+/// Scrapes `href`-, `src`- and `srcset`-values and urls inside the text and comments of a HTML-document.
+///
+/// Every candidate of a `srcset` is returned as its own link, together with its width- or density-descriptor.
+/// The url-sources of a `<meta http-equiv="Content-Security-Policy">` are returned with their directive,
+/// see [`csp_sources`].
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`Read`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<HtmlLink>, HtmlScrapingError>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    Ok(scrape_from_string(String::from_utf8_lossy(&bytes)))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<HtmlLink>, HtmlScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<HtmlLink>, HtmlScrapingError>);
+
+/// Like [`scrape`] but takes the HTML as a string.
+pub fn scrape_from_string<S>(html: S) -> Vec<HtmlLink>
+where
+    S: AsRef<str>,
+{
+    let mut links = vec![];
+    let mut rest = html.as_ref();
+    while let Some(start) = rest.find('<') {
+        links.append(&mut text_links(&rest[..start], HtmlLinkKind::PlainText));
+        let tag = &rest[start..];
+
+        // Comments may contain `>`, their content is treated as text.
+        if let Some(comment) = tag.strip_prefix("<!--") {
+            let end = comment.find("-->").unwrap_or(comment.len());
+            links.append(&mut text_links(&comment[..end], HtmlLinkKind::Comment));
+            rest = comment.get(end + 3..).unwrap_or_default();
+            continue;
+        }
+
+        let end = tag.find('>').unwrap_or(tag.len());
+        let attributes = tag_attributes(&tag[1..end]);
+        links.append(&mut security_policy_links(&attributes));
+        for (name, value) in attributes {
+            let value = value.replace("&amp;", "&");
+            let kind = HtmlLinkKind::Attribute(name.to_ascii_lowercase());
+            if name.eq_ignore_ascii_case("href") || name.eq_ignore_ascii_case("src") {
+                links.push(HtmlLink {
+                    url: value.clone(),
+                    kind,
+                    descriptor: None,
+                    raw_value: Some(value),
+                });
+            } else if name.eq_ignore_ascii_case("srcset") {
+                links.extend(
+                    srcset_candidates(&value)
+                        .into_iter()
+                        .map(|(url, descriptor)| HtmlLink {
+                            url: url.to_string(),
+                            kind: kind.clone(),
+                            descriptor: descriptor.map(str::to_string),
+                            raw_value: Some(value.clone()),
+                        }),
+                );
+            }
+        }
+        rest = tag.get(end + 1..).unwrap_or_default();
+    }
+    links.append(&mut text_links(rest, HtmlLinkKind::PlainText));
+    links
+}
+
+#[derive(Error, Debug)]
+pub enum HtmlScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlLink {
+    pub url: String,
+    pub kind: HtmlLinkKind,
+    /// The width- (`800w`) or pixel-density-descriptor (`2x`) of a `srcset`-candidate
+    pub descriptor: Option<String>,
+    /// The whole value of the attribute the link was found in, e.g. the complete `srcset`.
+    /// `None` for links inside the text or comments
+    pub raw_value: Option<String>,
+}
+
+impl Display for HtmlLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for HtmlLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HtmlLinkKind {
+    /// The link is the value of the attribute with the given (lowercase) name <br/>
+    /// Example: `<img srcset="https://link.example.com/image-2x.png 2x">`
+    Attribute(String),
+    /// The link is a source of the given (lowercase) directive of a content-security-policy <br/>
+    /// Example: `<meta http-equiv="Content-Security-Policy" content="script-src https://link.example.com">`
+    SecurityPolicy(String),
+    /// The link is inside a comment <br/>
+    /// Example: `<!-- https://link.example.com -->`
+    Comment,
+    /// The link is inside the text <br/>
+    /// Example: `<p>See https://link.example.com</p>`
+    PlainText,
+}
+
+/// Collects `href`-, `src`- and `srcset`-values and urls inside the text, in the order they appear.
+#[cfg(any(feature = "chm", feature = "clipboard", feature = "warc"))]
+pub(crate) fn links_in_html(html: &str) -> Vec<String> {
+    scrape_from_string(html)
+        .into_iter()
+        .map(|link| link.url)
+        .collect()
+}
+
+fn text_links(text: &str, kind: HtmlLinkKind) -> Vec<HtmlLink> {
+    find_urls(text)
+        .iter()
+        .map(|link| HtmlLink {
+            url: link.as_str().to_string(),
+            kind: kind.clone(),
+            descriptor: None,
+            raw_value: None,
+        })
+        .collect()
+}
+
+/// Returns the url-sources of a `<meta>`-tag with a `Content-Security-Policy` as its `http-equiv`.
+fn security_policy_links(attributes: &[(&str, &str)]) -> Vec<HtmlLink> {
+    let attribute = |name: &str| {
+        attributes
+            .iter()
+            .find(|(it, _)| it.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.replace("&amp;", "&"))
+    };
+    let is_policy = attribute("http-equiv").is_some_and(|header| {
+        header.eq_ignore_ascii_case("content-security-policy")
+            || header.eq_ignore_ascii_case("content-security-policy-report-only")
+    });
+    let Some(policy) = attribute("content").filter(|_| is_policy) else {
+        return vec![];
+    };
+    csp_sources(&policy)
+        .into_iter()
+        .map(|(directive, source)| HtmlLink {
+            url: source.to_string(),
+            kind: HtmlLinkKind::SecurityPolicy(directive),
+            descriptor: None,
+            raw_value: Some(policy.clone()),
+        })
+        .collect()
+}
+
+/// Splits a `srcset` into its image candidates and their descriptors,
+/// following the [srcset-grammar](https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute).
+///
+/// An url ends at the first whitespace, so urls may contain commas as long as they are not trailing.
+/// A descriptor ends at the next comma outside of parentheses.
+fn srcset_candidates(srcset: &str) -> Vec<(&str, Option<&str>)> {
+    let mut candidates = vec![];
+    let mut rest = srcset;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            return candidates;
+        }
+        let url_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let url = &rest[..url_end];
+        rest = &rest[url_end..];
+
+        // A trailing comma ends the candidate without descriptors
+        if url.ends_with(',') {
+            candidates.push((url.trim_end_matches(','), None));
+            continue;
+        }
+
+        let mut depth = 0usize;
+        let descriptor_end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth = depth.saturating_sub(1),
+                    ',' if depth == 0 => return true,
+                    _ => {}
+                }
+                false
+            })
+            .map_or(rest.len(), |(index, _)| index);
+        let descriptor = rest[..descriptor_end].trim();
+        candidates.push((url, Some(descriptor).filter(|it| !it.is_empty())));
+        rest = &rest[descriptor_end..];
+    }
+}
+
+/// Splits the inside of a tag (without `<` and `>`) into its attributes.
+/// Values may be double-, single- or unquoted.
+fn tag_attributes(tag: &str) -> Vec<(&str, &str)> {
+    let mut attributes = vec![];
+    let is_separator = |c: char| c.is_whitespace() || c == '=' || c == '/';
+    // Skip the tag name
+    let mut rest = tag.trim_start_matches(|c: char| !c.is_whitespace());
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            return attributes;
+        }
+        let name_end = rest.find(is_separator).unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+        let Some(value) = rest.strip_prefix('=') else {
+            if name.is_empty() {
+                // A stray `=`
+                rest = &rest[1..];
+            }
+            continue;
+        };
+
+        let value = value.trim_start();
+        let (value, remaining) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+                (&value[1..end], value.get(end + 1..).unwrap_or_default())
+            }
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+        attributes.push((name, value));
+        rest = remaining;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrape_srcset_test() {
+        let links = scrape_from_string(
+            r#"<img src="https://img.test.com/image.png"
+                    srcset="https://img.test.com/image-2x.png 2x,
+                            https://img.test.com/crop=10,20/image-800.png 800w,
+                            https://img.test.com/image-1600.png 1600w">"#,
+        );
+        println!("{:?}", links);
+        let srcset: Vec<_> = links
+            .iter()
+            .filter(|it| it.kind == HtmlLinkKind::Attribute("srcset".to_string()))
+            .map(|it| (it.url.as_str(), it.descriptor.as_deref()))
+            .collect();
+        assert_eq!(
+            srcset,
+            vec![
+                ("https://img.test.com/image-2x.png", Some("2x")),
+                (
+                    "https://img.test.com/crop=10,20/image-800.png",
+                    Some("800w")
+                ),
+                ("https://img.test.com/image-1600.png", Some("1600w")),
+            ]
+        );
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://img.test.com/image.png"
+                && it.kind == HtmlLinkKind::Attribute("src".to_string())
+                && it.descriptor.is_none()));
+    }
+
+    #[test]
+    fn scrape_security_policy_test() {
+        let links = scrape_from_string(
+            r#"<head><meta http-equiv="Content-Security-Policy"
+                content="default-src 'self' https://cdn.test.com; script-src 'unsafe-inline' https://js.test.com
+                         https://cdn.test.com 'nonce-abc'; img-src data: * *.img.test.com; sandbox allow-scripts">
+                <meta name="description" content="default-src https://no.test.com"></head>"#,
+        );
+        println!("{:?}", links);
+        let sources = crate::helpers::group_by_kind(&links, |link| match &link.kind {
+            HtmlLinkKind::SecurityPolicy(directive) => Some(directive.clone()),
+            _ => None,
+        });
+        let urls = |directive: &str| {
+            sources[&Some(directive.to_string())]
+                .iter()
+                .map(|link| link.url.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sources.len(), 3);
+        assert_eq!(urls("default-src"), ["https://cdn.test.com"]);
+        assert_eq!(
+            urls("script-src"),
+            ["https://js.test.com", "https://cdn.test.com"]
+        );
+        assert_eq!(urls("img-src"), ["*.img.test.com"]);
+    }
+
+    #[test]
+    fn srcset_commas_test() {
+        // Only a comma followed by whitespace or ending the url separates candidates
+        assert_eq!(
+            srcset_candidates("a.png, b.png,c.png 2x,"),
+            vec![("a.png", None), ("b.png,c.png", Some("2x"))]
+        );
+    }
+}
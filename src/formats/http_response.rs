@@ -0,0 +1,267 @@
+use crate::any_format_scraper::{self, LinkScrapingError};
+use crate::config::ScrapeConfig;
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Cursor};
+use thiserror::Error;
+
+/// Scrapes a raw HTTP-response (status-line, headers and body).
+///
+/// The urls of the `Link`-, `Location`-, `Content-Location`- and `Refresh`-headers are returned first,
+/// `Link`-headers may hold several comma-separated `<url>; rel="..."` entries.
+/// The body is scraped by the scraper registered for its `Content-Type`, see [`crate::any_format_scraper`].
+/// Without a `Content-Type` or if it is unknown the type is guessed from the body itself.
+///
+/// Header-values are returned as written, so `Location`s may be relative to the requested url.
+/// Chunked or compressed bodies have to be decoded first.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<HttpLink>, HttpResponseScrapingError>
+where
+    R: BufRead,
+{
+    let mut response = vec![];
+    reader.read_to_end(&mut response)?;
+    let (head, body) = split_response(&response);
+    let head = String::from_utf8_lossy(head);
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+    if !status_line.starts_with("HTTP/") {
+        return Err(HttpResponseScrapingError::InvalidResponseError(
+            status_line.to_string(),
+        ));
+    }
+
+    let mut links = vec![];
+    let mut content_type = None;
+    for (name, value) in lines.filter_map(|line| line.split_once(':')) {
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("Content-Type") {
+            content_type = Some(value.to_string());
+        }
+        let Some(header) = URL_HEADERS
+            .into_iter()
+            .find(|header| header.eq_ignore_ascii_case(name))
+        else {
+            continue;
+        };
+        let header_link = |url: &str, rel: Option<String>| HttpLink {
+            url: url.to_string(),
+            source: HttpLinkSource::Header(header.to_string()),
+            rel,
+        };
+        match header {
+            LINK_HEADER => links.extend(
+                link_header_entries(value)
+                    .into_iter()
+                    .map(|(url, rel)| header_link(url, rel)),
+            ),
+            REFRESH_HEADER => links.extend(refresh_url(value).map(|url| header_link(url, None))),
+            _ if !value.is_empty() => links.push(header_link(value, None)),
+            _ => {}
+        }
+    }
+
+    if !body.is_empty() {
+        links.extend(
+            scrape_body(body, content_type.as_deref())?
+                .iter()
+                .map(|link| HttpLink {
+                    url: link.url().to_string(),
+                    source: HttpLinkSource::Body,
+                    rel: None,
+                }),
+        );
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<HttpLink>, HttpResponseScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<HttpLink>, HttpResponseScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum HttpResponseScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid status-line of HTTP-response: {0}")]
+    InvalidResponseError(String),
+    #[error(transparent)]
+    BodyScrapingError(#[from] LinkScrapingError),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpLink {
+    pub url: String,
+    pub source: HttpLinkSource,
+    /// The `rel`-parameter of a `Link`-header entry, e.g. `next` or `preload`
+    pub rel: Option<String>,
+}
+
+impl Display for HttpLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for HttpLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HttpLinkSource {
+    /// The name of the header containing the link, e.g. `Link` or `Location`
+    Header(String),
+    Body,
+}
+
+const LINK_HEADER: &str = "Link";
+const REFRESH_HEADER: &str = "Refresh";
+const URL_HEADERS: [&str; 4] = [LINK_HEADER, "Location", "Content-Location", REFRESH_HEADER];
+
+/// Splits the response at the first empty line. Bare `\n` line-endings are accepted as well.
+fn split_response(response: &[u8]) -> (&[u8], &[u8]) {
+    let crlf = response.windows(4).position(|window| window == b"\r\n\r\n");
+    let lf = response.windows(2).position(|window| window == b"\n\n");
+    match (crlf, lf) {
+        (Some(crlf), lf) if lf.is_none_or(|lf| lf > crlf) => {
+            (&response[..crlf], &response[crlf + 4..])
+        }
+        (_, Some(lf)) => (&response[..lf], &response[lf + 2..]),
+        _ => (response, &[]),
+    }
+}
+
+/// Parses a `Link`-header like `<https://a.com>; rel="next", <https://b.com>; rel=preload` into urls and `rel`s.
+fn link_header_entries(value: &str) -> Vec<(&str, Option<String>)> {
+    let mut entries = vec![];
+    let mut rest = value;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>').map(|end| start + end) else {
+            break;
+        };
+        let url = rest[start + 1..end].trim();
+        rest = &rest[end + 1..];
+
+        // The parameters reach up to the next comma outside of a quoted string
+        let mut in_quotes = false;
+        let params_end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                c == ',' && !in_quotes
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let rel = rest[..params_end]
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("rel"))
+            .map(|(_, rel)| rel.trim().trim_matches('"').to_string());
+        rest = &rest[params_end..];
+
+        if !url.is_empty() {
+            entries.push((url, rel));
+        }
+    }
+    entries
+}
+
+/// Extracts the url of a `Refresh`-header like `5; url=https://a.com`.
+fn refresh_url(value: &str) -> Option<&str> {
+    let (_, url) = value.split_once(';')?;
+    let url = url.trim();
+    let url = url
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("url="))
+        .map_or(url, |_| &url[4..]);
+    let url = url.trim().trim_matches(['"', '\'']);
+    (!url.is_empty()).then_some(url)
+}
+
+fn scrape_body(
+    body: &[u8],
+    content_type: Option<&str>,
+) -> Result<Vec<any_format_scraper::Link>, LinkScrapingError> {
+    let config = ScrapeConfig::default();
+    let mime_type = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime_type| mime_type.trim().to_ascii_lowercase());
+    if let Some(mime_type) = mime_type {
+        match any_format_scraper::scrape_mime_type(Cursor::new(body), &mime_type, &config) {
+            Err(LinkScrapingError::FileTypeNotImplemented(_)) => {}
+            result => return result,
+        }
+    }
+    any_format_scraper::scrape_with_config(Cursor::new(body), &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_RESPONSE: &[u8] = include_bytes!("../../test_files/http/http_response_test.http");
+
+    fn header_link(url: &str, header: &str, rel: Option<&str>) -> HttpLink {
+        HttpLink {
+            url: url.to_string(),
+            source: HttpLinkSource::Header(header.to_string()),
+            rel: rel.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn scrape_http_response_test() {
+        let links = scrape_from_slice(TEST_RESPONSE).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links[..3],
+            [
+                header_link("https://http.test.com/page/2", "Link", Some("next")),
+                header_link("https://http.test.com/style.css", "Link", Some("preload")),
+                header_link("https://http.test.com/canonical", "Content-Location", None),
+            ]
+        );
+        let body_links = links[3..]
+            .iter()
+            .filter(|link| link.source == HttpLinkSource::Body)
+            .map(|link| link.url.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(body_links.len(), links.len() - 3);
+        assert!(body_links.contains(&"https://body.http.test.com"));
+    }
+
+    #[test]
+    fn header_only_test() {
+        let links = scrape_from_slice(
+            b"HTTP/1.1 301 Moved Permanently\r\nlocation: /moved\r\nRefresh: 0; URL='https://refresh.http.test.com'\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            links,
+            vec![
+                header_link("/moved", "Location", None),
+                header_link("https://refresh.http.test.com", "Refresh", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn link_header_entries_test() {
+        assert_eq!(
+            link_header_entries(r#"<https://a.com>; title="a, b"; REL="x y", <https://b.com>"#),
+            vec![
+                ("https://a.com", Some("x y".to_string())),
+                ("https://b.com", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_response_test() {
+        assert!(matches!(
+            scrape_from_slice(b"GET / HTTP/1.1\r\n\r\n"),
+            Err(HttpResponseScrapingError::InvalidResponseError(_))
+        ));
+    }
+}
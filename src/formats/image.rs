@@ -1,39 +1,47 @@
-use exif::Value;
+use exif::{Exif, In, Tag, Value};
 use std::fmt::{Display, Formatter};
 use std::io;
+use std::io::{Read, SeekFrom};
 use thiserror::Error;
 
-use crate::helpers::find_urls;
+use crate::formats::xml::XmlLinkKind;
+use crate::helpers::{find_urls, AsUrl};
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
 
+/// Scrapes links from the EXIF-data, XMP-packets and PNG text-chunks of an image.
+///
+/// Besides the text-fields of the EXIF-data, the `UserComment` is decoded according to its character-code
+/// and the printable parts of the vendor-specific `MakerNote` are searched for urls.
+/// GPS-coordinates are returned as a `geo:`-url ([RFC 5870](https://www.rfc-editor.org/rfc/rfc5870)),
+/// e.g. `geo:48.858222,-2.294500,35.5`, including the altitude if it was recorded.
+///
+/// With the `qr` feature, a QR-code inside a PNG-image is decoded as well.
 pub fn scrape<R>(mut reader: R) -> Result<Vec<ImageLink>, ImageScrapingError>
 where
     R: io::BufRead + io::Seek,
 {
-    let exif_res = exif::Reader::new().read_from_container(&mut reader);
+    let start = reader.stream_position()?;
+    let mut links = scrape_exif(&mut reader)?;
 
-    if let Err(exif::Error::NotFound(_)) = exif_res {
-        return Ok(vec![]);
+    reader.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    if bytes.starts_with(PNG_SIGNATURE) {
+        links.append(&mut scrape_png_chunks(&bytes)?);
+        #[cfg(feature = "qr")]
+        if let Some(payload) = crate::formats::qr::decode_png(&bytes) {
+            find_urls(&payload).iter().for_each(|link| {
+                links.push(ImageLink {
+                    url: link.as_str().to_string(),
+                    source: ImageLinkSource::QrCode,
+                })
+            });
+        }
+    } else {
+        links.append(&mut scrape_xmp_packets(&bytes)?);
     }
-    let exif = exif_res?;
 
-    Ok(exif
-        .fields()
-        .map(|field| {
-            if let Value::Ascii(_) = &field.value {
-                find_urls(&field.display_value().to_string())
-                    .iter()
-                    .map(|link| ImageLink {
-                        url: link.as_str().to_string(),
-                        exif_field: field.tag.to_string(),
-                    })
-                    .collect()
-            } else {
-                vec![]
-            }
-        })
-        .flatten()
-        .collect())
+    Ok(links)
 }
 gen_scrape_from_file!(scrape(Read) -> Result<Vec<ImageLink>, ImageScrapingError>);
 gen_scrape_from_slice!(scrape(Read) -> Result<Vec<ImageLink>, ImageScrapingError>);
@@ -44,12 +52,14 @@ pub enum ImageScrapingError {
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     ExifError(#[from] exif::Error),
+    #[error(transparent)]
+    XmlScrapingError(#[from] crate::formats::xml::XmlScrapingError),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ImageLink {
     pub url: String,
-    pub exif_field: String,
+    pub source: ImageLinkSource,
 }
 
 impl Display for ImageLink {
@@ -58,12 +68,304 @@ impl Display for ImageLink {
     }
 }
 
+impl AsUrl for ImageLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageLinkSource {
+    /// The link is inside an EXIF-field with the given name
+    Exif(String),
+    /// The link is inside the EXIF `UserComment`
+    UserComment,
+    /// The link is inside the vendor-specific EXIF `MakerNote`
+    MakerNote,
+    /// The link is a `geo:`-url of the GPS-coordinates of the EXIF-data
+    GpsPosition,
+    /// The link is inside an XMP-packet
+    Xmp,
+    /// The link is inside a PNG `tEXt`, `zTXt` or `iTXt` chunk with the given keyword
+    PngText(String),
+    /// The link is the payload of a QR-code shown in the image. Only found with the `qr` feature
+    QrCode,
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+
+fn scrape_exif<R>(reader: &mut R) -> Result<Vec<ImageLink>, ImageScrapingError>
+where
+    R: io::BufRead + io::Seek,
+{
+    let exif_res = exif::Reader::new().read_from_container(reader);
+
+    if let Err(exif::Error::NotFound(_)) = exif_res {
+        return Ok(vec![]);
+    }
+    let exif = exif_res?;
+
+    let links_in = |text: &str, source: ImageLinkSource| {
+        find_urls(text)
+            .iter()
+            .map(|link| ImageLink {
+                url: link.as_str().to_string(),
+                source: source.clone(),
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut links: Vec<ImageLink> = exif
+        .fields()
+        .flat_map(|field| match &field.value {
+            // `display_value` quotes every string and escapes non-ASCII bytes as `\xNN`,
+            // which would become part of the urls. Many writers store UTF-8 anyway, so decode it instead.
+            Value::Ascii(values) => values
+                .iter()
+                .flat_map(|value| {
+                    links_in(
+                        &String::from_utf8_lossy(value),
+                        ImageLinkSource::Exif(field.tag.to_string()),
+                    )
+                })
+                .collect(),
+            Value::Undefined(bytes, _) if field.tag == Tag::UserComment => links_in(
+                &decode_user_comment(bytes, exif.little_endian()),
+                ImageLinkSource::UserComment,
+            ),
+            // The layout of maker notes differs for every vendor, so only their printable runs are searched
+            Value::Undefined(bytes, _) if field.tag == Tag::MakerNote => bytes
+                .split(|byte| !(0x20..0x7F).contains(byte))
+                .filter(|run| run.len() >= 8)
+                .flat_map(|run| links_in(&String::from_utf8_lossy(run), ImageLinkSource::MakerNote))
+                .collect(),
+            _ => vec![],
+        })
+        .collect();
+    links.extend(gps_position(&exif).map(|url| ImageLink {
+        url,
+        source: ImageLinkSource::GpsPosition,
+    }));
+    Ok(links)
+}
+
+/// Decodes a `UserComment`, whose first 8 bytes name its character-code.
+///
+/// `UNICODE` is UCS-2 in the byte-order of the EXIF-data unless it starts with a byte-order-mark.
+/// `ASCII`, `JIS` and undefined comments are decoded as UTF-8, which keeps at least the ASCII-range of JIS intact.
+fn decode_user_comment(bytes: &[u8], little_endian: bool) -> String {
+    let (code, text) = bytes.split_at(bytes.len().min(8));
+    if code != b"UNICODE\0" {
+        return String::from_utf8_lossy(text)
+            .trim_end_matches('\0')
+            .to_string();
+    }
+    let (little_endian, text) = match text {
+        [0xFF, 0xFE, text @ ..] => (true, text),
+        [0xFE, 0xFF, text @ ..] => (false, text),
+        text => (little_endian, text),
+    };
+    let units = text.chunks_exact(2).map(|unit| {
+        if little_endian {
+            u16::from_le_bytes([unit[0], unit[1]])
+        } else {
+            u16::from_be_bytes([unit[0], unit[1]])
+        }
+    });
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect::<String>()
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// Returns the GPS-coordinates (and altitude) of the EXIF-data as a `geo:`-url,
+/// with latitude and longitude rounded to six decimals (about 0.1 meters).
+fn gps_position(exif: &Exif) -> Option<String> {
+    let rationals = |tag: Tag| match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) if values.iter().all(|value| value.denom != 0) => Some(
+            values
+                .iter()
+                .map(|value| value.to_f64())
+                .collect::<Vec<_>>(),
+        ),
+        _ => None,
+    };
+    let reference = |tag: Tag| match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values.first()?.first().copied(),
+        value => value.get_uint(0).and_then(|value| u8::try_from(value).ok()),
+    };
+    // Degrees, minutes and seconds, though some writers only use the first one or two
+    let coordinate = |tag: Tag, reference_tag: Tag, negative: u8, limit: f64| {
+        let parts = rationals(tag).filter(|parts| !parts.is_empty())?;
+        let value: f64 = parts
+            .iter()
+            .zip([1.0, 60.0, 3600.0])
+            .map(|(part, divisor)| part / divisor)
+            .sum();
+        let value = if reference(reference_tag) == Some(negative) {
+            -value
+        } else {
+            value
+        };
+        (value.abs() <= limit).then_some(value)
+    };
+
+    let latitude = coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, b'S', 90.0)?;
+    let longitude = coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, b'W', 180.0)?;
+    let mut url = format!("geo:{:.6},{:.6}", latitude, longitude);
+    if let Some(altitude) = rationals(Tag::GPSAltitude).and_then(|parts| parts.first().copied()) {
+        // A reference of 1 means below sea level
+        let altitude = if reference(Tag::GPSAltitudeRef) == Some(1) {
+            -altitude
+        } else {
+            altitude
+        };
+        url.push_str(&format!(",{}", altitude));
+    }
+    Some(url)
+}
+
+/// Finds all `<x:xmpmeta>` packets inside the raw bytes of an image.
+///
+/// JPEG, TIFF and WebP store XMP uncompressed, so no container-specific parsing is necessary.
+fn scrape_xmp_packets(bytes: &[u8]) -> Result<Vec<ImageLink>, ImageScrapingError> {
+    const START: &[u8] = b"<x:xmpmeta";
+    const END: &[u8] = b"</x:xmpmeta>";
+
+    let mut links = vec![];
+    let mut position = 0;
+    while let Some(start) = find_from(bytes, position, START) {
+        let Some(end) = find_from(bytes, start, END) else {
+            break;
+        };
+        position = end + END.len();
+        links.append(&mut scrape_xmp(&bytes[start..position])?);
+    }
+    Ok(links)
+}
+
+/// Namespace-declarations are omitted, since every XMP-packet is full of them.
+fn scrape_xmp(packet: &[u8]) -> Result<Vec<ImageLink>, ImageScrapingError> {
+    Ok(crate::formats::xml::scrape(packet)?
+        .into_iter()
+        .filter(|link| !matches!(link.kind, XmlLinkKind::NameSpace(_)))
+        .map(|link| ImageLink {
+            url: link.url,
+            source: ImageLinkSource::Xmp,
+        })
+        .collect())
+}
+
+/// Walks through the chunks of a PNG and scrapes its text-chunks.
+///
+/// Malformed or undecodable chunks are skipped.
+fn scrape_png_chunks(bytes: &[u8]) -> Result<Vec<ImageLink>, ImageScrapingError> {
+    let mut links = vec![];
+    let mut position = PNG_SIGNATURE.len();
+    while position + 8 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[position..position + 4].try_into().unwrap()) as usize;
+        let chunk_type = &bytes[position + 4..position + 8];
+        let data_start = position + 8;
+        let Some(data) = bytes.get(data_start..data_start + length) else {
+            break;
+        };
+
+        let text = match chunk_type {
+            b"tEXt" => decode_png_text(data),
+            b"zTXt" => decode_png_compressed_text(data),
+            b"iTXt" => decode_png_international_text(data),
+            b"IEND" => break,
+            _ => None,
+        };
+        if let Some((keyword, text)) = text {
+            if keyword == PNG_XMP_KEYWORD {
+                links.append(&mut scrape_xmp(text.as_bytes())?);
+            } else {
+                find_urls(&text).iter().for_each(|link| {
+                    links.push(ImageLink {
+                        url: link.as_str().to_string(),
+                        source: ImageLinkSource::PngText(keyword.clone()),
+                    })
+                });
+            }
+        }
+
+        // Skip data and CRC
+        position = data_start + length + 4;
+    }
+    Ok(links)
+}
+
+/// `keyword\0text`, both Latin-1
+fn decode_png_text(data: &[u8]) -> Option<(String, String)> {
+    let (keyword, text) = split_at_null(data)?;
+    Some((latin1_to_string(keyword), latin1_to_string(text)))
+}
+
+/// `keyword\0 compression-method compressed-text`, both Latin-1
+fn decode_png_compressed_text(data: &[u8]) -> Option<(String, String)> {
+    let (keyword, rest) = split_at_null(data)?;
+    let text = inflate(rest.get(1..)?)?;
+    Some((latin1_to_string(keyword), latin1_to_string(&text)))
+}
+
+/// `keyword\0 compression-flag compression-method language\0 translated-keyword\0 text`,
+/// where the text is UTF-8
+fn decode_png_international_text(data: &[u8]) -> Option<(String, String)> {
+    let (keyword, rest) = split_at_null(data)?;
+    let compressed = *rest.first()? == 1;
+    let (_language, rest) = split_at_null(rest.get(2..)?)?;
+    let (_translated_keyword, text) = split_at_null(rest)?;
+    let text = if compressed {
+        inflate(text)?
+    } else {
+        text.to_vec()
+    };
+    Some((
+        latin1_to_string(keyword),
+        String::from_utf8_lossy(&text).to_string(),
+    ))
+}
+
+fn split_at_null(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let index = data.iter().position(|&byte| byte == 0)?;
+    Some((&data[..index], &data[index + 1..]))
+}
+
+fn latin1_to_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| byte as char).collect()
+}
+
+fn inflate(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut inflated = Vec::new();
+    flate2::read::ZlibDecoder::new(bytes)
+        .read_to_end(&mut inflated)
+        .ok()?;
+    Some(inflated)
+}
+
+fn find_from(bytes: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(start..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| start + position)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     const TEST_JPG: &[u8] = include_bytes!("../../test_files/images/exif_test.jpg");
     const TEST_JPG_NO_EXIF: &[u8] = include_bytes!("../../test_files/images/no_exif_test.jpg");
+    const TEST_JPG_GPS: &[u8] = include_bytes!("../../test_files/images/exif_gps_test.jpg");
+    const TEST_JPG_XMP: &[u8] = include_bytes!("../../test_files/images/xmp_test.jpg");
+    const TEST_PNG_TEXT: &[u8] = include_bytes!("../../test_files/images/png_text_test.png");
+    const TEST_TIF_INVALID_UTF8: &[u8] =
+        include_bytes!("../../test_files/images/exif_invalid_utf8_test.tif");
+    #[cfg(feature = "qr")]
+    const TEST_PNG_QR: &[u8] = include_bytes!("../../test_files/images/qr_code_test.png");
 
     #[test]
     fn scrape_exif_test() {
@@ -71,17 +373,116 @@ mod tests {
         println!("{:?}", links);
         assert!(links.contains(&ImageLink {
             url: "https://test.exifdata.com".to_string(),
-            exif_field: "ImageDescription".to_string()
+            source: ImageLinkSource::Exif("ImageDescription".to_string())
         }));
         assert!(links.contains(&ImageLink {
             url: "https://test2.exifdata.com".to_string(),
-            exif_field: "ImageDescription".to_string()
+            source: ImageLinkSource::Exif("ImageDescription".to_string())
         }))
     }
 
+    #[test]
+    fn scrape_exif_invalid_utf8_test() {
+        let links = scrape_from_slice(TEST_TIF_INVALID_UTF8).unwrap();
+        println!("{:?}", links);
+        assert_eq!(links[0].url, "https://exif.test.com/über");
+        assert!(links[1].url.starts_with("https://broken.test.com/caf"));
+        assert!(links
+            .iter()
+            .all(|it| !it.url.contains("\\x") && !it.url.contains("ERROR")));
+    }
+
     #[test]
     fn scrape_empty_exif_data_test() {
         let links = scrape_from_slice(TEST_JPG_NO_EXIF).unwrap();
         assert_eq!(links.len(), 0)
     }
+
+    #[test]
+    fn scrape_gps_and_comments_test() {
+        let links = scrape_from_slice(TEST_JPG_GPS).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&ImageLink {
+            url: "geo:48.858222,-2.294500,35.5".to_string(),
+            source: ImageLinkSource::GpsPosition,
+        }));
+        assert!(links.contains(&ImageLink {
+            url: "https://usercomment.image.test.com/ä".to_string(),
+            source: ImageLinkSource::UserComment,
+        }));
+        assert!(links.contains(&ImageLink {
+            url: "https://makernote.image.test.com/firmware".to_string(),
+            source: ImageLinkSource::MakerNote,
+        }));
+    }
+
+    #[test]
+    fn decode_user_comment_test() {
+        assert_eq!(
+            decode_user_comment(b"ASCII\0\0\0https://ascii.test.com\0", true),
+            "https://ascii.test.com"
+        );
+        assert_eq!(
+            decode_user_comment(b"UNICODE\0\0h\0i", true),
+            "\u{6800}\u{6900}"
+        );
+        assert_eq!(decode_user_comment(b"UNICODE\0\0h\0i", false), "hi");
+        assert_eq!(decode_user_comment(b"UNICODE\0\xFF\xFEh\0i\0", false), "hi");
+    }
+
+    #[test]
+    fn scrape_xmp_test() {
+        let links = scrape_from_slice(TEST_JPG_XMP).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![ImageLink {
+                url: "https://xmp.test.com/source".to_string(),
+                source: ImageLinkSource::Xmp
+            }]
+        );
+    }
+
+    #[test]
+    fn scrape_png_text_test() {
+        let links = scrape_from_slice(TEST_PNG_TEXT).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&ImageLink {
+            url: "https://pngtext.test.com".to_string(),
+            source: ImageLinkSource::PngText("Source".to_string())
+        }));
+        assert!(links.contains(&ImageLink {
+            url: "https://pngztext.test.com".to_string(),
+            source: ImageLinkSource::PngText("Comment".to_string())
+        }));
+        assert!(links.contains(&ImageLink {
+            url: "https://pngitext.test.com".to_string(),
+            source: ImageLinkSource::PngText("Copyright".to_string())
+        }));
+    }
+
+    #[test]
+    #[cfg(feature = "qr")]
+    fn scrape_qr_code_test() {
+        let links = scrape_from_slice(TEST_PNG_QR).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![ImageLink {
+                url: "https://qr.image.test.com".to_string(),
+                source: ImageLinkSource::QrCode
+            }]
+        );
+    }
+
+    #[test]
+    fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/images/exif_test.jpg").unwrap();
+        let from_slice = scrape_from_slice(TEST_JPG).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
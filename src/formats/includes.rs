@@ -0,0 +1,101 @@
+use crate::helpers::AsUrl;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use url::Url;
+
+/// A link of a document that was reached by following includes (e.g. `@import`s) from the scraped document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncludedLink<T> {
+    pub link: T,
+    /// How many includes were followed to reach the document containing the link, `0` for the scraped document itself
+    pub depth: usize,
+    /// The urls of the included documents leading from the scraped document to the one containing the link.
+    /// Empty for links of the scraped document itself
+    pub chain: Vec<String>,
+}
+
+impl<T: Display> Display for IncludedLink<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.link)
+    }
+}
+
+impl<T: AsUrl> AsUrl for IncludedLink<T> {
+    fn url(&self) -> &str {
+        self.link.url()
+    }
+}
+
+/// Returns the links of a document together with the urls it includes.
+type ScrapeDocument<'a, T, E> = dyn Fn(&[u8]) -> Result<(Vec<T>, Vec<String>), E> + 'a;
+
+/// Scrapes the root document and every document it includes, up to `max_depth` includes deep.
+///
+/// Relative includes are resolved against the url of the including document, if that is absolute,
+/// those of the root document are passed to `fetch` as written.
+/// Every url is fetched at most once, which also breaks include-cycles. Urls `fetch` returns `None` for are skipped.
+pub(crate) fn resolve_includes<T, E>(
+    root: &[u8],
+    max_depth: usize,
+    fetch: &dyn Fn(&str) -> Option<Vec<u8>>,
+    scrape: &ScrapeDocument<'_, T, E>,
+) -> Result<Vec<IncludedLink<T>>, E> {
+    let mut links = vec![];
+    let mut visited = HashSet::new();
+    visit(
+        root,
+        vec![],
+        max_depth,
+        fetch,
+        scrape,
+        &mut visited,
+        &mut links,
+    )?;
+    Ok(links)
+}
+
+fn visit<T, E>(
+    document: &[u8],
+    chain: Vec<String>,
+    max_depth: usize,
+    fetch: &dyn Fn(&str) -> Option<Vec<u8>>,
+    scrape: &ScrapeDocument<'_, T, E>,
+    visited: &mut HashSet<String>,
+    links: &mut Vec<IncludedLink<T>>,
+) -> Result<(), E> {
+    let (found, includes) = scrape(document)?;
+    links.extend(found.into_iter().map(|link| IncludedLink {
+        link,
+        depth: chain.len(),
+        chain: chain.clone(),
+    }));
+    if chain.len() >= max_depth {
+        return Ok(());
+    }
+
+    let base = chain.last().and_then(|url| Url::parse(url).ok());
+    for include in includes {
+        let url = base
+            .as_ref()
+            .and_then(|base| base.join(&include).ok())
+            .map_or(include, |url| url.to_string());
+        if !visited.insert(url.clone()) {
+            continue;
+        }
+        let Some(included) = fetch(&url) else {
+            continue;
+        };
+        let mut included_chain = chain.clone();
+        included_chain.push(url);
+        visit(
+            &included,
+            included_chain,
+            max_depth,
+            fetch,
+            scrape,
+            visited,
+            links,
+        )?;
+    }
+    Ok(())
+}
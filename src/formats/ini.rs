@@ -0,0 +1,127 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes links from INI-, `.cfg`- and `.properties`-files, tagged with their section and key.
+///
+/// Keys are separated from their values by the first `=` or `:` of a line.
+/// Lines starting with `;` or `#` are comments and skipped, as is everything after a `;` or `#`
+/// that follows whitespace in an unquoted value. Values quoted with `"` or `'` are taken as they are.
+/// Urls on lines without a separator are returned with an empty key.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<IniLink>, IniScrapingError>
+where
+    R: BufRead,
+{
+    let mut links: Vec<IniLink> = vec![];
+    let mut section: Option<String> = None;
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with([';', '#']) {
+            line.clear();
+            continue;
+        }
+        if let Some(name) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            section = Some(name.trim().to_string());
+            line.clear();
+            continue;
+        }
+
+        let (key, value) = match trimmed.find(['=', ':']) {
+            // A line like `see https://...` has no key, its first `:` belongs to an url
+            Some(split) if !trimmed[split..].starts_with("://") => (
+                trimmed[..split].trim(),
+                unquote(trimmed[split + 1..].trim()),
+            ),
+            _ => ("", trimmed),
+        };
+        links.extend(find_urls(value).iter().map(|url| IniLink {
+            url: url.as_str().to_string(),
+            section: section.clone(),
+            key: key.to_string(),
+        }));
+        line.clear();
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<IniLink>, IniScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<IniLink>, IniScrapingError>);
+
+/// Removes the quotes around a value, or the inline comment after an unquoted one.
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(end) = value.strip_prefix(quote).and_then(|rest| rest.find(quote)) {
+            return &value[1..end + 1];
+        }
+    }
+    value
+        .char_indices()
+        .find(|(i, c)| matches!(c, ';' | '#') && value[..*i].ends_with(char::is_whitespace))
+        .map_or(value, |(i, _)| value[..i].trim_end())
+}
+
+#[derive(Error, Debug)]
+pub enum IniScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IniLink {
+    pub url: String,
+    /// The section the link was found in, e.g. `database` for `[database]`.
+    /// [`None`] for keys before the first section.
+    pub section: Option<String>,
+    /// The key whose value contains the link. Empty for lines without a key.
+    pub key: String,
+}
+
+impl Display for IniLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for IniLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INI: &[u8] = include_bytes!("../../test_files/ini/ini_test.ini");
+
+    #[test]
+    fn scrape_ini_test() {
+        let links = scrape_from_slice(TEST_INI).unwrap();
+        println!("{:?}", links);
+        let link = |url: &str, section: Option<&str>, key: &str| IniLink {
+            url: url.to_string(),
+            section: section.map(str::to_string),
+            key: key.to_string(),
+        };
+        assert_eq!(
+            links,
+            vec![
+                link("https://home.ini.test.com", None, "homepage"),
+                link("https://db.ini.test.com:5432/main", Some("database"), "url"),
+                link(
+                    "https://mirror.ini.test.com/quoted",
+                    Some("mirrors"),
+                    "primary"
+                ),
+                link("https://fallback.ini.test.com", Some("mirrors"), "fallback"),
+                link("https://docs.ini.test.com", Some("mirrors"), "docs"),
+                link("https://nokey.ini.test.com", Some("mirrors"), ""),
+            ]
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use crate::config::ResourceLimitExceededError;
+use crate::formats::limited_xml_reader::LimitedXmlReader;
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::io::{Cursor, Read, Seek};
+use thiserror::Error;
+use xml::reader::XmlEvent;
+use zip::result::ZipError;
+
+/// Scrapes links from Apple iWork documents (.pages, .numbers, .key).
+///
+/// Current versions store the document as snappy-compressed protobuf-archives (`*.iwa`), whose schema is not public.
+/// Every string inside of them is searched for urls, so hyperlinks and urls in the text can not be told apart
+/// and are both returned as [`IworkLinkKind::Archive`].
+/// The XML of the legacy format (iWork '09) is scraped like any other XML-document.
+///
+/// Fails with [`IworkScrapingError::NotAnIworkDocument`] for zip-archives containing neither.
+pub fn scrape<R>(reader: R) -> Result<Vec<IworkLink>, IworkScrapingError>
+where
+    R: Read + Seek,
+{
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut links: Vec<IworkLink> = vec![];
+    let mut is_iwork = false;
+    for file_name in archive
+        .file_names()
+        .map(|name| name.to_owned())
+        .collect_vec()
+    {
+        let mut entry = archive.by_name(&file_name)?;
+        if file_name.ends_with(".iwa") {
+            is_iwork = true;
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            scrape_iwa(&bytes, &file_name, &mut links)?;
+        } else if LEGACY_INDEX_FILES.contains(&file_name.as_str()) {
+            is_iwork = true;
+            scrape_legacy_index(entry, &file_name, &mut links)?;
+        } else if file_name == NESTED_INDEX {
+            // Documents saved between 2013 and 2015 zip their archives once more
+            is_iwork = true;
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            links.append(&mut scrape(Cursor::new(bytes))?);
+        }
+    }
+
+    if !is_iwork {
+        return Err(IworkScrapingError::NotAnIworkDocument);
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<IworkLink>, IworkScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<IworkLink>, IworkScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum IworkScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    ZipError(#[from] ZipError),
+    #[error(transparent)]
+    SnappyError(#[from] snap::Error),
+    #[error(transparent)]
+    ResourceLimitExceeded(#[from] ResourceLimitExceededError),
+    #[error(transparent)]
+    XmlReaderError(#[from] xml::reader::Error),
+    #[error("The iwa-archive {0} is not framed correctly.")]
+    InvalidIwaError(String),
+    #[error("The zip-archive is not an iWork document.")]
+    NotAnIworkDocument,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IworkLink {
+    pub url: String,
+    /// Path of the archive-entry containing the link
+    pub file: String,
+    pub kind: IworkLinkKind,
+}
+
+impl Display for IworkLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for IworkLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IworkLinkKind {
+    /// The link is inside a string of an iwa-archive, either as hyperlink or as text
+    Archive,
+    /// The link is the target of a hyperlink in a legacy document <br/>
+    /// Example: `<sf:link href="https://link.example.com">`
+    Hyperlink,
+    /// The link is inside the text of a legacy document
+    PlainText,
+}
+
+/// The XML-documents of the legacy format (Pages and Numbers, Keynote)
+const LEGACY_INDEX_FILES: [&str; 2] = ["index.xml", "index.apxl"];
+const NESTED_INDEX: &str = "Index.zip";
+/// Every chunk of an iwa-archive decompresses to a few kilobytes, anything beyond this is not an iwa-archive
+const MAX_CHUNK_LENGTH: usize = 1 << 24;
+/// How deep nested protobuf-messages are searched for strings
+const MAX_MESSAGE_DEPTH: usize = 32;
+
+fn scrape_legacy_index(
+    entry: impl Read,
+    file_name: &str,
+    links: &mut Vec<IworkLink>,
+) -> Result<(), IworkScrapingError> {
+    let mut parser = LimitedXmlReader::new(entry);
+    loop {
+        match parser.next::<IworkScrapingError>()? {
+            XmlEvent::StartElement { attributes, .. } => attributes
+                .iter()
+                .filter(|attribute| attribute.name.local_name == "href")
+                .for_each(|attribute| {
+                    find_urls(&attribute.value).iter().for_each(|link| {
+                        links.push(IworkLink {
+                            url: link.as_str().to_string(),
+                            file: file_name.to_string(),
+                            kind: IworkLinkKind::Hyperlink,
+                        })
+                    })
+                }),
+            XmlEvent::Characters(text) => find_urls(&text).iter().for_each(|link| {
+                links.push(IworkLink {
+                    url: link.as_str().to_string(),
+                    file: file_name.to_string(),
+                    kind: IworkLinkKind::PlainText,
+                })
+            }),
+            XmlEvent::EndDocument => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+fn scrape_iwa(
+    bytes: &[u8],
+    file_name: &str,
+    links: &mut Vec<IworkLink>,
+) -> Result<(), IworkScrapingError> {
+    let stream = decompress_iwa(bytes, file_name)?;
+    let mut strings = vec![];
+    for message in archive_messages(&stream) {
+        strings_in_message(message, 0, &mut strings);
+    }
+    for string in strings {
+        find_urls(string).iter().for_each(|link| {
+            links.push(IworkLink {
+                url: link.as_str().to_string(),
+                file: file_name.to_string(),
+                kind: IworkLinkKind::Archive,
+            })
+        });
+    }
+    Ok(())
+}
+
+/// An iwa-archive is a sequence of chunks, each starting with a `0`-byte and the 24-bit little-endian length
+/// of the raw snappy-block following it.
+fn decompress_iwa(bytes: &[u8], file_name: &str) -> Result<Vec<u8>, IworkScrapingError> {
+    let invalid = || IworkScrapingError::InvalidIwaError(file_name.to_string());
+    let mut stream = vec![];
+    let mut position = 0;
+    while position < bytes.len() {
+        let header = bytes.get(position..position + 4).ok_or_else(invalid)?;
+        if header[0] != 0 {
+            return Err(invalid());
+        }
+        let length = u32::from_le_bytes([header[1], header[2], header[3], 0]) as usize;
+        let chunk = bytes
+            .get(position + 4..position + 4 + length)
+            .ok_or_else(invalid)?;
+        if snap::raw::decompress_len(chunk)? > MAX_CHUNK_LENGTH {
+            return Err(ResourceLimitExceededError {
+                limit: format!("iwa-chunks of more than {} bytes", MAX_CHUNK_LENGTH),
+            }
+            .into());
+        }
+        stream.append(&mut snap::raw::Decoder::new().decompress_vec(chunk)?);
+        position += 4 + length;
+    }
+    Ok(stream)
+}
+
+/// Splits the decompressed stream into the payload-messages of its archives.
+///
+/// Every archive starts with the varint-length of an `ArchiveInfo`, whose `MessageInfo`s (field 2)
+/// contain the lengths (field 3) of the messages following it.
+fn archive_messages(stream: &[u8]) -> Vec<&[u8]> {
+    let mut messages = vec![];
+    let mut position = 0;
+    while let Some((info_length, varint_length)) = read_varint(&stream[position..]) {
+        position += varint_length;
+        let Some(info) = stream.get(position..position + info_length as usize) else {
+            break;
+        };
+        position += info_length as usize;
+        let message_lengths = protobuf_fields(info)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(field, value)| match (field, value) {
+                (2, WireValue::Bytes(message_info)) => protobuf_fields(message_info),
+                _ => None,
+            })
+            .flat_map(|message_info| {
+                message_info
+                    .into_iter()
+                    .filter_map(|(field, value)| match (field, value) {
+                        (3, WireValue::Varint(length)) => Some(length as usize),
+                        _ => None,
+                    })
+            });
+        for length in message_lengths {
+            let Some(message) = stream.get(position..position + length) else {
+                return messages;
+            };
+            messages.push(message);
+            position += length;
+        }
+    }
+    messages
+}
+
+/// Collects every string containing an url from a protobuf-message without knowing its schema.
+///
+/// Length-delimited fields are decoded as nested messages where possible, and as strings otherwise.
+fn strings_in_message<'a>(message: &'a [u8], depth: usize, strings: &mut Vec<&'a str>) {
+    if depth > MAX_MESSAGE_DEPTH {
+        return;
+    }
+    let Some(fields) = protobuf_fields(message) else {
+        return;
+    };
+    for (_, value) in fields {
+        let WireValue::Bytes(bytes) = value else {
+            continue;
+        };
+        let found = strings.len();
+        strings_in_message(bytes, depth + 1, strings);
+        if strings.len() > found {
+            continue;
+        }
+        if let Ok(string) = std::str::from_utf8(bytes) {
+            if !find_urls(string).is_empty() {
+                strings.push(string);
+            }
+        }
+    }
+}
+
+enum WireValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Decodes the fields of a protobuf-message. Returns [`None`] if it is not a valid message.
+fn protobuf_fields(message: &[u8]) -> Option<Vec<(u64, WireValue<'_>)>> {
+    let mut fields = vec![];
+    let mut position = 0;
+    while position < message.len() {
+        let (key, length) = read_varint(&message[position..])?;
+        position += length;
+        let field = key >> 3;
+        if field == 0 {
+            return None;
+        }
+        let value = match key & 7 {
+            0 => {
+                let (value, length) = read_varint(&message[position..])?;
+                position += length;
+                WireValue::Varint(value)
+            }
+            1 | 5 => {
+                position += if key & 7 == 1 { 8 } else { 4 };
+                if position > message.len() {
+                    return None;
+                }
+                WireValue::Fixed
+            }
+            2 => {
+                let (length, varint_length) = read_varint(&message[position..])?;
+                position += varint_length;
+                let bytes = message.get(position..position.checked_add(length as usize)?)?;
+                position += length as usize;
+                WireValue::Bytes(bytes)
+            }
+            _ => return None,
+        };
+        fields.push((field, value));
+    }
+    Some(fields)
+}
+
+/// Returns the value of the varint at the start of `bytes` and its length.
+fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (index, byte) in bytes.iter().take(10).enumerate() {
+        value |= ((byte & 0x7f) as u64) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Some((value, index + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_PAGES: &[u8] = include_bytes!("../../test_files/iwork/pages_test.pages");
+    const TEST_PAGES_LEGACY: &[u8] =
+        include_bytes!("../../test_files/iwork/pages_legacy_test.pages");
+    const TEST_DOCX: &[u8] = include_bytes!("../../test_files/ooxml/docx_test.docx");
+
+    #[test]
+    fn scrape_pages_test() {
+        let links = scrape_from_slice(TEST_PAGES).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&IworkLink {
+            url: "https://hyperlink.iwork.test.com/page".to_string(),
+            file: "Index/Document.iwa".to_string(),
+            kind: IworkLinkKind::Archive
+        }));
+        assert!(links.contains(&IworkLink {
+            url: "https://plaintext.iwork.test.com".to_string(),
+            file: "Index/Document.iwa".to_string(),
+            kind: IworkLinkKind::Archive
+        }));
+        assert_eq!(links.len(), 2);
+    }
+
+    #[test]
+    fn scrape_legacy_pages_test() {
+        let links = scrape_from_slice(TEST_PAGES_LEGACY).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&IworkLink {
+            url: "https://hyperlink.legacy.test.com/".to_string(),
+            file: "index.xml".to_string(),
+            kind: IworkLinkKind::Hyperlink
+        }));
+        assert!(links.contains(&IworkLink {
+            url: "https://plaintext.legacy.test.com".to_string(),
+            file: "index.xml".to_string(),
+            kind: IworkLinkKind::PlainText
+        }));
+    }
+
+    #[test]
+    fn fail_on_other_zip_test() {
+        assert!(matches!(
+            scrape_from_slice(TEST_DOCX),
+            Err(IworkScrapingError::NotAnIworkDocument)
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+
+/// Scrapes links from all string-values of a JSON-document.
+///
+/// Every link is tagged with the [JSON Pointer](https://datatracker.ietf.org/doc/html/rfc6901) of its value.
+pub fn scrape<R>(reader: R) -> Result<Vec<JsonLink>, JsonScrapingError>
+where
+    R: Read,
+{
+    let value: Value = serde_json::from_reader(reader)?;
+    Ok(scrape_value(&value))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<JsonLink>, JsonScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<JsonLink>, JsonScrapingError>);
+
+/// Scrapes links from an already parsed JSON-value.
+pub fn scrape_value(value: &Value) -> Vec<JsonLink> {
+    let mut collector: Vec<JsonLink> = vec![];
+    collect_links(value, String::new(), &mut collector);
+    collector
+}
+
+#[derive(Error, Debug)]
+pub enum JsonScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonLink {
+    pub url: String,
+    /// JSON Pointer to the string containing the link <br/>
+    /// Example: `/nested/links/0`
+    pub path: String,
+}
+
+impl Display for JsonLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for JsonLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+fn collect_links(value: &Value, path: String, collector: &mut Vec<JsonLink>) {
+    match value {
+        Value::String(string) => find_urls(string).iter().for_each(|link| {
+            collector.push(JsonLink {
+                url: link.as_str().to_string(),
+                path: path.clone(),
+            })
+        }),
+        Value::Array(values) => values.iter().enumerate().for_each(|(index, value)| {
+            collect_links(value, format!("{}/{}", path, index), collector)
+        }),
+        Value::Object(map) => map.iter().for_each(|(key, value)| {
+            let key = key.replace('~', "~0").replace('/', "~1");
+            collect_links(value, format!("{}/{}", path, key), collector)
+        }),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_JSON: &[u8] = include_bytes!("../../test_files/json/json_test.json");
+
+    #[test]
+    fn scrape_json_test() {
+        let links = scrape_from_slice(TEST_JSON).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&JsonLink {
+            url: "https://homepage.test.com".to_string(),
+            path: "/homepage".to_string()
+        }));
+        assert!(links.contains(&JsonLink {
+            url: "https://array.test.com".to_string(),
+            path: "/nested/links/0".to_string()
+        }));
+        assert!(links.contains(&JsonLink {
+            url: "https://object.test.com".to_string(),
+            path: "/nested/links/1/href".to_string()
+        }));
+        assert!(links.contains(&JsonLink {
+            url: "https://escaped.test.com".to_string(),
+            path: "/nested/a~1b".to_string()
+        }));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::formats::json::{scrape_value, JsonScrapingError};
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+/// Scrapes links from newline-delimited JSON (`.jsonl`/`.ndjson`), treating every line as an independent record.
+///
+/// Malformed lines do not abort the scrape, but are reported in [`JsonlScrapeResult::warnings`].
+pub fn scrape<R>(reader: R) -> Result<JsonlScrapeResult, JsonScrapingError>
+where
+    R: BufRead,
+{
+    let mut result = JsonlScrapeResult::default();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<Value>(&line) {
+            Ok(value) => scrape_value(&value).into_iter().for_each(|link| {
+                result.links.push(JsonlLink {
+                    url: link.url,
+                    line: index + 1,
+                    path: link.path,
+                })
+            }),
+            Err(error) => result.warnings.push(JsonlWarning {
+                line: index + 1,
+                message: error.to_string(),
+            }),
+        }
+    }
+    Ok(result)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<JsonlScrapeResult, JsonScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<JsonlScrapeResult, JsonScrapingError>);
+
+#[derive(Debug, Clone, Default)]
+pub struct JsonlScrapeResult {
+    pub links: Vec<JsonlLink>,
+    /// Lines that could not be parsed and were skipped
+    pub warnings: Vec<JsonlWarning>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonlLink {
+    pub url: String,
+    /// 1-based line of the record containing the link
+    pub line: usize,
+    /// JSON Pointer to the string containing the link inside the record
+    pub path: String,
+}
+
+impl Display for JsonlLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for JsonlLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonlWarning {
+    /// 1-based line that was skipped
+    pub line: usize,
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_JSONL: &[u8] = include_bytes!("../../test_files/json/jsonl_test.jsonl");
+
+    #[test]
+    fn scrape_jsonl_test() {
+        let result = scrape_from_slice(TEST_JSONL).unwrap();
+        println!("{:?}", result);
+        assert!(result.links.contains(&JsonlLink {
+            url: "https://first.test.com/page".to_string(),
+            line: 1,
+            path: "/target/url".to_string()
+        }));
+        assert!(result.links.contains(&JsonlLink {
+            url: "https://tag.test.com".to_string(),
+            line: 1,
+            path: "/tags/1".to_string()
+        }));
+        assert!(result.links.contains(&JsonlLink {
+            url: "https://third.test.com".to_string(),
+            line: 3,
+            path: "/target/url".to_string()
+        }));
+        assert!(!result.links.iter().any(|it| it.line == 2));
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].line, 2);
+    }
+}
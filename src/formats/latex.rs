@@ -0,0 +1,270 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes links from LaTeX- and BibTeX-sources.
+///
+/// The arguments of `\url` and `\href` and the `url`- and `doi`-fields of BibTeX-entries are returned as a whole,
+/// even if they contain characters that end an url in plain text. DOIs are resolved via `https://doi.org/`.
+/// Links in the rest of the text are found as usual, comments (starting with an unescaped `%`) are skipped.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<LatexLink>, LatexScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(scrape_from_string(content))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<LatexLink>, LatexScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<LatexLink>, LatexScrapingError>);
+
+/// Like [`scrape`] but takes the source as a string.
+pub fn scrape_from_string<S>(content: S) -> Vec<LatexLink>
+where
+    S: AsRef<str>,
+{
+    let content = content.as_ref();
+    let bytes = content.as_bytes();
+    let mut links: Vec<LatexLink> = vec![];
+    // Everything that is neither a comment nor the argument of a link-command
+    let mut text = String::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            i = find_from(bytes, i, b"\n").unwrap_or(bytes.len());
+            continue;
+        }
+
+        if bytes[i] == b'\\' {
+            let name_end = i + 1 + count_while(&bytes[i + 1..], u8::is_ascii_alphabetic);
+            let name = &content[i + 1..name_end];
+            if LINK_COMMANDS.contains(&name) {
+                if let Some((argument, end)) = braced_argument(content, name_end) {
+                    push_link(&mut links, unescape(argument), name);
+                    text.push(' ');
+                    i = end;
+                    continue;
+                }
+            }
+            // Control symbols like `\%` are copied as a whole, so the escaped character is not interpreted.
+            let end = if name.is_empty() {
+                name_end + content[name_end..].chars().next().map_or(0, char::len_utf8)
+            } else {
+                name_end
+            };
+            text.push_str(&content[i..end]);
+            i = end;
+            continue;
+        }
+
+        let is_word_start = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        if bytes[i].is_ascii_alphabetic() && is_word_start {
+            let word_end = i + count_while(&bytes[i..], u8::is_ascii_alphanumeric);
+            let word = &content[i..word_end];
+            if let Some(field) = BIBTEX_FIELDS
+                .iter()
+                .find(|field| field.eq_ignore_ascii_case(word))
+            {
+                if let Some((value, end)) = bibtex_value(content, word_end) {
+                    push_link(&mut links, value.trim().to_string(), field);
+                    text.push(' ');
+                    i = end;
+                    continue;
+                }
+            }
+            text.push_str(word);
+            i = word_end;
+            continue;
+        }
+
+        let c = content[i..].chars().next().unwrap();
+        text.push(c);
+        i += c.len_utf8();
+    }
+
+    links.extend(find_urls(&text).iter().map(|link| LatexLink {
+        url: link.as_str().to_string(),
+        command: None,
+    }));
+    links
+}
+
+#[derive(Error, Debug)]
+pub enum LatexScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatexLink {
+    pub url: String,
+    /// The LaTeX-command (`url`, `href`) or BibTeX-field (`url`, `doi`) containing the link.
+    /// [`None`] for links found in the text.
+    pub command: Option<String>,
+}
+
+impl Display for LatexLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for LatexLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Commands whose first argument is an url
+const LINK_COMMANDS: [&str; 2] = ["url", "href"];
+/// BibTeX-fields containing an url or a DOI
+const BIBTEX_FIELDS: [&str; 2] = ["url", "doi"];
+const DOI_RESOLVER: &str = "https://doi.org/";
+
+fn push_link(links: &mut Vec<LatexLink>, value: String, command: &str) {
+    if value.is_empty() {
+        return;
+    }
+    let url = if command == "doi" && !value.contains("://") {
+        format!("{}{}", DOI_RESOLVER, value.trim_start_matches("doi:"))
+    } else {
+        value
+    };
+    links.push(LatexLink {
+        url,
+        command: Some(command.to_string()),
+    })
+}
+
+/// Reads the `{...}`-argument starting after optional whitespace at `start`.
+///
+/// Returns the content of the braces and the position after the closing brace.
+fn braced_argument(content: &str, start: usize) -> Option<(&str, usize)> {
+    let bytes = content.as_bytes();
+    let open = start + count_while(&bytes[start..], u8::is_ascii_whitespace);
+    if bytes.get(open) != Some(&b'{') {
+        return None;
+    }
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&content[open + 1..i], i + 1));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Reads the value of a BibTeX-field like `= {...}` or `= "..."` starting after the field name at `start`.
+pub(crate) fn bibtex_value(content: &str, start: usize) -> Option<(&str, usize)> {
+    let bytes = content.as_bytes();
+    let equals = start + count_while(&bytes[start..], u8::is_ascii_whitespace);
+    if bytes.get(equals) != Some(&b'=') {
+        return None;
+    }
+    let value_start = equals + 1 + count_while(&bytes[equals + 1..], u8::is_ascii_whitespace);
+    match bytes.get(value_start)? {
+        b'{' => braced_argument(content, value_start),
+        b'"' => {
+            let end = find_from(bytes, value_start + 1, b"\"")?;
+            Some((&content[value_start + 1..end], end + 1))
+        }
+        _ => None,
+    }
+}
+
+/// Removes the backslashes of characters that have to be escaped inside of `\href`.
+fn unescape(argument: &str) -> String {
+    let mut url = String::with_capacity(argument.len());
+    let mut chars = argument.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&next) if c == '\\' && matches!(next, '%' | '#' | '&' | '_' | '~' | '$') => {
+                url.push(next);
+                chars.next();
+            }
+            _ => url.push(c),
+        }
+    }
+    url
+}
+
+fn count_while(bytes: &[u8], predicate: impl Fn(&u8) -> bool) -> usize {
+    bytes.iter().take_while(|byte| predicate(byte)).count()
+}
+
+fn find_from(bytes: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(start..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| start + position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_LATEX: &[u8] = include_bytes!("../../test_files/latex/latex_test.tex");
+    const TEST_BIBTEX: &[u8] = include_bytes!("../../test_files/latex/bibtex_test.bib");
+
+    #[test]
+    fn scrape_latex_test() {
+        let links = scrape_from_slice(TEST_LATEX).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&LatexLink {
+            url: "https://url.test.com/repo_name%20v2".to_string(),
+            command: Some("url".to_string())
+        }));
+        assert!(links.contains(&LatexLink {
+            url: "https://href.test.com/docs#install".to_string(),
+            command: Some("href".to_string())
+        }));
+        assert!(links.contains(&LatexLink {
+            url: "https://plaintext.test.com".to_string(),
+            command: None
+        }));
+        assert!(!links.iter().any(|it| it.url.contains("commented.test.com")));
+        assert_eq!(links.len(), 3);
+    }
+
+    #[test]
+    fn scrape_bibtex_test() {
+        let links = scrape_from_slice(TEST_BIBTEX).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&LatexLink {
+            url: "https://bibtex.test.com/paper.pdf".to_string(),
+            command: Some("url".to_string())
+        }));
+        assert!(links.contains(&LatexLink {
+            url: "https://doi.org/10.1234/links.5678".to_string(),
+            command: Some("doi".to_string())
+        }));
+        assert!(links.contains(&LatexLink {
+            url: "https://note.test.com".to_string(),
+            command: None
+        }));
+    }
+
+    #[test]
+    fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/latex/latex_test.tex").unwrap();
+        let from_slice = scrape_from_slice(TEST_LATEX).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(from_file, from_slice);
+    }
+}
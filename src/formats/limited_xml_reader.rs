@@ -0,0 +1,110 @@
+use crate::config::ResourceLimitExceededError;
+use std::io::Read;
+use xml::common::{Position, TextPosition};
+use xml::reader::{ErrorKind, ParserConfig2, XmlEvent};
+use xml::EventReader;
+
+/// Longest text a custom entity may expand to, including nested entities
+const MAX_ENTITY_EXPANSION_LENGTH: usize = 1 << 16;
+/// How often entities may expand into other entities
+const MAX_ENTITY_EXPANSION_DEPTH: u8 = 4;
+/// Most events a single document may produce
+const MAX_EVENTS: usize = 1 << 26;
+
+/// An [`EventReader`] that gives up on documents that would take unreasonable time or memory to parse,
+/// e.g. because of entities expanding exponentially ("billion laughs").
+pub(crate) struct LimitedXmlReader<R: Read> {
+    parser: EventReader<R>,
+    events: usize,
+}
+
+impl<R: Read> LimitedXmlReader<R> {
+    pub(crate) fn new(source: R) -> Self {
+        Self::with_comments(source, false)
+    }
+
+    /// Like [`LimitedXmlReader::new`], but emits comments as [`XmlEvent::Comment`] if `comments` is set.
+    pub(crate) fn with_comments(source: R, comments: bool) -> Self {
+        Self {
+            parser: ParserConfig2::new()
+                .ignore_comments(!comments)
+                .max_entity_expansion_length(MAX_ENTITY_EXPANSION_LENGTH)
+                .max_entity_expansion_depth(MAX_ENTITY_EXPANSION_DEPTH)
+                // xml-rs only knows UTF-8, UTF-16, ISO-8859-1 and US-ASCII and would fail at the declaration
+                // of legacy encodings like `windows-1252`, so these are read as ISO-8859-1
+                .ignore_invalid_encoding_declarations(true)
+                .create_reader(source),
+            events: 0,
+        }
+    }
+
+    /// How many events were read so far.
+    #[cfg(test)]
+    pub(crate) fn events(&self) -> usize {
+        self.events
+    }
+
+    /// Like [`EventReader::next`], but fails with a [`ResourceLimitExceededError`] once a limit is exceeded.
+    pub(crate) fn next<E>(&mut self) -> Result<XmlEvent, E>
+    where
+        E: From<xml::reader::Error> + From<ResourceLimitExceededError>,
+    {
+        self.events += 1;
+        if self.events > MAX_EVENTS {
+            return Err(ResourceLimitExceededError {
+                limit: format!("more than {} xml-events", MAX_EVENTS),
+            }
+            .into());
+        }
+        self.parser.next().map_err(|e| match e.kind() {
+            // The parser only reports its limits as messages.
+            ErrorKind::Syntax(message) if is_limit_message(message) => ResourceLimitExceededError {
+                limit: message.to_string(),
+            }
+            .into(),
+            _ => e.into(),
+        })
+    }
+
+    /// Like [`LimitedXmlReader::next`], but with `lenient` a malformed event ends the document like
+    /// [`XmlEvent::EndDocument`], keeping what was read so far. Exceeded limits are errors nevertheless.
+    pub(crate) fn next_or_end<E>(&mut self, lenient: bool) -> Result<XmlEvent, E>
+    where
+        E: From<xml::reader::Error> + From<ResourceLimitExceededError>,
+    {
+        match self.next::<ReadError>() {
+            Ok(xml_event) => Ok(xml_event),
+            Err(ReadError::Malformed(_)) if lenient => Ok(XmlEvent::EndDocument),
+            Err(ReadError::Malformed(e)) => Err(e.into()),
+            Err(ReadError::LimitExceeded(e)) => Err(e.into()),
+        }
+    }
+}
+
+/// Tells malformed documents apart from exceeded limits.
+enum ReadError {
+    Malformed(xml::reader::Error),
+    LimitExceeded(ResourceLimitExceededError),
+}
+
+impl From<xml::reader::Error> for ReadError {
+    fn from(e: xml::reader::Error) -> Self {
+        ReadError::Malformed(e)
+    }
+}
+
+impl From<ResourceLimitExceededError> for ReadError {
+    fn from(e: ResourceLimitExceededError) -> Self {
+        ReadError::LimitExceeded(e)
+    }
+}
+
+impl<R: Read> Position for LimitedXmlReader<R> {
+    fn position(&self) -> TextPosition {
+        self.parser.position()
+    }
+}
+
+fn is_limit_message(message: &str) -> bool {
+    message == "Entity too big" || message.contains("allowed by the parser's configuration")
+}
@@ -0,0 +1,248 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+
+/// Scrapes links from a Windows shortcut (`.lnk`, MS-SHLLINK).
+///
+/// Inspects the link target, description, relative path, working directory, arguments, icon location
+/// and environment-variable target.
+///
+/// Reads the whole stream before processing the contents.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<LnkLink>, LnkScrapingError>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    if !bytes.starts_with(LNK_MAGIC) {
+        return Err(LnkScrapingError::NotALnkError);
+    }
+
+    let flags = read_u32(&bytes, 0x14)?;
+    let is_unicode = flags & IS_UNICODE != 0;
+    let mut fields: Vec<(&str, String)> = vec![];
+    let mut position = HEADER_SIZE;
+
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        position += 2 + read_u16(&bytes, position)? as usize;
+    }
+
+    if flags & HAS_LINK_INFO != 0 {
+        let size = read_u32(&bytes, position)? as usize;
+        let link_info = bytes
+            .get(position..position + size)
+            .ok_or(LnkScrapingError::UnexpectedEndError)?;
+        fields.push(("target", target_from_link_info(link_info)?));
+        position += size;
+    }
+
+    for (flag, field) in STRING_DATA {
+        if flags & flag == 0 {
+            continue;
+        }
+        let characters = read_u16(&bytes, position)? as usize;
+        position += 2;
+        let length = if is_unicode {
+            characters * 2
+        } else {
+            characters
+        };
+        let data = bytes
+            .get(position..position + length)
+            .ok_or(LnkScrapingError::UnexpectedEndError)?;
+        fields.push((field, decode(data, is_unicode)));
+        position += length;
+    }
+
+    // Extra data blocks are terminated by a block smaller than 4 bytes.
+    while let Ok(size) = read_u32(&bytes, position) {
+        let size = size as usize;
+        if size < 4 {
+            break;
+        }
+        if read_u32(&bytes, position + 4)? == ENVIRONMENT_VARIABLE_DATA_BLOCK {
+            let block = bytes
+                .get(position..position + size)
+                .ok_or(LnkScrapingError::UnexpectedEndError)?;
+            let unicode_target = block.get(268..788).map(|target| decode(target, true));
+            let ansi_target = block.get(8..268).map(|target| decode(target, false));
+            if let Some(target) = unicode_target.filter(|it| !it.is_empty()).or(ansi_target) {
+                fields.push(("environment_target", target));
+            }
+        }
+        position += size;
+    }
+
+    Ok(fields
+        .into_iter()
+        .flat_map(|(field, value)| {
+            find_urls(&value)
+                .iter()
+                .map(|link| LnkLink {
+                    url: link.as_str().to_string(),
+                    field: field.to_string(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<LnkLink>, LnkScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<LnkLink>, LnkScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum LnkScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Given file was not a Windows shortcut.")]
+    NotALnkError,
+    #[error("The shortcut ended in the middle of a structure.")]
+    UnexpectedEndError,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LnkLink {
+    pub url: String,
+    /// The field containing the link. One of `target`, `description`, `relative_path`, `working_dir`,
+    /// `arguments`, `icon_location` or `environment_target`
+    pub field: String,
+}
+
+impl Display for LnkLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for LnkLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Header size followed by the shell link CLSID `00021401-0000-0000-C000-000000000046`
+const LNK_MAGIC: &[u8] = &[
+    0x4C, 0x00, 0x00, 0x00, 0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x46,
+];
+const HEADER_SIZE: usize = 0x4C;
+
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x01;
+const HAS_LINK_INFO: u32 = 0x02;
+const IS_UNICODE: u32 = 0x80;
+/// The optional string data structures in the order they appear in the file
+const STRING_DATA: [(u32, &str); 5] = [
+    (0x04, "description"),
+    (0x08, "relative_path"),
+    (0x10, "working_dir"),
+    (0x20, "arguments"),
+    (0x40, "icon_location"),
+];
+
+const ENVIRONMENT_VARIABLE_DATA_BLOCK: u32 = 0xA000_0001;
+
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x01;
+const COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX: u32 = 0x02;
+
+/// Builds the target path from either the local base path or the network share name, and the common path suffix.
+fn target_from_link_info(link_info: &[u8]) -> Result<String, LnkScrapingError> {
+    let header_size = read_u32(link_info, 4)?;
+    let flags = read_u32(link_info, 8)?;
+    let has_unicode_offsets = header_size >= 0x24;
+
+    let unicode_or_ansi_at = |unicode_offset: usize, ansi_offset: usize| {
+        if has_unicode_offsets {
+            let offset = read_u32(link_info, unicode_offset)? as usize;
+            if offset != 0 {
+                return Ok(null_terminated(link_info, offset, true));
+            }
+        }
+        let offset = read_u32(link_info, ansi_offset)? as usize;
+        Ok::<_, LnkScrapingError>(null_terminated(link_info, offset, false))
+    };
+
+    let suffix = unicode_or_ansi_at(32, 24)?;
+    if flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+        return Ok(unicode_or_ansi_at(28, 16)? + &suffix);
+    }
+    if flags & COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX != 0 {
+        let network_link = read_u32(link_info, 20)? as usize;
+        let net_name_offset = read_u32(link_info, network_link + 8)? as usize;
+        let net_name = null_terminated(link_info, network_link + net_name_offset, false);
+        return Ok(if suffix.is_empty() {
+            net_name
+        } else {
+            format!("{}\\{}", net_name, suffix)
+        });
+    }
+    Ok(suffix)
+}
+
+fn null_terminated(bytes: &[u8], offset: usize, is_unicode: bool) -> String {
+    let Some(bytes) = bytes.get(offset..) else {
+        return String::new();
+    };
+    decode(bytes, is_unicode)
+}
+
+/// Decodes UTF-16LE or ANSI (as Latin-1) up to the first null-character.
+fn decode(bytes: &[u8], is_unicode: bool) -> String {
+    if is_unicode {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes
+            .iter()
+            .take_while(|&&byte| byte != 0)
+            .map(|&byte| byte as char)
+            .collect()
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, LnkScrapingError> {
+    let bytes = bytes
+        .get(offset..offset + 2)
+        .ok_or(LnkScrapingError::UnexpectedEndError)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, LnkScrapingError> {
+    let bytes = bytes
+        .get(offset..offset + 4)
+        .ok_or(LnkScrapingError::UnexpectedEndError)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_LNK: &[u8] = include_bytes!("../../test_files/lnk/lnk_test.lnk");
+
+    #[test]
+    fn scrape_lnk_test() {
+        let links = scrape_from_slice(TEST_LNK).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&LnkLink {
+            url: "https://payload.test.com/run".to_string(),
+            field: "arguments".to_string()
+        }));
+        assert!(links.contains(&LnkLink {
+            url: "https://description.test.com".to_string(),
+            field: "description".to_string()
+        }));
+    }
+
+    #[test]
+    fn fail_on_non_lnk_test() {
+        assert!(matches!(
+            scrape_from_slice(b"https://test.com"),
+            Err(LnkScrapingError::NotALnkError)
+        ));
+    }
+}
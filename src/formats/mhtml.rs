@@ -0,0 +1,341 @@
+use crate::formats::html::{self, HtmlLinkKind};
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes a web page saved as MHTML (`.mht`, `.mhtml`), a MIME `multipart/related`-archive of the page and its resources.
+///
+/// Every part is returned with its `Content-Location` as a [`MhtmlLinkSource::ResourceLocation`],
+/// HTML-parts are additionally scraped with [`crate::formats::html`].
+/// Nested multiparts and `message/rfc822`-parts are walked as well,
+/// `base64`- and `quoted-printable`-encoded parts are decoded first.
+///
+/// HTML-parts are decoded as UTF-8, regardless of their `charset`.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<MhtmlLink>, MhtmlScrapingError>
+where
+    R: BufRead,
+{
+    let mut message = vec![];
+    reader.read_to_end(&mut message)?;
+    let (headers, _) = parse_part(&message);
+    if header_value(&headers, "Content-Type").is_none() {
+        return Err(MhtmlScrapingError::NotMhtmlError);
+    }
+
+    let mut links = vec![];
+    scrape_part(&message, 0, &mut links)?;
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<MhtmlLink>, MhtmlScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<MhtmlLink>, MhtmlScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum MhtmlScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid MIME-part: {0}")]
+    InvalidPartError(String),
+    #[error("Given file was not a MIME-message.")]
+    NotMhtmlError,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MhtmlLink {
+    pub url: String,
+    pub source: MhtmlLinkSource,
+    /// The `Content-Location` of the part containing the link, e.g. the url the page was saved from
+    pub part_location: Option<String>,
+    /// Where inside the HTML the link was found. `None` for [`MhtmlLinkSource::ResourceLocation`]s
+    pub html_kind: Option<HtmlLinkKind>,
+}
+
+impl Display for MhtmlLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for MhtmlLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MhtmlLinkSource {
+    /// The link was found inside an HTML-part
+    HtmlBody,
+    /// The link is the `Content-Location` of a part, i.e. the url a resource of the page was saved from
+    ResourceLocation,
+}
+
+/// Multiparts nested deeper than this are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 16;
+
+fn scrape_part(
+    part: &[u8],
+    depth: usize,
+    links: &mut Vec<MhtmlLink>,
+) -> Result<(), MhtmlScrapingError> {
+    if depth > MAX_DEPTH {
+        return Err(MhtmlScrapingError::InvalidPartError(
+            "Multiparts are nested too deeply".to_string(),
+        ));
+    }
+    let (headers, body) = parse_part(part);
+    let content_type = header_value(&headers, "Content-Type").unwrap_or("text/plain");
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let location = header_value(&headers, "Content-Location")
+        .filter(|location| !location.is_empty())
+        .map(str::to_string);
+    if let Some(location) = &location {
+        links.push(MhtmlLink {
+            url: location.clone(),
+            source: MhtmlLinkSource::ResourceLocation,
+            part_location: None,
+            html_kind: None,
+        });
+    }
+
+    if mime.starts_with("multipart/") {
+        let boundary = header_parameter(content_type, "boundary").ok_or_else(|| {
+            MhtmlScrapingError::InvalidPartError(format!("`{}` has no boundary", mime))
+        })?;
+        for sub_part in split_multipart(body, &boundary) {
+            scrape_part(sub_part, depth + 1, links)?;
+        }
+    } else if mime == "message/rfc822" {
+        scrape_part(&decode_body(&headers, body), depth + 1, links)?;
+    } else if mime == "text/html" || mime == "application/xhtml+xml" {
+        let body = decode_body(&headers, body);
+        links.extend(
+            html::scrape_from_string(String::from_utf8_lossy(&body))
+                .into_iter()
+                .map(|link| MhtmlLink {
+                    url: link.url,
+                    source: MhtmlLinkSource::HtmlBody,
+                    part_location: location.clone(),
+                    html_kind: Some(link.kind),
+                }),
+        );
+    }
+    Ok(())
+}
+
+/// Splits a part at the first empty line into its unfolded headers and its body.
+/// Bare `\n` line-endings are accepted as well.
+fn parse_part(part: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let crlf = part.windows(4).position(|window| window == b"\r\n\r\n");
+    let lf = part.windows(2).position(|window| window == b"\n\n");
+    let (head, body) = match (crlf, lf) {
+        (Some(crlf), lf) if lf.is_none_or(|lf| lf > crlf) => (&part[..crlf], &part[crlf + 4..]),
+        (_, Some(lf)) => (&part[..lf], &part[lf + 2..]),
+        _ => (part, &[][..]),
+    };
+    // A part without headers starts with the empty line
+    let (head, body) = match part {
+        [b'\r', b'\n', body @ ..] | [b'\n', body @ ..] => (&[][..], body),
+        _ => (head, body),
+    };
+
+    let mut headers: Vec<(String, String)> = vec![];
+    for line in String::from_utf8_lossy(head).lines() {
+        // Long header values are folded onto lines starting with whitespace
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+/// Header names are case-insensitive.
+fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Extracts a parameter like the `boundary` of `multipart/related; boundary="abc"`.
+fn header_parameter(value: &str, name: &str) -> Option<String> {
+    value
+        .split(';')
+        .skip(1)
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Returns the parts between the `--boundary`-lines, the preamble and epilogue are dropped.
+/// The line break before a boundary belongs to the boundary, not to the part.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = vec![];
+    let mut start = None;
+    let mut position = 0;
+    for line in body.split_inclusive(|&byte| byte == b'\n') {
+        let rest = line
+            .trim_ascii_end()
+            .strip_prefix(delimiter.as_bytes())
+            .map(<[u8]>::trim_ascii_end);
+        if let Some(rest @ (b"" | b"--")) = rest {
+            if let Some(start) = start {
+                parts.push(strip_line_break(&body[start..position]));
+            }
+            if rest == b"--" {
+                return parts;
+            }
+            start = Some(position + line.len());
+        }
+        position += line.len();
+    }
+    // A truncated multipart keeps its last part
+    parts.extend(start.map(|start| &body[start..]));
+    parts
+}
+
+fn strip_line_break(part: &[u8]) -> &[u8] {
+    let part = part.strip_suffix(b"\n").unwrap_or(part);
+    part.strip_suffix(b"\r").unwrap_or(part)
+}
+
+/// Decodes the body according to its `Content-Transfer-Encoding`. `7bit`, `8bit` and `binary` are returned as they are.
+fn decode_body(headers: &[(String, String)], body: &[u8]) -> Vec<u8> {
+    match header_value(headers, "Content-Transfer-Encoding").map(str::to_ascii_lowercase) {
+        Some(encoding) if encoding == "base64" => decode_base64(body),
+        Some(encoding) if encoding == "quoted-printable" => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    }
+}
+
+/// Decodes base64, skipping line breaks and any other characters outside of the alphabet.
+fn decode_base64(encoded: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(encoded.len() / 4 * 3);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in encoded {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => continue,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    decoded
+}
+
+/// Decodes `=XX`-escapes and removes soft line breaks (a `=` at the end of a line).
+/// Malformed escapes are kept as they are.
+fn decode_quoted_printable(encoded: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut rest = encoded;
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'=' {
+            decoded.push(byte);
+            continue;
+        }
+        match rest {
+            [b'\r', b'\n', tail @ ..] | [b'\n', tail @ ..] => rest = tail,
+            [high, low, tail @ ..] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                let digit = |byte: u8| (byte as char).to_digit(16).unwrap_or_default() as u8;
+                decoded.push(digit(*high) << 4 | digit(*low));
+                rest = tail;
+            }
+            _ => decoded.push(byte),
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MHTML: &[u8] = include_bytes!("../../test_files/mhtml/mhtml_test.mht");
+
+    #[test]
+    fn scrape_mhtml_test() {
+        let links = scrape_from_slice(TEST_MHTML).unwrap();
+        println!("{:?}", links);
+        let urls = |source: MhtmlLinkSource| {
+            links
+                .iter()
+                .filter(|link| link.source == source)
+                .map(|link| link.url.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            urls(MhtmlLinkSource::ResourceLocation),
+            vec![
+                "https://page.mhtml.test.com/",
+                "https://page.mhtml.test.com/style.css",
+                "https://img.mhtml.test.com/logo.png",
+                "cid:frame-1@mhtml.blink",
+            ]
+        );
+        let body_links = urls(MhtmlLinkSource::HtmlBody);
+        assert!(body_links.contains(&"https://page.mhtml.test.com/style.css"));
+        assert!(body_links.contains(&"https://link.mhtml.test.com/about"));
+        assert!(body_links.contains(&"https://text.mhtml.test.com/grüße"));
+        // The html of the frame is base64-encoded
+        let frame_link = links
+            .iter()
+            .find(|link| link.url == "https://frame.mhtml.test.com/")
+            .unwrap();
+        assert_eq!(
+            frame_link.part_location.as_deref(),
+            Some("cid:frame-1@mhtml.blink")
+        );
+        assert_eq!(
+            frame_link.html_kind,
+            Some(HtmlLinkKind::Attribute("href".to_string()))
+        );
+    }
+
+    #[test]
+    fn not_mhtml_test() {
+        assert!(matches!(
+            scrape_from_slice(b"<html></html>"),
+            Err(MhtmlScrapingError::NotMhtmlError)
+        ));
+        assert!(matches!(
+            scrape_from_slice(b"Content-Type: multipart/related\r\n\r\n"),
+            Err(MhtmlScrapingError::InvalidPartError(_))
+        ));
+    }
+
+    #[test]
+    fn decode_test() {
+        assert_eq!(
+            decode_base64(b"aHR0cHM6\r\nLy9h\r\nLmNvbQ=="),
+            b"https://a.com"
+        );
+        assert_eq!(
+            decode_quoted_printable(b"https://a.com/gr=C3=BC=\r\n=C3=9Fe =XY="),
+            "https://a.com/grüße =XY=".as_bytes()
+        );
+    }
+}
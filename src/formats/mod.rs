@@ -1,7 +1,74 @@
+#[cfg(feature = "apk")]
+/// Android (.apk) and iOS (.ipa) app packages
+pub mod apk;
+#[cfg(feature = "asciidoc")]
+/// .adoc
+pub mod asciidoc;
+#[cfg(feature = "chm")]
+/// Compiled HTML help files
+pub mod chm;
+#[cfg(feature = "clipboard")]
+/// HTML copied to the Windows clipboard (CF_HTML)
+pub mod clipboard;
 #[cfg(any(feature = "odf", feature = "ooxml"))]
 mod compressed_formats_common;
+#[cfg(feature = "css")]
+/// Stylesheets (.css)
+pub mod css;
+#[cfg(feature = "dicom")]
+/// DICOM medical images
+pub mod dicom;
+#[cfg(all(feature = "embedded_objects", any(feature = "odf", feature = "ooxml")))]
+mod embedded;
+#[cfg(feature = "fixed_width")]
+/// Fixed-width text with known column positions
+pub mod fixed_width;
+#[cfg(feature = "font")]
+/// .ttf, .otf, .ttc, .woff, .woff2
+pub mod font;
+#[cfg(feature = "geo")]
+/// .gpx, .kml
+pub mod geo;
+#[cfg(feature = "html")]
+/// HTML that is not well-formed XML
+pub mod html;
+#[cfg(feature = "crawler")]
+/// Raw HTTP-responses
+pub mod http_response;
 #[cfg(feature = "image")]
 pub mod image;
+#[cfg(any(feature = "css", feature = "xml", feature = "xlink"))]
+mod includes;
+#[cfg(feature = "ini")]
+/// .ini, .cfg, .properties
+pub mod ini;
+#[cfg(feature = "iwork")]
+/// .pages, .numbers, .key
+pub mod iwork;
+#[cfg(feature = "json")]
+/// .json
+pub mod json;
+#[cfg(feature = "json")]
+/// .jsonl, .ndjson
+pub mod jsonl;
+#[cfg(feature = "latex")]
+/// .tex, .bib
+pub mod latex;
+#[cfg(any(
+    feature = "xml",
+    feature = "xlink",
+    feature = "ooxml",
+    feature = "odf",
+    feature = "crawler",
+    feature = "iwork"
+))]
+mod limited_xml_reader;
+#[cfg(feature = "lnk")]
+/// Windows shortcuts
+pub mod lnk;
+#[cfg(feature = "mhtml")]
+/// Saved web pages (.mht, .mhtml)
+pub mod mhtml;
 #[cfg(feature = "odf")]
 /// .odt, .ods, .odp
 pub mod odf;
@@ -13,8 +80,40 @@ pub mod pdf;
 #[cfg(feature = "plaintext")]
 /// Any plaintext-format
 pub mod plaintext;
+#[cfg(feature = "plist")]
+/// Binary and XML property lists (.plist)
+pub mod plist;
+#[cfg(feature = "proto")]
+/// .proto, .textproto
+pub mod proto;
+#[cfg(all(
+    feature = "qr",
+    any(feature = "image", feature = "ooxml", feature = "odf", feature = "pdf")
+))]
+mod qr;
+#[cfg(feature = "references")]
+/// .ris, .enw, .bib
+pub mod references;
+#[cfg(feature = "crawler")]
+/// robots.txt and sitemaps
+pub mod robots;
+#[cfg(feature = "rst")]
+/// .rst
+pub mod rst;
 #[cfg(feature = "rtf")]
 pub mod rtf;
+#[cfg(feature = "shell")]
+/// Shell scripts, systemd units and .desktop-files
+pub mod shell;
+#[cfg(feature = "source")]
+/// Comments and string literals of source code
+pub mod source_code;
+#[cfg(feature = "torrent")]
+/// BitTorrent metainfo-files (.torrent)
+pub mod torrent;
+#[cfg(feature = "warc")]
+/// Web archives (.warc)
+pub mod warc;
 #[cfg(any(feature = "xml", feature = "xlink"))]
 /// Also contains xml-based formats
 pub mod xml;
@@ -1,13 +1,22 @@
-use crate::formats::compressed_formats_common::unified_unzip_scrape;
-use crate::formats::odf::OdfLinkKind::{Hyperlink, PlainText};
-use crate::helpers::find_urls;
+use crate::config::ScrapeConfig;
+use crate::formats::compressed_formats_common::{
+    group_by_entry, unified_unzip_scrape, ArchiveUrl, FromZipComment, LinkCollector,
+};
+pub use crate::formats::compressed_formats_common::{
+    ZipCommentSource, ZipEntryWarning, ZipScrapeResult,
+};
+use crate::formats::limited_xml_reader::LimitedXmlReader;
+use crate::formats::odf::OdfLinkKind::{Comment, Hyperlink, Metadata, PlainText};
+use crate::helpers::{find_urls, redact_url, sentence_around, AsUrl};
+use crate::links::HasLocation;
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::io::{Read, Seek};
+use std::sync::Arc;
 use thiserror::Error;
 use xml::common::{Position, TextPosition};
 use xml::reader::XmlEvent;
-use xml::EventReader;
 
 /// Scrapes all links from a given ooxml-file
 ///
@@ -17,13 +26,128 @@ pub fn scrape<R>(reader: R) -> Result<Vec<OdfLink>, OdfScrapingError>
 where
     R: Read + Seek,
 {
-    unified_unzip_scrape(reader, |reader, file_name, links| {
+    scrape_with_config(reader, &ScrapeConfig::default())
+}
+
+/// Like [`scrape`], but applies the given [`ScrapeConfig`].
+///
+/// With [`ScrapeConfig::structured_only`] the text is not searched for urls,
+/// so just [`OdfLinkKind::Hyperlink`]s are returned.
+pub fn scrape_with_config<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<OdfLink>, OdfScrapingError>
+where
+    R: Read + Seek,
+{
+    Ok(scrape_with_warnings(reader, config)?.links)
+}
+
+/// Like [`scrape`], but groups the links by the part of the document they were found in
+/// (e.g. `content.xml` or `meta.xml`).
+///
+/// The parts are sorted by name, the links of each part keep the order they were found in.
+pub fn scrape_grouped<R>(reader: R) -> Result<BTreeMap<String, Vec<OdfLink>>, OdfScrapingError>
+where
+    R: Read + Seek,
+{
+    Ok(group_by_entry(scrape(reader)?, |link| &link.location.file))
+}
+
+/// Like [`scrape_with_config`], but also returns the entries that were skipped
+/// because of [`ScrapeConfig::skip_corrupt_entries`].
+pub fn scrape_with_warnings<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<ZipScrapeResult<OdfLink>, OdfScrapingError>
+where
+    R: Read + Seek,
+{
+    scrape_with_progress(reader, config, |_, _, _| {})
+}
+
+/// Like [`scrape_with_warnings`], but calls `on_entry` with the name, index and total number of every entry
+/// of the archive before it is scraped, e.g. to show which part of a large document is being processed.
+pub fn scrape_with_progress<R, F>(
+    reader: R,
+    config: &ScrapeConfig,
+    mut on_entry: F,
+) -> Result<ZipScrapeResult<OdfLink>, OdfScrapingError>
+where
+    R: Read + Seek,
+    F: FnMut(&str, usize, usize),
+{
+    scrape_archive(reader, config, &mut on_entry)
+}
+
+/// Like [`scrape_with_warnings`], but links with identical urls share a single [`Arc<str>`],
+/// which saves memory for documents repeating the same url over and over.
+pub fn scrape_interned<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<ZipScrapeResult<OdfLink<Arc<str>>>, OdfScrapingError>
+where
+    R: Read + Seek,
+{
+    scrape_archive(reader, config, &mut |_, _, _| {})
+}
+
+fn scrape_archive<R, U>(
+    reader: R,
+    config: &ScrapeConfig,
+    on_entry: &mut dyn FnMut(&str, usize, usize),
+) -> Result<ZipScrapeResult<OdfLink<U>>, OdfScrapingError>
+where
+    R: Read + Seek,
+    U: ArchiveUrl,
+{
+    let extractor = |reader: &mut dyn Read,
+                     file_name: &str,
+                     links: &mut LinkCollector<OdfLink<U>>| {
+        #[cfg(feature = "embedded_objects")]
+        if config.embedded_object_depth > 0 && is_ole_object(file_name) {
+            return scrape_from_embedded_object(reader, file_name, config, links);
+        }
+        #[cfg(feature = "qr")]
+        if file_name.starts_with("Pictures/") && file_name.to_ascii_lowercase().ends_with(".png") {
+            return scrape_qr_code(reader, file_name, links);
+        }
         if file_name.ends_with(".xml") {
-            scrape_from_xml_file(reader, &file_name, links)
+            scrape_from_xml_file(
+                reader,
+                file_name,
+                !config.structured_only,
+                config.with_sentence,
+                links,
+            )
         } else {
             Ok(())
         }
-    })
+    };
+    let mut result = unified_unzip_scrape(reader, config, extractor, on_entry)?;
+    if let Some(policy) = config.redact {
+        for link in &mut result.links {
+            link.url = U::from(redact_url(link.url.as_ref(), policy));
+        }
+    }
+    Ok(result)
+}
+
+/// Like [`scrape`], but also returns the first `length` characters of the text in `content.xml` as a preview,
+/// collected while scraping instead of with a separate call to [`extract_text`].
+pub fn scrape_with_preview<R>(
+    reader: R,
+    length: usize,
+) -> Result<(Vec<OdfLink>, String), OdfScrapingError>
+where
+    R: Read + Seek,
+{
+    let config = ScrapeConfig {
+        preview_length: length,
+        ..ScrapeConfig::default()
+    };
+    let result = scrape_with_warnings(reader, &config)?;
+    Ok((result.links, result.preview))
 }
 gen_scrape_from_file!(scrape(Read) -> Result<Vec<OdfLink>, OdfScrapingError>);
 gen_scrape_from_slice!(scrape(Read) -> Result<Vec<OdfLink>, OdfScrapingError>);
@@ -33,21 +157,70 @@ pub enum OdfScrapingError {
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
+    InputTooLargeError(#[from] crate::config::InputTooLargeError),
+    #[error(transparent)]
+    ResourceLimitExceeded(#[from] crate::config::ResourceLimitExceededError),
+    #[error(transparent)]
     XmlReaderError(#[from] xml::reader::Error),
     #[error(transparent)]
     ZipError(#[from] zip::result::ZipError),
+    #[cfg(feature = "embedded_objects")]
+    #[error(transparent)]
+    EmbeddedObjectError(Box<crate::any_format_scraper::LinkScrapingError>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct OdfLink {
-    pub url: String,
+pub struct OdfLink<U = String> {
+    /// A [`String`], unless scraped with [`scrape_interned`]
+    pub url: U,
     pub location: OdfLinkLocation,
     pub kind: OdfLinkKind,
+    /// Position of this link in the order it was found across all parts of the document.
+    /// Links dropped by the filters of [`ScrapeConfig`] leave gaps.
+    pub sequence: usize,
+    /// The sentence containing the link. Only set with [`ScrapeConfig::with_sentence`] for links inside the text
+    pub sentence: Option<String>,
 }
 
-impl Display for OdfLink {
+impl<U: AsRef<str>> Display for OdfLink<U> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.url)
+        write!(f, "{}", self.url.as_ref())
+    }
+}
+
+impl<U: ArchiveUrl> FromZipComment for OdfLink<U> {
+    type Url = U;
+
+    fn from_zip_comment(url: U, source: ZipCommentSource, entry: &str, sequence: usize) -> Self {
+        OdfLink {
+            url,
+            location: OdfLinkLocation {
+                file: entry.to_string(),
+                position: TextPosition::new(),
+                cell: None,
+            },
+            kind: OdfLinkKind::ZipComment(source),
+            sequence,
+            sentence: None,
+        }
+    }
+}
+
+impl<U: AsRef<str>> AsUrl for OdfLink<U> {
+    fn url(&self) -> &str {
+        self.url.as_ref()
+    }
+}
+
+impl<U> HasLocation for OdfLink<U> {
+    type Location = (String, u64, u64);
+
+    fn location(&self) -> Self::Location {
+        (
+            self.location.file.clone(),
+            self.location.position.row,
+            self.location.position.column,
+        )
     }
 }
 
@@ -56,14 +229,32 @@ impl Display for OdfLink {
 pub struct OdfLinkLocation {
     pub file: String,
     pub position: TextPosition,
+    /// The table-cell containing the link as `<table>.<column><row>`, e.g. `Sheet1.B2` for a note of a spreadsheet-cell.
+    /// Only set for links inside the cells of `content.xml`
+    pub cell: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OdfLinkKind {
-    /// The link is contained as Text or as a Comment inside the document
+    /// The link is contained as Text inside the document
     PlainText,
     /// The link is contained as a Hyperlink inside the document
     Hyperlink,
+    /// The link is contained in a Comment (`office:annotation`), e.g. a note of a spreadsheet-cell.
+    /// Takes precedence over [`OdfLinkKind::Hyperlink`] for hyperlinks inside a comment
+    Comment,
+    /// The link is contained in a comment of the zip-archive, not the document itself.
+    /// Only found with [`ScrapeConfig::scrape_zip_comments`]
+    ZipComment(ZipCommentSource),
+    /// The link is contained in the document properties (`meta.xml`)
+    Metadata,
+    /// The link was found inside an object embedded into the document (e.g. a spreadsheet inside a text document).
+    /// The location is the path of the embedded object inside the package.
+    /// Only found with [`ScrapeConfig::embedded_object_depth`] and the `embedded_objects` feature
+    EmbeddedObject,
+    /// The link is the payload of a QR-code in an image of the document (e.g. `Pictures/image1.png`).
+    /// Only found with [`ScrapeConfig::qr_code_images`] and the `qr` feature
+    EmbeddedImageQr,
 }
 
 /// Scrapes all links from a given odf file.
@@ -77,18 +268,122 @@ where
         .map_err(|e| OdfScrapingError::from(e))
 }
 
+/// Extracts the plain text of a given odf-file, one paragraph per line.
+///
+/// Only `content.xml` is read, so headers, footers and the document properties are not part of the text.
+pub fn extract_text<R>(reader: R) -> Result<String, OdfScrapingError>
+where
+    R: Read + Seek,
+{
+    crate::formats::compressed_formats_common::extract_text(reader, |file_name| {
+        file_name == "content.xml"
+    })
+}
+
+/// Embedded ODF-documents are stored as `Object 1/content.xml` and are scraped like the document itself,
+/// while OLE-objects (e.g. a spreadsheet from MS Office) are stored as a single `Object 1`-file.
+#[cfg(feature = "embedded_objects")]
+fn is_ole_object(file_name: &str) -> bool {
+    file_name.starts_with("Object ") && !file_name.contains('/')
+}
+
+#[cfg(feature = "embedded_objects")]
+fn scrape_from_embedded_object<U: ArchiveUrl>(
+    data: impl Read,
+    file_name: &str,
+    config: &ScrapeConfig,
+    collector: &mut LinkCollector<OdfLink<U>>,
+) -> Result<(), OdfScrapingError> {
+    crate::formats::embedded::scrape_embedded_object(data, config)
+        .map_err(|e| OdfScrapingError::EmbeddedObjectError(Box::new(e)))?
+        .iter()
+        .for_each(|url| {
+            let url = collector.url(url);
+            let sequence = collector.next_sequence();
+            collector.links.push(OdfLink {
+                url,
+                location: OdfLinkLocation {
+                    file: file_name.to_string(),
+                    position: TextPosition::new(),
+                    cell: None,
+                },
+                kind: OdfLinkKind::EmbeddedObject,
+                sequence,
+                sentence: None,
+            })
+        });
+    Ok(())
+}
+
+/// Scrapes the QR-code of a PNG-image inside `Pictures/`.
+#[cfg(feature = "qr")]
+fn scrape_qr_code<U: ArchiveUrl>(
+    data: &mut dyn Read,
+    file_name: &str,
+    collector: &mut LinkCollector<OdfLink<U>>,
+) -> Result<(), OdfScrapingError> {
+    for url in collector.qr_code_urls(data)? {
+        let sequence = collector.next_sequence();
+        collector.links.push(OdfLink {
+            url,
+            location: OdfLinkLocation {
+                file: file_name.to_string(),
+                position: TextPosition::new(),
+                cell: None,
+            },
+            kind: OdfLinkKind::EmbeddedImageQr,
+            sequence,
+            sentence: None,
+        });
+    }
+    Ok(())
+}
+
 /// Scrapes links from given .xml file-text
 ///
 /// All tags and tag-attributes are omitted to filter out functional urls.
 /// This might be too aggressive in some cases though
-fn scrape_from_xml_file(
+///
+/// Sentences are segmented per text-node, so a `<text:span>` inside a sentence cuts it short.
+fn scrape_from_xml_file<U: ArchiveUrl>(
     data: impl Read,
     filename: &str,
-    collector: &mut Vec<OdfLink>,
+    include_text: bool,
+    with_sentence: bool,
+    collector: &mut LinkCollector<OdfLink<U>>,
 ) -> Result<(), OdfScrapingError> {
-    let mut parser = EventReader::new(data);
+    let mut parser = LimitedXmlReader::new(data);
+    let is_preview_part = filename == "content.xml";
+    // Links inside comments (`<office:annotation>`) are returned as such and are not part of the preview
+    let mut annotation_depth = 0;
+    let mut cells = CellTracker::default();
 
-    while let Ok(xml_event) = &parser.next() {
+    loop {
+        let xml_event = &parser.next_or_end::<OdfScrapingError>(collector.lenient_xml())?;
+        match xml_event {
+            XmlEvent::StartElement { name, .. } if name.local_name == "annotation" => {
+                annotation_depth += 1
+            }
+            XmlEvent::EndElement { name } if name.local_name == "annotation" => {
+                annotation_depth -= 1
+            }
+            _ => {}
+        }
+        if is_preview_part {
+            cells.track(xml_event);
+            match xml_event {
+                XmlEvent::Characters(chars) if annotation_depth == 0 => {
+                    collector.push_preview(chars)
+                }
+                // Paragraphs and headings are separated by a space
+                XmlEvent::EndElement { name }
+                    if annotation_depth == 0 && matches!(name.local_name.as_str(), "p" | "h") =>
+                {
+                    collector.push_preview(" ")
+                }
+                _ => {}
+            }
+        }
         match xml_event {
             XmlEvent::StartElement {
                 name, attributes, ..
@@ -102,29 +397,48 @@ fn scrape_from_xml_file(
                     .find(|&attr| attr.name.local_name == "href");
                 if let Some(href) = maybe_href {
                     let link = OdfLink {
-                        url: href.value.to_string(),
+                        url: collector.url(&href.value),
                         location: OdfLinkLocation {
                             file: filename.to_string(),
                             position: parser.position(),
+                            cell: cells.current(),
+                        },
+                        kind: if annotation_depth > 0 {
+                            Comment
+                        } else {
+                            Hyperlink
                         },
-                        kind: Hyperlink,
+                        sequence: collector.next_sequence(),
+                        sentence: None,
                     };
-                    collector.push(link);
+                    collector.links.push(link);
                 }
             }
-            XmlEvent::Characters(chars) => collector.append(
-                &mut find_urls(&chars)
-                    .iter()
-                    .map(|link| OdfLink {
-                        url: link.as_str().to_string(),
+            XmlEvent::Characters(chars) if include_text => {
+                let chars = collector.clean(chars);
+                find_urls(&chars).iter().for_each(|link| {
+                    let url = collector.url(link.as_str());
+                    let sequence = collector.next_sequence();
+                    collector.links.push(OdfLink {
+                        url,
                         location: OdfLinkLocation {
                             file: filename.to_string(),
                             position: parser.position(),
+                            cell: cells.current(),
                         },
-                        kind: PlainText,
+                        kind: if filename == "meta.xml" {
+                            Metadata
+                        } else if annotation_depth > 0 {
+                            Comment
+                        } else {
+                            PlainText
+                        },
+                        sequence,
+                        sentence: with_sentence
+                            .then(|| sentence_around(&chars, link.start()..link.end()).to_string()),
                     })
-                    .collect(),
-            ),
+                })
+            }
             XmlEvent::EndDocument => break,
             _ => {}
         };
@@ -132,13 +446,124 @@ fn scrape_from_xml_file(
     Ok(())
 }
 
+const TABLE_NAMESPACE: &str = "urn:oasis:names:tc:opendocument:xmlns:table:1.0";
+
+/// Tracks the table-cell the parser is in, see [`OdfLinkLocation::cell`].
+///
+/// Rows and cells repeated with `table:number-rows-repeated` or `table:number-columns-repeated` are counted as often
+/// as they are repeated. Tables nested inside a cell are tracked as well.
+#[derive(Default)]
+struct CellTracker {
+    tables: Vec<TableCursor>,
+}
+
+/// The position inside a table: the 0-based row and column of the next cell and whether a cell is open.
+struct TableCursor {
+    name: String,
+    row: u64,
+    column: u64,
+    rows_repeated: u64,
+    columns_repeated: u64,
+    in_cell: bool,
+}
+
+impl CellTracker {
+    fn track(&mut self, xml_event: &XmlEvent) {
+        match xml_event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } if name.namespace.as_deref() == Some(TABLE_NAMESPACE) => {
+                let attribute = |local_name: &str| {
+                    attributes
+                        .iter()
+                        .find(|attribute| attribute.name.local_name == local_name)
+                        .map(|attribute| attribute.value.as_str())
+                };
+                let repeated = |local_name: &str| {
+                    attribute(local_name)
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or(1)
+                };
+                match name.local_name.as_str() {
+                    "table" => self.tables.push(TableCursor {
+                        name: attribute("name").unwrap_or_default().to_string(),
+                        row: 0,
+                        column: 0,
+                        rows_repeated: 1,
+                        columns_repeated: 1,
+                        in_cell: false,
+                    }),
+                    "table-row" => {
+                        if let Some(table) = self.tables.last_mut() {
+                            table.column = 0;
+                            table.rows_repeated = repeated("number-rows-repeated");
+                        }
+                    }
+                    "table-cell" | "covered-table-cell" => {
+                        if let Some(table) = self.tables.last_mut() {
+                            table.columns_repeated = repeated("number-columns-repeated");
+                            table.in_cell = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::EndElement { name } if name.namespace.as_deref() == Some(TABLE_NAMESPACE) => {
+                match name.local_name.as_str() {
+                    "table" => {
+                        self.tables.pop();
+                    }
+                    "table-row" => {
+                        if let Some(table) = self.tables.last_mut() {
+                            table.row = table.row.saturating_add(table.rows_repeated);
+                        }
+                    }
+                    "table-cell" | "covered-table-cell" => {
+                        if let Some(table) = self.tables.last_mut() {
+                            table.column = table.column.saturating_add(table.columns_repeated);
+                            table.in_cell = false;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the reference of the innermost open cell, e.g. `Sheet1.B2`.
+    fn current(&self) -> Option<String> {
+        let table = self.tables.iter().rev().find(|table| table.in_cell)?;
+        Some(format!(
+            "{}.{}{}",
+            table.name,
+            column_name(table.column),
+            table.row + 1
+        ))
+    }
+}
+
+/// Converts a 0-based column index into its letters, e.g. `0` into `A` and `27` into `AB`.
+fn column_name(mut column: u64) -> String {
+    let mut letters = vec![];
+    loop {
+        letters.push(b'A' + (column % 26) as u8);
+        if column < 26 {
+            break;
+        }
+        column = column / 26 - 1;
+    }
+    letters.iter().rev().map(|&letter| letter as char).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::include_bytes;
-    use std::io::Cursor;
+    use std::io::{Cursor, Write};
 
     const TEST_ODT: &[u8] = include_bytes!("../../test_files/odf/odt_test.odt");
+    const TEST_ODT_METADATA: &[u8] = include_bytes!("../../test_files/odf/odt_metadata_test.odt");
     const TEST_ODS: &[u8] = include_bytes!("../../test_files/odf/ods_test.ods");
     const TEST_ODP: &[u8] = include_bytes!("../../test_files/odf/odp_test.odp");
     const TEST_OTT: &[u8] = include_bytes!("../../test_files/odf/ott_test.ott");
@@ -155,6 +580,30 @@ mod tests {
             .any(|it| it.url == "https://hyperlink.test.com/" && it.kind == Hyperlink));
     }
 
+    #[test]
+    fn scrape_with_progress_test() {
+        let mut indices = vec![];
+        scrape_with_progress(
+            std::io::Cursor::new(TEST_ODT),
+            &ScrapeConfig::default(),
+            |_, index, total| indices.push((index, total)),
+        )
+        .unwrap();
+        let total = zip::ZipArchive::new(std::io::Cursor::new(TEST_ODT))
+            .unwrap()
+            .len();
+        assert_eq!(indices, (0..total).map(|i| (i, total)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn scrape_grouped_test() {
+        let groups = scrape_grouped(std::io::Cursor::new(TEST_ODT)).unwrap();
+        println!("{:?}", groups.keys());
+        assert!(groups["content.xml"]
+            .iter()
+            .any(|it| it.url == "https://hyperlink.test.com/"));
+    }
+
     #[test]
     pub fn scrape_ods_test() {
         let links = scrape_from_slice(TEST_ODS).unwrap();
@@ -167,6 +616,38 @@ mod tests {
             .any(|it| it.url == "https://hyperlink.test.com/" && it.kind == Hyperlink));
     }
 
+    #[test]
+    pub fn cell_comment_test() {
+        let links = scrape_from_slice(TEST_ODS).unwrap();
+        let comments = links
+            .iter()
+            .filter(|it| it.kind == Comment)
+            .map(|it| (it.url.as_str(), it.location.cell.as_deref()))
+            .collect::<Vec<_>>();
+        println!("{:?}", comments);
+        // The note of `E8` holds a hyperlink whose text is the url again
+        assert_eq!(
+            comments,
+            [
+                ("https://comment.test.com/", Some("Sheet1.E8")),
+                ("https://comment.test.com", Some("Sheet1.E8"))
+            ]
+        );
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://hyperlink.test.com/"
+                && it.location.cell.as_deref() == Some("Sheet1.A2")));
+    }
+
+    #[test]
+    fn column_name_test() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(column_name(702), "AAA");
+    }
+
     #[test]
     pub fn scrape_odp_test() {
         let links = scrape_from_slice(TEST_ODP).unwrap();
@@ -191,9 +672,106 @@ mod tests {
             .any(|it| it.url == "https://hyperlink.test.com/" && it.kind == Hyperlink));
     }
 
+    #[test]
+    pub fn scrape_metadata_test() {
+        let links = scrape_from_slice(TEST_ODT_METADATA).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://metadata.test.com/source" && it.kind == Metadata));
+    }
+
+    #[test]
+    pub fn sequence_test() {
+        let links = scrape_from_slice(TEST_ODT).unwrap();
+        assert!(links
+            .iter()
+            .enumerate()
+            .all(|(index, link)| link.sequence == index));
+    }
+
+    #[test]
+    pub fn intern_urls_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file("content.xml", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"<document>").unwrap();
+        for _ in 0..200 {
+            writer
+                .write_all(b"<a href=\"https://footer.test.com\">https://footer.test.com</a>")
+                .unwrap();
+        }
+        writer.write_all(b"</document>").unwrap();
+        writer.finish().unwrap();
+        let repetitive_odt = buffer.into_inner();
+
+        let links = scrape_interned(Cursor::new(&repetitive_odt), &ScrapeConfig::default())
+            .unwrap()
+            .links;
+        assert_eq!(links.len(), 400);
+        assert!(links.iter().all(|it| Arc::ptr_eq(&it.url, &links[0].url)));
+    }
+
+    #[test]
+    pub fn strict_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file("content.xml", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer
+            .write_all(b"<document><p>https://good.test.com</p><p>https://cut")
+            .unwrap();
+        writer.finish().unwrap();
+        let truncated_odt = buffer.into_inner();
+
+        let links = scrape_from_slice(&truncated_odt).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://good.test.com");
+
+        let config = ScrapeConfig {
+            strict: true,
+            ..ScrapeConfig::default()
+        };
+        assert!(matches!(
+            scrape_with_config(Cursor::new(&truncated_odt), &config),
+            Err(OdfScrapingError::XmlReaderError(_))
+        ));
+    }
+
     #[test]
     pub fn scrape_unfiltered_test() {
         let links = scrape_unfiltered(Cursor::new(TEST_ODT)).unwrap();
         assert_eq!(links.len(), 47);
     }
+
+    #[test]
+    pub fn extract_text_test() {
+        let text = extract_text(Cursor::new(TEST_ODT)).unwrap();
+        println!("{:?}", text);
+        assert!(text.contains("Hyperlink Test"));
+        assert!(text.contains("https://plaintext.test.com"));
+    }
+
+    #[test]
+    pub fn scrape_with_preview_test() {
+        let (links, preview) = scrape_with_preview(Cursor::new(TEST_ODT), 200).unwrap();
+        println!("{:?}", preview);
+        assert_eq!(links.len(), scrape_from_slice(TEST_ODT).unwrap().len());
+        // The comment is not part of the preview
+        assert_eq!(preview, "https://plaintext.test.com Hyperlink Test");
+    }
+
+    #[test]
+    pub fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/odf/odt_test.odt").unwrap();
+        let from_slice = scrape_from_slice(TEST_ODT).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,13 +1,26 @@
-use crate::formats::compressed_formats_common::unified_unzip_scrape;
-use crate::formats::ooxml::OoxmlLinkKind::{Comment, Hyperlink, PlainText};
-use crate::helpers::find_urls;
+use crate::config::{AlternateContentBranch, ScrapeConfig};
+use crate::formats::compressed_formats_common::{
+    group_by_entry, unified_unzip_scrape_with_unfiltered, ArchiveUrl, FromZipComment, LinkCollector,
+};
+pub use crate::formats::compressed_formats_common::{
+    ZipCommentSource, ZipEntryWarning, ZipScrapeResult,
+};
+use crate::formats::limited_xml_reader::LimitedXmlReader;
+use crate::formats::ooxml::OoxmlLinkKind::{
+    Comment, ExternalMedia, ExternalReference, Hyperlink, InternalReference, Metadata, PlainText,
+};
+use crate::helpers::{find_urls, redact_url, sentence_around, AsUrl};
+use crate::links::HasLocation;
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::io::{Cursor, Read, Seek};
+use std::sync::Arc;
 use thiserror::Error;
+use xml::attribute::OwnedAttribute;
 use xml::common::{Position, TextPosition};
+use xml::name::OwnedName;
 use xml::reader::XmlEvent;
-use xml::EventReader;
 
 /// Scrapes all links from a given ooxml-file
 ///
@@ -17,15 +30,146 @@ pub fn scrape<R>(reader: R) -> Result<Vec<OoxmlLink>, OoxmlScrapingError>
 where
     R: Read + Seek,
 {
-    unified_unzip_scrape(reader, |reader, file_name, links| {
-        if file_name.ends_with(".rels") {
-            scrape_from_rels_file(reader, &file_name, links)
-        } else if file_name.ends_with(".xml") {
-            scrape_from_xml_file(reader, &file_name, links)
-        } else {
-            Ok(())
+    scrape_with_config(reader, &ScrapeConfig::default())
+}
+
+/// Like [`scrape`], but applies the given [`ScrapeConfig`].
+///
+/// With [`ScrapeConfig::structured_only`] only the relationship-files are scraped,
+/// so just [`OoxmlLinkKind::Hyperlink`]s, [`OoxmlLinkKind::ExternalMedia`] and [`OoxmlLinkKind::ExternalReference`]s
+/// are returned.
+///
+/// With [`ScrapeConfig::internal_references`] the targets of internal relationships are returned as well,
+/// resolved to their full path inside the package (e.g. `word/media/image1.png`).
+pub fn scrape_with_config<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<OoxmlLink>, OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    Ok(scrape_with_warnings(reader, config)?.links)
+}
+
+/// Like [`scrape`], but groups the links by the part of the document they were found in
+/// (e.g. `word/document.xml`, `word/comments.xml` or `word/_rels/document.xml.rels`).
+///
+/// The parts are sorted by name, the links of each part keep the order they were found in.
+pub fn scrape_grouped<R>(reader: R) -> Result<BTreeMap<String, Vec<OoxmlLink>>, OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    Ok(group_by_entry(scrape(reader)?, |link| &link.location.file))
+}
+
+/// Like [`scrape_with_config`], but also returns the entries that were skipped
+/// because of [`ScrapeConfig::skip_corrupt_entries`].
+pub fn scrape_with_warnings<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<ZipScrapeResult<OoxmlLink>, OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    Ok(scrape_archive(reader, config, false, &mut |_, _, _| {})?.0)
+}
+
+/// Like [`scrape_with_warnings`], but calls `on_entry` with the name, index and total number of every entry
+/// of the archive before it is scraped, e.g. to show which part of a large document is being processed.
+pub fn scrape_with_progress<R, F>(
+    reader: R,
+    config: &ScrapeConfig,
+    mut on_entry: F,
+) -> Result<ZipScrapeResult<OoxmlLink>, OoxmlScrapingError>
+where
+    R: Read + Seek,
+    F: FnMut(&str, usize, usize),
+{
+    Ok(scrape_archive(reader, config, false, &mut on_entry)?.0)
+}
+
+/// Like [`scrape`], but also returns the first `length` characters of the main text as a preview,
+/// collected while scraping instead of with a separate call to [`extract_text`].
+///
+/// See [`ScrapeConfig::preview_length`] for which parts of the document the preview is taken from.
+pub fn scrape_with_preview<R>(
+    reader: R,
+    length: usize,
+) -> Result<(Vec<OoxmlLink>, String), OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    let config = ScrapeConfig {
+        preview_length: length,
+        ..ScrapeConfig::default()
+    };
+    let result = scrape_with_warnings(reader, &config)?;
+    Ok((result.links, result.preview))
+}
+
+/// Returns the results of [`scrape`] and [`scrape_unfiltered`] at once, traversing the archive just once.
+///
+/// Useful to compare what the filtering dropped, without unzipping and parsing the document twice.
+pub fn scrape_both<R>(reader: R) -> Result<(Vec<OoxmlLink>, Vec<String>), OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    let (result, unfiltered) =
+        scrape_archive(reader, &ScrapeConfig::default(), true, &mut |_, _, _| {})?;
+    Ok((result.links, unfiltered))
+}
+
+/// Like [`scrape_with_warnings`], but links with identical urls share a single [`Arc<str>`],
+/// which saves memory for documents repeating the same url over and over.
+pub fn scrape_interned<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<ZipScrapeResult<OoxmlLink<Arc<str>>>, OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    Ok(scrape_archive(reader, config, false, &mut |_, _, _| {})?.0)
+}
+
+fn scrape_archive<R, U>(
+    reader: R,
+    config: &ScrapeConfig,
+    collect_unfiltered: bool,
+    on_entry: &mut dyn FnMut(&str, usize, usize),
+) -> Result<(ZipScrapeResult<OoxmlLink<U>>, Vec<String>), OoxmlScrapingError>
+where
+    R: Read + Seek,
+    U: ArchiveUrl,
+{
+    let (mut result, unfiltered) = unified_unzip_scrape_with_unfiltered(
+        reader,
+        config,
+        collect_unfiltered,
+        |reader, file_name, links: &mut LinkCollector<OoxmlLink<U>>| {
+            #[cfg(feature = "embedded_objects")]
+            if config.embedded_object_depth > 0 && file_name.contains("/embeddings/") {
+                return scrape_from_embedded_object(reader, file_name, config, links);
+            }
+            #[cfg(feature = "qr")]
+            if file_name.contains("/media/") && file_name.to_ascii_lowercase().ends_with(".png") {
+                return scrape_qr_code(reader, file_name, links);
+            }
+            if file_name.ends_with(".rels") {
+                scrape_from_rels_file(reader, file_name, config.internal_references, links)
+            } else if file_name.ends_with(".xml") && !config.structured_only {
+                scrape_from_xml_file(reader, file_name, config, links)
+            } else {
+                Ok(())
+            }
+        },
+        on_entry,
+    )?;
+    if let Some(policy) = config.redact {
+        for link in &mut result.links {
+            link.url = U::from(redact_url(link.url.as_ref(), policy));
         }
-    })
+    }
+    Ok((result, unfiltered))
 }
 gen_scrape_from_file!(scrape(Read) -> Result<Vec<OoxmlLink>, OoxmlScrapingError>);
 gen_scrape_from_slice!(scrape(Read) -> Result<Vec<OoxmlLink>, OoxmlScrapingError>);
@@ -35,21 +179,93 @@ pub enum OoxmlScrapingError {
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
+    InputTooLargeError(#[from] crate::config::InputTooLargeError),
+    #[error(transparent)]
+    ResourceLimitExceeded(#[from] crate::config::ResourceLimitExceededError),
+    #[error(transparent)]
     XmlReaderError(#[from] xml::reader::Error),
     #[error(transparent)]
     ZipError(#[from] zip::result::ZipError),
+    #[cfg(feature = "embedded_objects")]
+    #[error(transparent)]
+    EmbeddedObjectError(Box<crate::any_format_scraper::LinkScrapingError>),
 }
 
 #[derive(Debug, Clone)]
-pub struct OoxmlLink {
-    pub url: String,
+pub struct OoxmlLink<U = String> {
+    /// A [`String`], unless scraped with [`scrape_interned`]
+    pub url: U,
     pub location: OoxmlLinkLocation,
     pub kind: OoxmlLinkKind,
+    /// Position of this link in the order it was found across all parts of the document.
+    /// Links dropped by the filters of [`ScrapeConfig`] leave gaps.
+    pub sequence: usize,
+    /// The sentence containing the link. Only set with [`ScrapeConfig::with_sentence`] for links inside the text
+    pub sentence: Option<String>,
+    /// The whole value of the relationship-attribute the link was found in. `None` for links found anywhere else
+    pub raw_value: Option<String>,
+    /// The tracked change (`w:ins` or `w:del`) the text containing the link is part of.
+    /// Hyperlinks are stored in the relationships of a part, so they are never attributed to a change
+    pub revision: Option<Revision>,
+}
+
+/// A tracked change of a Word-document, recorded while reviewing it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Revision {
+    pub kind: RevisionKind,
+    /// The `w:author` of the change, empty if it was not recorded
+    pub author: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RevisionKind {
+    /// The text was inserted (`w:ins`)
+    Inserted,
+    /// The text was deleted (`w:del`), but the deletion has not been accepted yet
+    Deleted,
 }
 
-impl Display for OoxmlLink {
+impl<U: AsRef<str>> Display for OoxmlLink<U> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.url)
+        write!(f, "{}", self.url.as_ref())
+    }
+}
+
+impl<U: ArchiveUrl> FromZipComment for OoxmlLink<U> {
+    type Url = U;
+
+    fn from_zip_comment(url: U, source: ZipCommentSource, entry: &str, sequence: usize) -> Self {
+        OoxmlLink {
+            url,
+            location: OoxmlLinkLocation {
+                file: entry.to_string(),
+                position: TextPosition::new(),
+                shared_string: None,
+            },
+            kind: OoxmlLinkKind::ZipComment(source),
+            sequence,
+            sentence: None,
+            raw_value: None,
+            revision: None,
+        }
+    }
+}
+
+impl<U: AsRef<str>> AsUrl for OoxmlLink<U> {
+    fn url(&self) -> &str {
+        self.url.as_ref()
+    }
+}
+
+impl<U> HasLocation for OoxmlLink<U> {
+    type Location = (String, u64, u64);
+
+    fn location(&self) -> Self::Location {
+        (
+            self.location.file.clone(),
+            self.location.position.row,
+            self.location.position.column,
+        )
     }
 }
 
@@ -58,9 +274,19 @@ impl Display for OoxmlLink {
 pub struct OoxmlLinkLocation {
     pub file: String,
     pub position: TextPosition,
+    /// The index of the shared string (`<si>`) of `xl/sharedStrings.xml` containing the link.
+    /// See [`shared_string_cells`] for the cells using it. `None` for links found anywhere else
+    pub shared_string: Option<usize>,
+}
+
+impl OoxmlLinkLocation {
+    /// Returns whether the link was found by [`scrape_from_text`], so its location inside the package is unknown.
+    pub fn is_from_text(&self) -> bool {
+        self.file.is_empty()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OoxmlLinkKind {
     /// The link is contained as Text inside the document
     PlainText,
@@ -68,6 +294,142 @@ pub enum OoxmlLinkKind {
     Hyperlink,
     /// The link is contained as a Comment added to the document
     Comment,
+    /// The link is contained in a comment of the zip-archive, not the document itself.
+    /// Only found with [`ScrapeConfig::scrape_zip_comments`]
+    ZipComment(ZipCommentSource),
+    /// The link is contained in the document properties (`docProps/core.xml`, `docProps/app.xml`, `docProps/custom.xml`)
+    Metadata,
+    /// The link is the target of an external image-, audio-, video- or OLE-object-relationship,
+    /// i.e. media that is linked instead of embedded and loaded whenever the document is opened
+    ExternalMedia,
+    /// The link is the target of any other external relationship that is not a hyperlink,
+    /// e.g. a template or an external data connection
+    ExternalReference,
+    /// The link is the target of an internal relationship, resolved to its path inside the package.
+    /// Only found with [`ScrapeConfig::internal_references`]
+    InternalReference,
+    /// The link was found inside an object embedded into the document (e.g. a spreadsheet inside a text document).
+    /// The location is the path of the embedded object inside the package.
+    /// Only found with [`ScrapeConfig::embedded_object_depth`] and the `embedded_objects` feature
+    EmbeddedObject,
+    /// The link is the payload of a QR-code in an image of the document (e.g. `word/media/image1.png`).
+    /// Only found with [`ScrapeConfig::qr_code_images`] and the `qr` feature
+    EmbeddedImageQr,
+}
+
+/// Scrapes the QR-code of a PNG-image inside `word/media/`, `xl/media/` or `ppt/media/`.
+#[cfg(feature = "qr")]
+fn scrape_qr_code<U: ArchiveUrl>(
+    data: &mut dyn Read,
+    file_name: &str,
+    collector: &mut LinkCollector<OoxmlLink<U>>,
+) -> Result<(), OoxmlScrapingError> {
+    for url in collector.qr_code_urls(data)? {
+        let sequence = collector.next_sequence();
+        collector.links.push(OoxmlLink {
+            url,
+            location: OoxmlLinkLocation {
+                file: file_name.to_string(),
+                position: TextPosition::new(),
+                shared_string: None,
+            },
+            kind: OoxmlLinkKind::EmbeddedImageQr,
+            sequence,
+            sentence: None,
+            raw_value: None,
+            revision: None,
+        });
+    }
+    Ok(())
+}
+
+/// Scrapes an object inside `word/embeddings/`, `xl/embeddings/` or `ppt/embeddings/`.
+#[cfg(feature = "embedded_objects")]
+fn scrape_from_embedded_object<U: ArchiveUrl>(
+    data: impl Read,
+    file_name: &str,
+    config: &ScrapeConfig,
+    collector: &mut LinkCollector<OoxmlLink<U>>,
+) -> Result<(), OoxmlScrapingError> {
+    crate::formats::embedded::scrape_embedded_object(data, config)
+        .map_err(|e| OoxmlScrapingError::EmbeddedObjectError(Box::new(e)))?
+        .iter()
+        .for_each(|url| {
+            let url = collector.url(url);
+            let sequence = collector.next_sequence();
+            collector.links.push(OoxmlLink {
+                url,
+                location: OoxmlLinkLocation {
+                    file: file_name.to_string(),
+                    position: TextPosition::new(),
+                    shared_string: None,
+                },
+                kind: OoxmlLinkKind::EmbeddedObject,
+                sequence,
+                sentence: None,
+                raw_value: None,
+                revision: None,
+            })
+        });
+    Ok(())
+}
+
+/// Extracts the plain text of a given ooxml-file, one paragraph per line.
+///
+/// Only the body of documents (including headers, footers, foot- and endnotes), the slides of presentations
+/// and the shared strings of spreadsheets are read. Comments and document properties are not part of the text.
+pub fn extract_text<R>(reader: R) -> Result<String, OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    crate::formats::compressed_formats_common::extract_text(reader, is_text_part)
+}
+
+/// Scrapes links from text previously returned by [`extract_text`], without parsing the document again.
+///
+/// All links are [`OoxmlLinkKind::PlainText`]. Their part of the document is unknown,
+/// so their location is empty (see [`OoxmlLinkLocation::is_from_text`]).
+pub fn scrape_from_text(text: &str) -> Vec<OoxmlLink> {
+    find_urls(text)
+        .iter()
+        .enumerate()
+        .map(|(sequence, link)| OoxmlLink {
+            url: link.as_str().to_string(),
+            location: OoxmlLinkLocation {
+                file: String::new(),
+                position: TextPosition::new(),
+                shared_string: None,
+            },
+            kind: OoxmlLinkKind::PlainText,
+            sequence,
+            sentence: None,
+            raw_value: None,
+            revision: None,
+        })
+        .collect()
+}
+
+/// The parts holding the main text: the body of documents, the slides of presentations and the shared strings.
+fn is_preview_part(file_name: &str) -> bool {
+    file_name == "word/document.xml"
+        || file_name == "xl/sharedStrings.xml"
+        || file_name
+            .strip_prefix("ppt/slides/slide")
+            .is_some_and(|name| name.ends_with(".xml") && !name.contains('/'))
+}
+
+fn is_text_part(file_name: &str) -> bool {
+    let Some(name) = file_name.strip_suffix(".xml") else {
+        return false;
+    };
+    match name.rsplit_once('/') {
+        Some(("word", part)) => ["document", "header", "footer", "footnotes", "endnotes"]
+            .iter()
+            .any(|prefix| part.starts_with(prefix)),
+        Some(("ppt/slides", part)) => part.starts_with("slide"),
+        Some(("xl", part)) => part == "sharedStrings",
+        _ => false,
+    }
 }
 
 /// Scrapes all links from a given ooxml file.
@@ -90,34 +452,86 @@ pub fn scrape_unfiltered_from_slice(
     scrape_unfiltered(Cursor::new(bytes))
 }
 
+/// The last segments of the relationship-types of linked media (`.../relationships/image`, ...)
+const MEDIA_RELATIONSHIP_TYPES: [&str; 5] = ["image", "audio", "video", "media", "oleObject"];
+
 /// Scrapes links from given .rels file
-fn scrape_from_rels_file(
+///
+/// The kind of a link depends on the `TargetMode` and `Type` of its relationship.
+fn scrape_from_rels_file<U: ArchiveUrl>(
     data: impl Read,
     file_name: &str,
-    collector: &mut Vec<OoxmlLink>,
+    internal_references: bool,
+    collector: &mut LinkCollector<OoxmlLink<U>>,
 ) -> Result<(), OoxmlScrapingError> {
-    let mut parser = EventReader::new(data);
-    while let Ok(xml_event) = &parser.next() {
+    let mut parser = LimitedXmlReader::new(data);
+    loop {
+        let xml_event = &parser.next_or_end::<OoxmlScrapingError>(collector.lenient_xml())?;
         if let XmlEvent::StartElement {
             name: _,
             attributes,
             ..
         } = xml_event
         {
-            let attributes_with_potential_links = attributes
-                .iter()
-                .filter(|att| &att.name.local_name != "Type");
-            for attribute in attributes_with_potential_links {
-                find_urls(&attribute.value).iter().for_each(|link| {
-                    collector.push(OoxmlLink {
-                        url: link.as_str().to_string(),
+            let attribute = |name: &str| {
+                attributes
+                    .iter()
+                    .find(|att| att.name.local_name == name)
+                    .map(|att| att.value.as_str())
+            };
+            let relationship_type = attribute("Type")
+                .and_then(|it| it.rsplit('/').next())
+                .unwrap_or_default();
+            let kind = match attribute("TargetMode") {
+                Some("External") if relationship_type == "hyperlink" => Hyperlink,
+                Some("External") if MEDIA_RELATIONSHIP_TYPES.contains(&relationship_type) => {
+                    ExternalMedia
+                }
+                Some("External") => ExternalReference,
+                _ => InternalReference,
+            };
+
+            if kind == InternalReference {
+                if let Some(target) = attribute("Target").filter(|_| internal_references) {
+                    let url = collector.url(&resolve_target(file_name, target));
+                    let sequence = collector.next_sequence();
+                    collector.links.push(OoxmlLink {
+                        url,
                         location: OoxmlLinkLocation {
                             file: file_name.to_string(),
                             position: parser.position(),
+                            shared_string: None,
                         },
-                        kind: Hyperlink,
+                        kind,
+                        sequence,
+                        sentence: None,
+                        raw_value: Some(target.to_string()),
+                        revision: None,
                     })
-                })
+                }
+            } else {
+                let attributes_with_potential_links = attributes
+                    .iter()
+                    .filter(|att| &att.name.local_name != "Type");
+                for attribute in attributes_with_potential_links {
+                    find_urls(&attribute.value).iter().for_each(|link| {
+                        let url = collector.url(link.as_str());
+                        let sequence = collector.next_sequence();
+                        collector.links.push(OoxmlLink {
+                            url,
+                            location: OoxmlLinkLocation {
+                                file: file_name.to_string(),
+                                position: parser.position(),
+                                shared_string: None,
+                            },
+                            kind,
+                            sequence,
+                            sentence: None,
+                            raw_value: Some(attribute.value.clone()),
+                            revision: None,
+                        })
+                    })
+                }
             }
         }
 
@@ -128,39 +542,118 @@ fn scrape_from_rels_file(
     Ok(())
 }
 
+/// Resolves the target of an internal relationship against the part the .rels file belongs to.
+///
+/// `word/_rels/document.xml.rels` belongs to `word/document.xml`, so `media/image1.png` resolves to
+/// `word/media/image1.png`. Targets starting with `/` are relative to the root of the package.
+fn resolve_target(rels_file: &str, target: &str) -> String {
+    let base = if target.starts_with('/') {
+        ""
+    } else {
+        rels_file
+            .rsplit_once("_rels/")
+            .map_or("", |(directory, _)| directory)
+    };
+
+    let mut segments: Vec<&str> = base.split('/').filter(|it| !it.is_empty()).collect();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
 /// Scrapes links from given .xml file-text
 ///
 /// All tags and tag-attributes are omitted to filter out functional urls.
 /// This might be too aggressive in some cases though
-fn scrape_from_xml_file(
+///
+/// Sentences are segmented per text-run, so a change of formatting inside a sentence cuts it short.
+///
+/// Of every `mc:AlternateContent`-block only the branch chosen by [`ScrapeConfig::alternate_content`] is scraped.
+fn scrape_from_xml_file<U: ArchiveUrl>(
     data: impl Read,
     file_name: &str,
-    collector: &mut Vec<OoxmlLink>,
+    config: &ScrapeConfig,
+    collector: &mut LinkCollector<OoxmlLink<U>>,
 ) -> Result<(), OoxmlScrapingError> {
-    let mut parser = EventReader::new(data);
-    while let Ok(xml_event) = &parser.next() {
+    let mut parser = LimitedXmlReader::new(data);
+    let mut alternate_content = AlternateContentFilter::new(config.alternate_content);
+    let is_preview_part = config.preview_length > 0 && is_preview_part(file_name);
+    // The open `w:ins`- and `w:del`-elements, the innermost one is the change of the text
+    let mut revisions: Vec<Revision> = vec![];
+    let is_shared_strings = file_name == SHARED_STRINGS_PART;
+    // The index of the current shared string
+    let mut shared_string: Option<usize> = None;
+    loop {
+        let xml_event = &parser.next_or_end::<OoxmlScrapingError>(collector.lenient_xml())?;
         let raw_text = match xml_event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                alternate_content.start_element(name);
+                if is_revision_element(name) {
+                    revisions.push(revision(name, attributes));
+                }
+                if is_shared_strings && name.local_name == "si" {
+                    shared_string = Some(shared_string.map_or(0, |index| index + 1));
+                }
+                None
+            }
+            XmlEvent::EndElement { name } => {
+                if is_revision_element(name) {
+                    revisions.pop();
+                }
+                // Paragraphs and shared strings are separated by a space
+                if is_preview_part
+                    && !alternate_content.is_skipping()
+                    && matches!(name.local_name.as_str(), "p" | "si")
+                {
+                    collector.push_preview(" ");
+                }
+                alternate_content.end_element(name);
+                None
+            }
+            _ if alternate_content.is_skipping() => None,
             XmlEvent::Characters(str) => Some(str),
             XmlEvent::Whitespace(str) => Some(str),
             _ => None,
         };
         if let Some(text) = raw_text {
+            if is_preview_part {
+                collector.push_preview(text);
+            }
+            let text = collector.clean(text);
             find_urls(&text).iter().for_each(|link| {
-                collector.push(OoxmlLink {
-                    url: link.as_str().to_string(),
+                let url = collector.url(link.as_str());
+                let sequence = collector.next_sequence();
+                collector.links.push(OoxmlLink {
+                    url,
                     location: OoxmlLinkLocation {
                         file: file_name.to_string(),
                         position: parser.position(),
+                        shared_string,
                     },
                     kind: if file_name.contains("/comment") {
                         Comment
+                    } else if file_name.starts_with("docProps/") {
+                        Metadata
+                    } else if file_name.contains("/_rels/") {
+                        Hyperlink
                     } else {
-                        if file_name.contains("/_rels/") {
-                            Hyperlink
-                        } else {
-                            PlainText
-                        }
+                        PlainText
                     },
+                    sequence,
+                    sentence: config
+                        .with_sentence
+                        .then(|| sentence_around(&text, link.start()..link.end()).to_string()),
+                    raw_value: None,
+                    revision: revisions.last().cloned(),
                 })
             });
         }
@@ -172,14 +665,200 @@ fn scrape_from_xml_file(
     Ok(())
 }
 
+const SHARED_STRINGS_PART: &str = "xl/sharedStrings.xml";
+
+/// Maps the shared strings of a spreadsheet to the cells using them, so a link found once in `xl/sharedStrings.xml`
+/// can be reported for every cell it appears in, see [`OoxmlLinkLocation::shared_string`].
+///
+/// The worksheets are read in the order of their part-names, the cells in the order of their worksheet.
+/// Cells with inline strings do not use shared strings, their links are found in the worksheet itself.
+pub fn shared_string_cells<R>(
+    reader: R,
+) -> Result<BTreeMap<usize, Vec<CellReference>>, OoxmlScrapingError>
+where
+    R: Read + Seek,
+{
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut sheets = archive
+        .file_names()
+        .filter(|name| name.starts_with("xl/worksheets/") && name.ends_with(".xml"))
+        .filter(|name| !name.contains("/_rels/"))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    sheets.sort();
+
+    let mut cells: BTreeMap<usize, Vec<CellReference>> = BTreeMap::new();
+    for sheet in sheets {
+        let mut parser = LimitedXmlReader::new(archive.by_name(&sheet)?);
+        // The reference of the open cell if its value is a shared string, e.g. `B3`
+        let mut cell: Option<String> = None;
+        let mut in_value = false;
+        loop {
+            match parser.next_or_end::<OoxmlScrapingError>(true)? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => match name.local_name.as_str() {
+                    "c" => {
+                        let attribute = |local_name: &str| {
+                            attributes
+                                .iter()
+                                .find(|attribute| attribute.name.local_name == local_name)
+                                .map(|attribute| attribute.value.as_str())
+                        };
+                        cell = (attribute("t") == Some("s"))
+                            .then(|| attribute("r").unwrap_or_default().to_string());
+                    }
+                    "v" => in_value = cell.is_some(),
+                    _ => {}
+                },
+                XmlEvent::EndElement { name } => match name.local_name.as_str() {
+                    "c" => cell = None,
+                    "v" => in_value = false,
+                    _ => {}
+                },
+                XmlEvent::Characters(value) if in_value => {
+                    if let (Some(cell), Ok(index)) = (&cell, value.trim().parse()) {
+                        cells.entry(index).or_default().push(CellReference {
+                            sheet: sheet.clone(),
+                            cell: cell.clone(),
+                        });
+                    }
+                }
+                XmlEvent::EndDocument => break,
+                _ => {}
+            }
+        }
+    }
+    Ok(cells)
+}
+
+/// A cell of a spreadsheet, see [`shared_string_cells`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellReference {
+    /// The worksheet-part containing the cell, e.g. `xl/worksheets/sheet1.xml`
+    pub sheet: String,
+    /// The reference of the cell, e.g. `B3`
+    pub cell: String,
+}
+
+const WORDPROCESSING_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+
+fn is_revision_element(name: &OwnedName) -> bool {
+    name.namespace_ref() == Some(WORDPROCESSING_NAMESPACE)
+        && matches!(name.local_name.as_str(), "ins" | "del")
+}
+
+/// Returns the change recorded by a `w:ins`- or `w:del`-element.
+fn revision(name: &OwnedName, attributes: &[OwnedAttribute]) -> Revision {
+    let kind = if name.local_name == "ins" {
+        RevisionKind::Inserted
+    } else {
+        RevisionKind::Deleted
+    };
+    let author = attributes
+        .iter()
+        .find(|attribute| {
+            attribute.name.local_name == "author"
+                && attribute.name.namespace_ref() == Some(WORDPROCESSING_NAMESPACE)
+        })
+        .map(|attribute| attribute.value.clone())
+        .unwrap_or_default();
+    Revision { kind, author }
+}
+
+const MARKUP_COMPATIBILITY_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/markup-compatibility/2006";
+
+/// Skips the branches of `mc:AlternateContent`-blocks that were not chosen.
+struct AlternateContentFilter {
+    branch: AlternateContentBranch,
+    /// Whether a branch was already taken, for every open `mc:AlternateContent`
+    taken: Vec<bool>,
+    /// Depth inside the skipped branch, `0` if nothing is skipped
+    skip_depth: usize,
+}
+
+impl AlternateContentFilter {
+    fn new(branch: AlternateContentBranch) -> Self {
+        Self {
+            branch,
+            taken: vec![],
+            skip_depth: 0,
+        }
+    }
+
+    fn is_skipping(&self) -> bool {
+        self.skip_depth > 0
+    }
+
+    fn start_element(&mut self, name: &OwnedName) {
+        if self.is_skipping() {
+            self.skip_depth += 1;
+            return;
+        }
+        if name.namespace_ref() != Some(MARKUP_COMPATIBILITY_NAMESPACE) {
+            return;
+        }
+        match name.local_name.as_str() {
+            "AlternateContent" => self.taken.push(false),
+            "Choice" | "Fallback" => {
+                let is_choice = name.local_name == "Choice";
+                let Some(taken) = self.taken.last_mut() else {
+                    return;
+                };
+                let wanted = match self.branch {
+                    AlternateContentBranch::Choice => true,
+                    AlternateContentBranch::Fallback => !is_choice,
+                };
+                if *taken || !wanted {
+                    self.skip_depth = 1;
+                } else {
+                    *taken = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_element(&mut self, name: &OwnedName) {
+        if self.is_skipping() {
+            self.skip_depth -= 1;
+            return;
+        }
+        if name.namespace_ref() == Some(MARKUP_COMPATIBILITY_NAMESPACE)
+            && name.local_name == "AlternateContent"
+        {
+            self.taken.pop();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::include_bytes;
+    use std::io::Write;
 
     const TEST_DOCX: &[u8] = include_bytes!("../../test_files/ooxml/docx_test.docx");
+    const TEST_DOCX_METADATA: &[u8] =
+        include_bytes!("../../test_files/ooxml/docx_metadata_test.docx");
+    const TEST_DOCX_ALTERNATE_CONTENT: &[u8] =
+        include_bytes!("../../test_files/ooxml/docx_alternate_content_test.docx");
+    const TEST_DOCX_IMAGE: &[u8] = include_bytes!("../../test_files/ooxml/docx_image_test.docx");
+    #[cfg(feature = "embedded_objects")]
+    const TEST_DOCX_EMBEDDED: &[u8] =
+        include_bytes!("../../test_files/ooxml/docx_embedded_test.docx");
+    #[cfg(feature = "qr")]
+    const TEST_DOCX_QR: &[u8] = include_bytes!("../../test_files/ooxml/docx_qr_test.docx");
+    const TEST_DOCX_REVISION: &[u8] =
+        include_bytes!("../../test_files/ooxml/docx_revision_test.docx");
     const TEST_PPTX: &[u8] = include_bytes!("../../test_files/ooxml/pptx_test.pptx");
     const TEST_XLSX: &[u8] = include_bytes!("../../test_files/ooxml/xlsx_test.xlsx");
+    const TEST_XLSX_EXTERNAL_MEDIA: &[u8] =
+        include_bytes!("../../test_files/ooxml/xlsx_external_media_test.xlsx");
+    const TEST_XLSX_SHARED_STRINGS: &[u8] =
+        include_bytes!("../../test_files/ooxml/xlsx_shared_strings_test.xlsx");
 
     #[test]
     pub fn scrape_docx_test() {
@@ -196,6 +875,22 @@ mod tests {
             .any(|it| it.url == "https://plaintext.test.com" && it.kind == PlainText));
     }
 
+    #[test]
+    fn scrape_grouped_test() {
+        let groups = scrape_grouped(Cursor::new(TEST_DOCX)).unwrap();
+        println!("{:?}", groups.keys());
+        assert!(groups["word/document.xml"]
+            .iter()
+            .any(|it| it.url == "https://plaintext.test.com"));
+        assert!(groups["word/comments.xml"]
+            .iter()
+            .all(|it| it.kind == Comment && it.location.file == "word/comments.xml"));
+        assert_eq!(
+            groups.values().map(Vec::len).sum::<usize>(),
+            scrape_from_slice(TEST_DOCX).unwrap().len()
+        );
+    }
+
     #[test]
     pub fn scrape_pptx_test() {
         let links = scrape_from_slice(TEST_PPTX).unwrap();
@@ -226,10 +921,692 @@ mod tests {
             .any(|it| it.url == "https://plaintext.test.com" && it.kind == PlainText));
     }
 
+    #[test]
+    pub fn shared_string_cells_test() {
+        let links = scrape_from_slice(TEST_XLSX_SHARED_STRINGS).unwrap();
+        println!("{:?}", links);
+        let location = |url: &str| {
+            links
+                .iter()
+                .find(|link| link.url == url)
+                .map(|link| (link.location.file.as_str(), link.location.shared_string))
+                .unwrap()
+        };
+        assert_eq!(
+            location("https://shared.test.com"),
+            ("xl/sharedStrings.xml", Some(3))
+        );
+        assert_eq!(
+            location("https://inline.test.com"),
+            ("xl/worksheets/sheet1.xml", None)
+        );
+
+        let cells = shared_string_cells(Cursor::new(TEST_XLSX_SHARED_STRINGS)).unwrap();
+        let cell = |cell: &str| CellReference {
+            sheet: "xl/worksheets/sheet1.xml".to_string(),
+            cell: cell.to_string(),
+        };
+        assert_eq!(cells[&3], [cell("B1"), cell("B3")]);
+        assert_eq!(cells[&0], [cell("A1")]);
+        assert_eq!(cells.len(), 4);
+    }
+
+    #[test]
+    pub fn scrape_metadata_test() {
+        let links = scrape_from_slice(TEST_DOCX_METADATA).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://metadata.test.com/source" && it.kind == Metadata));
+        assert!(!links
+            .iter()
+            .any(|it| it.url == "https://metadata.test.com/source" && it.kind == PlainText));
+    }
+
+    #[test]
+    pub fn sequence_test() {
+        let links = scrape_from_slice(TEST_DOCX).unwrap();
+        assert!(links
+            .iter()
+            .enumerate()
+            .all(|(index, link)| link.sequence == index));
+    }
+
+    #[test]
+    pub fn sequence_with_dropped_links_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("word/document.xml", options).unwrap();
+        writer
+            .write_all(b"<document><t>https://a.test.com</t></document>")
+            .unwrap();
+        writer.start_file("word/comments.xml", options).unwrap();
+        writer
+            .write_all(b"<comments><p>http://localhost/x https://b.test.com</p></comments>")
+            .unwrap();
+        writer.finish().unwrap();
+        let docx = buffer.into_inner();
+
+        let config = ScrapeConfig {
+            drop_private_hosts: true,
+            deterministic: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(&docx), &config).unwrap();
+        assert_eq!(
+            links.iter().map(|it| it.url.as_str()).collect::<Vec<_>>(),
+            ["https://b.test.com", "https://a.test.com"]
+        );
+        assert!(links.windows(2).all(|it| it[0].sequence < it[1].sequence));
+    }
+
+    #[test]
+    pub fn structured_only_test() {
+        let config = ScrapeConfig {
+            structured_only: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(TEST_DOCX), &config).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://hyperlink.test.com/" && it.kind == Hyperlink));
+        assert!(links.iter().all(|it| it.kind == Hyperlink));
+        assert!(!links
+            .iter()
+            .any(|it| it.url == "https://plaintext.test.com"));
+    }
+
+    #[test]
+    pub fn skip_corrupt_entries_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("word/document.xml", options).unwrap();
+        writer
+            .write_all(b"<document><t>https://plaintext.test.com</t></document>")
+            .unwrap();
+        writer.start_file("word/comments.xml", options).unwrap();
+        writer
+            .write_all(b"<comments><p>https://partial.test.com</p><p>https://cut")
+            .unwrap();
+        writer.finish().unwrap();
+        let corrupt_docx = buffer.into_inner();
+
+        // By default a malformed entry just ends early
+        let urls = scrape_from_slice(&corrupt_docx)
+            .unwrap()
+            .into_iter()
+            .map(|it| it.url)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec!["https://plaintext.test.com", "https://partial.test.com"]
+        );
+
+        let config = ScrapeConfig {
+            skip_corrupt_entries: true,
+            ..ScrapeConfig::default()
+        };
+        let result = scrape_with_warnings(Cursor::new(&corrupt_docx), &config).unwrap();
+        assert!(result
+            .links
+            .iter()
+            .any(|it| it.url == "https://plaintext.test.com"));
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].entry, "word/comments.xml");
+
+        let config = ScrapeConfig {
+            strict: true,
+            ..config
+        };
+        assert!(scrape_with_config(Cursor::new(&corrupt_docx), &config).is_err());
+    }
+
+    #[test]
+    pub fn strict_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file(
+                "word/document.xml",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer
+            .write_all(b"<document><t>https://good.test.com</t><t>https://cut")
+            .unwrap();
+        writer.finish().unwrap();
+        let truncated_docx = buffer.into_inner();
+
+        let links = scrape_from_slice(&truncated_docx).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://good.test.com");
+
+        let config = ScrapeConfig {
+            strict: true,
+            ..ScrapeConfig::default()
+        };
+        assert!(matches!(
+            scrape_with_config(Cursor::new(&truncated_docx), &config),
+            Err(OoxmlScrapingError::XmlReaderError(_))
+        ));
+    }
+
+    #[test]
+    pub fn scrape_zip_comments_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file(
+                "word/document.xml",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"<document/>").unwrap();
+        writer.set_comment("Created by https://archive-comment.test.com");
+        writer.finish().unwrap();
+        let commented_docx = buffer.into_inner();
+
+        assert!(scrape_from_slice(&commented_docx).unwrap().is_empty());
+
+        let config = ScrapeConfig {
+            scrape_zip_comments: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(&commented_docx), &config).unwrap();
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://archive-comment.test.com"
+                && it.kind == OoxmlLinkKind::ZipComment(ZipCommentSource::ArchiveComment)));
+    }
+
+    #[test]
+    pub fn intern_urls_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file(
+                "word/document.xml",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"<document>").unwrap();
+        for _ in 0..200 {
+            writer
+                .write_all(b"<p><t>Visit https://footer.test.com</t></p>")
+                .unwrap();
+        }
+        writer.write_all(b"</document>").unwrap();
+        writer.finish().unwrap();
+        let repetitive_docx = buffer.into_inner();
+
+        let links = scrape_interned(Cursor::new(&repetitive_docx), &ScrapeConfig::default())
+            .unwrap()
+            .links;
+        assert_eq!(links.len(), 200);
+        assert_eq!(&*links[0].url, "https://footer.test.com");
+        assert!(links.iter().all(|it| Arc::ptr_eq(&it.url, &links[0].url)));
+    }
+
+    #[test]
+    pub fn deterministic_test() {
+        let config = ScrapeConfig {
+            deterministic: true,
+            ..ScrapeConfig::default()
+        };
+        let first = scrape_with_config(std::io::Cursor::new(TEST_DOCX), &config).unwrap();
+        let second = scrape_with_config(std::io::Cursor::new(TEST_DOCX), &config).unwrap();
+        assert!(!first.is_empty());
+        assert_eq!(format!("{:?}", first), format!("{:?}", second));
+    }
+
+    #[test]
+    pub fn deterministic_entry_order_test() {
+        let config = ScrapeConfig {
+            deterministic: true,
+            ..ScrapeConfig::default()
+        };
+        let visited = std::cell::RefCell::new(vec![]);
+        unified_unzip_scrape_with_unfiltered(
+            std::io::Cursor::new(TEST_DOCX),
+            &config,
+            false,
+            |_, file_name, _: &mut LinkCollector<OoxmlLink>| {
+                visited.borrow_mut().push(file_name.to_string());
+                Ok::<_, OoxmlScrapingError>(())
+            },
+            &mut |_, _, _| {},
+        )
+        .unwrap();
+        let visited = visited.into_inner();
+        println!("{:?}", visited);
+        assert!(visited.len() > 1);
+        assert!(visited.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    pub fn scrape_with_progress_test() {
+        let mut entries = vec![];
+        let result = scrape_with_progress(
+            Cursor::new(TEST_XLSX),
+            &ScrapeConfig::default(),
+            |entry, index, total| entries.push((entry.to_string(), index, total)),
+        )
+        .unwrap();
+        assert!(!result.links.is_empty());
+
+        let archive = zip::ZipArchive::new(Cursor::new(TEST_XLSX)).unwrap();
+        assert_eq!(entries.len(), archive.len());
+        assert!(entries
+            .iter()
+            .enumerate()
+            .all(|(i, (_, index, total))| *index == i && *total == archive.len()));
+        assert_eq!(
+            entries
+                .iter()
+                .map(|(entry, _, _)| entry.as_str())
+                .collect::<Vec<_>>(),
+            archive.file_names().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    pub fn max_input_bytes_test() {
+        let config = ScrapeConfig {
+            max_input_bytes: Some(1024),
+            ..ScrapeConfig::default()
+        };
+        assert!(matches!(
+            scrape_with_config(Cursor::new(TEST_DOCX), &config),
+            Err(OoxmlScrapingError::InputTooLargeError(_))
+        ));
+        // Not even a zip-file, so it would fail while parsing
+        assert!(matches!(
+            scrape_with_config(Cursor::new([0u8; 2048]), &config),
+            Err(OoxmlScrapingError::InputTooLargeError(_))
+        ));
+    }
+
+    #[test]
+    pub fn group_by_kind_test() {
+        let links = scrape_from_slice(TEST_DOCX).unwrap();
+        let groups = crate::helpers::group_by_kind(links, |link| link.kind);
+        let urls = |kind| {
+            groups[&kind]
+                .iter()
+                .map(|it| it.url.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert!(urls(Hyperlink).contains(&"https://hyperlink.test.com/"));
+        assert!(urls(Comment).contains(&"https://comment.test.com"));
+        assert!(urls(PlainText).contains(&"https://plaintext.test.com"));
+        assert!(!urls(PlainText).contains(&"https://hyperlink.test.com/"));
+    }
+
+    #[test]
+    pub fn extract_text_test() {
+        let text = extract_text(Cursor::new(TEST_DOCX)).unwrap();
+        println!("{:?}", text);
+        assert!(text.contains("Hyperlink Test"));
+        assert!(text.contains("https://plaintext.test.com"));
+        assert!(!text.contains("https://comment.test.com"));
+    }
+
+    #[test]
+    pub fn scrape_with_preview_test() {
+        let (links, preview) = scrape_with_preview(Cursor::new(TEST_DOCX), 200).unwrap();
+        println!("{:?}", preview);
+        assert_eq!(links.len(), scrape_from_slice(TEST_DOCX).unwrap().len());
+        // Paragraphs are separated by a space, the comment is not part of the preview
+        assert_eq!(preview, "https://plaintext.test.com Hyperlink Test");
+
+        let (_, preview) = scrape_with_preview(Cursor::new(TEST_DOCX), 12).unwrap();
+        assert_eq!(preview, "https://plai");
+    }
+
+    #[test]
+    pub fn max_links_test() {
+        let total = scrape_from_slice(TEST_DOCX).unwrap().len();
+        assert!(total > 1);
+        let scrape_max = |max_links: usize| {
+            let config = ScrapeConfig {
+                max_links: Some(max_links),
+                ..Default::default()
+            };
+            scrape_with_warnings(Cursor::new(TEST_DOCX), &config).unwrap()
+        };
+
+        let result = scrape_max(total - 1);
+        assert_eq!(result.links.len(), total - 1);
+        assert!(result.truncated);
+
+        let result = scrape_max(total);
+        assert_eq!(result.links.len(), total);
+        assert!(!result.truncated);
+
+        let result =
+            scrape_with_warnings(Cursor::new(TEST_DOCX), &ScrapeConfig::default()).unwrap();
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    pub fn max_links_counts_accepted_links_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file(
+                "word/document.xml",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer
+            .write_all(
+                b"<document><p><t>See http://localhost/a and http://192.168.0.1/b \
+                then https://one.test.com and https://two.test.com</t></p></document>",
+            )
+            .unwrap();
+        writer.finish().unwrap();
+        let docx = buffer.into_inner();
+        let scrape_max = |max_links: usize| {
+            let config = ScrapeConfig {
+                drop_private_hosts: true,
+                max_links: Some(max_links),
+                ..Default::default()
+            };
+            scrape_with_warnings(Cursor::new(&docx), &config).unwrap()
+        };
+
+        let result = scrape_max(2);
+        let urls = result
+            .links
+            .iter()
+            .map(|it| it.url.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(urls, vec!["https://one.test.com", "https://two.test.com"]);
+        assert!(!result.truncated);
+
+        let result = scrape_max(1);
+        assert_eq!(result.links.len(), 1);
+        assert_eq!(result.links[0].url, "https://one.test.com");
+        assert!(result.truncated);
+    }
+
+    #[test]
+    pub fn scrape_from_text_test() {
+        let text = extract_text(Cursor::new(TEST_DOCX)).unwrap();
+        let links = scrape_from_text(&text);
+        println!("{:?}", links);
+        assert!(!links.is_empty());
+        assert!(links
+            .iter()
+            .all(|it| it.kind == PlainText && it.location.is_from_text()));
+
+        let scraped = scrape_from_slice(TEST_DOCX).unwrap();
+        assert!(links.iter().all(|link| scraped
+            .iter()
+            .any(|it| it.url == link.url && it.kind == PlainText)));
+    }
+
+    #[test]
+    pub fn external_media_test() {
+        let links = scrape_from_slice(TEST_XLSX_EXTERNAL_MEDIA).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://media.test.com/tracking.png"
+                && it.kind == ExternalMedia
+                && it.location.file == "xl/drawings/_rels/drawing1.xml.rels"));
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://hyperlink.test.com/" && it.kind == Hyperlink));
+    }
+
+    #[test]
+    pub fn relationship_targets_test() {
+        let links = scrape_from_slice(TEST_DOCX_IMAGE).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://hyperlink.test.com/" && it.kind == Hyperlink));
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://template.test.com/report.dotx"
+                && it.kind == ExternalReference));
+        assert!(!links.iter().any(|it| it.kind == InternalReference));
+
+        let config = ScrapeConfig {
+            internal_references: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(TEST_DOCX_IMAGE), &config).unwrap();
+        let internal = links
+            .iter()
+            .filter(|it| it.kind == InternalReference)
+            .map(|it| it.url.as_str())
+            .collect::<Vec<_>>();
+        assert!(internal.contains(&"word/media/image1.png"));
+        assert!(internal.contains(&"word/document.xml"));
+        assert!(internal.contains(&"word/settings.xml"));
+    }
+
+    #[test]
+    pub fn strip_invisible_chars_test() {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        writer
+            .start_file(
+                "word/document.xml",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer
+            .write_all(
+                "<document><t>see https://hyphen\u{ad}ated.test.com</t></document>".as_bytes(),
+            )
+            .unwrap();
+        writer.finish().unwrap();
+        let hyphenated_docx = buffer.into_inner();
+
+        assert_eq!(
+            scrape_from_slice(&hyphenated_docx).unwrap()[0].url,
+            "https://hyphen\u{ad}ated.test.com"
+        );
+
+        let config = ScrapeConfig {
+            strip_invisible_chars: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(&hyphenated_docx), &config).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://hyphenated.test.com");
+    }
+
+    #[test]
+    pub fn alternate_content_test() {
+        let links = scrape_from_slice(TEST_DOCX_ALTERNATE_CONTENT).unwrap();
+        println!("{:?}", links);
+        let urls = links.iter().map(|it| it.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            [
+                "https://both.test.com",
+                "https://choice.test.com",
+                "https://after.test.com"
+            ]
+        );
+
+        let config = ScrapeConfig {
+            alternate_content: AlternateContentBranch::Fallback,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(TEST_DOCX_ALTERNATE_CONTENT), &config).unwrap();
+        let urls = links.iter().map(|it| it.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            [
+                "https://both.test.com",
+                "https://fallback.test.com",
+                "https://after.test.com"
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "embedded_objects")]
+    pub fn embedded_object_test() {
+        let links = scrape_from_slice(TEST_DOCX_EMBEDDED).unwrap();
+        assert!(!links.iter().any(|it| it.url == "https://embedded.test.com"));
+
+        let config = ScrapeConfig {
+            embedded_object_depth: 1,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(TEST_DOCX_EMBEDDED), &config).unwrap();
+        println!("{:?}", links);
+        assert!(links.iter().any(|it| it.url == "https://embedded.test.com"
+            && it.kind == OoxmlLinkKind::EmbeddedObject
+            && it.location.file == "word/embeddings/Microsoft_Excel_Worksheet.xlsx"));
+    }
+
+    #[test]
+    #[cfg(feature = "qr")]
+    pub fn qr_code_image_test() {
+        let links = scrape_from_slice(TEST_DOCX_QR).unwrap();
+        assert!(!links
+            .iter()
+            .any(|it| it.kind == OoxmlLinkKind::EmbeddedImageQr));
+
+        let config = ScrapeConfig {
+            qr_code_images: 4,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(Cursor::new(TEST_DOCX_QR), &config).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://flyer.qr.test.com/tickets"
+                && it.kind == OoxmlLinkKind::EmbeddedImageQr
+                && it.location.file == "word/media/image1.png"));
+    }
+
+    #[test]
+    pub fn revision_test() {
+        let links = scrape_from_slice(TEST_DOCX_REVISION).unwrap();
+        println!("{:?}", links);
+        let revision_of = |url: &str| {
+            links
+                .iter()
+                .find(|it| it.url == url)
+                .map(|it| it.revision.clone())
+                .unwrap()
+        };
+        assert_eq!(revision_of("https://original.revision.test.com"), None);
+        assert_eq!(
+            revision_of("https://inserted.revision.test.com"),
+            Some(Revision {
+                kind: RevisionKind::Inserted,
+                author: "Jane Reviewer".to_string()
+            })
+        );
+        assert_eq!(
+            revision_of("https://deleted.revision.test.com"),
+            Some(Revision {
+                kind: RevisionKind::Deleted,
+                author: "John Editor".to_string()
+            })
+        );
+    }
+
+    #[test]
+    pub fn resolve_target_test() {
+        assert_eq!(
+            resolve_target("word/_rels/document.xml.rels", "media/image1.png"),
+            "word/media/image1.png"
+        );
+        assert_eq!(
+            resolve_target(
+                "ppt/slides/_rels/slide1.xml.rels",
+                "../slideLayouts/slideLayout1.xml"
+            ),
+            "ppt/slideLayouts/slideLayout1.xml"
+        );
+        assert_eq!(
+            resolve_target("_rels/.rels", "word/document.xml"),
+            "word/document.xml"
+        );
+        assert_eq!(
+            resolve_target("xl/_rels/workbook.xml.rels", "/xl/styles.xml"),
+            "xl/styles.xml"
+        );
+    }
+
     #[test]
     pub fn scrape_unfiltered_test() {
         let mut links = scrape_unfiltered_from_slice(TEST_DOCX).unwrap();
         links.sort();
         assert_eq!(links.len(), 50);
     }
+
+    /// Counts the bytes read from the underlying archive
+    struct CountingReader<R> {
+        inner: R,
+        read: usize,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.inner.read(buf)?;
+            self.read += read;
+            Ok(read)
+        }
+    }
+
+    impl<R: Seek> Seek for CountingReader<R> {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    pub fn scrape_both_test() {
+        let (links, unfiltered) = scrape_both(Cursor::new(TEST_DOCX)).unwrap();
+        assert_eq!(
+            links.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            scrape_from_slice(TEST_DOCX)
+                .unwrap()
+                .iter()
+                .map(|it| it.url())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(unfiltered, scrape_unfiltered_from_slice(TEST_DOCX).unwrap());
+        assert!(links
+            .iter()
+            .all(|it| unfiltered.contains(&it.url.to_string())));
+
+        // Every entry is decompressed just once
+        let counting_reader = || CountingReader {
+            inner: Cursor::new(TEST_DOCX),
+            read: 0,
+        };
+        let mut both = counting_reader();
+        scrape_both(&mut both).unwrap();
+        let mut filtered = counting_reader();
+        scrape(&mut filtered).unwrap();
+        let mut unfiltered = counting_reader();
+        scrape_unfiltered(&mut unfiltered).unwrap();
+        assert!(both.read <= filtered.read.max(unfiltered.read));
+    }
+
+    #[test]
+    pub fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/ooxml/docx_test.docx").unwrap();
+        let from_slice = scrape_from_slice(TEST_DOCX).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
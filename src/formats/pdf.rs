@@ -1,9 +1,13 @@
+use crate::config::ScrapeConfig;
 use crate::gen_scrape_from_file;
-use crate::helpers::find_urls;
+use crate::helpers::{find_urls, push_preview, redact_url, AsUrl};
+use crate::links::HasLocation;
 use mupdf::{Document, Page};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::io::{Read};
 use std::string::String;
+use std::sync::mpsc::{self, Receiver, Sender};
 use thiserror::Error;
 
 /// Takes a PDF as a byte stream and scrapes all links from it.
@@ -24,12 +28,108 @@ gen_scrape_from_file!(scrape_from_slice(AsRef<[u8]>) -> Result<Vec<PdfLink>, Pdf
 
 /// Takes a PDF as a byte slice and scrapes all links from it.
 ///
+/// Besides the text and the hyperlinks of every page, the parameters of JavaScript-, Launch-, SubmitForm-,
+/// GoToR- and ImportData-actions are scraped as [`PdfLinkKind::Action`]s.
+///
 /// For encrypted files please use [`scrape_encrypted`] instead
 pub fn scrape_from_slice<T>(buffer: T) -> Result<Vec<PdfLink>, PdfScrapingError>
 where
     T: AsRef<[u8]>,
 {
-    scrape_from_doc(bytes_to_pdf(buffer.as_ref())?)
+    let (mut links, _) = scrape_from_doc(bytes_to_pdf(buffer.as_ref())?, false, 0)?;
+    links.extend(find_action_links(&RawObjects::parse(buffer.as_ref())));
+    Ok(links)
+}
+
+/// Like [`scrape_from_slice`], but only returns the links accepted by the given [`ScrapeConfig`].
+///
+/// With [`ScrapeConfig::internal_destinations`] links jumping to another place inside the document are returned
+/// as [`PdfLinkKind::InternalDestination`]s as well.
+/// With the `qr` feature and [`ScrapeConfig::qr_code_images`], QR-codes in images are decoded
+/// as [`PdfLinkKind::EmbeddedImageQr`]s.
+pub fn scrape_with_config<T>(
+    buffer: T,
+    config: &ScrapeConfig,
+) -> Result<Vec<PdfLink>, PdfScrapingError>
+where
+    T: AsRef<[u8]>,
+{
+    let (mut links, _) = scrape_from_doc(
+        bytes_to_pdf(buffer.as_ref())?,
+        config.internal_destinations,
+        0,
+    )?;
+    let objects = RawObjects::parse(buffer.as_ref());
+    links.extend(find_action_links(&objects));
+    #[cfg(feature = "qr")]
+    links.extend(find_qr_code_links(&objects, config.qr_code_images));
+    links.retain(|link| config.accepts(&link.url));
+    if let Some(policy) = config.redact {
+        for link in &mut links {
+            link.url = redact_url(&link.url, policy);
+        }
+    }
+    Ok(links)
+}
+
+/// Like [`scrape_from_slice`], but also returns the first `length` characters of the first page as a preview,
+/// taken from the same text the plaintext-links are searched in. Whitespace is collapsed into single spaces.
+pub fn scrape_with_preview<T>(
+    buffer: T,
+    length: usize,
+) -> Result<(Vec<PdfLink>, String), PdfScrapingError>
+where
+    T: AsRef<[u8]>,
+{
+    let (mut links, preview) = scrape_from_doc(bytes_to_pdf(buffer.as_ref())?, false, length)?;
+    links.extend(find_action_links(&RawObjects::parse(buffer.as_ref())));
+    Ok((links, preview))
+}
+
+/// Like [`scrape_from_slice`], but only scrapes the given 1-based page numbers, in the given order.
+/// [`PdfLinkKind::Action`]s do not belong to a page, so they are not returned.
+///
+/// The other pages are never rendered, which saves a lot of time for long documents
+/// whose links are concentrated on a few known pages (e.g. the references at the end).
+/// Fails with [`PdfScrapingError::PageOutOfRangeError`] before scraping anything if a page does not exist.
+pub fn scrape_pages<T>(bytes: T, pages: &[usize]) -> Result<Vec<PdfLink>, PdfScrapingError>
+where
+    T: AsRef<[u8]>,
+{
+    let doc = bytes_to_pdf(bytes.as_ref())?;
+    check_doc(&doc)?;
+
+    let page_count = doc.page_count()? as usize;
+    if let Some(&page) = pages.iter().find(|&&page| page == 0 || page > page_count) {
+        return Err(PdfScrapingError::PageOutOfRangeError { page, page_count });
+    }
+
+    let mut links: Vec<PdfLink> = vec![];
+    for &page_number in pages {
+        let page = doc.load_page(page_number as i32 - 1)?;
+        find_text_links(&page.to_text()?, page_number, &mut links);
+        find_hyperlinks(&page, page_number, false, &mut links)?;
+    }
+    Ok(links)
+}
+
+/// Like [`scrape_from_slice`], but scrapes the PDF on a separate thread and sends every link as soon as its page
+/// is processed, so the links of large documents can be consumed before the whole document is scraped.
+///
+/// The [`PdfLinkKind::Action`]s are sent after the links of the last page.
+/// Errors are sent as the last message of the channel. The thread stops once the [`Receiver`] is dropped.
+pub fn scrape_channel<T>(bytes: T) -> Receiver<Result<PdfLink, PdfScrapingError>>
+where
+    T: AsRef<[u8]> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        if let Err(e) = send_links(bytes.as_ref(), &sender) {
+            // Nobody to report to if the receiver is gone already.
+            let _ = sender.send(Err(e));
+        }
+    });
+    receiver
 }
 
 #[derive(Error, Debug)]
@@ -44,6 +144,8 @@ pub enum PdfScrapingError {
     FileNotEncryptedError,
     #[error("Given file was not a PDF.")]
     NotAPdfError,
+    #[error("Page {page} does not exist, the document has {page_count} pages.")]
+    PageOutOfRangeError { page: usize, page_count: usize },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -59,15 +161,55 @@ impl Display for PdfLink {
     }
 }
 
+impl AsUrl for PdfLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl HasLocation for PdfLink {
+    type Location = usize;
+
+    fn location(&self) -> Self::Location {
+        self.location.page
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PdfLinkLocation {
     pub page: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+impl PdfLinkLocation {
+    /// Returns whether the link was found by [`scrape_from_text`], so its page is unknown.
+    ///
+    /// The page of [`PdfLinkKind::Action`]s is unknown as well, so this returns `true` for them, too.
+    pub fn is_from_text(&self) -> bool {
+        self.page == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PdfLinkKind {
     PlainText,
     Hyperlink,
+    /// The link jumps to another place inside the document, its url is the destination as fragment (`#name`).
+    /// Only found with [`ScrapeConfig::internal_destinations`]
+    InternalDestination {
+        /// The named destination, or mupdf's description of an explicit one (e.g. `page=3&view=Fit`)
+        name: String,
+        /// The 1-based number of the page the destination is on
+        target_page: usize,
+    },
+    /// The link is a parameter of an action, e.g. the script of a `JavaScript`-action
+    /// or the file-specification of a `Launch`- or `SubmitForm`-action.
+    Action {
+        /// The type of the action without the leading `/`, e.g. `SubmitForm`
+        action_type: String,
+    },
+    /// The link is encoded in a QR-code inside an image of the page.
+    /// Only found with the `qr` feature and [`ScrapeConfig::qr_code_images`]
+    EmbeddedImageQr,
 }
 
 /// Like [`scrape`] for encrypted files.
@@ -75,59 +217,154 @@ pub enum PdfLinkKind {
 ///
 /// I created an <a href="https://github.com/messense/mupdf-rs/issues/82">issue</a> for it.
 /// However, I don't think it is likely to be resolved.
-pub fn scrape_encrypted(bytes: &[u8], password: &str) -> Result<Vec<PdfLink>, PdfScrapingError> {
-    let mut doc = bytes_to_pdf(bytes)?;
+pub fn scrape_encrypted<T>(bytes: T, password: &str) -> Result<Vec<PdfLink>, PdfScrapingError>
+where
+    T: AsRef<[u8]>,
+{
+    let mut doc = bytes_to_pdf(bytes.as_ref())?;
     if !doc.needs_password()? {
         return Err(PdfScrapingError::FileNotEncryptedError);
     }
 
     doc.authenticate(password)?;
-    scrape_from_doc(doc)
+    Ok(scrape_from_doc(doc, false, 0)?.0)
 }
 
-fn scrape_from_doc(doc: Document) -> Result<Vec<PdfLink>, PdfScrapingError> {
+/// Extracts the plain text of a PDF, the pages separated by line breaks.
+///
+/// This is the same text [`scrape`] searches for [`PdfLinkKind::PlainText`]-links.
+pub fn extract_text<T>(bytes: T) -> Result<String, PdfScrapingError>
+where
+    T: AsRef<[u8]>,
+{
+    let doc = bytes_to_pdf(bytes.as_ref())?;
+    check_doc(&doc)?;
+
+    let mut text = String::new();
+    for page_res in doc.pages()? {
+        text.push_str(&page_res?.to_text()?);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Scrapes links from text previously returned by [`extract_text`], without parsing the PDF again.
+///
+/// Only [`PdfLinkKind::PlainText`]-links can be found this way. Their page is unknown,
+/// so it is set to `0` (see [`PdfLinkLocation::is_from_text`]).
+pub fn scrape_from_text(text: &str) -> Vec<PdfLink> {
+    find_urls(text)
+        .iter()
+        .map(|link| PdfLink {
+            url: link.as_str().to_string(),
+            location: PdfLinkLocation { page: 0 },
+            kind: PdfLinkKind::PlainText,
+        })
+        .collect()
+}
+
+fn check_doc(doc: &Document) -> Result<(), PdfScrapingError> {
     if !doc.is_pdf() {
         return Err(PdfScrapingError::NotAPdfError);
     }
     if doc.needs_password()? {
         return Err(PdfScrapingError::FileEncryptedError);
     }
+    Ok(())
+}
+
+/// Scrapes all pages. With a `preview_length` the beginning of the first page is returned as well.
+fn scrape_from_doc(
+    doc: Document,
+    internal_destinations: bool,
+    preview_length: usize,
+) -> Result<(Vec<PdfLink>, String), PdfScrapingError> {
+    check_doc(&doc)?;
 
     let mut links: Vec<PdfLink> = vec![];
+    let mut preview = String::new();
     let mut page_number = 1;
     for page_res in doc.pages()? {
         let page = page_res?;
-        find_text_links(&page, page_number, &mut links)?;
-        find_hyperlinks(&page, page_number, &mut links)?;
+        let text = page.to_text()?;
+        if page_number == 1 {
+            push_preview(&mut preview, &text, preview_length);
+        }
+        find_text_links(&text, page_number, &mut links);
+        find_hyperlinks(&page, page_number, internal_destinations, &mut links)?;
         page_number += 1
     }
 
-    Ok(links)
+    Ok((links, preview.trim_end().to_string()))
 }
 
-/// Finds plaintext links on a page
-fn find_text_links(
-    page: &Page,
-    page_number: usize,
-    links: &mut Vec<PdfLink>,
+/// Sends the links of each page as soon as it is scraped. Returns early if the receiver was dropped.
+fn send_links(
+    bytes: &[u8],
+    sender: &Sender<Result<PdfLink, PdfScrapingError>>,
 ) -> Result<(), PdfScrapingError> {
-    find_urls(&page.to_text()?).iter().for_each(|link| {
+    let doc = bytes_to_pdf(bytes)?;
+    check_doc(&doc)?;
+
+    let mut page_number = 1;
+    for page_res in doc.pages()? {
+        let page = page_res?;
+        let mut links: Vec<PdfLink> = vec![];
+        find_text_links(&page.to_text()?, page_number, &mut links);
+        find_hyperlinks(&page, page_number, false, &mut links)?;
+        for link in links {
+            if sender.send(Ok(link)).is_err() {
+                return Ok(());
+            }
+        }
+        page_number += 1
+    }
+    for link in find_action_links(&RawObjects::parse(bytes)) {
+        if sender.send(Ok(link)).is_err() {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Finds plaintext links in the text of a page
+fn find_text_links(text: &str, page_number: usize, links: &mut Vec<PdfLink>) {
+    find_urls(text).iter().for_each(|link| {
         links.push(PdfLink {
             url: link.as_str().to_string(),
             location: PdfLinkLocation { page: page_number },
             kind: PdfLinkKind::PlainText,
         })
     });
-    Ok(())
 }
 
-/// Finds hyperlinks on a page
+/// Finds hyperlinks on a page, and with `internal_destinations` the links to other places inside the document.
+///
+/// mupdf resolves the destination of internal links to their (0-based) page and describes them
+/// with an uri like `#nameddest=chapter2` or `#page=3&view=Fit`.
 fn find_hyperlinks(
     page: &Page,
     page_number: usize,
+    internal_destinations: bool,
     links: &mut Vec<PdfLink>,
 ) -> Result<(), PdfScrapingError> {
     for link in page.links()? {
+        if let Some(destination) = link.uri.strip_prefix('#') {
+            if internal_destinations {
+                let name = destination
+                    .strip_prefix("nameddest=")
+                    .unwrap_or(destination);
+                links.push(PdfLink {
+                    url: format!("#{}", name),
+                    location: PdfLinkLocation { page: page_number },
+                    kind: PdfLinkKind::InternalDestination {
+                        name: name.to_string(),
+                        target_page: link.page as usize + 1,
+                    },
+                });
+            }
+            continue;
+        }
         find_urls(&link.uri).iter().for_each(|link| {
             links.push(PdfLink {
                 url: link.as_str().to_string(),
@@ -143,6 +380,775 @@ fn bytes_to_pdf(bytes: &[u8]) -> Result<Document, PdfScrapingError> {
     Ok(Document::from_bytes(bytes, "file.pdf")?)
 }
 
+/// Action-types whose parameters may point outside the document, see [`find_action_links`]
+const ACTION_TYPES: [&str; 5] = ["JavaScript", "Launch", "SubmitForm", "GoToR", "ImportData"];
+/// Keys of action-dictionaries holding the script or the file-specification of the action
+const ACTION_PARAMETERS: [&str; 5] = ["JS", "F", "Win", "Mac", "Unix"];
+/// Maximum nesting of arrays and dictionaries, and of references followed while collecting parameters
+const MAX_OBJECT_DEPTH: usize = 64;
+
+/// Scrapes the parameters of JavaScript-, Launch-, SubmitForm-, GoToR- and ImportData-actions.
+///
+/// mupdf only exposes the uris of link-annotations, so the raw objects of the file are parsed instead,
+/// including the ones inside object streams. Document-level scripts of the `/JavaScript`-names-tree
+/// are actions as well, so they are found the same way. The page of these links is unknown and set to `0`.
+fn find_action_links(objects: &RawObjects) -> Vec<PdfLink> {
+    let mut links: Vec<PdfLink> = vec![];
+    for object in objects.objects.values() {
+        visit_dictionaries(object, 0, &mut |dictionary| {
+            let Some(RawObject::Name(action_type)) = dictionary_value(dictionary, "S") else {
+                return;
+            };
+            if !ACTION_TYPES.contains(&action_type.as_str()) {
+                return;
+            }
+            let mut texts: Vec<String> = vec![];
+            for key in ACTION_PARAMETERS {
+                if let Some(value) = dictionary_value(dictionary, key) {
+                    objects.collect_strings(value, 0, &mut texts);
+                }
+            }
+            for text in texts {
+                find_urls(&text).iter().for_each(|link| {
+                    links.push(PdfLink {
+                        url: link.as_str().to_string(),
+                        location: PdfLinkLocation { page: 0 },
+                        kind: PdfLinkKind::Action {
+                            action_type: action_type.clone(),
+                        },
+                    })
+                });
+            }
+        });
+    }
+    links
+}
+
+/// Decodes the QR-codes of at most `limit` image-XObjects, see [`ScrapeConfig::qr_code_images`].
+///
+/// The page of an image is found through the `/Resources` of the page tree, images that are only used
+/// inside forms or patterns get the page `0`.
+#[cfg(feature = "qr")]
+fn find_qr_code_links(objects: &RawObjects, limit: usize) -> Vec<PdfLink> {
+    let pages = objects.image_pages();
+    let mut links: Vec<PdfLink> = vec![];
+    let images = objects.objects.iter().filter(|(_, object)| {
+        matches!(object, RawObject::Stream(entries, _)
+            if dictionary_value(entries, "Subtype") == Some(&RawObject::Name("Image".to_string())))
+    });
+    for (number, image) in images.take(limit) {
+        let Some(payload) = objects.decode_image(image) else {
+            continue;
+        };
+        find_urls(&payload).iter().for_each(|link| {
+            links.push(PdfLink {
+                url: link.as_str().to_string(),
+                location: PdfLinkLocation {
+                    page: pages.get(number).copied().unwrap_or_default(),
+                },
+                kind: PdfLinkKind::EmbeddedImageQr,
+            })
+        });
+    }
+    links
+}
+
+/// Reverses the PNG-predictors (`/Predictor` 10 to 15) of a decoded stream, rows are `row_length` bytes long.
+#[cfg(feature = "qr")]
+fn unfilter_png_rows(data: &[u8], row_length: usize, pixel_length: usize) -> Vec<u8> {
+    let mut samples = Vec::with_capacity(data.len());
+    let mut previous = vec![0u8; row_length];
+    for row in data.chunks_exact(row_length + 1) {
+        let (filter, row) = (row[0], &row[1..]);
+        let mut current = row.to_vec();
+        for i in 0..row_length {
+            let left = if i >= pixel_length {
+                current[i - pixel_length]
+            } else {
+                0
+            };
+            let up = previous[i];
+            let up_left = if i >= pixel_length {
+                previous[i - pixel_length]
+            } else {
+                0
+            };
+            let predicted = match filter {
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => {
+                    let estimate = left as i16 + up as i16 - up_left as i16;
+                    let (a, b, c) = (
+                        (estimate - left as i16).abs(),
+                        (estimate - up as i16).abs(),
+                        (estimate - up_left as i16).abs(),
+                    );
+                    if a <= b && a <= c {
+                        left
+                    } else if b <= c {
+                        up
+                    } else {
+                        up_left
+                    }
+                }
+                _ => 0,
+            };
+            current[i] = current[i].wrapping_add(predicted);
+        }
+        samples.extend_from_slice(&current);
+        previous = current;
+    }
+    samples
+}
+
+/// Calls `on_dictionary` for every dictionary nested inside `object`, without following references.
+fn visit_dictionaries<F>(object: &RawObject, depth: usize, on_dictionary: &mut F)
+where
+    F: FnMut(&[(String, RawObject)]),
+{
+    if depth > MAX_OBJECT_DEPTH {
+        return;
+    }
+    match object {
+        RawObject::Dictionary(entries) | RawObject::Stream(entries, _) => {
+            on_dictionary(entries);
+            for (_, value) in entries {
+                visit_dictionaries(value, depth + 1, on_dictionary);
+            }
+        }
+        RawObject::Array(items) => {
+            for item in items {
+                visit_dictionaries(item, depth + 1, on_dictionary);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn dictionary_value<'a>(entries: &'a [(String, RawObject)], key: &str) -> Option<&'a RawObject> {
+    entries
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value)
+}
+
+/// An object of the PDF-syntax, reduced to what is needed to find actions.
+#[derive(Debug, Clone, PartialEq)]
+enum RawObject {
+    Name(String),
+    String(Vec<u8>),
+    Integer(i64),
+    Array(Vec<RawObject>),
+    Dictionary(Vec<(String, RawObject)>),
+    /// The object number of an indirect reference
+    Reference(u32),
+    /// The dictionary and the still encoded data of a stream
+    Stream(Vec<(String, RawObject)>, Vec<u8>),
+    /// Reals, booleans, `null` and everything that could not be parsed
+    Other,
+}
+
+/// The indirect objects of a PDF-file by their object number.
+struct RawObjects {
+    objects: BTreeMap<u32, RawObject>,
+}
+
+impl RawObjects {
+    /// Finds every `<number> <generation> obj` in the file, a later definition replacing an earlier one
+    /// like it does with incremental updates. Objects inside object streams are added afterwards.
+    fn parse(bytes: &[u8]) -> Self {
+        let mut objects = BTreeMap::new();
+        let mut search_start = 0;
+        while let Some(position) = find_bytes(bytes, search_start, b"obj") {
+            search_start = position + 3;
+            let is_keyword = bytes
+                .get(position + 3)
+                .is_none_or(|&byte| is_whitespace(byte) || is_delimiter(byte));
+            let Some(number) = object_number_before(bytes, position).filter(|_| is_keyword) else {
+                continue;
+            };
+            let mut parser = RawParser::new(bytes, position + 3);
+            let object = match parser.parse_object(0) {
+                RawObject::Dictionary(entries) => match parser.stream_data() {
+                    Some(data) => RawObject::Stream(entries, data.to_vec()),
+                    None => RawObject::Dictionary(entries),
+                },
+                object => object,
+            };
+            search_start = search_start.max(parser.position);
+            objects.insert(number, object);
+        }
+
+        let mut compressed = vec![];
+        for object in objects.values() {
+            if let RawObject::Stream(entries, _) = object {
+                if dictionary_value(entries, "Type") == Some(&RawObject::Name("ObjStm".to_string()))
+                {
+                    compressed.extend(parse_object_stream(object));
+                }
+            }
+        }
+        for (number, object) in compressed {
+            objects.entry(number).or_insert(object);
+        }
+        Self { objects }
+    }
+
+    /// Collects the text of all strings inside `object`, following references and decoding streams (e.g. scripts).
+    fn collect_strings(&self, object: &RawObject, depth: usize, texts: &mut Vec<String>) {
+        if depth > MAX_OBJECT_DEPTH {
+            return;
+        }
+        match object {
+            RawObject::String(bytes) => texts.push(decode_text(bytes)),
+            RawObject::Stream(..) => {
+                texts.extend(decode_stream(object).map(|data| decode_text(&data)))
+            }
+            RawObject::Reference(number) => {
+                if let Some(object) = self.objects.get(number) {
+                    self.collect_strings(object, depth + 1, texts);
+                }
+            }
+            RawObject::Array(items) => {
+                for item in items {
+                    self.collect_strings(item, depth + 1, texts);
+                }
+            }
+            RawObject::Dictionary(entries) => {
+                for (_, value) in entries {
+                    self.collect_strings(value, depth + 1, texts);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "qr")]
+impl RawObjects {
+    /// Follows references until a direct object is reached.
+    fn resolve<'a>(&'a self, object: &'a RawObject) -> &'a RawObject {
+        let mut object = object;
+        for _ in 0..MAX_OBJECT_DEPTH {
+            match object {
+                RawObject::Reference(number) => match self.objects.get(number) {
+                    Some(referenced) => object = referenced,
+                    None => return &RawObject::Other,
+                },
+                _ => return object,
+            }
+        }
+        &RawObject::Other
+    }
+
+    fn entry<'a>(&'a self, entries: &'a [(String, RawObject)], key: &str) -> Option<&'a RawObject> {
+        dictionary_value(entries, key).map(|value| self.resolve(value))
+    }
+
+    /// Maps the object numbers of the images used by a page to its 1-based page number.
+    fn image_pages(&self) -> BTreeMap<u32, usize> {
+        let mut pages = BTreeMap::new();
+        let root = self.objects.values().find_map(|object| match object {
+            RawObject::Dictionary(entries)
+                if self.entry(entries, "Type") == Some(&RawObject::Name("Catalog".to_string())) =>
+            {
+                self.entry(entries, "Pages")
+            }
+            _ => None,
+        });
+        if let Some(root) = root {
+            self.visit_page_tree(root, None, 0, &mut 0, &mut pages);
+        }
+        pages
+    }
+
+    /// Walks the `/Kids` of the page tree in order, `/Resources` are inherited from the parent nodes.
+    fn visit_page_tree(
+        &self,
+        node: &RawObject,
+        inherited_resources: Option<&RawObject>,
+        depth: usize,
+        page_number: &mut usize,
+        pages: &mut BTreeMap<u32, usize>,
+    ) {
+        let RawObject::Dictionary(entries) = node else {
+            return;
+        };
+        if depth > MAX_OBJECT_DEPTH {
+            return;
+        }
+        let resources = self.entry(entries, "Resources").or(inherited_resources);
+        if let Some(RawObject::Array(kids)) = self.entry(entries, "Kids") {
+            for kid in kids {
+                self.visit_page_tree(self.resolve(kid), resources, depth + 1, page_number, pages);
+            }
+            return;
+        }
+
+        *page_number += 1;
+        let Some(RawObject::Dictionary(resources)) = resources else {
+            return;
+        };
+        if let Some(RawObject::Dictionary(x_objects)) = self.entry(resources, "XObject") {
+            for (_, x_object) in x_objects {
+                if let RawObject::Reference(number) = x_object {
+                    pages.entry(*number).or_insert(*page_number);
+                }
+            }
+        }
+    }
+
+    /// Returns the payload of a QR-code inside an image-XObject.
+    ///
+    /// Only gray and RGB images with 1 or 8 bits per component and image-masks are supported,
+    /// either not encoded or encoded with `/FlateDecode`.
+    fn decode_image(&self, image: &RawObject) -> Option<String> {
+        let RawObject::Stream(entries, _) = image else {
+            return None;
+        };
+        let integer = |key: &str| match self.entry(entries, key) {
+            Some(RawObject::Integer(value)) => usize::try_from(*value).ok(),
+            _ => None,
+        };
+        let (width, height) = (integer("Width")?, integer("Height")?);
+        if width.checked_mul(height)? > crate::formats::qr::MAX_QR_IMAGE_PIXELS {
+            return None;
+        }
+        let bits = integer("BitsPerComponent").unwrap_or(1);
+        let components = match self.entry(entries, "ColorSpace") {
+            Some(RawObject::Name(name)) if name == "DeviceGray" || name == "CalGray" => 1,
+            Some(RawObject::Name(name)) if name == "DeviceRGB" || name == "CalRGB" => 3,
+            // Image-masks have no color space and always 1 bit per sample
+            None if bits == 1 => 1,
+            _ => return None,
+        };
+        let row_length = (width * components * bits).div_ceil(8);
+        let mut data = decode_stream_with_limit(image, (row_length + 1) * height)?;
+
+        let parameters = match self.entry(entries, "DecodeParms") {
+            Some(RawObject::Array(parameters)) => parameters.first().map(|it| self.resolve(it)),
+            parameters => parameters,
+        };
+        if let Some(RawObject::Dictionary(parameters)) = parameters {
+            if matches!(
+                self.entry(parameters, "Predictor"),
+                Some(RawObject::Integer(10..))
+            ) {
+                data = unfilter_png_rows(&data, row_length, (components * bits).div_ceil(8));
+            }
+        }
+        if let Some(RawObject::Array(decode)) = self.entry(entries, "Decode") {
+            if decode.first() == Some(&RawObject::Integer(1)) {
+                data.iter_mut().for_each(|byte| *byte = !*byte);
+            }
+        }
+        crate::formats::qr::decode_samples(width, height, components, bits, &data)
+    }
+}
+
+/// Parses the objects of an object stream, which starts with pairs of object numbers and offsets.
+fn parse_object_stream(stream: &RawObject) -> Vec<(u32, RawObject)> {
+    let (RawObject::Stream(entries, _), Some(data)) = (stream, decode_stream(stream)) else {
+        return vec![];
+    };
+    let (Some(&RawObject::Integer(count)), Some(&RawObject::Integer(first))) = (
+        dictionary_value(entries, "N"),
+        dictionary_value(entries, "First"),
+    ) else {
+        return vec![];
+    };
+
+    let mut header = RawParser::new(&data, 0);
+    let mut objects = vec![];
+    for _ in 0..count {
+        let (RawObject::Integer(number), RawObject::Integer(offset)) =
+            (header.parse_object(0), header.parse_object(0))
+        else {
+            break;
+        };
+        let (Ok(number), Ok(start)) = (u32::try_from(number), usize::try_from(first + offset))
+        else {
+            break;
+        };
+        if start < data.len() {
+            objects.push((number, RawParser::new(&data, start).parse_object(0)));
+        }
+    }
+    objects
+}
+
+/// Returns the data of a stream, if it is not encoded or only with `/FlateDecode`.
+fn decode_stream(stream: &RawObject) -> Option<Vec<u8>> {
+    decode_stream_with_limit(stream, usize::MAX)
+}
+
+/// Like [`decode_stream`], but stops decoding after `limit` bytes.
+fn decode_stream_with_limit(stream: &RawObject, limit: usize) -> Option<Vec<u8>> {
+    let RawObject::Stream(entries, data) = stream else {
+        return None;
+    };
+    let flate = RawObject::Name("FlateDecode".to_string());
+    match dictionary_value(entries, "Filter") {
+        None => Some(data.iter().take(limit).copied().collect()),
+        Some(RawObject::Array(filters)) if filters.is_empty() => {
+            Some(data.iter().take(limit).copied().collect())
+        }
+        Some(filter) if filter == &flate || filter == &RawObject::Array(vec![flate.clone()]) => {
+            let mut decoded = Vec::new();
+            // A truncated stream still yields what could be decoded
+            let _ = flate2::read::ZlibDecoder::new(data.as_slice())
+                .take(limit as u64)
+                .read_to_end(&mut decoded);
+            Some(decoded)
+        }
+        _ => None,
+    }
+}
+
+/// Text strings are either UTF-16BE with a byte order mark or PDFDocEncoding, which matches Latin-1 for urls.
+fn decode_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xfe, 0xff]) {
+        let units = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        String::from_utf16_lossy(&units)
+    } else if let Some(utf8) = bytes.strip_prefix(&[0xef, 0xbb, 0xbf]) {
+        String::from_utf8_lossy(utf8).to_string()
+    } else {
+        bytes.iter().map(|&byte| byte as char).collect()
+    }
+}
+
+/// Returns the object number of `<number> <generation> obj`, given the position of `obj`.
+fn object_number_before(bytes: &[u8], position: usize) -> Option<u32> {
+    let before = &bytes[..position];
+    let before = before.trim_ascii_end();
+    let generation_start = before
+        .iter()
+        .rposition(|byte| !byte.is_ascii_digit())
+        .map_or(0, |index| index + 1);
+    if generation_start == before.len() {
+        return None;
+    }
+    let before = before[..generation_start].trim_ascii_end();
+    if before.len() == generation_start {
+        return None;
+    }
+    let number_start = before
+        .iter()
+        .rposition(|byte| !byte.is_ascii_digit())
+        .map_or(0, |index| index + 1);
+    if number_start == before.len()
+        || (number_start > 0 && !is_whitespace(before[number_start - 1]))
+    {
+        return None;
+    }
+    std::str::from_utf8(&before[number_start..])
+        .ok()?
+        .parse()
+        .ok()
+}
+
+fn find_bytes(bytes: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(start..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| start + position)
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+/// A minimal parser for the object syntax of PDF.
+struct RawParser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> RawParser<'a> {
+    fn new(bytes: &'a [u8], position: usize) -> Self {
+        Self { bytes, position }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            if byte == b'%' {
+                while self
+                    .peek()
+                    .is_some_and(|byte| byte != b'\n' && byte != b'\r')
+                {
+                    self.position += 1;
+                }
+            } else if is_whitespace(byte) {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Reads a keyword or number, i.e. everything up to the next whitespace or delimiter.
+    fn token(&mut self) -> &'a [u8] {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|byte| !is_whitespace(byte) && !is_delimiter(byte))
+        {
+            self.position += 1;
+        }
+        &self.bytes[start..self.position]
+    }
+
+    fn parse_object(&mut self, depth: usize) -> RawObject {
+        self.skip_whitespace();
+        let Some(byte) = self.peek() else {
+            return RawObject::Other;
+        };
+        if depth > MAX_OBJECT_DEPTH {
+            self.position += 1;
+            return RawObject::Other;
+        }
+        match byte {
+            b'<' if self.bytes.get(self.position + 1) == Some(&b'<') => {
+                self.position += 2;
+                self.parse_dictionary(depth)
+            }
+            b'<' => self.parse_hex_string(),
+            b'(' => self.parse_literal_string(),
+            b'[' => {
+                self.position += 1;
+                let mut items = vec![];
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        None => break,
+                        Some(b']') => {
+                            self.position += 1;
+                            break;
+                        }
+                        _ => items.push(self.parse_object(depth + 1)),
+                    }
+                }
+                RawObject::Array(items)
+            }
+            b'/' => {
+                self.position += 1;
+                RawObject::Name(self.parse_name())
+            }
+            _ if is_delimiter(byte) => {
+                // Stray closing brackets and the like
+                self.position += 1;
+                RawObject::Other
+            }
+            _ => self.parse_number_or_reference(),
+        }
+    }
+
+    fn parse_dictionary(&mut self, depth: usize) -> RawObject {
+        let mut entries = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some(b'>') => {
+                    self.position += 1;
+                    if self.peek() == Some(b'>') {
+                        self.position += 1;
+                    }
+                    break;
+                }
+                Some(b'/') => {
+                    self.position += 1;
+                    let key = self.parse_name();
+                    let value = self.parse_object(depth + 1);
+                    entries.push((key, value));
+                }
+                // Skips values without a key
+                _ => {
+                    self.parse_object(depth + 1);
+                }
+            }
+        }
+        RawObject::Dictionary(entries)
+    }
+
+    /// Names may contain any byte written as `#xx`.
+    fn parse_name(&mut self) -> String {
+        let token = self.token();
+        let mut name = Vec::with_capacity(token.len());
+        let mut i = 0;
+        while i < token.len() {
+            let escaped = (token[i] == b'#')
+                .then(|| token.get(i + 1..i + 3))
+                .flatten()
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+            match escaped {
+                Some(byte) => {
+                    name.push(byte);
+                    i += 3;
+                }
+                None => {
+                    name.push(token[i]);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&name).to_string()
+    }
+
+    fn parse_hex_string(&mut self) -> RawObject {
+        self.position += 1;
+        let mut digits = vec![];
+        while let Some(byte) = self.peek() {
+            self.position += 1;
+            if byte == b'>' {
+                break;
+            }
+            if let Some(digit) = (byte as char).to_digit(16) {
+                digits.push(digit as u8);
+            }
+        }
+        // An odd number of digits is completed with a 0
+        RawObject::String(
+            digits
+                .chunks(2)
+                .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0))
+                .collect(),
+        )
+    }
+
+    /// Literal strings may contain balanced parentheses and escapes, including octal ones like `\072`.
+    fn parse_literal_string(&mut self) -> RawObject {
+        self.position += 1;
+        let mut string = vec![];
+        let mut depth = 0;
+        while let Some(byte) = self.peek() {
+            self.position += 1;
+            match byte {
+                b'(' => {
+                    depth += 1;
+                    string.push(byte);
+                }
+                b')' if depth == 0 => break,
+                b')' => {
+                    depth -= 1;
+                    string.push(byte);
+                }
+                b'\\' => self.parse_escape(&mut string),
+                b'\r' => {
+                    if self.peek() == Some(b'\n') {
+                        self.position += 1;
+                    }
+                    string.push(b'\n');
+                }
+                _ => string.push(byte),
+            }
+        }
+        RawObject::String(string)
+    }
+
+    fn parse_escape(&mut self, string: &mut Vec<u8>) {
+        let Some(byte) = self.peek() else {
+            return;
+        };
+        self.position += 1;
+        match byte {
+            b'n' => string.push(b'\n'),
+            b'r' => string.push(b'\r'),
+            b't' => string.push(b'\t'),
+            b'b' => string.push(0x08),
+            b'f' => string.push(0x0c),
+            b'0'..=b'7' => {
+                let mut value = (byte - b'0') as u32;
+                for _ in 0..2 {
+                    match self.peek() {
+                        Some(digit @ b'0'..=b'7') => {
+                            value = value * 8 + (digit - b'0') as u32;
+                            self.position += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                string.push(value as u8);
+            }
+            // A backslash at the end of a line continues the string on the next one
+            b'\r' => {
+                if self.peek() == Some(b'\n') {
+                    self.position += 1;
+                }
+            }
+            b'\n' => {}
+            _ => string.push(byte),
+        }
+    }
+
+    /// Parses a number, which is the start of a reference if it is followed by a generation and `R`.
+    fn parse_number_or_reference(&mut self) -> RawObject {
+        let token = self.token();
+        if token.is_empty() {
+            self.position += 1;
+            return RawObject::Other;
+        }
+        let Some(number) = std::str::from_utf8(token)
+            .ok()
+            .and_then(|token| token.parse::<i64>().ok())
+        else {
+            return RawObject::Other;
+        };
+
+        let after_number = self.position;
+        self.skip_whitespace();
+        let generation = self.token();
+        if !generation.is_empty() && generation.iter().all(u8::is_ascii_digit) {
+            self.skip_whitespace();
+            if self.token() == b"R" {
+                if let Ok(number) = u32::try_from(number) {
+                    return RawObject::Reference(number);
+                }
+            }
+        }
+        self.position = after_number;
+        RawObject::Integer(number)
+    }
+
+    /// Returns the data following the `stream`-keyword after a dictionary, up to `endstream`.
+    fn stream_data(&mut self) -> Option<&'a [u8]> {
+        let dictionary_end = self.position;
+        self.skip_whitespace();
+        if self.token() != b"stream" {
+            self.position = dictionary_end;
+            return None;
+        }
+        match self.peek() {
+            Some(b'\r') if self.bytes.get(self.position + 1) == Some(&b'\n') => self.position += 2,
+            Some(b'\r' | b'\n') => self.position += 1,
+            _ => {}
+        }
+        let start = self.position;
+        let end = find_bytes(self.bytes, start, b"endstream").unwrap_or(self.bytes.len());
+        self.position = end;
+        let data = &self.bytes[start..end];
+        // The line break before `endstream` does not belong to the data
+        let data = data.strip_suffix(b"\n").unwrap_or(data);
+        Some(data.strip_suffix(b"\r").unwrap_or(data))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,6 +1157,11 @@ mod tests {
     const NOT_A_PDF: &[u8] = include_bytes!("../../test_files/ooxml/xlsx_test.xlsx");
     const TEST_PDF: &[u8] = include_bytes!("../../test_files/pdf/pdf_test.pdf");
     const TEST_PDFA: &[u8] = include_bytes!("../../test_files/pdf/pdfa_test.pdf");
+    const TEST_PDF_INTERNAL_DESTINATION: &[u8] =
+        include_bytes!("../../test_files/pdf/pdf_internal_destination_test.pdf");
+    const TEST_PDF_ACTION: &[u8] = include_bytes!("../../test_files/pdf/pdf_action_test.pdf");
+    #[cfg(feature = "qr")]
+    const TEST_PDF_QR: &[u8] = include_bytes!("../../test_files/pdf/pdf_qr_test.pdf");
     const TEST_PDF_ENCRYPTED: &[u8] = include_bytes!("../../test_files/pdf/pdf_protected_test.pdf"); // pass: asdfasdf
 
     #[test]
@@ -183,6 +1194,168 @@ mod tests {
             .any(|it| it.url == "https://plaintext.test.com" && it.kind == PdfLinkKind::PlainText));
     }
 
+    #[test]
+    fn internal_destinations_test() {
+        assert!(scrape_from_slice(TEST_PDF_INTERNAL_DESTINATION)
+            .unwrap()
+            .is_empty());
+
+        let config = ScrapeConfig {
+            internal_destinations: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(TEST_PDF_INTERNAL_DESTINATION, &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![PdfLink {
+                url: "#chapter2".to_string(),
+                location: PdfLinkLocation { page: 1 },
+                kind: PdfLinkKind::InternalDestination {
+                    name: "chapter2".to_string(),
+                    target_page: 2
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn action_links_test() {
+        let links = scrape_from_slice(TEST_PDF_ACTION).unwrap();
+        println!("{:?}", links);
+        let action = |url: &str, action_type: &str| PdfLink {
+            url: url.to_string(),
+            location: PdfLinkLocation { page: 0 },
+            kind: PdfLinkKind::Action {
+                action_type: action_type.to_string(),
+            },
+        };
+        assert_eq!(
+            links
+                .into_iter()
+                .filter(|link| matches!(link.kind, PdfLinkKind::Action { .. }))
+                .collect::<Vec<_>>(),
+            vec![
+                action("https://submit.pdf.test.com/form", "SubmitForm"),
+                // The url of the `OpenAction`-script is written with an octal escape (`https\072//`)
+                action("https://script.pdf.test.com/open", "JavaScript"),
+                // The script of the `/JavaScript`-names-tree is a compressed stream
+                action("https://names.pdf.test.com/doc", "JavaScript"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "qr")]
+    fn qr_code_image_test() {
+        assert!(scrape_from_slice(TEST_PDF_QR).unwrap().is_empty());
+
+        let config = ScrapeConfig {
+            qr_code_images: 4,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(TEST_PDF_QR, &config).unwrap();
+        println!("{:?}", links);
+        let qr_code = |url: &str, page: usize| PdfLink {
+            url: url.to_string(),
+            location: PdfLinkLocation { page },
+            kind: PdfLinkKind::EmbeddedImageQr,
+        };
+        assert_eq!(
+            links,
+            vec![
+                // A 1-bit image-mask, inherited from the `/Resources` of the page tree
+                qr_code("https://mask.qr.test.com", 1),
+                // An 8-bit gray image, encoded with a PNG-predictor
+                qr_code("https://predictor.qr.test.com/pdf", 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_with_preview_test() {
+        let (links, preview) = scrape_with_preview(TEST_PDF, 200).unwrap();
+        println!("{:?}", preview);
+        assert_eq!(links, scrape_from_slice(TEST_PDF).unwrap());
+        assert!(!preview.is_empty());
+        assert!(preview.chars().count() <= 200);
+        assert!(!preview.contains('\n'));
+    }
+
+    #[test]
+    fn extract_text_test() {
+        let text = extract_text(TEST_PDF).unwrap();
+        println!("{:?}", text);
+        assert!(text.contains("https://plaintext.test.com"));
+    }
+
+    #[test]
+    fn scrape_from_text_test() {
+        let links = scrape_from_text(&extract_text(TEST_PDF).unwrap());
+        println!("{:?}", links);
+        assert!(links.iter().all(|it| it.location.is_from_text()));
+        assert_eq!(
+            links.iter().map(|it| &it.url).collect::<Vec<_>>(),
+            scrape(TEST_PDF)
+                .unwrap()
+                .iter()
+                .filter(|it| it.kind == PdfLinkKind::PlainText)
+                .map(|it| &it.url)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn scrape_from_vec_test() {
+        let links = scrape_from_slice(TEST_PDF.to_vec()).unwrap();
+        assert_eq!(links, scrape(TEST_PDF).unwrap());
+    }
+
+    #[test]
+    fn scrape_pages_test() {
+        let all_links = scrape(TEST_PDF).unwrap();
+        let last_page = all_links.iter().map(|it| it.location.page).max().unwrap();
+        let links = scrape_pages(TEST_PDF, &[last_page]).unwrap();
+        assert!(!links.is_empty());
+        assert!(links.iter().all(|it| it.location.page == last_page));
+        assert_eq!(
+            links.len(),
+            all_links
+                .iter()
+                .filter(|it| it.location.page == last_page)
+                .count()
+        );
+    }
+
+    #[test]
+    fn scrape_pages_out_of_range_test() {
+        assert!(matches!(
+            scrape_pages(TEST_PDF, &[0]),
+            Err(PdfScrapingError::PageOutOfRangeError { page: 0, .. })
+        ));
+        assert!(matches!(
+            scrape_pages(TEST_PDF, &[1, 1000]),
+            Err(PdfScrapingError::PageOutOfRangeError { page: 1000, .. })
+        ));
+    }
+
+    #[test]
+    fn scrape_channel_test() {
+        let links = scrape_channel(TEST_PDF)
+            .iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(!links.is_empty());
+        assert_eq!(links.len(), scrape(TEST_PDF).unwrap().len());
+    }
+
+    #[test]
+    fn scrape_channel_error_test() {
+        let mut messages = scrape_channel(NOT_A_PDF).into_iter();
+        assert!(matches!(messages.next(), Some(Err(_))));
+        assert!(messages.next().is_none());
+    }
+
     #[test]
     fn fail_on_encrypted_without_pw_test() {
         let links = scrape(TEST_PDF_ENCRYPTED);
@@ -209,4 +1382,15 @@ mod tests {
         let error = bytes_to_pdf(NOT_A_PDF);
         assert!(error.is_err())
     }
+
+    #[test]
+    fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/pdf/pdf_test.pdf").unwrap();
+        let from_slice = scrape(TEST_PDF).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,33 +1,346 @@
-use crate::helpers::find_urls;
+use crate::config::ScrapeConfig;
+use crate::helpers::{
+    find_matches, find_urls, is_invisible_char, redact_url, sentence_around, strip_invisible_chars,
+    AsUrl, MatchKind,
+};
+use crate::links::HasLocation;
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
 use std::fmt::{Display, Formatter};
 use std::io::BufRead;
+use std::ops::Range;
 use thiserror::Error;
 
-pub fn scrape<R>(mut buf_reader: R) -> Result<Vec<TextFileLink>, TextFileScrapingError>
+pub fn scrape<R>(buf_reader: R) -> Result<Vec<TextFileLink>, TextFileScrapingError>
 where
     R: BufRead,
 {
-    let mut collector: Vec<TextFileLink> = vec![];
-    let mut contents = String::new();
-    let mut line_result = buf_reader.read_line(&mut contents)?;
-    let mut current_line = 1;
-    while line_result > 0 {
-        find_urls(&contents).iter().for_each(|link| {
-            collector.push(TextFileLink {
-                url: link.as_str().to_string(),
+    scrape_with_config(buf_reader, &ScrapeConfig::default())
+}
+
+/// Like [`scrape`], but applies the given [`ScrapeConfig`].
+///
+/// With [`ScrapeConfig::join_wrapped_lines`] a link reaching the end of a line is joined with the beginning of
+/// the next line, if that looks like the continuation of an url (contains one of `/?=&%#~_` or an inner `.`),
+/// or if the line ends with a quoted-printable soft line break (`=`).
+/// Joined links are attributed to the line they start on.
+///
+/// With [`ScrapeConfig::with_sentence`] the text is split into paragraphs at blank lines,
+/// so a sentence may span multiple lines.
+pub fn scrape_with_config<R>(
+    buf_reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<TextFileLink>, TextFileScrapingError>
+where
+    R: BufRead,
+{
+    let mut buf_reader = buf_reader.take(read_limit(config));
+    let mut scraper = LineScraper::new(config);
+    let mut contents = vec![];
+    let mut total_bytes = 0;
+    while buf_reader.read_until(b'\n', &mut contents)? > 0 {
+        total_bytes += contents.len() as u64;
+        config.check_input_size(total_bytes)?;
+        scraper.scrape_line(utf8_line(&contents)?);
+        contents.clear();
+    }
+    Ok(scraper.finish())
+}
+
+/// Like [`scrape_with_config`], but reads the lines asynchronously.
+///
+/// The stream is processed line by line, so it does not need to be buffered completely.
+/// Lines and positions match the ones of the synchronous scraper.
+#[cfg(feature = "async")]
+pub async fn scrape_async<R>(
+    buf_reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<TextFileLink>, TextFileScrapingError>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    let mut buf_reader = buf_reader.take(read_limit(config));
+    let mut scraper = LineScraper::new(config);
+    let mut contents = vec![];
+    let mut total_bytes = 0;
+    while buf_reader.read_until(b'\n', &mut contents).await? > 0 {
+        total_bytes += contents.len() as u64;
+        config.check_input_size(total_bytes)?;
+        scraper.scrape_line(utf8_line(&contents)?);
+        contents.clear();
+    }
+    Ok(scraper.finish())
+}
+
+/// The number of bytes to read at most. A single byte beyond [`ScrapeConfig::max_input_bytes`] is enough to reject
+/// the input, so a huge line without any line break is never buffered completely.
+fn read_limit(config: &ScrapeConfig) -> u64 {
+    config
+        .max_input_bytes
+        .map_or(u64::MAX, |limit| (limit as u64).saturating_add(1))
+}
+
+/// Decodes a line once its size was checked, as the [`read_limit`] may cut it in the middle of a character.
+fn utf8_line(contents: &[u8]) -> Result<&str, std::io::Error> {
+    std::str::from_utf8(contents)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Collects the links of a text that is fed line by line.
+struct LineScraper<'a> {
+    config: &'a ScrapeConfig,
+    collector: Vec<TextFileLink>,
+    /// Index and text of the last link, if it reached the end of the previous line
+    wrapped: Option<(usize, String)>,
+    current_line: usize,
+    /// Lines since the last blank line, joined by spaces. Only filled with [`ScrapeConfig::with_sentence`]
+    paragraph: String,
+    /// Index and byte-range inside [`Self::paragraph`] of the links found in the current paragraph
+    paragraph_links: Vec<(usize, Range<usize>)>,
+}
+
+impl<'a> LineScraper<'a> {
+    fn new(config: &'a ScrapeConfig) -> Self {
+        Self {
+            config,
+            collector: vec![],
+            wrapped: None,
+            current_line: 1,
+            paragraph: String::new(),
+            paragraph_links: vec![],
+        }
+    }
+
+    fn scrape_line(&mut self, contents: &str) {
+        let raw_line = contents.trim_end_matches(['\n', '\r']);
+        let line = if self.config.strip_invisible_chars {
+            strip_invisible_chars(raw_line)
+        } else {
+            Cow::Borrowed(raw_line)
+        };
+        let line = line.as_ref();
+
+        let mut offset = 0;
+        let mut continued: Option<(usize, String)> = None;
+        if let Some((index, fragment)) = self.wrapped.take() {
+            if let Some((url, consumed)) = join_wrapped_url(&fragment, line) {
+                self.collector[index].url = url.clone();
+                offset = consumed;
+                continued = Some((index, url));
+            }
+        }
+
+        let rest = &line[offset..];
+        let links = find_matches(rest, self.config);
+        links.iter().for_each(|link| {
+            self.collector.push(TextFileLink {
+                url: link.as_str(rest).to_string(),
+                kind: link.kind,
                 location: TextFileLinkLocation {
-                    line: current_line,
-                    pos: link.start(),
+                    line: self.current_line,
+                    pos: raw_position(raw_line, offset + link.start),
                 },
+                sentence: None,
             })
         });
 
-        contents.clear();
-        line_result = buf_reader.read_line(&mut contents)?;
-        current_line += 1;
+        if self.config.with_sentence {
+            if line.trim().is_empty() {
+                self.attach_sentences();
+            } else {
+                if !self.paragraph.is_empty() {
+                    self.paragraph.push(' ');
+                }
+                let start = self.paragraph.len() + offset;
+                let first_index = self.collector.len() - links.len();
+                self.paragraph_links
+                    .extend(links.iter().enumerate().map(|(index, link)| {
+                        (first_index + index, start + link.start..start + link.end)
+                    }));
+                self.paragraph.push_str(line);
+            }
+        }
+
+        if self.config.join_wrapped_lines {
+            self.wrapped = match links.last() {
+                Some(link) if link.kind == MatchKind::Url && offset + link.end == line.len() => {
+                    Some((self.collector.len() - 1, link.as_str(rest).to_string()))
+                }
+                None if offset == line.len() => continued,
+                _ => None,
+            };
+        }
+
+        self.current_line += 1;
+    }
+
+    /// Attaches the sentences of the current paragraph to its links and starts a new paragraph.
+    fn attach_sentences(&mut self) {
+        for (index, range) in self.paragraph_links.drain(..) {
+            self.collector[index].sentence =
+                Some(sentence_around(&self.paragraph, range).to_string());
+        }
+        self.paragraph.clear();
+    }
+
+    /// Removes the links that will not change anymore from the collector.
+    ///
+    /// The last link may still be continued on the next line, and links of the current paragraph
+    /// still need their sentence, so these (and all links after them) are kept.
+    fn drain_completed(&mut self) -> Vec<TextFileLink> {
+        let completed = [
+            self.wrapped.as_ref().map(|(index, _)| *index),
+            self.paragraph_links.first().map(|(index, _)| *index),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(self.collector.len());
+        let links = self.collector.drain(..completed).collect();
+        if let Some((index, _)) = &mut self.wrapped {
+            *index -= completed;
+        }
+        for (index, _) in &mut self.paragraph_links {
+            *index -= completed;
+        }
+        self.post_process(links)
+    }
+
+    fn finish(mut self) -> Vec<TextFileLink> {
+        self.attach_sentences();
+        let links = std::mem::take(&mut self.collector);
+        self.post_process(links)
+    }
+
+    fn post_process(&self, mut links: Vec<TextFileLink>) -> Vec<TextFileLink> {
+        #[cfg(feature = "encoding")]
+        if self.config.repair_mojibake {
+            for link in &mut links {
+                if let Some(repaired) = crate::helpers::repair_mojibake(&link.url) {
+                    link.url = repaired;
+                }
+            }
+        }
+        links.retain(|link| self.config.accepts(&link.url));
+        if let Some(policy) = self.config.redact {
+            for link in &mut links {
+                link.url = redact_url(&link.url, policy);
+            }
+        }
+        links
+    }
+}
+
+/// Scrapes text that arrives in chunks of arbitrary size, e.g. from a socket.
+///
+/// Chunks do not need to be aligned to lines or even characters, the incomplete last line is buffered
+/// until the rest of it arrives. Lines and positions are counted across all chunks,
+/// so they match the ones [`scrape_with_config`] would return for the whole text.
+/// # Example
+/// ```
+/// use link_scraper::formats::plaintext::StreamingScraper;
+///
+/// let mut scraper = StreamingScraper::new();
+/// assert!(scraper.push(b"see https://exam").is_empty());
+/// let links = scraper.push(b"ple.com\n");
+/// assert_eq!(links[0].url, "https://example.com");
+/// assert!(scraper.finish().is_empty());
+/// ```
+pub struct StreamingScraper<'a> {
+    scraper: LineScraper<'a>,
+    /// The incomplete last line
+    pending: Vec<u8>,
+}
+
+impl StreamingScraper<'static> {
+    /// Creates a scraper using the default [`ScrapeConfig`].
+    pub fn new() -> Self {
+        static DEFAULT_CONFIG: Lazy<ScrapeConfig> = Lazy::new(ScrapeConfig::default);
+        Self::with_config(&DEFAULT_CONFIG)
+    }
+}
+
+impl Default for StreamingScraper<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> StreamingScraper<'a> {
+    /// Creates a scraper applying the given [`ScrapeConfig`] like [`scrape_with_config`].
+    pub fn with_config(config: &'a ScrapeConfig) -> Self {
+        Self {
+            scraper: LineScraper::new(config),
+            pending: vec![],
+        }
     }
-    Ok(collector)
+
+    /// Scrapes all lines completed by `chunk` and returns the links that will not change anymore.
+    ///
+    /// With [`ScrapeConfig::join_wrapped_lines`] a link at the end of a line is held back until the next line
+    /// arrives, with [`ScrapeConfig::with_sentence`] links are held back until their paragraph ends.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<TextFileLink> {
+        self.pending.extend_from_slice(chunk);
+        if let Some(last_newline) = self.pending.iter().rposition(|&byte| byte == b'\n') {
+            let rest = self.pending.split_off(last_newline + 1);
+            let lines = std::mem::replace(&mut self.pending, rest);
+            for line in lines.split_inclusive(|&byte| byte == b'\n') {
+                self.scraper.scrape_line(&String::from_utf8_lossy(line));
+            }
+        }
+        self.scraper.drain_completed()
+    }
+
+    /// Scrapes the incomplete last line and returns all links that were held back.
+    pub fn finish(mut self) -> Vec<TextFileLink> {
+        if !self.pending.is_empty() {
+            self.scraper
+                .scrape_line(&String::from_utf8_lossy(&self.pending));
+        }
+        self.scraper.finish()
+    }
+}
+
+/// Translates a position inside the line without invisible characters into one inside the `raw_line`.
+fn raw_position(raw_line: &str, position: usize) -> usize {
+    let mut stripped = 0;
+    for (index, c) in raw_line.char_indices() {
+        if stripped == position {
+            return index;
+        }
+        if !is_invisible_char(c) {
+            stripped += c.len_utf8();
+        }
+    }
+    raw_line.len()
+}
+
+/// Tries to continue the url `fragment` with the beginning of `line`.
+///
+/// Returns the joined url and the amount of bytes of `line` it consumed.
+fn join_wrapped_url(fragment: &str, line: &str) -> Option<(String, usize)> {
+    let soft_break = fragment.strip_suffix('=');
+    let fragment = soft_break.unwrap_or(fragment);
+    let first_word = line.split(char::is_whitespace).next()?;
+    if first_word.is_empty() || (soft_break.is_none() && !is_url_continuation(first_word)) {
+        return None;
+    }
+
+    let joined = format!("{}{}", fragment, line);
+    let link = find_urls(&joined)
+        .into_iter()
+        .next()
+        .filter(|link| link.start() == 0 && link.end() > fragment.len())?;
+    Some((link.as_str().to_string(), link.end() - fragment.len()))
+}
+
+/// Plain words at the beginning of a line are most likely not part of the url on the line before.
+fn is_url_continuation(word: &str) -> bool {
+    let word = word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+    word.contains(['/', '?', '=', '&', '%', '#', '~', '_']) || word.contains('.')
 }
 gen_scrape_from_file!(scrape(Read)-> Result<Vec<TextFileLink>, TextFileScrapingError>);
 gen_scrape_from_slice!(scrape(Read)-> Result<Vec<TextFileLink>, TextFileScrapingError>);
@@ -36,12 +349,18 @@ gen_scrape_from_slice!(scrape(Read)-> Result<Vec<TextFileLink>, TextFileScraping
 pub enum TextFileScrapingError {
     #[error(transparent)]
     IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    InputTooLargeError(#[from] crate::config::InputTooLargeError),
 }
 
 #[derive(Debug, Clone)]
 pub struct TextFileLink {
     pub url: String,
+    /// Email-addresses are only returned with [`ScrapeConfig::include_emails`]
+    pub kind: MatchKind,
     pub location: TextFileLinkLocation,
+    /// The sentence containing the link. Only set with [`ScrapeConfig::with_sentence`]
+    pub sentence: Option<String>,
 }
 
 impl Display for TextFileLink {
@@ -50,6 +369,20 @@ impl Display for TextFileLink {
     }
 }
 
+impl AsUrl for TextFileLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl HasLocation for TextFileLink {
+    type Location = (usize, usize);
+
+    fn location(&self) -> Self::Location {
+        (self.location.line, self.location.pos)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TextFileLinkLocation {
     pub line: usize,
@@ -59,8 +392,11 @@ pub struct TextFileLinkLocation {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::{RedactPolicy, UrlMatch, UrlMatcher};
+    use std::sync::Arc;
 
     const TEST_XML: &[u8] = include_bytes!("../../test_files/xml/xml_test.xml");
+    const TEST_EMAILS: &[u8] = include_bytes!("../../test_files/plaintext/emails_test.txt");
 
     #[test]
     fn scrape_test() {
@@ -76,4 +412,275 @@ mod tests {
         assert!(links.iter().any(|it| it.url == "https://cdata.test.com"));
         assert!(links.iter().any(|it| it.url == "https://ns.test.com"));
     }
+
+    #[test]
+    fn join_wrapped_lines_test() {
+        let config = ScrapeConfig {
+            join_wrapped_lines: true,
+            ..ScrapeConfig::default()
+        };
+        let text = "first line\nsee https://wrapped.test.com/some/\nlong/path.html for details\n\
+            and https://soft.test.com/a=\nb?c=d\n\
+            or https://not.wrapped.test.com\nand some text";
+        let links = scrape_with_config(text.as_bytes(), &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].url, "https://wrapped.test.com/some/long/path.html");
+        assert_eq!(links[0].location.line, 2);
+        assert_eq!(links[0].location.pos, 4);
+        assert_eq!(links[1].url, "https://soft.test.com/ab?c=d");
+        assert_eq!(links[2].url, "https://not.wrapped.test.com");
+
+        let unjoined = scrape(text.as_bytes()).unwrap();
+        assert_eq!(unjoined[0].url, "https://wrapped.test.com/some/");
+    }
+
+    #[test]
+    fn with_sentence_test() {
+        let config = ScrapeConfig {
+            with_sentence: true,
+            ..ScrapeConfig::default()
+        };
+        let text = "Intro. The docs at https://docs.test.com/v1.2/index.html explain it.\n\
+            They were moved.\n\nSee https://other.test.com! Then stop.";
+        let links = scrape_with_config(text.as_bytes(), &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(links[0].url, "https://docs.test.com/v1.2/index.html");
+        assert_eq!(
+            links[0].sentence.as_deref(),
+            Some("The docs at https://docs.test.com/v1.2/index.html explain it.")
+        );
+        assert_eq!(
+            links[1].sentence.as_deref(),
+            Some("See https://other.test.com!")
+        );
+
+        assert!(scrape(text.as_bytes()).unwrap()[0].sentence.is_none());
+    }
+
+    #[test]
+    fn strip_invisible_chars_test() {
+        let config = ScrapeConfig {
+            strip_invisible_chars: true,
+            ..ScrapeConfig::default()
+        };
+        let text = "\u{200b}see https://ex\u{ad}ample.test.com/re\u{ad}port.pdf";
+        assert_eq!(
+            scrape(text.as_bytes()).unwrap()[0].url,
+            "https://ex\u{ad}ample.test.com/re\u{ad}port.pdf"
+        );
+
+        let links = scrape_with_config(text.as_bytes(), &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://example.test.com/report.pdf");
+        assert_eq!(links[0].location.pos, text.find("https").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "encoding")]
+    fn repair_mojibake_test() {
+        let config = ScrapeConfig {
+            repair_mojibake: true,
+            ..ScrapeConfig::default()
+        };
+        let text = "broken https://Ã¼ber.test.com/straÃŸe and fine https://über.test.com/café";
+        let links = scrape_with_config(text.as_bytes(), &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(links[0].url, "https://über.test.com/straße");
+        assert_eq!(
+            url::Url::parse(&links[0].url).unwrap().host_str(),
+            Some("xn--ber-goa.test.com")
+        );
+        assert_eq!(links[1].url, "https://über.test.com/café");
+    }
+
+    #[test]
+    fn redact_test() {
+        let config = ScrapeConfig {
+            redact: Some(RedactPolicy::HostOnly),
+            drop_private_hosts: true,
+            ..ScrapeConfig::default()
+        };
+        let text = "login https://x.com/secret?token=abc or http://localhost/admin?token=abc";
+        let links = scrape_with_config(text.as_bytes(), &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links.iter().map(|link| link.url()).collect::<Vec<_>>(),
+            ["https://x.com"]
+        );
+    }
+
+    #[test]
+    fn require_valid_tld_test() {
+        let mut config = ScrapeConfig {
+            require_valid_tld: true,
+            ..ScrapeConfig::default()
+        };
+        let text = "http://foo and http://foo.com and http://localhost:8080/ and http://10.0.0.1/";
+        let urls = |config: &ScrapeConfig| {
+            scrape_with_config(text.as_bytes(), config)
+                .unwrap()
+                .into_iter()
+                .map(|link| link.url)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(urls(&config), ["http://foo.com", "http://10.0.0.1/"]);
+
+        config.allow_localhost = true;
+        assert_eq!(
+            urls(&config),
+            [
+                "http://foo.com",
+                "http://localhost:8080/",
+                "http://10.0.0.1/"
+            ]
+        );
+    }
+
+    #[test]
+    fn include_emails_test() {
+        let config = ScrapeConfig {
+            include_emails: true,
+            ..ScrapeConfig::default()
+        };
+        let links = scrape_with_config(TEST_EMAILS, &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links
+                .iter()
+                .map(|it| (it.url.as_str(), it.kind))
+                .collect::<Vec<_>>(),
+            [
+                ("https://website.test.com/contact", MatchKind::Url),
+                ("support@email.test.com", MatchKind::Email),
+                ("https://other.test.com", MatchKind::Url),
+            ]
+        );
+
+        let links = scrape(TEST_EMAILS).unwrap();
+        assert_eq!(links.len(), 2);
+        assert!(links.iter().all(|it| it.kind == MatchKind::Url));
+    }
+
+    /// Matches every whitespace-separated word starting with `urn:`
+    struct UrnMatcher;
+
+    impl UrlMatcher for UrnMatcher {
+        fn find(&self, content: &str) -> Vec<UrlMatch> {
+            content
+                .split_whitespace()
+                .filter(|word| word.starts_with("urn:"))
+                .map(|word| {
+                    let start = word.as_ptr() as usize - content.as_ptr() as usize;
+                    UrlMatch {
+                        start,
+                        end: start + word.len(),
+                        kind: MatchKind::Url,
+                    }
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn custom_url_matcher_test() {
+        let config = ScrapeConfig {
+            url_matcher: Some(Arc::new(UrnMatcher)),
+            // `urn:`-urls count as schema-urls
+            include_schema_urls: true,
+            ..ScrapeConfig::default()
+        };
+        let text = "https://ignored.test.com\nbook urn:isbn:0451450523 and urn:ietf:rfc:2648\n";
+        let links = scrape_with_config(text.as_bytes(), &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links
+                .iter()
+                .map(|it| (it.url.as_str(), it.location.line, it.location.pos))
+                .collect::<Vec<_>>(),
+            [("urn:isbn:0451450523", 2, 5), ("urn:ietf:rfc:2648", 2, 29)]
+        );
+    }
+
+    #[test]
+    fn streaming_scraper_test() {
+        let mut scraper = StreamingScraper::new();
+        assert!(scraper.push(b"first line\nsee https://split.te").is_empty());
+        let links = scraper.push(b"st.com/page and \xc3");
+        assert!(links.is_empty());
+        let links = scraper.push(b"\xbc\nlast https://last.test.com");
+        println!("{:?}", links);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://split.test.com/page");
+        assert_eq!(links[0].location.line, 2);
+        assert_eq!(links[0].location.pos, 4);
+
+        let links = scraper.finish();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://last.test.com");
+        assert_eq!(links[0].location.line, 3);
+    }
+
+    #[test]
+    fn streaming_scraper_holds_back_wrapped_links_test() {
+        let config = ScrapeConfig {
+            join_wrapped_lines: true,
+            ..ScrapeConfig::default()
+        };
+        let mut scraper = StreamingScraper::with_config(&config);
+        assert!(scraper
+            .push(b"see https://wrapped.test.com/some/\n")
+            .is_empty());
+        let links = scraper.push(b"long/path.html for details\n");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://wrapped.test.com/some/long/path.html");
+        assert_eq!(links[0].location.line, 1);
+        assert!(scraper.finish().is_empty());
+    }
+
+    #[test]
+    fn max_input_bytes_test() {
+        let config = ScrapeConfig {
+            max_input_bytes: Some(16),
+            ..ScrapeConfig::default()
+        };
+        assert!(matches!(
+            scrape_with_config(TEST_XML, &config),
+            Err(TextFileScrapingError::InputTooLargeError(_))
+        ));
+
+        // The limit ends inside the `ü`, a single line is not read any further
+        let line = format!("{}ü{}", "a".repeat(16), "b".repeat(1 << 20));
+        assert!(matches!(
+            scrape_with_config(line.as_bytes(), &config),
+            Err(TextFileScrapingError::InputTooLargeError(e)) if e.size == 17
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn scrape_async_test() {
+        let config = ScrapeConfig::default();
+        let links = scrape_async(TEST_XML, &config).await.unwrap();
+        let sync_links = scrape_with_config(TEST_XML, &config).unwrap();
+        assert_eq!(links.len(), sync_links.len());
+        assert!(links
+            .iter()
+            .zip(sync_links.iter())
+            .all(|(a, b)| a.url == b.url
+                && a.location.line == b.location.line
+                && a.location.pos == b.location.pos));
+    }
+
+    #[test]
+    fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/xml/xml_test.xml").unwrap();
+        let from_slice = scrape_from_slice(TEST_XML).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
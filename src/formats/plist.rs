@@ -0,0 +1,99 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use ::plist::Value;
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Seek};
+use thiserror::Error;
+
+/// Scrapes links from all string-values of a property list (e.g. an iOS `Info.plist`).
+///
+/// Binary (`bplist00`) and XML property lists are both supported.
+/// Every link is tagged with the path of keys and array-indices leading to its value, formatted like a
+/// [JSON Pointer](https://datatracker.ietf.org/doc/html/rfc6901).
+pub fn scrape<R>(reader: R) -> Result<Vec<PlistLink>, PlistScrapingError>
+where
+    R: Read + Seek,
+{
+    let value = Value::from_reader(reader)?;
+    let mut collector: Vec<PlistLink> = vec![];
+    collect_links(&value, String::new(), &mut collector);
+    Ok(collector)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<PlistLink>, PlistScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<PlistLink>, PlistScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum PlistScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    PlistError(#[from] ::plist::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlistLink {
+    pub url: String,
+    /// Keys and array-indices leading to the string containing the link <br/>
+    /// Example: `/CFBundleURLTypes/0/CFBundleURLName`
+    pub key_path: String,
+}
+
+impl Display for PlistLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for PlistLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+fn collect_links(value: &Value, key_path: String, collector: &mut Vec<PlistLink>) {
+    match value {
+        Value::String(string) => find_urls(string).iter().for_each(|link| {
+            collector.push(PlistLink {
+                url: link.as_str().to_string(),
+                key_path: key_path.clone(),
+            })
+        }),
+        Value::Array(values) => values.iter().enumerate().for_each(|(index, value)| {
+            collect_links(value, format!("{}/{}", key_path, index), collector)
+        }),
+        Value::Dictionary(dictionary) => dictionary.iter().for_each(|(key, value)| {
+            let key = key.replace('~', "~0").replace('/', "~1");
+            collect_links(value, format!("{}/{}", key_path, key), collector)
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_BINARY_PLIST: &[u8] = include_bytes!("../../test_files/plist/binary_test.plist");
+    const TEST_XML_PLIST: &[u8] = include_bytes!("../../test_files/plist/xml_test.plist");
+
+    #[test]
+    fn scrape_binary_plist_test() {
+        assert!(TEST_BINARY_PLIST.starts_with(b"bplist00"));
+        let links = scrape_from_slice(TEST_BINARY_PLIST).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&PlistLink {
+            url: "https://api.plist.test.com/v2".to_string(),
+            key_path: "/APIBaseURL".to_string()
+        }));
+        assert!(links.contains(&PlistLink {
+            url: "https://support.plist.test.com".to_string(),
+            key_path: "/Endpoints/0/URL".to_string()
+        }));
+    }
+
+    #[test]
+    fn scrape_xml_plist_test() {
+        let links = scrape_from_slice(TEST_XML_PLIST).unwrap();
+        assert_eq!(links, scrape_from_slice(TEST_BINARY_PLIST).unwrap());
+    }
+}
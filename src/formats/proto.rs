@@ -0,0 +1,145 @@
+use crate::formats::source_code::{tokenize, Language, SourceLinkContext};
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes links from the comments and option strings of a `.proto` schema.
+///
+/// Handles `//` and `/* */` comments. Everything outside of comments and string literals is ignored.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<ProtoLink>, ProtoScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(scrape_from_string(content))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<ProtoLink>, ProtoScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<ProtoLink>, ProtoScrapingError>);
+
+/// Like [`scrape`] but takes the schema as a string.
+pub fn scrape_from_string<S>(content: S) -> Vec<ProtoLink>
+where
+    S: AsRef<str>,
+{
+    scrape_with_language(content.as_ref(), Language::CStyle)
+}
+
+/// Like [`scrape_from_string`] but for messages in the Protocol Buffers text format (`.textproto`, `.txtpb`),
+/// whose comments start with `#`.
+pub fn scrape_text_format<S>(content: S) -> Vec<ProtoLink>
+where
+    S: AsRef<str>,
+{
+    // Python has the same comment-syntax and escapes inside of string literals.
+    scrape_with_language(content.as_ref(), Language::Python)
+}
+
+#[derive(Error, Debug)]
+pub enum ProtoScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtoLink {
+    pub url: String,
+    pub context: ProtoLinkContext,
+    /// 1-based line the link starts on
+    pub line: usize,
+}
+
+impl Display for ProtoLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for ProtoLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProtoLinkContext {
+    /// The link is inside a comment <br/>
+    /// Example: `// see https://link.example.com`
+    Comment,
+    /// The link is inside a string, usually the value of an option <br/>
+    /// Example: `option (google.api.http) = { get: "https://link.example.com" };`
+    OptionString,
+}
+
+fn scrape_with_language(content: &str, language: Language) -> Vec<ProtoLink> {
+    let mut collector: Vec<ProtoLink> = vec![];
+    tokenize(content, language, |segment, line, context| {
+        let context = match context {
+            SourceLinkContext::Comment => ProtoLinkContext::Comment,
+            SourceLinkContext::StringLiteral => ProtoLinkContext::OptionString,
+        };
+        find_urls(segment).iter().for_each(|link| {
+            collector.push(ProtoLink {
+                url: link.as_str().to_string(),
+                context,
+                line: line + segment[..link.start()].matches('\n').count(),
+            })
+        });
+    });
+    collector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_PROTO: &[u8] = include_bytes!("../../test_files/proto/proto_test.proto");
+
+    #[test]
+    fn scrape_proto_test() {
+        let links = scrape_from_slice(TEST_PROTO).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&ProtoLink {
+            url: "https://docs.proto.test.com/users".to_string(),
+            context: ProtoLinkContext::Comment,
+            line: 5
+        }));
+        assert!(links.contains(&ProtoLink {
+            url: "https://api.proto.test.com/v1/users".to_string(),
+            context: ProtoLinkContext::OptionString,
+            line: 10
+        }));
+        assert!(links.contains(&ProtoLink {
+            url: "https://block.proto.test.com".to_string(),
+            context: ProtoLinkContext::Comment,
+            line: 17
+        }));
+    }
+
+    #[test]
+    fn scrape_text_format_test() {
+        let links = scrape_text_format(
+            "# https://comment.proto.test.com\nendpoint: \"https://string.proto.test.com\"",
+        );
+        assert_eq!(
+            links,
+            vec![
+                ProtoLink {
+                    url: "https://comment.proto.test.com".to_string(),
+                    context: ProtoLinkContext::Comment,
+                    line: 1
+                },
+                ProtoLink {
+                    url: "https://string.proto.test.com".to_string(),
+                    context: ProtoLinkContext::OptionString,
+                    line: 2
+                }
+            ]
+        );
+    }
+}
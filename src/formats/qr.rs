@@ -0,0 +1,845 @@
+/// Images with more pixels are not decoded, to bound the cost of pathological images.
+pub(crate) const MAX_QR_IMAGE_PIXELS: usize = 4096 * 4096;
+
+/// Decodes the first QR-code found in a PNG-image and returns its payload.
+///
+/// Returns [`None`] if the image is not a valid PNG, is larger than [`MAX_QR_IMAGE_PIXELS`]
+/// or does not contain a readable QR-code.
+#[cfg(any(feature = "image", feature = "ooxml", feature = "odf"))]
+pub(crate) fn decode_png(bytes: &[u8]) -> Option<String> {
+    let mut decoder = png::Decoder::new_with_limits(
+        bytes,
+        png::Limits {
+            bytes: MAX_QR_IMAGE_PIXELS * 8,
+        },
+    );
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().ok()?;
+    let (width, height) = (reader.info().width as usize, reader.info().height as usize);
+    if width.checked_mul(height)? > MAX_QR_IMAGE_PIXELS {
+        return None;
+    }
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).ok()?;
+    let luma = buffer[..frame.buffer_size()]
+        .chunks_exact(frame.color_type.samples())
+        .map(|pixel| match pixel {
+            [gray] => *gray,
+            [gray, alpha] => over_white(*gray, *alpha),
+            [r, g, b] => to_luma(*r, *g, *b),
+            [r, g, b, alpha, ..] => over_white(to_luma(*r, *g, *b), *alpha),
+            [] => 255,
+        })
+        .collect::<Vec<_>>();
+    decode_luma(width, height, &luma)
+}
+
+/// Decodes the first QR-code found in uncompressed pixels, e.g. the samples of a PDF image.
+///
+/// Every row starts at a new byte. Only gray (`1` component) and RGB (`3` components) with 1 or 8 bits are supported.
+#[cfg(feature = "pdf")]
+pub(crate) fn decode_samples(
+    width: usize,
+    height: usize,
+    components: usize,
+    bits: usize,
+    samples: &[u8],
+) -> Option<String> {
+    if width.checked_mul(height)? > MAX_QR_IMAGE_PIXELS {
+        return None;
+    }
+    let luma = match (components, bits) {
+        (1, 1) => {
+            let row_length = width.div_ceil(8);
+            samples
+                .chunks_exact(row_length)
+                .take(height)
+                .flat_map(|row| {
+                    (0..width).map(move |x| {
+                        if row[x / 8] >> (7 - x % 8) & 1 == 0 {
+                            0
+                        } else {
+                            255
+                        }
+                    })
+                })
+                .collect::<Vec<_>>()
+        }
+        (1, 8) => samples.to_vec(),
+        (3, 8) => samples
+            .chunks_exact(3)
+            .map(|pixel| to_luma(pixel[0], pixel[1], pixel[2]))
+            .collect(),
+        _ => return None,
+    };
+    decode_luma(width, height, &luma)
+}
+
+fn to_luma(r: u8, g: u8, b: u8) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+/// Transparent pixels are treated as white, since that is what they are usually printed on.
+#[cfg(any(feature = "image", feature = "ooxml", feature = "odf"))]
+fn over_white(gray: u8, alpha: u8) -> u8 {
+    ((gray as u32 * alpha as u32 + 255 * (255 - alpha as u32)) / 255) as u8
+}
+
+/// Decodes the first QR-code found in an image given as one luma-byte per pixel, row by row.
+///
+/// Only upright or rotated codes are found, perspective distortion is not corrected.
+fn decode_luma(width: usize, height: usize, luma: &[u8]) -> Option<String> {
+    if width.checked_mul(height)? > MAX_QR_IMAGE_PIXELS || luma.len() < width * height {
+        return None;
+    }
+    let image = BinaryImage::new(width, height, luma)?;
+    let [top_left, top_right, bottom_left] = find_finder_patterns(&image)?;
+    // Rotated codes cross the rows and columns diagonally, which makes their runs longer by 1 / cos(angle)
+    let (dx, dy) = (top_right.x - top_left.x, top_right.y - top_left.y);
+    let rotation = dx.abs().max(dy.abs()) / dx.hypot(dy);
+    let module_size =
+        rotation * (top_left.module_size + top_right.module_size + bottom_left.module_size) / 3.0;
+    let estimated = ((top_left.distance(&top_right) + top_left.distance(&bottom_left))
+        / (2.0 * module_size))
+        .round() as usize
+        + 7;
+    // Misjudged module sizes are corrected by also trying the neighbouring versions
+    let size = match estimated % 4 {
+        0 => estimated + 1,
+        2 => estimated - 1,
+        3 => estimated + 2,
+        _ => estimated,
+    };
+    [size, size.saturating_sub(4), size + 4]
+        .into_iter()
+        .filter(|size| (21..=177).contains(size))
+        .find_map(|size| {
+            let grid = Grid::sample(&image, size, &top_left, &top_right, &bottom_left);
+            grid.decode()
+        })
+}
+
+/// The pixels of an image, split into dark and light at the middle of the darkest and the lightest pixel.
+struct BinaryImage {
+    width: usize,
+    height: usize,
+    dark: Vec<bool>,
+}
+
+impl BinaryImage {
+    fn new(width: usize, height: usize, luma: &[u8]) -> Option<Self> {
+        let luma = &luma[..width * height];
+        let min = *luma.iter().min()?;
+        let max = *luma.iter().max()?;
+        if max - min < 32 {
+            return None;
+        }
+        let threshold = min + (max - min) / 2;
+        Some(Self {
+            width,
+            height,
+            dark: luma.iter().map(|&value| value <= threshold).collect(),
+        })
+    }
+
+    fn is_dark(&self, x: isize, y: isize) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.dark[y as usize * self.width + x as usize]
+    }
+}
+
+/// The center of one of the three squares in the corners of a QR-code.
+#[derive(Debug, Clone, Copy)]
+struct FinderPattern {
+    x: f64,
+    y: f64,
+    module_size: f64,
+    /// How often the pattern was found while scanning the rows of the image
+    count: usize,
+}
+
+impl FinderPattern {
+    fn distance(&self, other: &FinderPattern) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// Whether five runs of alternating colors have the ratio 1:1:3:1:1 of a finder pattern.
+fn is_finder_ratio(runs: &[usize; 5]) -> bool {
+    let total: usize = runs.iter().sum();
+    if total < 7 {
+        return false;
+    }
+    let module = total as f64 / 7.0;
+    let tolerance = module / 2.0;
+    runs.iter()
+        .zip([1.0, 1.0, 3.0, 1.0, 1.0])
+        .all(|(&run, modules)| (run as f64 - module * modules).abs() < tolerance * modules)
+}
+
+/// Scans every row for the 1:1:3:1:1 pattern of dark and light runs,
+/// checks it vertically and returns the three patterns found most often as top-left, top-right and bottom-left.
+fn find_finder_patterns(image: &BinaryImage) -> Option<[FinderPattern; 3]> {
+    let mut patterns: Vec<FinderPattern> = vec![];
+    for y in 0..image.height {
+        let row = &image.dark[y * image.width..(y + 1) * image.width];
+        // Start and length of every run of equally colored pixels
+        let mut runs: Vec<(usize, usize)> = vec![];
+        for (x, &dark) in row.iter().enumerate() {
+            if x > 0 && row[x - 1] == dark {
+                runs.last_mut()?.1 += 1;
+            } else {
+                runs.push((x, 1));
+            }
+        }
+        for window in runs.windows(5) {
+            if !row[window[0].0] {
+                continue;
+            }
+            let lengths = [
+                window[0].1,
+                window[1].1,
+                window[2].1,
+                window[3].1,
+                window[4].1,
+            ];
+            if !is_finder_ratio(&lengths) {
+                continue;
+            }
+            let center_x = window[2].0 as f64 + window[2].1 as f64 / 2.0;
+            let horizontal_total: usize = lengths.iter().sum();
+            let Some((center_y, vertical_total)) =
+                cross_check(image, center_x, y as f64 + 0.5, false, horizontal_total)
+            else {
+                continue;
+            };
+            let Some((center_x, horizontal_total)) =
+                cross_check(image, center_x, center_y, true, horizontal_total)
+            else {
+                continue;
+            };
+            let module_size = (horizontal_total + vertical_total) as f64 / 14.0;
+            match patterns.iter_mut().find(|pattern| {
+                (pattern.x - center_x).abs() <= module_size
+                    && (pattern.y - center_y).abs() <= module_size
+            }) {
+                Some(pattern) => {
+                    let count = pattern.count as f64;
+                    pattern.x = (pattern.x * count + center_x) / (count + 1.0);
+                    pattern.y = (pattern.y * count + center_y) / (count + 1.0);
+                    pattern.module_size =
+                        (pattern.module_size * count + module_size) / (count + 1.0);
+                    pattern.count += 1;
+                }
+                None => patterns.push(FinderPattern {
+                    x: center_x,
+                    y: center_y,
+                    module_size,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    patterns.sort_by_key(|pattern| std::cmp::Reverse(pattern.count));
+    let [a, b, c] = [*patterns.first()?, *patterns.get(1)?, *patterns.get(2)?];
+    // The top-left pattern is the one opposite of the longest side
+    let (top_left, mut top_right, mut bottom_left) =
+        if b.distance(&c) >= a.distance(&b) && b.distance(&c) >= a.distance(&c) {
+            (a, b, c)
+        } else if a.distance(&c) >= a.distance(&b) {
+            (b, a, c)
+        } else {
+            (c, a, b)
+        };
+    let cross = (top_right.x - top_left.x) * (bottom_left.y - top_left.y)
+        - (top_right.y - top_left.y) * (bottom_left.x - top_left.x);
+    if cross < 0.0 {
+        std::mem::swap(&mut top_right, &mut bottom_left);
+    }
+    Some([top_left, top_right, bottom_left])
+}
+
+/// Counts the runs of a finder pattern through `(x, y)` vertically or horizontally.
+///
+/// Returns the refined center along that direction and the total length of the runs,
+/// if they have the ratio of a finder pattern and about the length found in the other direction.
+fn cross_check(
+    image: &BinaryImage,
+    x: f64,
+    y: f64,
+    horizontal: bool,
+    expected_total: usize,
+) -> Option<(f64, usize)> {
+    let (x, y) = (x as isize, y as isize);
+    let pixel = |offset: isize| {
+        if horizontal {
+            image.is_dark(x + offset, y)
+        } else {
+            image.is_dark(x, y + offset)
+        }
+    };
+    let limit = (image.width.max(image.height)) as isize;
+    if !pixel(0) {
+        return None;
+    }
+    // The runs before the center (dark, light, dark), the center and the runs after it
+    let mut runs = [0usize; 5];
+    let mut offset = 0;
+    for (run, dark) in [(2, true), (1, false), (0, true)] {
+        while offset > -limit && pixel(offset) == dark {
+            runs[run] += 1;
+            offset -= 1;
+        }
+    }
+    let mut offset = 1;
+    for (run, dark) in [(2, true), (3, false), (4, true)] {
+        while offset < limit && pixel(offset) == dark {
+            runs[run] += 1;
+            offset += 1;
+        }
+    }
+    let total: usize = runs.iter().sum();
+    if !is_finder_ratio(&runs) || 5 * total.abs_diff(expected_total) >= 2 * expected_total {
+        return None;
+    }
+    let center_end = offset - runs[4] as isize - runs[3] as isize;
+    let center = center_end as f64 - runs[2] as f64 / 2.0;
+    let position = if horizontal { x } else { y };
+    Some((position as f64 + center, total))
+}
+
+/// Error correction levels in the order of their format bits
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EcLevel {
+    M,
+    L,
+    H,
+    Q,
+}
+
+/// The modules of a QR-code, `true` being dark.
+struct Grid {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl Grid {
+    /// Samples the center of every module, mapping the grid onto the image through the centers of the finder patterns.
+    fn sample(
+        image: &BinaryImage,
+        size: usize,
+        top_left: &FinderPattern,
+        top_right: &FinderPattern,
+        bottom_left: &FinderPattern,
+    ) -> Self {
+        let span = (size - 7) as f64;
+        let mut modules = Vec::with_capacity(size * size);
+        for row in 0..size {
+            for column in 0..size {
+                let u = (column as f64 + 0.5 - 3.5) / span;
+                let v = (row as f64 + 0.5 - 3.5) / span;
+                let x =
+                    top_left.x + u * (top_right.x - top_left.x) + v * (bottom_left.x - top_left.x);
+                let y =
+                    top_left.y + u * (top_right.y - top_left.y) + v * (bottom_left.y - top_left.y);
+                modules.push(image.is_dark(x.floor() as isize, y.floor() as isize));
+            }
+        }
+        Self { size, modules }
+    }
+
+    fn get(&self, column: usize, row: usize) -> bool {
+        self.modules[row * self.size + column]
+    }
+
+    fn decode(&self) -> Option<String> {
+        let version = (self.size - 17) / 4;
+        let (ec_level, mask) = self.format_information()?;
+        let function_modules = function_modules(version, self.size);
+
+        // The codewords are read in pairs of columns from the right, alternating upwards and downwards
+        let mut codewords: Vec<u8> = vec![];
+        let mut current = 0u8;
+        let mut bit_count = 0;
+        let mut upwards = true;
+        let mut right = self.size - 1;
+        while right > 0 {
+            if right == 6 {
+                // The vertical timing pattern is skipped
+                right -= 1;
+            }
+            for count in 0..self.size {
+                let row = if upwards {
+                    self.size - 1 - count
+                } else {
+                    count
+                };
+                for column in [right, right - 1] {
+                    if function_modules[row * self.size + column] {
+                        continue;
+                    }
+                    current = (current << 1)
+                        | (self.get(column, row) ^ is_masked(mask, row, column)) as u8;
+                    bit_count += 1;
+                    if bit_count == 8 {
+                        codewords.push(current);
+                        bit_count = 0;
+                        current = 0;
+                    }
+                }
+            }
+            upwards = !upwards;
+            right = right.saturating_sub(2);
+        }
+
+        let data = correct_blocks(&codewords, version, ec_level)?;
+        decode_segments(&data, version)
+    }
+
+    /// Reads both copies of the format information and returns the error correction level and mask.
+    fn format_information(&self) -> Option<(EcLevel, u8)> {
+        let mut first = 0u16;
+        let positions = (0..6)
+            .map(|column| (column, 8))
+            .chain([(7, 8), (8, 8), (8, 7)])
+            .chain((0..6).rev().map(|row| (8, row)));
+        for (column, row) in positions {
+            first = (first << 1) | self.get(column, row) as u16;
+        }
+        let mut second = 0u16;
+        let positions = (self.size - 7..self.size)
+            .rev()
+            .map(|row| (8, row))
+            .chain((self.size - 8..self.size).map(|column| (column, 8)));
+        for (column, row) in positions {
+            second = (second << 1) | self.get(column, row) as u16;
+        }
+
+        // Up to three flipped bits are corrected by choosing the closest valid format
+        let (distance, format) = (0u16..32)
+            .map(|format| {
+                let codeword = format_codeword(format);
+                let distance =
+                    ((codeword ^ first).count_ones()).min((codeword ^ second).count_ones());
+                (distance, format)
+            })
+            .min()?;
+        if distance > 3 {
+            return None;
+        }
+        let ec_level = [EcLevel::M, EcLevel::L, EcLevel::H, EcLevel::Q][(format >> 3) as usize];
+        Some((ec_level, (format & 0b111) as u8))
+    }
+}
+
+/// Adds the BCH(15, 5) error correction bits to the five format bits and applies the format mask.
+fn format_codeword(format: u16) -> u16 {
+    let mut remainder = format << 10;
+    for bit in (10..15).rev() {
+        if remainder & (1 << bit) != 0 {
+            remainder ^= 0x537 << (bit - 10);
+        }
+    }
+    ((format << 10) | remainder) ^ 0x5412
+}
+
+fn is_masked(mask: u8, row: usize, column: usize) -> bool {
+    let (i, j) = (row, column);
+    match mask {
+        0 => (i + j) % 2 == 0,
+        1 => i % 2 == 0,
+        2 => j % 3 == 0,
+        3 => (i + j) % 3 == 0,
+        4 => (i / 2 + j / 3) % 2 == 0,
+        5 => (i * j) % 2 + (i * j) % 3 == 0,
+        6 => ((i * j) % 2 + (i * j) % 3) % 2 == 0,
+        _ => ((i + j) % 2 + (i * j) % 3) % 2 == 0,
+    }
+}
+
+/// The centers of the alignment patterns in both directions.
+fn alignment_positions(version: usize, size: usize) -> Vec<usize> {
+    if version == 1 {
+        return vec![];
+    }
+    let count = version / 7 + 2;
+    let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let mut positions = vec![6];
+    positions.extend((1..count).rev().map(|i| size - 7 - (count - 1 - i) * step));
+    positions[1..].sort();
+    positions
+}
+
+/// Marks the modules that do not hold data: finder patterns, separators, format and version information,
+/// timing patterns and alignment patterns.
+fn function_modules(version: usize, size: usize) -> Vec<bool> {
+    let mut modules = vec![false; size * size];
+    let mut mark = |left: usize, top: usize, width: usize, height: usize| {
+        for row in top..top + height {
+            for column in left..left + width {
+                modules[row * size + column] = true;
+            }
+        }
+    };
+    mark(0, 0, 9, 9);
+    mark(size - 8, 0, 8, 9);
+    mark(0, size - 8, 9, 8);
+    let positions = alignment_positions(version, size);
+    for (i, &x) in positions.iter().enumerate() {
+        for (j, &y) in positions.iter().enumerate() {
+            let last = positions.len() - 1;
+            if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                continue;
+            }
+            mark(x - 2, y - 2, 5, 5);
+        }
+    }
+    mark(6, 9, 1, size - 17);
+    mark(9, 6, size - 17, 1);
+    if version >= 7 {
+        mark(size - 11, 0, 3, 6);
+        mark(0, size - 11, 6, 3);
+    }
+    modules
+}
+
+/// Error correction codewords per block, by error correction level (L, M, Q, H) and version.
+const EC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// Number of error correction blocks, by error correction level (L, M, Q, H) and version.
+const EC_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// Splits the interleaved codewords into their blocks, corrects errors and returns the data codewords.
+fn correct_blocks(codewords: &[u8], version: usize, ec_level: EcLevel) -> Option<Vec<u8>> {
+    let level = match ec_level {
+        EcLevel::L => 0,
+        EcLevel::M => 1,
+        EcLevel::Q => 2,
+        EcLevel::H => 3,
+    };
+    let ec_length = EC_CODEWORDS_PER_BLOCK[level][version] as usize;
+    let block_count = EC_BLOCKS[level][version] as usize;
+    let total = raw_data_modules(version) / 8;
+    let codewords = codewords.get(..total)?;
+    let short_blocks = block_count - total % block_count;
+    let short_data_length = (total / block_count).checked_sub(ec_length)?;
+    let data_length = |block: usize| short_data_length + (block >= short_blocks) as usize;
+
+    let mut blocks: Vec<Vec<u8>> = vec![vec![]; block_count];
+    let mut codewords = codewords.iter();
+    for i in 0..=short_data_length {
+        for (index, block) in blocks.iter_mut().enumerate() {
+            if i < data_length(index) {
+                block.push(*codewords.next()?);
+            }
+        }
+    }
+    for _ in 0..ec_length {
+        for block in blocks.iter_mut() {
+            block.push(*codewords.next()?);
+        }
+    }
+
+    let mut data = vec![];
+    for (index, mut block) in blocks.into_iter().enumerate() {
+        reed_solomon::correct(&mut block, ec_length)?;
+        data.extend_from_slice(&block[..data_length(index)]);
+    }
+    Some(data)
+}
+
+/// The number of modules holding data or error correction, including the remainder bits.
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignment_count = version / 7 + 2;
+        modules -= (25 * alignment_count - 10) * alignment_count - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+const ALPHANUMERIC_CHARS: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Decodes the numeric-, alphanumeric- and byte-segments of the data. Kanji-segments end the decoding.
+///
+/// The bytes are decoded as UTF-8, or as ISO-8859-1 if they are not valid UTF-8.
+fn decode_segments(data: &[u8], version: usize) -> Option<String> {
+    let mut reader = BitReader { data, position: 0 };
+    let size_class = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    let mut bytes: Vec<u8> = vec![];
+    while let Some(mode) = reader.read(4) {
+        match mode {
+            // Numeric
+            0b0001 => {
+                let mut count = reader.read([10, 12, 14][size_class])?;
+                while count > 0 {
+                    let digits = count.min(3);
+                    let value = reader.read([4, 7, 10][digits - 1])?;
+                    bytes.extend(format!("{:0width$}", value, width = digits).bytes());
+                    count -= digits;
+                }
+            }
+            // Alphanumeric
+            0b0010 => {
+                let mut count = reader.read([9, 11, 13][size_class])?;
+                while count >= 2 {
+                    let value = reader.read(11)?;
+                    bytes.push(*ALPHANUMERIC_CHARS.get(value / 45)?);
+                    bytes.push(*ALPHANUMERIC_CHARS.get(value % 45)?);
+                    count -= 2;
+                }
+                if count == 1 {
+                    bytes.push(*ALPHANUMERIC_CHARS.get(reader.read(6)?)?);
+                }
+            }
+            // Byte
+            0b0100 => {
+                let count = reader.read([8, 16, 16][size_class])?;
+                for _ in 0..count {
+                    bytes.push(reader.read(8)? as u8);
+                }
+            }
+            // ECI, the bytes are decoded the same way regardless of the announced encoding
+            0b0111 => {
+                let first = reader.read(8)?;
+                if first & 0x80 != 0 {
+                    reader.read(if first & 0x40 == 0 { 8 } else { 16 })?;
+                }
+            }
+            // Structured append
+            0b0011 => {
+                reader.read(16)?;
+            }
+            // FNC1 in the second position carries an application indicator
+            0b1001 => {
+                reader.read(8)?;
+            }
+            // FNC1 in the first position
+            0b0101 => {}
+            _ => break,
+        }
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&byte| byte as char).collect(),
+    })
+}
+
+/// Reads the data codewords bit by bit, most significant first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: usize) -> Option<usize> {
+        if self.position + bits > self.data.len() * 8 {
+            return None;
+        }
+        let mut value = 0;
+        for _ in 0..bits {
+            let bit = self.data[self.position / 8] >> (7 - self.position % 8) & 1;
+            value = (value << 1) | bit as usize;
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+/// Reed-Solomon error correction over GF(256) with the primitive polynomial `x^8 + x^4 + x^3 + x^2 + 1`.
+mod reed_solomon {
+    /// Powers of the generator `α = 2`
+    const EXP: [u8; 255] = {
+        let mut table = [0u8; 255];
+        let mut value: u16 = 1;
+        let mut i = 0;
+        while i < 255 {
+            table[i] = value as u8;
+            value <<= 1;
+            if value & 0x100 != 0 {
+                value ^= 0x11d;
+            }
+            i += 1;
+        }
+        table
+    };
+
+    /// Logarithms to the base `α`, undefined for `0`
+    const LOG: [u8; 256] = {
+        let mut table = [0u8; 256];
+        let mut i = 0;
+        while i < 255 {
+            table[EXP[i] as usize] = i as u8;
+            i += 1;
+        }
+        table
+    };
+
+    fn exp(power: usize) -> u8 {
+        EXP[power % 255]
+    }
+
+    fn multiply(a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            exp(LOG[a as usize] as usize + LOG[b as usize] as usize)
+        }
+    }
+
+    fn inverse(a: u8) -> u8 {
+        exp(255 - LOG[a as usize] as usize)
+    }
+
+    /// Evaluates a polynomial, given with its highest coefficient first, at `x`.
+    fn evaluate(polynomial: &[u8], x: u8) -> u8 {
+        polynomial
+            .iter()
+            .fold(0, |result, &coefficient| multiply(result, x) ^ coefficient)
+    }
+
+    /// Corrects up to `ec_length / 2` erroneous codewords of a block in place.
+    ///
+    /// Returns [`None`] if the block has too many errors to be corrected.
+    pub(super) fn correct(block: &mut [u8], ec_length: usize) -> Option<()> {
+        let syndromes = (0..ec_length)
+            .map(|i| evaluate(block, exp(i)))
+            .collect::<Vec<_>>();
+        if syndromes.iter().all(|&syndrome| syndrome == 0) {
+            return Some(());
+        }
+
+        // Berlekamp-Massey finds the error locator, lowest coefficient first
+        let mut locator = vec![1u8];
+        let mut previous = vec![1u8];
+        let mut length = 0;
+        let mut shift = 1;
+        let mut previous_discrepancy = 1u8;
+        for n in 0..ec_length {
+            let discrepancy = (0..=length.min(locator.len() - 1))
+                .fold(0, |sum, i| sum ^ multiply(locator[i], syndromes[n - i]));
+            if discrepancy == 0 {
+                shift += 1;
+                continue;
+            }
+            let factor = multiply(discrepancy, inverse(previous_discrepancy));
+            let mut next = locator.clone();
+            next.resize(next.len().max(previous.len() + shift), 0);
+            for (i, &coefficient) in previous.iter().enumerate() {
+                next[i + shift] ^= multiply(factor, coefficient);
+            }
+            if 2 * length <= n {
+                previous = locator;
+                length = n + 1 - length;
+                previous_discrepancy = discrepancy;
+                shift = 1;
+            } else {
+                shift += 1;
+            }
+            locator = next;
+        }
+        while locator.len() > 1 && locator.last() == Some(&0) {
+            locator.pop();
+        }
+        let error_count = locator.len() - 1;
+        if error_count == 0 || 2 * error_count > ec_length {
+            return None;
+        }
+
+        // The error evaluator is the product of the syndromes and the locator, modulo x^ec_length
+        let mut evaluator = vec![0u8; ec_length];
+        for (i, &coefficient) in locator.iter().enumerate() {
+            for (j, &syndrome) in syndromes.iter().enumerate() {
+                if i + j < ec_length {
+                    evaluator[i + j] ^= multiply(coefficient, syndrome);
+                }
+            }
+        }
+        let evaluate_low_first = |polynomial: &[u8], x: u8| {
+            polynomial
+                .iter()
+                .rev()
+                .fold(0, |result, &coefficient| multiply(result, x) ^ coefficient)
+        };
+
+        // Chien search: the codeword at `position` (counted from the end) is wrong if α^-position is a root
+        let mut corrected = 0;
+        for position in 0..block.len() {
+            let x_inverse = exp(255 - position % 255);
+            if evaluate_low_first(&locator, x_inverse) != 0 {
+                continue;
+            }
+            // Forney: the error is Ω(x^-1) / (x^-1 Λ'(x^-1)), keeping the odd coefficients of Λ gives x Λ'(x)
+            let derivative = locator
+                .iter()
+                .enumerate()
+                .map(|(i, &coefficient)| if i % 2 == 1 { coefficient } else { 0 })
+                .collect::<Vec<_>>();
+            let denominator = evaluate_low_first(&derivative, x_inverse);
+            if denominator == 0 {
+                return None;
+            }
+            let magnitude = multiply(
+                evaluate_low_first(&evaluator, x_inverse),
+                inverse(denominator),
+            );
+            let index = block.len() - 1 - position;
+            block[index] ^= magnitude;
+            corrected += 1;
+        }
+        if corrected != error_count {
+            return None;
+        }
+        (0..ec_length)
+            .all(|i| evaluate(block, exp(i)) == 0)
+            .then_some(())
+    }
+}
@@ -0,0 +1,301 @@
+use crate::formats::latex::bibtex_value;
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes the url- and DOI-fields of reference-manager exports in the RIS-, EndNote- or BibTeX-format.
+///
+/// The format is recognized by the syntax of the first line that is not blank:
+/// - RIS: `UR`, `L1` to `L4`, `LK` and `DO`, entries end with `ER`
+/// - EndNote: `%U` and `%R`, entries start with `%0`
+/// - BibTeX: `url` and `doi`, entries start with `@` (`@comment`, `@string` and `@preamble` are skipped)
+///
+/// Bare DOIs (`10.1234/...` or `doi:10.1234/...`) are resolved via `https://doi.org/`.
+/// All other fields are ignored, even if they contain urls.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<ReferenceLink>, ReferencesScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    scrape_from_string(content)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<ReferenceLink>, ReferencesScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<ReferenceLink>, ReferencesScrapingError>);
+
+/// Like [`scrape`] but takes the references as a string.
+pub fn scrape_from_string<S>(content: S) -> Result<Vec<ReferenceLink>, ReferencesScrapingError>
+where
+    S: AsRef<str>,
+{
+    let content = content.as_ref().trim_start_matches('\u{feff}');
+    let first_line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if ris_field(first_line).is_some() {
+        Ok(scrape_ris(content))
+    } else if endnote_field(first_line).is_some() {
+        Ok(scrape_endnote(content))
+    } else if first_line.starts_with('@') {
+        Ok(scrape_bibtex(content))
+    } else {
+        Err(ReferencesScrapingError::UnknownFormatError)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ReferencesScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("The references are neither RIS, EndNote nor BibTeX")]
+    UnknownFormatError,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceLink {
+    pub url: String,
+    /// The field containing the link, e.g. `UR` (RIS), `%U` (EndNote) or `doi` (BibTeX, always in lowercase)
+    pub field: String,
+    /// The index of the entry containing the link, starting at `0`
+    pub entry_index: usize,
+}
+
+impl Display for ReferenceLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for ReferenceLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+const RIS_URL_FIELDS: [&str; 6] = ["UR", "L1", "L2", "L3", "L4", "LK"];
+const RIS_DOI_FIELD: &str = "DO";
+const ENDNOTE_URL_FIELD: &str = "%U";
+const ENDNOTE_DOI_FIELD: &str = "%R";
+const BIBTEX_URL_FIELD: &str = "url";
+const BIBTEX_DOI_FIELD: &str = "doi";
+const DOI_RESOLVER: &str = "https://doi.org/";
+
+/// Splits a RIS-line like `UR  - https://...` into its tag and value.
+fn ris_field(line: &str) -> Option<(&str, &str)> {
+    let tag = line.get(..2)?;
+    let is_tag = tag
+        .bytes()
+        .all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+        && tag.as_bytes()[0].is_ascii_uppercase();
+    let separator = line.get(2..)?;
+    (is_tag && separator.starts_with("  -"))
+        .then(|| (tag, separator.trim_start_matches("  -").trim()))
+}
+
+/// Splits an EndNote-line like `%U https://...` into its tag (including the `%`) and value.
+fn endnote_field(line: &str) -> Option<(&str, &str)> {
+    let tag = line.get(..2)?;
+    let value = line.get(2..)?;
+    (tag.starts_with('%') && tag.as_bytes()[1].is_ascii_graphic() && value.starts_with(' '))
+        .then(|| (tag, value.trim()))
+}
+
+fn scrape_ris(content: &str) -> Vec<ReferenceLink> {
+    let mut links = vec![];
+    let mut entry_index = 0;
+    for (tag, value) in content.lines().filter_map(|line| ris_field(line.trim())) {
+        if tag == "ER" {
+            entry_index += 1;
+        } else if RIS_URL_FIELDS.contains(&tag) {
+            push_urls(&mut links, value, tag, entry_index);
+        } else if tag == RIS_DOI_FIELD {
+            push_doi(&mut links, value, tag, entry_index);
+        }
+    }
+    links
+}
+
+fn scrape_endnote(content: &str) -> Vec<ReferenceLink> {
+    let mut links = vec![];
+    let mut entry_index = None;
+    for (tag, value) in content
+        .lines()
+        .filter_map(|line| endnote_field(line.trim()))
+    {
+        if tag == "%0" {
+            entry_index = Some(entry_index.map_or(0, |index| index + 1));
+        }
+        let index = entry_index.unwrap_or_default();
+        if tag == ENDNOTE_URL_FIELD {
+            push_urls(&mut links, value, tag, index);
+        } else if tag == ENDNOTE_DOI_FIELD {
+            push_doi(&mut links, value, tag, index);
+        }
+    }
+    links
+}
+
+fn scrape_bibtex(content: &str) -> Vec<ReferenceLink> {
+    let mut links = vec![];
+    let mut entry_index = 0;
+    let entry_starts = content
+        .match_indices('@')
+        .map(|(start, _)| start)
+        .filter(|&start| {
+            content[..start]
+                .trim_end_matches([' ', '\t'])
+                .ends_with('\n')
+                || start == 0
+        })
+        .collect::<Vec<_>>();
+    for (i, &start) in entry_starts.iter().enumerate() {
+        let end = entry_starts.get(i + 1).copied().unwrap_or(content.len());
+        let entry = &content[start..end];
+        let entry_type = entry[1..]
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if matches!(entry_type.as_str(), "comment" | "string" | "preamble") {
+            continue;
+        }
+
+        for (field_start, _) in entry.match_indices(|c: char| c.is_ascii_alphabetic()) {
+            let is_word_start = !entry[..field_start]
+                .ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !is_word_start {
+                continue;
+            }
+            let Some(field) = [BIBTEX_URL_FIELD, BIBTEX_DOI_FIELD]
+                .into_iter()
+                .find(|field| {
+                    entry[field_start..]
+                        .get(..field.len())
+                        .is_some_and(|word| word.eq_ignore_ascii_case(field))
+                })
+            else {
+                continue;
+            };
+            let Some((value, _)) = bibtex_value(entry, field_start + field.len()) else {
+                continue;
+            };
+            if field == BIBTEX_URL_FIELD {
+                push_urls(&mut links, value, field, entry_index);
+            } else {
+                push_doi(&mut links, value, field, entry_index);
+            }
+        }
+        entry_index += 1;
+    }
+    links
+}
+
+/// Url-fields usually hold a single url, but some exports join several with `;`.
+fn push_urls(links: &mut Vec<ReferenceLink>, value: &str, field: &str, entry_index: usize) {
+    links.extend(find_urls(value).iter().map(|link| ReferenceLink {
+        url: link.as_str().to_string(),
+        field: field.to_string(),
+        entry_index,
+    }));
+}
+
+fn push_doi(links: &mut Vec<ReferenceLink>, value: &str, field: &str, entry_index: usize) {
+    let value = value.trim();
+    let url = if value.contains("://") {
+        value.to_string()
+    } else {
+        let doi = value
+            .get(..4)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("doi:"))
+            .map_or(value, |_| value[4..].trim());
+        if !doi.starts_with("10.") {
+            return;
+        }
+        format!("{}{}", DOI_RESOLVER, doi)
+    };
+    links.push(ReferenceLink {
+        url,
+        field: field.to_string(),
+        entry_index,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_RIS: &[u8] = include_bytes!("../../test_files/references/references_test.ris");
+
+    fn link(url: &str, field: &str, entry_index: usize) -> ReferenceLink {
+        ReferenceLink {
+            url: url.to_string(),
+            field: field.to_string(),
+            entry_index,
+        }
+    }
+
+    #[test]
+    fn scrape_ris_test() {
+        let links = scrape_from_slice(TEST_RIS).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                link("https://ris.references.test.com/article", "UR", 0),
+                link("https://doi.org/10.1234/ris.5678", "DO", 0),
+                link("https://pdf.references.test.com/book.pdf", "L1", 1),
+                link("https://doi.org/10.1234/book.42", "DO", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_endnote_test() {
+        let links = scrape_from_string(
+            "%0 Journal Article\n%T Links\n%U https://endnote.references.test.com\n\n\
+             %0 Book\n%R 10.1234/endnote\n%Z https://note.references.test.com\n",
+        )
+        .unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                link("https://endnote.references.test.com", "%U", 0),
+                link("https://doi.org/10.1234/endnote", "%R", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_bibtex_test() {
+        let links = scrape_from_string(
+            "@comment{url = {https://comment.references.test.com}}\n\
+             @article{key,\n  title = {Links},\n  URL = {https://bibtex.references.test.com},\n}\n\
+             @book{other,\n  doi = \"10.1234/bibtex\",\n  pdfurl = {https://ignored.references.test.com}\n}\n",
+        )
+        .unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                link("https://bibtex.references.test.com", "url", 0),
+                link("https://doi.org/10.1234/bibtex", "doi", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_format_test() {
+        assert!(matches!(
+            scrape_from_string("https://plain.test.com"),
+            Err(ReferencesScrapingError::UnknownFormatError)
+        ));
+    }
+}
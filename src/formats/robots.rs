@@ -0,0 +1,233 @@
+use crate::formats::limited_xml_reader::LimitedXmlReader;
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Read};
+use thiserror::Error;
+use xml::reader::XmlEvent;
+
+/// Scrapes the `Sitemap`-urls and the `Allow`- and `Disallow`-paths of a `robots.txt`.
+///
+/// `Allow`- and `Disallow`-paths are returned as written, usually relative to the host serving the `robots.txt`,
+/// together with the user-agents of the group they belong to. Comments (starting with `#`) are skipped.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<RobotsLink>, RobotsScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(scrape_from_string(content))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<RobotsLink>, RobotsScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<RobotsLink>, RobotsScrapingError>);
+
+/// Like [`scrape`] but takes the `robots.txt` as a string.
+pub fn scrape_from_string<S>(content: S) -> Vec<RobotsLink>
+where
+    S: AsRef<str>,
+{
+    let mut links: Vec<RobotsLink> = vec![];
+    let mut user_agents: Vec<String> = vec![];
+    // Consecutive `User-agent`-lines form one group, any other rule ends it
+    let mut in_group_header = false;
+    for line in content.as_ref().lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let directive = match key.trim().to_ascii_lowercase().as_str() {
+            "user-agent" => {
+                if !in_group_header {
+                    user_agents.clear();
+                    in_group_header = true;
+                }
+                user_agents.push(value.to_string());
+                continue;
+            }
+            "sitemap" => RobotsDirective::Sitemap,
+            "allow" => RobotsDirective::Allow,
+            "disallow" => RobotsDirective::Disallow,
+            _ => {
+                in_group_header = false;
+                continue;
+            }
+        };
+        in_group_header = false;
+        if value.is_empty() {
+            continue;
+        }
+        links.push(RobotsLink {
+            url: value.to_string(),
+            directive,
+            user_agents: if directive == RobotsDirective::Sitemap {
+                vec![]
+            } else {
+                user_agents.clone()
+            },
+        });
+    }
+    links
+}
+
+/// Scrapes the `<loc>`-urls of a sitemap or sitemap-index.
+///
+/// The `<loc>` of a `<sitemap>` inside a `<sitemapindex>` is returned as [`RobotsDirective::IndexLoc`],
+/// the `<loc>` of an `<url>` inside a `<urlset>` as [`RobotsDirective::Loc`].
+/// Other `loc`-elements (e.g. of image-sitemaps) are skipped.
+pub fn scrape_sitemap<R>(reader: R) -> Result<Vec<RobotsLink>, RobotsScrapingError>
+where
+    R: Read,
+{
+    let mut links: Vec<RobotsLink> = vec![];
+    // Local names of all open elements
+    let mut elements: Vec<String> = vec![];
+    let mut parser = LimitedXmlReader::new(reader);
+    loop {
+        match parser.next::<RobotsScrapingError>()? {
+            XmlEvent::StartElement { name, .. } => elements.push(name.local_name),
+            XmlEvent::EndElement { .. } => {
+                elements.pop();
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                let parent = elements.len().checked_sub(2).map(|index| &elements[index]);
+                let directive = match (elements.last(), parent) {
+                    (Some(loc), Some(parent)) if loc == "loc" && parent == "sitemap" => {
+                        RobotsDirective::IndexLoc
+                    }
+                    (Some(loc), Some(parent)) if loc == "loc" && parent == "url" => {
+                        RobotsDirective::Loc
+                    }
+                    _ => continue,
+                };
+                links.push(RobotsLink {
+                    url: text.trim().to_string(),
+                    directive,
+                    user_agents: vec![],
+                })
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+    Ok(links)
+}
+
+#[derive(Error, Debug)]
+pub enum RobotsScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    ResourceLimitExceeded(#[from] crate::config::ResourceLimitExceededError),
+    #[error(transparent)]
+    XmlReaderError(#[from] xml::reader::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotsLink {
+    pub url: String,
+    pub directive: RobotsDirective,
+    /// The user-agents of the group an `Allow`- or `Disallow`-path belongs to. Empty for everything else
+    pub user_agents: Vec<String>,
+}
+
+impl Display for RobotsLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for RobotsLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RobotsDirective {
+    /// The link is a sitemap listed in a `robots.txt` <br/>
+    /// Example: `Sitemap: https://link.example.com/sitemap.xml`
+    Sitemap,
+    /// The link is a path crawlers may visit <br/>
+    /// Example: `Allow: /public/`
+    Allow,
+    /// The link is a path crawlers must not visit <br/>
+    /// Example: `Disallow: /admin/`
+    Disallow,
+    /// The link is a page listed in a sitemap <br/>
+    /// Example: `<urlset><url><loc>https://link.example.com/page</loc></url></urlset>`
+    Loc,
+    /// The link is another sitemap listed in a sitemap-index <br/>
+    /// Example: `<sitemapindex><sitemap><loc>https://link.example.com/sitemap1.xml</loc></sitemap></sitemapindex>`
+    IndexLoc,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_ROBOTS: &[u8] = include_bytes!("../../test_files/robots/robots_test.txt");
+    const TEST_SITEMAP: &[u8] = include_bytes!("../../test_files/robots/sitemap_test.xml");
+    const TEST_SITEMAP_INDEX: &[u8] =
+        include_bytes!("../../test_files/robots/sitemap_index_test.xml");
+
+    #[test]
+    fn scrape_robots_test() {
+        let links = scrape_from_slice(TEST_ROBOTS).unwrap();
+        println!("{:?}", links);
+        let sitemaps: Vec<_> = links
+            .iter()
+            .filter(|it| it.directive == RobotsDirective::Sitemap)
+            .map(|it| it.url.as_str())
+            .collect();
+        assert_eq!(
+            sitemaps,
+            [
+                "https://robots.test.com/sitemap.xml",
+                "https://robots.test.com/news/sitemap.xml"
+            ]
+        );
+        assert!(links.contains(&RobotsLink {
+            url: "/admin/".to_string(),
+            directive: RobotsDirective::Disallow,
+            user_agents: vec!["Googlebot".to_string(), "Bingbot".to_string()]
+        }));
+        assert!(links.contains(&RobotsLink {
+            url: "/public/".to_string(),
+            directive: RobotsDirective::Allow,
+            user_agents: vec!["*".to_string()]
+        }));
+        assert!(!links.iter().any(|it| it.url.contains("commented")));
+    }
+
+    #[test]
+    fn scrape_sitemap_test() {
+        let links = scrape_sitemap(TEST_SITEMAP).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                RobotsLink {
+                    url: "https://sitemap.test.com/".to_string(),
+                    directive: RobotsDirective::Loc,
+                    user_agents: vec![]
+                },
+                RobotsLink {
+                    url: "https://sitemap.test.com/about".to_string(),
+                    directive: RobotsDirective::Loc,
+                    user_agents: vec![]
+                }
+            ]
+        );
+
+        let links = scrape_sitemap(TEST_SITEMAP_INDEX).unwrap();
+        println!("{:?}", links);
+        assert_eq!(links.len(), 2);
+        assert!(links
+            .iter()
+            .all(|it| it.directive == RobotsDirective::IndexLoc));
+    }
+}
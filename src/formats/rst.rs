@@ -0,0 +1,204 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes links from reStructuredText-sources.
+///
+/// The targets of embedded links (`` `text <https://link.example.com>`_ ``) and of hyperlink-targets
+/// (`.. _name: https://link.example.com`) are returned without the surrounding markup.
+/// References to other targets (`` `text <name_>`_ ``) and internal targets (`.. _name:`) are skipped.
+/// Links in the rest of the text are found as usual.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<RstLink>, RstScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(scrape_from_string(content))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<RstLink>, RstScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<RstLink>, RstScrapingError>);
+
+/// Like [`scrape`] but takes the source as a string.
+pub fn scrape_from_string<S>(content: S) -> Vec<RstLink>
+where
+    S: AsRef<str>,
+{
+    let mut links: Vec<RstLink> = vec![];
+    let mut lines = content.as_ref().lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some((name, target)) = hyperlink_target(line) {
+            // The target may be on the following, indented line
+            let target = match target {
+                "" => match lines.peek() {
+                    Some(next) if next.starts_with(char::is_whitespace) => {
+                        lines.next().unwrap().trim()
+                    }
+                    _ => "",
+                },
+                target => target,
+            };
+            if is_external(target) {
+                links.push(RstLink {
+                    url: target.to_string(),
+                    kind: RstLinkKind::TargetDefinition,
+                    text: name.map(str::to_string),
+                });
+            }
+            continue;
+        }
+
+        // The line without the embedded links, which are blanked out to keep the positions intact
+        let mut text = line.to_string();
+        let mut search = 0;
+        while let Some(found) = line[search..].find('`') {
+            let open = search + found;
+            let Some(close) = line[open + 1..].find("`_").map(|close| open + 1 + close) else {
+                break;
+            };
+            search = close + 2;
+            let reference = &line[open + 1..close];
+            let Some((label, target)) = reference
+                .strip_suffix('>')
+                .and_then(|reference| reference.rsplit_once('<'))
+            else {
+                continue;
+            };
+            let end = if line[search..].starts_with('_') {
+                search + 1
+            } else {
+                search
+            };
+            if is_external(target.trim()) {
+                let label = label.trim();
+                links.push(RstLink {
+                    url: target.trim().to_string(),
+                    kind: RstLinkKind::InlineLink,
+                    text: Some(label.to_string()).filter(|label| !label.is_empty()),
+                });
+            }
+            text.replace_range(open..end, &" ".repeat(end - open));
+            search = end;
+        }
+
+        links.extend(find_urls(&text).iter().map(|link| RstLink {
+            url: link.as_str().to_string(),
+            kind: RstLinkKind::PlainText,
+            text: None,
+        }));
+    }
+    links
+}
+
+#[derive(Error, Debug)]
+pub enum RstScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RstLink {
+    pub url: String,
+    pub kind: RstLinkKind,
+    /// The link text of an embedded link, or the name of the hyperlink-target ([`None`] for anonymous targets)
+    pub text: Option<String>,
+}
+
+impl Display for RstLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for RstLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RstLinkKind {
+    /// The link is embedded into a hyperlink-reference <br/>
+    /// Example: `` `Example <https://link.example.com>`_ ``
+    InlineLink,
+    /// The link is the target of a (possibly anonymous) hyperlink-target <br/>
+    /// Example: `.. _Example: https://link.example.com`
+    TargetDefinition,
+    /// The link is inside the text without any markup <br/>
+    /// Example: `See https://link.example.com`
+    PlainText,
+}
+
+/// Splits a hyperlink-target like `.. _name: target` or `__ target` into its name and target.
+fn hyperlink_target(line: &str) -> Option<(Option<&str>, &str)> {
+    let line = line.trim_start();
+    if let Some(target) = line.strip_prefix("__ ") {
+        return Some((None, target.trim()));
+    }
+    let rest = line.strip_prefix(".. _")?;
+    if let Some(target) = rest.strip_prefix("_:") {
+        return Some((None, target.trim()));
+    }
+    // Names containing colons have to be quoted with backticks
+    let (name, target) = match rest.strip_prefix('`') {
+        Some(quoted) => {
+            let (name, target) = quoted.split_once("`:")?;
+            (name, target)
+        }
+        None => rest.split_once(':')?,
+    };
+    Some((Some(name.trim()), target.trim()))
+}
+
+/// Targets ending with `_` reference another target instead of being an url.
+fn is_external(target: &str) -> bool {
+    !target.is_empty() && !target.ends_with('_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_RST: &[u8] = include_bytes!("../../test_files/rst/rst_test.rst");
+
+    #[test]
+    fn scrape_rst_test() {
+        let links = scrape_from_slice(TEST_RST).unwrap();
+        println!("{:?}", links);
+        assert!(links.contains(&RstLink {
+            url: "https://inline.test.com/docs".to_string(),
+            kind: RstLinkKind::InlineLink,
+            text: Some("The docs".to_string())
+        }));
+        assert!(links.contains(&RstLink {
+            url: "https://anonymous.test.com".to_string(),
+            kind: RstLinkKind::InlineLink,
+            text: Some("anonymous one".to_string())
+        }));
+        assert!(links.contains(&RstLink {
+            url: "https://target.test.com/guide".to_string(),
+            kind: RstLinkKind::TargetDefinition,
+            text: Some("Guide".to_string())
+        }));
+        assert!(links.contains(&RstLink {
+            url: "https://plaintext.test.com".to_string(),
+            kind: RstLinkKind::PlainText,
+            text: None
+        }));
+        assert_eq!(links.len(), 4);
+    }
+
+    #[test]
+    fn trailing_markup_is_not_part_of_url_test() {
+        let links = scrape_from_string("See `A <https://inline.test.com/a>`_, or `B <b_>`_.");
+        assert_eq!(
+            links.iter().map(|it| it.url.as_str()).collect::<Vec<_>>(),
+            ["https://inline.test.com/a"]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::gen_scrape_from_file;
-use crate::helpers::find_urls;
+use crate::helpers::{find_urls, AsUrl};
 use itertools::Itertools;
 use rtf_parser::lexer::Lexer;
 use rtf_parser::tokens::Token;
@@ -79,6 +79,12 @@ impl Display for RtfLink {
     }
 }
 
+impl AsUrl for RtfLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +98,15 @@ mod tests {
             .iter()
             .any(|it| it.url == "https://plaintext.test.com"));
     }
+
+    #[test]
+    fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/rtf/rtf_test.rtf").unwrap();
+        let from_slice = scrape_from_slice(TEST_RTF).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
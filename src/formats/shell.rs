@@ -0,0 +1,575 @@
+use crate::helpers::{find_urls, find_urls_with_stops, AsUrl, SOURCE_CODE_STOPS};
+use crate::links::HasLocation;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes links from shell scripts, systemd units and `.desktop`-files.
+///
+/// Files whose first line (after blank lines and comments) is a section header like `[Service]` are read as
+/// systemd units or `.desktop`-files: the values of `Exec*`-keys are scraped like a line of a shell script,
+/// all other values are searched for urls as they are.
+///
+/// Shell scripts are split into words like the shell does, honoring quotes, escapes and `#`-comments,
+/// so urls are returned without the quotes around them. The bodies of here-documents are scraped line by line
+/// and command substitutions (`$(...)` and backticks) like every other command.
+/// Variable expansions (`$HOST`, `${HOST}`) cut a word in two, e.g. `https://${HOST}/file` yields no url at all
+/// instead of a wrong one.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(mut reader: R) -> Result<Vec<ShellLink>, ShellScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(scrape_from_string(content))
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<ShellLink>, ShellScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<ShellLink>, ShellScrapingError>);
+
+/// Like [`scrape`] but takes the script or unit as a string.
+pub fn scrape_from_string<S>(content: S) -> Vec<ShellLink>
+where
+    S: AsRef<str>,
+{
+    let content = content.as_ref().trim_start_matches('\u{feff}');
+    if is_unit_file(content) {
+        scrape_unit_file(content)
+    } else {
+        let mut scanner = Scanner::new(content, 1);
+        scanner.run();
+        scanner.links
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ShellScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShellLink {
+    pub url: String,
+    pub context: ShellLinkContext,
+    /// 1-based line the link starts on
+    pub line: usize,
+}
+
+impl Display for ShellLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for ShellLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl HasLocation for ShellLink {
+    type Location = usize;
+
+    fn location(&self) -> Self::Location {
+        self.line
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShellLinkContext {
+    /// The link is a word of a command that is executed, including variable-assignments <br/>
+    /// Example: `curl -fsSL "https://link.example.com/install.sh"` or `ExecStart=/usr/bin/wget https://link.example.com`
+    Command,
+    /// The link is inside a comment <br/>
+    /// Example: `# see https://link.example.com`
+    Comment,
+    /// The link is inside the body of a here-document
+    HereDoc,
+    /// The link is the value of a key of a systemd unit or `.desktop`-file that is not executed <br/>
+    /// Example: `Documentation=https://link.example.com`
+    Setting,
+}
+
+/// Keys of systemd units (`ExecStart`, `ExecStartPre`, ...) and `.desktop`-files (`Exec`, `TryExec`) holding a command.
+const EXEC_KEY_PREFIX: &str = "Exec";
+const TRY_EXEC_KEY: &str = "TryExec";
+/// Separates the words of a command, see the `metacharacters` of bash.
+const WORD_SEPARATORS: &[u8] = b" \t\r;&|()>";
+/// Marks where a variable expansion was removed from a word, so urls are never found across it.
+const EXPANSION: u8 = 0;
+
+/// A section header like `[Unit]` or `[Desktop Entry]` as the first line that is no comment.
+fn is_unit_file(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+        .is_some_and(|line| {
+            line.ends_with(']')
+                && line
+                    .strip_prefix('[')
+                    .is_some_and(|name| name.starts_with(|c: char| c.is_ascii_alphanumeric()))
+        })
+}
+
+fn scrape_unit_file(content: &str) -> Vec<ShellLink> {
+    let mut links = vec![];
+    let mut lines = content.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let mut line = line.trim().to_string();
+        // Systemd joins lines ending with a backslash
+        while line.ends_with('\\') {
+            line.pop();
+            match lines.next() {
+                Some((_, next)) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+
+        let line_number = index + 1;
+        if let Some(comment) = line.strip_prefix(['#', ';']) {
+            push_urls(&mut links, comment, line_number, ShellLinkContext::Comment);
+        } else if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            if key.starts_with(EXEC_KEY_PREFIX) || key == TRY_EXEC_KEY {
+                let mut scanner = Scanner::new(value.trim(), line_number);
+                scanner.run();
+                links.append(&mut scanner.links);
+            } else {
+                push_urls(&mut links, value, line_number, ShellLinkContext::Setting);
+            }
+        }
+    }
+    links
+}
+
+fn push_urls(links: &mut Vec<ShellLink>, text: &str, line: usize, context: ShellLinkContext) {
+    links.extend(
+        find_urls_with_stops(text, SOURCE_CODE_STOPS)
+            .into_iter()
+            .map(|(_, url)| ShellLink {
+                url: url.to_string(),
+                context,
+                line,
+            }),
+    );
+}
+
+/// A here-document whose body starts on the line after its operator (`<<EOF`, `<<-EOF` or `<<'EOF'`).
+struct PendingHereDoc {
+    delimiter: String,
+    strip_tabs: bool,
+    /// Quoted delimiters keep the body literal, otherwise variables are expanded in it
+    expands: bool,
+}
+
+/// Splits a shell script into words, comments and here-documents and collects the links inside of them.
+struct Scanner<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    line: usize,
+    word: Vec<u8>,
+    word_line: usize,
+    here_docs: Vec<PendingHereDoc>,
+    links: Vec<ShellLink>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str, first_line: usize) -> Self {
+        Scanner {
+            text,
+            bytes: text.as_bytes(),
+            position: 0,
+            line: first_line,
+            word: vec![],
+            word_line: first_line,
+            here_docs: vec![],
+            links: vec![],
+        }
+    }
+
+    fn run(&mut self) {
+        while let Some(&byte) = self.bytes.get(self.position) {
+            match byte {
+                b'\n' => {
+                    self.finish_word();
+                    self.position += 1;
+                    self.line += 1;
+                    self.read_here_docs();
+                }
+                b'<' => {
+                    self.finish_word();
+                    let rest = &self.bytes[self.position..];
+                    if rest.starts_with(b"<<<") {
+                        self.position += 3;
+                    } else if rest.starts_with(b"<<") {
+                        self.position += 2;
+                        self.read_here_doc_operator();
+                    } else {
+                        self.position += 1;
+                    }
+                }
+                byte if WORD_SEPARATORS.contains(&byte) => {
+                    self.finish_word();
+                    self.position += 1;
+                }
+                b'#' if self.word.is_empty() => {
+                    let start = self.position + 1;
+                    let end = self.line_end(start);
+                    let comment = &self.text[start..end];
+                    push_urls(
+                        &mut self.links,
+                        comment,
+                        self.line,
+                        ShellLinkContext::Comment,
+                    );
+                    self.position = end;
+                }
+                b'\\' => {
+                    match self.bytes.get(self.position + 1) {
+                        Some(b'\n') => self.line += 1,
+                        Some(&escaped) => self.push(&[escaped]),
+                        None => {}
+                    }
+                    self.position += 2;
+                }
+                b'\'' => {
+                    let start = self.position + 1;
+                    let end = self.find(start, b'\'');
+                    self.push(&self.bytes[start..end]);
+                    self.line += count_newlines(&self.bytes[start..end]);
+                    self.position = end + 1;
+                }
+                b'"' => self.read_double_quoted(),
+                b'$' => self.read_expansion(),
+                b'`' => self.read_backticks(),
+                byte => {
+                    self.push(&[byte]);
+                    self.position += 1;
+                }
+            }
+        }
+        self.finish_word();
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        if self.word.is_empty() {
+            self.word_line = self.line;
+        }
+        self.word.extend_from_slice(bytes);
+    }
+
+    fn finish_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+        let word = String::from_utf8_lossy(&self.word).to_string();
+        for part in word.split(EXPANSION as char) {
+            self.links
+                .extend(find_urls(part).iter().map(|link| ShellLink {
+                    url: link.as_str().to_string(),
+                    context: ShellLinkContext::Command,
+                    line: self.word_line,
+                }));
+        }
+        self.word.clear();
+    }
+
+    /// The position of `needle` at or after `start`, or the end of the text.
+    fn find(&self, start: usize, needle: u8) -> usize {
+        self.bytes
+            .get(start..)
+            .and_then(|rest| rest.iter().position(|&byte| byte == needle))
+            .map_or(self.bytes.len(), |position| start + position)
+    }
+
+    fn line_end(&self, start: usize) -> usize {
+        self.find(start, b'\n')
+    }
+
+    fn read_double_quoted(&mut self) {
+        self.position += 1;
+        while let Some(&byte) = self.bytes.get(self.position) {
+            match byte {
+                b'"' => {
+                    self.position += 1;
+                    return;
+                }
+                b'\\'
+                    if self
+                        .bytes
+                        .get(self.position + 1)
+                        .is_some_and(|next| b"$`\"\\\n".contains(next)) =>
+                {
+                    match self.bytes[self.position + 1] {
+                        b'\n' => self.line += 1,
+                        escaped => self.push(&[escaped]),
+                    }
+                    self.position += 2;
+                }
+                b'$' => self.read_expansion(),
+                b'`' => self.read_backticks(),
+                byte => {
+                    if byte == b'\n' {
+                        self.line += 1;
+                    }
+                    self.push(&[byte]);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    /// Skips a variable expansion, scraping the commands of a command substitution.
+    fn read_expansion(&mut self) {
+        let start = self.position;
+        let end = match self.bytes.get(start + 1) {
+            Some(b'(') => {
+                let end = self.matching(start + 1, b'(', b')');
+                // `$((...))` is arithmetic, not a command
+                if self.bytes.get(start + 2) != Some(&b'(') {
+                    self.scan_nested(start + 2, end);
+                }
+                end + 1
+            }
+            Some(b'{') => self.matching(start + 1, b'{', b'}') + 1,
+            Some(byte) if byte.is_ascii_alphanumeric() || *byte == b'_' => {
+                let length = self.bytes[start + 1..]
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_alphanumeric() || **byte == b'_')
+                    .count();
+                start + 1 + length
+            }
+            Some(byte) if b"@*#?$!-".contains(byte) => start + 2,
+            _ => {
+                // A lone `$` (or `$'...'`, whose quotes are handled on their own) is taken literally
+                if self.bytes.get(start + 1) != Some(&b'\'') {
+                    self.push(b"$");
+                }
+                self.position += 1;
+                return;
+            }
+        };
+        let end = end.min(self.bytes.len());
+        self.line += count_newlines(&self.bytes[start..end]);
+        self.push(&[EXPANSION]);
+        self.position = end;
+    }
+
+    fn read_backticks(&mut self) {
+        let start = self.position + 1;
+        let end = self.find(start, b'`');
+        self.scan_nested(start, end);
+        self.line += count_newlines(&self.bytes[start..end]);
+        self.push(&[EXPANSION]);
+        self.position = end + 1;
+    }
+
+    /// Scrapes the commands of a command substitution.
+    fn scan_nested(&mut self, start: usize, end: usize) {
+        let end = end.min(self.bytes.len());
+        if start >= end {
+            return;
+        }
+        let mut nested = Scanner::new(&self.text[start..end], self.line);
+        nested.run();
+        self.links.append(&mut nested.links);
+    }
+
+    /// The position of the bracket closing the one at `open_position`, or the end of the text.
+    fn matching(&self, open_position: usize, open: u8, close: u8) -> usize {
+        let mut depth = 0;
+        for (position, &byte) in self.bytes.iter().enumerate().skip(open_position) {
+            if byte == open {
+                depth += 1;
+            } else if byte == close {
+                depth -= 1;
+                if depth == 0 {
+                    return position;
+                }
+            }
+        }
+        self.bytes.len()
+    }
+
+    /// Reads the delimiter following a `<<`.
+    fn read_here_doc_operator(&mut self) {
+        let strip_tabs = self.bytes.get(self.position) == Some(&b'-');
+        if strip_tabs {
+            self.position += 1;
+        }
+        while matches!(self.bytes.get(self.position), Some(b' ' | b'\t')) {
+            self.position += 1;
+        }
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| !WORD_SEPARATORS.contains(byte) && !b"<\n".contains(byte))
+        {
+            self.position += 1;
+        }
+        let raw = &self.text[start..self.position];
+        if raw.is_empty() {
+            return;
+        }
+        self.here_docs.push(PendingHereDoc {
+            delimiter: raw.replace(['\'', '"', '\\'], ""),
+            strip_tabs,
+            expands: !raw.contains(['\'', '"', '\\']),
+        });
+    }
+
+    /// Scrapes the bodies of the here-documents opened on the previous line.
+    fn read_here_docs(&mut self) {
+        for here_doc in std::mem::take(&mut self.here_docs) {
+            while self.position < self.bytes.len() {
+                let end = self.line_end(self.position);
+                let body_line = self.text[self.position..end].trim_end_matches('\r');
+                self.position = (end + 1).min(self.bytes.len());
+                self.line += 1;
+                let delimiter_line = if here_doc.strip_tabs {
+                    body_line.trim_start_matches('\t')
+                } else {
+                    body_line
+                };
+                if delimiter_line == here_doc.delimiter {
+                    break;
+                }
+                let parts = if here_doc.expands {
+                    body_line.split('$').collect()
+                } else {
+                    vec![body_line]
+                };
+                for part in parts {
+                    push_urls(
+                        &mut self.links,
+                        part,
+                        self.line - 1,
+                        ShellLinkContext::HereDoc,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SCRIPT: &[u8] = include_bytes!("../../test_files/shell/shell_test.sh");
+    const TEST_UNIT: &[u8] = include_bytes!("../../test_files/shell/shell_test.service");
+
+    fn link(url: &str, context: ShellLinkContext, line: usize) -> ShellLink {
+        ShellLink {
+            url: url.to_string(),
+            context,
+            line,
+        }
+    }
+
+    #[test]
+    fn scrape_script_test() {
+        let links = scrape_from_slice(TEST_SCRIPT).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                link(
+                    "https://comment.shell.test.com",
+                    ShellLinkContext::Comment,
+                    2
+                ),
+                link(
+                    "https://download.shell.test.com/install.sh",
+                    ShellLinkContext::Command,
+                    4
+                ),
+                link(
+                    "https://quoted.shell.test.com/a;b",
+                    ShellLinkContext::Command,
+                    5
+                ),
+                link(
+                    "https://version.shell.test.com/latest",
+                    ShellLinkContext::Command,
+                    6
+                ),
+                link(
+                    "https://inline.shell.test.com",
+                    ShellLinkContext::Comment,
+                    7
+                ),
+                link(
+                    "https://heredoc.shell.test.com",
+                    ShellLinkContext::HereDoc,
+                    9
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrape_unit_file_test() {
+        let links = scrape_from_slice(TEST_UNIT).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                link("https://docs.unit.test.com", ShellLinkContext::Setting, 3),
+                link(
+                    "https://comment.unit.test.com",
+                    ShellLinkContext::Comment,
+                    6
+                ),
+                link(
+                    "https://download.unit.test.com/data",
+                    ShellLinkContext::Command,
+                    7
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn expansions_are_not_urls_test() {
+        let links = scrape_from_string(
+            "wget https://${HOST}/file \"https://$HOST.test.com\" $'https://ansi.test.com'",
+        );
+        assert_eq!(
+            links
+                .iter()
+                .map(|link| link.url.as_str())
+                .collect::<Vec<_>>(),
+            ["https://ansi.test.com"]
+        );
+    }
+
+    #[test]
+    fn quoted_here_doc_test() {
+        let links = scrape_from_string(
+            "cat <<-'EOF' > out\n\thttps://literal.test.com/$PATH\n\tEOF\necho https://after.test.com\n",
+        );
+        assert_eq!(
+            links,
+            vec![
+                link(
+                    "https://literal.test.com/$PATH",
+                    ShellLinkContext::HereDoc,
+                    2
+                ),
+                link("https://after.test.com", ShellLinkContext::Command, 4),
+            ]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use crate::helpers::{find_urls_with_stops, AsUrl, SOURCE_CODE_STOPS};
+use crate::links::HasLocation;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Scrapes links from the comments and string literals of a source file.
+///
+/// Everything outside of comments and string literals is ignored, to avoid false positives from code.
+///
+/// Reads the whole stream before processing the contents.
+/// Use [`scrape_from_string`] to omit the [`BufRead`].
+pub fn scrape<R>(
+    mut reader: R,
+    language: Language,
+) -> Result<Vec<SourceLink>, SourceCodeScrapingError>
+where
+    R: BufRead,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(scrape_from_string(content, language))
+}
+
+/// Like [`scrape`] but takes the source code as a string.
+pub fn scrape_from_string<S>(content: S, language: Language) -> Vec<SourceLink>
+where
+    S: AsRef<str>,
+{
+    let mut collector: Vec<SourceLink> = vec![];
+    tokenize(content.as_ref(), language, |segment, line, context| {
+        collector.append(&mut links_in_segment(segment, line, context))
+    });
+    collector
+}
+
+/// Convenience function, that uses [`scrape`] to scrape links from a buffer.
+pub fn scrape_from_slice<T>(
+    buffer: T,
+    language: Language,
+) -> Result<Vec<SourceLink>, SourceCodeScrapingError>
+where
+    T: AsRef<[u8]>,
+{
+    scrape(buffer.as_ref(), language)
+}
+
+/// Convenience function, that reads a file and uses [`scrape`] to scrape links from its content.
+pub fn scrape_from_file<P>(
+    path: P,
+    language: Language,
+) -> Result<Vec<SourceLink>, SourceCodeScrapingError>
+where
+    P: AsRef<std::path::Path>,
+{
+    scrape(
+        std::io::BufReader::new(std::fs::File::open(path)?),
+        language,
+    )
+}
+
+#[derive(Error, Debug)]
+pub enum SourceCodeScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+/// The comment- and string-syntax used to tokenize the source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    /// `//` and `/* */` comments, `"` and `'` literals (C, C++, Java, JavaScript, Rust, Go, ...)
+    CStyle,
+    /// `#` comments, `"`, `'`, `"""` and `'''` literals
+    Python,
+    /// `#` comments, `"` and `'` literals
+    Shell,
+}
+
+impl Language {
+    fn line_comment(&self) -> &'static str {
+        match self {
+            Language::CStyle => "//",
+            Language::Python | Language::Shell => "#",
+        }
+    }
+
+    fn block_comment(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Language::CStyle => Some(("/*", "*/")),
+            Language::Python | Language::Shell => None,
+        }
+    }
+
+    /// Longer delimiters come first, so `"""` is not mistaken for an empty `""`.
+    fn string_delimiters(&self) -> &'static [&'static str] {
+        match self {
+            Language::CStyle | Language::Shell => &["\"", "'"],
+            Language::Python => &["\"\"\"", "'''", "\"", "'"],
+        }
+    }
+
+    fn has_escapes(&self, delimiter: &str) -> bool {
+        !(*self == Language::Shell && delimiter == "'")
+    }
+
+    fn is_multiline(&self, delimiter: &str) -> bool {
+        *self == Language::Shell || delimiter.len() == 3
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceLink {
+    pub url: String,
+    pub context: SourceLinkContext,
+    /// 1-based line the link starts on
+    pub line: usize,
+}
+
+impl Display for SourceLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for SourceLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl HasLocation for SourceLink {
+    type Location = usize;
+
+    fn location(&self) -> Self::Location {
+        self.line
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceLinkContext {
+    /// The link is inside a line- or block-comment <br/>
+    /// Example: `// see https://link.example.com`
+    Comment,
+    /// The link is inside a string literal <br/>
+    /// Example: `let url = "https://link.example.com";`
+    StringLiteral,
+}
+
+/// Walks through the source and calls `on_segment` with the content, starting line and context
+/// of every comment and string literal.
+pub(crate) fn tokenize<F>(text: &str, language: Language, mut on_segment: F)
+where
+    F: FnMut(&str, usize, SourceLinkContext),
+{
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let mut line = 1;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest[0] == b'\n' {
+            line += 1;
+            i += 1;
+            continue;
+        }
+
+        let line_comment = language.line_comment();
+        // In shell-scripts `#` only starts a comment at the beginning of a word (think of `$#`).
+        let is_word_start =
+            language != Language::Shell || i == 0 || bytes[i - 1].is_ascii_whitespace();
+        if rest.starts_with(line_comment.as_bytes()) && is_word_start {
+            let start = i + line_comment.len();
+            let end = find_from(bytes, start, b"\n").unwrap_or(bytes.len());
+            on_segment(&text[start..end], line, SourceLinkContext::Comment);
+            i = end;
+            continue;
+        }
+
+        if let Some((open, close)) = language.block_comment() {
+            if rest.starts_with(open.as_bytes()) {
+                let start = i + open.len();
+                let end = find_from(bytes, start, close.as_bytes()).unwrap_or(bytes.len());
+                on_segment(&text[start..end], line, SourceLinkContext::Comment);
+                let next = (end + close.len()).min(bytes.len());
+                line += count_newlines(&bytes[i..next]);
+                i = next;
+                continue;
+            }
+        }
+
+        if let Some(delimiter) = language
+            .string_delimiters()
+            .iter()
+            .find(|delimiter| rest.starts_with(delimiter.as_bytes()))
+        {
+            let start = i + delimiter.len();
+            let mut end = start;
+            let mut terminated = false;
+            while end < bytes.len() {
+                if language.has_escapes(delimiter) && bytes[end] == b'\\' {
+                    end += 2;
+                    continue;
+                }
+                if bytes[end..].starts_with(delimiter.as_bytes()) {
+                    terminated = true;
+                    break;
+                }
+                if bytes[end] == b'\n' && !language.is_multiline(delimiter) {
+                    break;
+                }
+                end += 1;
+            }
+            let end = end.min(bytes.len());
+            on_segment(&text[start..end], line, SourceLinkContext::StringLiteral);
+            line += count_newlines(&bytes[i..end]);
+            i = if terminated {
+                end + delimiter.len()
+            } else {
+                end
+            };
+            continue;
+        }
+
+        i += 1;
+    }
+}
+
+fn links_in_segment(segment: &str, line: usize, context: SourceLinkContext) -> Vec<SourceLink> {
+    find_urls_with_stops(segment, SOURCE_CODE_STOPS)
+        .into_iter()
+        .map(|(start, url)| SourceLink {
+            url: url.to_string(),
+            context,
+            line: line + count_newlines(&segment.as_bytes()[..start]),
+        })
+        .collect()
+}
+
+fn find_from(bytes: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(start..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| start + position)
+}
+
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_C: &str = r#"int x = https://code.test.com;
+// see https://comment.test.com
+char* s = "https://string.test.com";
+/* multi
+   line https://block.test.com */"#;
+
+    const TEST_PYTHON: &str = r#"x = https://code.test.com
+def f():
+    """
+    Docs at https://docstring.test.com
+    """
+    return 'https://string.test.com'  # https://comment.test.com"#;
+
+    #[test]
+    fn scrape_c_style_test() {
+        let links = scrape_from_slice(TEST_C, Language::CStyle).unwrap();
+        println!("{:?}", links);
+        assert!(!links.iter().any(|it| it.url == "https://code.test.com"));
+        assert!(links.iter().any(|it| it.url == "https://comment.test.com"
+            && it.context == SourceLinkContext::Comment
+            && it.line == 2));
+        assert!(links.iter().any(|it| it.url == "https://string.test.com"
+            && it.context == SourceLinkContext::StringLiteral
+            && it.line == 3));
+        assert!(links.iter().any(|it| it.url == "https://block.test.com"
+            && it.context == SourceLinkContext::Comment
+            && it.line == 5));
+    }
+
+    #[test]
+    fn scrape_python_test() {
+        let links = scrape_from_slice(TEST_PYTHON, Language::Python).unwrap();
+        println!("{:?}", links);
+        assert!(!links.iter().any(|it| it.url == "https://code.test.com"));
+        assert!(links.iter().any(|it| it.url == "https://docstring.test.com"
+            && it.context == SourceLinkContext::StringLiteral
+            && it.line == 4));
+        assert!(links.iter().any(|it| it.url == "https://string.test.com"
+            && it.context == SourceLinkContext::StringLiteral));
+        assert!(links.iter().any(|it| it.url == "https://comment.test.com"
+            && it.context == SourceLinkContext::Comment
+            && it.line == 6));
+    }
+
+    #[test]
+    fn code_stops_are_trimmed_test() {
+        let links = scrape_from_string(
+            "// https://comment.test.com;\n/* see (https://block.test.com/a;) */",
+            Language::CStyle,
+        );
+        assert_eq!(
+            links.iter().map(|it| it.url.as_str()).collect::<Vec<_>>(),
+            ["https://comment.test.com", "https://block.test.com/a"]
+        );
+    }
+
+    #[test]
+    fn shell_variable_is_not_a_comment_test() {
+        let links = scrape_from_string(
+            "echo $# https://code.test.com # https://comment.test.com",
+            Language::Shell,
+        );
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://comment.test.com");
+    }
+}
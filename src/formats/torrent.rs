@@ -0,0 +1,255 @@
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+
+/// Scrapes the trackers and web seeds of a BitTorrent metainfo-file (`.torrent`).
+///
+/// Returns the `announce`-url, the urls of the tiers of the `announce-list`
+/// ([BEP 12](https://www.bittorrent.org/beps/bep_0012.html)) and the web seeds of the `url-list`
+/// ([BEP 19](https://www.bittorrent.org/beps/bep_0019.html)), in this order.
+/// Clients ignore the `announce`-url if there is an `announce-list`, it is usually repeated in its first tier.
+///
+/// Reads the whole stream before processing the contents.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<TorrentLink>, TorrentScrapingError>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let (metainfo, _) = parse_value(&bytes, 0, 0)?;
+    let Value::Dict(metainfo) = metainfo else {
+        return Err(TorrentScrapingError::NotATorrentError);
+    };
+    let value = |key: &str| {
+        metainfo
+            .iter()
+            .find(|(it, _)| *it == key.as_bytes())
+            .map(|(_, value)| value)
+    };
+
+    let mut links = vec![];
+    let mut push = |url: &Value, kind: TorrentLinkKind, tier: Option<usize>| {
+        if let Value::Bytes(url) = url {
+            let url = String::from_utf8_lossy(url).trim().to_string();
+            if !url.is_empty() {
+                links.push(TorrentLink { url, kind, tier });
+            }
+        }
+    };
+
+    if let Some(announce) = value("announce") {
+        push(announce, TorrentLinkKind::Announce, None);
+    }
+    if let Some(Value::List(tiers)) = value("announce-list") {
+        for (index, tier) in tiers.iter().enumerate() {
+            match tier {
+                Value::List(urls) => urls
+                    .iter()
+                    .for_each(|url| push(url, TorrentLinkKind::Announce, Some(index))),
+                // Some clients write a flat list of urls instead of a list of tiers
+                url => push(url, TorrentLinkKind::Announce, Some(index)),
+            }
+        }
+    }
+    match value("url-list") {
+        Some(Value::List(urls)) => urls
+            .iter()
+            .for_each(|url| push(url, TorrentLinkKind::WebSeed, None)),
+        Some(url) => push(url, TorrentLinkKind::WebSeed, None),
+        None => {}
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<TorrentLink>, TorrentScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<TorrentLink>, TorrentScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum TorrentScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid bencoding at byte {0}.")]
+    InvalidBencodeError(usize),
+    #[error("Given file was not a torrent.")]
+    NotATorrentError,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TorrentLink {
+    pub url: String,
+    pub kind: TorrentLinkKind,
+    /// The index of the tier of the `announce-list` containing the tracker. `None` for all other links
+    pub tier: Option<usize>,
+}
+
+impl Display for TorrentLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for TorrentLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TorrentLinkKind {
+    /// The link is a tracker of the `announce`-key or the `announce-list`
+    Announce,
+    /// The link is a web seed of the `url-list`, serving the files over HTTP or FTP
+    WebSeed,
+}
+
+/// Lists and dictionaries nested deeper than this are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+enum Value<'a> {
+    Integer,
+    Bytes(&'a [u8]),
+    List(Vec<Value<'a>>),
+    Dict(Vec<(&'a [u8], Value<'a>)>),
+}
+
+/// Parses the bencoded value starting at `position` and returns it together with the position after it.
+fn parse_value(
+    bytes: &[u8],
+    position: usize,
+    depth: usize,
+) -> Result<(Value<'_>, usize), TorrentScrapingError> {
+    let invalid = || TorrentScrapingError::InvalidBencodeError(position);
+    if depth > MAX_DEPTH {
+        return Err(invalid());
+    }
+    match bytes.get(position).ok_or_else(invalid)? {
+        b'i' => {
+            let end = find(bytes, position, b'e').ok_or_else(invalid)?;
+            Ok((Value::Integer, end + 1))
+        }
+        b'l' => {
+            let mut values = vec![];
+            let mut position = position + 1;
+            while bytes.get(position) != Some(&b'e') {
+                let (value, next) = parse_value(bytes, position, depth + 1)?;
+                values.push(value);
+                position = next;
+            }
+            Ok((Value::List(values), position + 1))
+        }
+        b'd' => {
+            let mut entries = vec![];
+            let mut position = position + 1;
+            while bytes.get(position) != Some(&b'e') {
+                let (Value::Bytes(key), next) = parse_value(bytes, position, depth + 1)? else {
+                    return Err(TorrentScrapingError::InvalidBencodeError(position));
+                };
+                let (value, next) = parse_value(bytes, next, depth + 1)?;
+                entries.push((key, value));
+                position = next;
+            }
+            Ok((Value::Dict(entries), position + 1))
+        }
+        b'0'..=b'9' => {
+            let colon = find(bytes, position, b':').ok_or_else(invalid)?;
+            let length: usize = std::str::from_utf8(&bytes[position..colon])
+                .ok()
+                .and_then(|length| length.parse().ok())
+                .ok_or_else(invalid)?;
+            let end = (colon + 1).checked_add(length).ok_or_else(invalid)?;
+            let value = bytes.get(colon + 1..end).ok_or_else(invalid)?;
+            Ok((Value::Bytes(value), end))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn find(bytes: &[u8], from: usize, byte: u8) -> Option<usize> {
+    bytes[from..]
+        .iter()
+        .position(|&it| it == byte)
+        .map(|index| from + index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_TORRENT: &[u8] = include_bytes!("../../test_files/torrent/torrent_test.torrent");
+
+    fn link(url: &str, kind: TorrentLinkKind, tier: Option<usize>) -> TorrentLink {
+        TorrentLink {
+            url: url.to_string(),
+            kind,
+            tier,
+        }
+    }
+
+    #[test]
+    fn scrape_torrent_test() {
+        let links = scrape_from_slice(TEST_TORRENT).unwrap();
+        println!("{:?}", links);
+        use TorrentLinkKind::{Announce, WebSeed};
+        assert_eq!(
+            links,
+            vec![
+                link(
+                    "udp://tracker.torrent.test.com:1337/announce",
+                    Announce,
+                    None
+                ),
+                link(
+                    "udp://tracker.torrent.test.com:1337/announce",
+                    Announce,
+                    Some(0)
+                ),
+                link(
+                    "https://tracker2.torrent.test.com/announce",
+                    Announce,
+                    Some(0)
+                ),
+                link(
+                    "http://backup.torrent.test.com:6969/announce",
+                    Announce,
+                    Some(1)
+                ),
+                link("https://seed.torrent.test.com/files/", WebSeed, None),
+                link("https://mirror.torrent.test.com/test.txt", WebSeed, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_web_seed_test() {
+        let links = scrape_from_slice(
+            b"d13:announce-listl21:https://a.torrent.com21:https://b.torrent.come8:url-list21:https://c.torrent.come",
+        )
+        .unwrap();
+        assert_eq!(
+            links,
+            vec![
+                link("https://a.torrent.com", TorrentLinkKind::Announce, Some(0)),
+                link("https://b.torrent.com", TorrentLinkKind::Announce, Some(1)),
+                link("https://c.torrent.com", TorrentLinkKind::WebSeed, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_torrent_test() {
+        assert!(matches!(
+            scrape_from_slice(b"l4:spame"),
+            Err(TorrentScrapingError::NotATorrentError)
+        ));
+        assert!(matches!(
+            scrape_from_slice(b"d8:announce99:short"),
+            Err(TorrentScrapingError::InvalidBencodeError(11))
+        ));
+        let nested = [b"l".repeat(100), b"e".repeat(100)].concat();
+        assert!(matches!(
+            scrape_from_slice(nested),
+            Err(TorrentScrapingError::InvalidBencodeError(_))
+        ));
+    }
+}
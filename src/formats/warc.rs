@@ -0,0 +1,275 @@
+use crate::formats::html::links_in_html;
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Read};
+use thiserror::Error;
+
+/// Scrapes the payloads of the `response`- and `resource`-records of a WARC-file.
+///
+/// The payload of a `response` is the body of the archived HTTP-response (de-chunked if necessary),
+/// the payload of a `resource` is the whole record block.
+/// HTML-payloads are scraped with [`crate::formats::html`], other textual payloads (`text/*`, JSON, XML, ...)
+/// are searched for urls. Binary payloads and all other record types (`request`, `metadata`, ...) are skipped.
+///
+/// Compressed WARC-files (`.warc.gz`) have to be decompressed first.
+pub fn scrape<R>(mut reader: R) -> Result<Vec<WarcLink>, WarcScrapingError>
+where
+    R: BufRead,
+{
+    let mut links: Vec<WarcLink> = vec![];
+    while let Some(record) = read_record(&mut reader)? {
+        let (content_type, payload) = match record.header("WARC-Type") {
+            Some("response") => match split_http_response(&record.block) {
+                Some(response) => response,
+                None => continue,
+            },
+            Some("resource") => (
+                record.header("Content-Type").map(str::to_string),
+                record.block.clone(),
+            ),
+            _ => continue,
+        };
+        let record_id = record.header("WARC-Record-ID").unwrap_or_default();
+        let target_uri = record.header("WARC-Target-URI").unwrap_or_default();
+        links.extend(
+            payload_links(content_type.as_deref(), &payload)
+                .into_iter()
+                .map(|url| WarcLink {
+                    url,
+                    record_id: record_id.to_string(),
+                    target_uri: target_uri.to_string(),
+                }),
+        );
+    }
+    Ok(links)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<WarcLink>, WarcScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<WarcLink>, WarcScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum WarcScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid WARC-record: {0}")]
+    InvalidRecordError(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarcLink {
+    pub url: String,
+    /// The `WARC-Record-ID` of the record containing the link, e.g. `<urn:uuid:...>`
+    pub record_id: String,
+    /// The `WARC-Target-URI` of the record containing the link, i.e. the url the payload was archived from
+    pub target_uri: String,
+}
+
+impl Display for WarcLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for WarcLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+struct WarcRecord {
+    headers: Vec<(String, String)>,
+    block: Vec<u8>,
+}
+
+impl WarcRecord {
+    fn header(&self, name: &str) -> Option<&str> {
+        header_value(&self.headers, name)
+    }
+}
+
+/// Reads the next record, skipping the line breaks between records.
+/// Returns [`None`] at the end of the stream.
+fn read_record(reader: &mut impl BufRead) -> Result<Option<WarcRecord>, WarcScrapingError> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            break;
+        }
+    }
+    if !line.starts_with("WARC/") {
+        return Err(WarcScrapingError::InvalidRecordError(format!(
+            "Expected a version line like `WARC/1.0`, found `{}`",
+            line.trim_end()
+        )));
+    }
+
+    let mut headers = vec![];
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(WarcScrapingError::InvalidRecordError(
+                "The header block is not terminated".to_string(),
+            ));
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let length: u64 = header_value(&headers, "Content-Length")
+        .and_then(|length| length.parse().ok())
+        .ok_or_else(|| {
+            WarcScrapingError::InvalidRecordError("Missing or invalid Content-Length".to_string())
+        })?;
+    let mut block = Vec::new();
+    reader.take(length).read_to_end(&mut block)?;
+    if (block.len() as u64) < length {
+        return Err(WarcScrapingError::InvalidRecordError(
+            "The record block is truncated".to_string(),
+        ));
+    }
+    Ok(Some(WarcRecord { headers, block }))
+}
+
+/// Header names are case-insensitive.
+fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Splits an archived HTTP-response into the `Content-Type` and the (de-chunked) body.
+fn split_http_response(block: &[u8]) -> Option<(Option<String>, Vec<u8>)> {
+    let header_end = block.windows(4).position(|window| window == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&block[..header_end]);
+    let headers = head
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect::<Vec<_>>();
+    let body = &block[header_end + 4..];
+    let body = match header_value(&headers, "Transfer-Encoding") {
+        Some(encoding) if encoding.eq_ignore_ascii_case("chunked") => dechunk(body),
+        _ => body.to_vec(),
+    };
+    Some((
+        header_value(&headers, "Content-Type").map(str::to_string),
+        body,
+    ))
+}
+
+/// Joins the chunks of a body sent with `Transfer-Encoding: chunked`, keeping what could be read of a broken one.
+fn dechunk(mut body: &[u8]) -> Vec<u8> {
+    let mut joined = vec![];
+    while let Some(line_end) = body.windows(2).position(|window| window == b"\r\n") {
+        let size = String::from_utf8_lossy(&body[..line_end]);
+        // Chunk extensions follow the size after a `;`
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let Ok(size) = usize::from_str_radix(size, 16) else {
+            break;
+        };
+        body = &body[line_end + 2..];
+        if size == 0 {
+            break;
+        }
+        let chunk = &body[..size.min(body.len())];
+        joined.extend_from_slice(chunk);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+    joined
+}
+
+/// Scrapes a payload according to its `Content-Type`.
+fn payload_links(content_type: Option<&str>, payload: &[u8]) -> Vec<String> {
+    let mime = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let text = String::from_utf8_lossy(payload);
+    if mime == "text/html" || mime == "application/xhtml+xml" {
+        links_in_html(&text)
+    } else if is_textual(&mime) {
+        find_urls(&text)
+            .iter()
+            .map(|link| link.as_str().to_string())
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+fn is_textual(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || [
+            "application/json",
+            "application/xml",
+            "application/javascript",
+        ]
+        .contains(&mime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_WARC: &[u8] = include_bytes!("../../test_files/warc/warc_test.warc");
+
+    #[test]
+    fn scrape_warc_test() {
+        let links = scrape_from_slice(TEST_WARC).unwrap();
+        println!("{:?}", links);
+        let response = |url: &str| WarcLink {
+            url: url.to_string(),
+            record_id: "<urn:uuid:00000000-0000-0000-0000-000000000003>".to_string(),
+            target_uri: "https://page.warc.test.com/".to_string(),
+        };
+        assert_eq!(
+            links,
+            vec![
+                response("https://link.warc.test.com/about"),
+                response("https://img.warc.test.com/logo.png"),
+                response("https://text.warc.test.com"),
+                WarcLink {
+                    url: "https://resource.warc.test.com/notes".to_string(),
+                    record_id: "<urn:uuid:00000000-0000-0000-0000-000000000004>".to_string(),
+                    target_uri: "https://page.warc.test.com/notes.txt".to_string(),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn dechunk_test() {
+        assert_eq!(
+            dechunk(b"5\r\nhttps\r\n3;ext=1\r\n://\r\n0\r\n\r\n"),
+            b"https://"
+        );
+    }
+
+    #[test]
+    fn invalid_record_test() {
+        assert!(matches!(
+            scrape_from_slice(b"HTTP/1.1 200 OK\r\n\r\n"),
+            Err(WarcScrapingError::InvalidRecordError(_))
+        ));
+        assert!(matches!(
+            scrape_from_slice(
+                b"WARC/1.0\r\nWARC-Type: resource\r\nContent-Length: 100\r\n\r\nshort"
+            ),
+            Err(WarcScrapingError::InvalidRecordError(_))
+        ));
+    }
+}
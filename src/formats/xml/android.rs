@@ -0,0 +1,98 @@
+use crate::helpers::{find_urls, AsUrl};
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use xml::common::{Position, TextPosition};
+use xml::reader::XmlEvent;
+use xml::EventReader;
+
+use super::XmlScrapingError;
+
+/// Scrapes links from an Android resource file (e.g. `res/values/strings.xml`).
+///
+/// Every link is tagged with the `name` of the resource containing it,
+/// so items of a `<string-array>` or `<plurals>` carry the name of the array.
+pub fn scrape<R>(reader: R) -> Result<Vec<AndroidStringLink>, XmlScrapingError>
+where
+    R: Read,
+{
+    let mut collector: Vec<AndroidStringLink> = vec![];
+    // `name`-attribute of every open element
+    let mut names: Vec<Option<String>> = vec![];
+    let mut parser = EventReader::new(reader);
+    loop {
+        match parser.next()? {
+            XmlEvent::StartElement { attributes, .. } => names.push(
+                attributes
+                    .into_iter()
+                    .find(|attribute| attribute.name.local_name == "name")
+                    .map(|attribute| attribute.value),
+            ),
+            XmlEvent::EndElement { .. } => {
+                names.pop();
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                let name = names.iter().rev().find_map(|name| name.clone());
+                find_urls(&text).iter().for_each(|link| {
+                    collector.push(AndroidStringLink {
+                        url: link.as_str().to_string(),
+                        name: name.clone(),
+                        location: parser.position(),
+                    })
+                })
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+    Ok(collector)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<AndroidStringLink>, XmlScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<AndroidStringLink>, XmlScrapingError>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AndroidStringLink {
+    pub url: String,
+    /// The `name` of the innermost resource containing the link <br/>
+    /// Example: `tos_url` for `<string name="tos_url">https://link.example.com/tos</string>`
+    pub name: Option<String>,
+    pub location: TextPosition,
+}
+
+impl Display for AndroidStringLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for AndroidStringLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_STRINGS: &[u8] = include_bytes!("../../../test_files/android/strings_test.xml");
+
+    #[test]
+    fn scrape_android_strings_test() {
+        let links = scrape_from_slice(TEST_STRINGS).unwrap();
+        println!("{:?}", links);
+        let names = links
+            .iter()
+            .map(|it| (it.url.as_str(), it.name.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("https://tos.android.test.com", Some("tos_url")),
+                ("https://help.android.test.com/faq", Some("help_text")),
+                ("https://mirror1.android.test.com", Some("mirrors")),
+                ("https://mirror2.android.test.com", Some("mirrors")),
+            ]
+        );
+    }
+}
@@ -1,8 +1,13 @@
-use crate::helpers::find_urls;
+use crate::config::ScrapeConfig;
+use crate::formats::includes::resolve_includes;
+pub use crate::formats::includes::IncludedLink;
+use crate::formats::limited_xml_reader::LimitedXmlReader;
+use crate::helpers::{find_urls, is_schema_url, AsUrl};
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
 use std::fmt::{Display, Formatter};
 use std::io::Read;
 use thiserror::Error;
+use url::Url;
 use xml::attribute::OwnedAttribute;
 use xml::common::{Position, TextPosition};
 use xml::name::OwnedName;
@@ -11,49 +16,155 @@ use xml::reader::XmlEvent;
 use xml::EventReader;
 
 /// Scrapes links from any file with a xml-schema
+///
+/// The target of a `<meta http-equiv="refresh">` is extracted from its `content`-attribute
+/// and resolved against the document's `<base href>` if it is relative.
+///
+/// Namespace- and schema-urls are dropped, use [`scrape_with_config`] with [`ScrapeConfig::include_schema_urls`] to keep them.
+///
+/// Documents declaring UTF-8, UTF-16, ISO-8859-1 or US-ASCII are decoded accordingly.
+/// Other 8-bit encodings, e.g. `windows-1252`, are read as ISO-8859-1, which keeps their urls intact.
+///
+/// Parsing stops silently at the first malformed event, returning the links found up to that point.
+/// Use [`scrape_with_config`] with [`ScrapeConfig::strict`] to get an error instead.
+///
+/// Documents expanding entities excessively or producing an excessive amount of events
+/// are always aborted with [`XmlScrapingError::ResourceLimitExceeded`].
 pub fn scrape<R>(reader: R) -> Result<Vec<XmlLink>, XmlScrapingError>
+where
+    R: Read,
+{
+    scrape_with_config(reader, &ScrapeConfig::default())
+}
+
+/// Like [`scrape`], but applies the given [`ScrapeConfig`].
+///
+/// With [`ScrapeConfig::strict`] the first malformed event is returned as [`XmlScrapingError::XmlReaderError`].
+pub fn scrape_with_config<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<XmlLink>, XmlScrapingError>
+where
+    R: Read,
+{
+    scrape_with_options(reader, config, &XmlScrapeOptions::default())
+}
+
+/// Like [`scrape_with_config`], but only collects the kinds of links enabled in the given [`XmlScrapeOptions`].
+///
+/// Disabled kinds are skipped while parsing instead of being filtered afterward,
+/// e.g. disabled comments are not even emitted by the parser.
+///
+/// # Example
+/// ```
+/// use link_scraper::config::ScrapeConfig;
+/// use link_scraper::formats::xml::{scrape_with_options, XmlLinkKind, XmlScrapeOptions};
+///
+/// let xml = r#"<a xmlns:x="https://namespace.example.com" href="https://link.example.com">https://text.example.com</a>"#;
+/// let options = XmlScrapeOptions {
+///     text: false,
+///     namespaces: false,
+///     ..Default::default()
+/// };
+/// let links = scrape_with_options(xml.as_bytes(), &ScrapeConfig::default(), &options).unwrap();
+/// assert_eq!(links.len(), 1);
+/// assert!(matches!(links[0].kind, XmlLinkKind::Attribute(_)));
+/// ```
+pub fn scrape_with_options<R>(
+    reader: R,
+    config: &ScrapeConfig,
+    options: &XmlScrapeOptions,
+) -> Result<Vec<XmlLink>, XmlScrapingError>
+where
+    R: Read,
+{
+    let mut parser = LimitedXmlReader::with_comments(reader, options.comments);
+    scrape_events(&mut parser, config, options)
+}
+
+fn scrape_events<R>(
+    parser: &mut LimitedXmlReader<R>,
+    config: &ScrapeConfig,
+    options: &XmlScrapeOptions,
+) -> Result<Vec<XmlLink>, XmlScrapingError>
 where
     R: Read,
 {
     let mut collector: Vec<XmlLink> = vec![];
     let mut namespaces: Vec<NamespaceOccurrence> = vec![];
+    let mut base: Option<Url> = None;
 
     let mut current_parent: Option<OwnedName> = None;
-    let mut parser = EventReader::new(reader);
-    while let Ok(xml_event) = &parser.next() {
+    loop {
+        let xml_event = &match parser.next() {
+            Ok(xml_event) => xml_event,
+            Err(e @ XmlScrapingError::ResourceLimitExceeded(_)) => return Err(e),
+            Err(e) if config.strict => return Err(e),
+            Err(_) => break,
+        };
         match xml_event {
             XmlEvent::StartElement {
                 name,
                 attributes,
                 namespace,
             } => {
-                namespace.0.iter().for_each(|(ns_name, ns_ref)| {
-                    let ns_occurence = NamespaceOccurrence {
-                        namespace: ns_name.to_string(),
-                        namespace_uri: ns_ref.to_string(),
-                        first_occurrence: parser.position(),
-                    };
-                    if !&namespaces.contains(&ns_occurence) {
-                        namespaces.push(ns_occurence);
-                    }
-                });
+                if options.namespaces {
+                    namespace.0.iter().for_each(|(ns_name, ns_ref)| {
+                        let ns_occurence = NamespaceOccurrence {
+                            namespace: ns_name.to_string(),
+                            namespace_uri: ns_ref.to_string(),
+                            first_occurrence: parser.position(),
+                        };
+                        if !&namespaces.contains(&ns_occurence) {
+                            namespaces.push(ns_occurence);
+                        }
+                    });
+                }
                 current_parent = Some(name.clone());
+                if base.is_none() && name.local_name.eq_ignore_ascii_case("base") {
+                    base = attribute_value(attributes, "href")
+                        .and_then(|href| Url::parse(href.trim()).ok());
+                }
+                if !options.attributes {
+                    continue;
+                }
+                if let Some(content) = meta_refresh_content(name, attributes) {
+                    if let Some(url) = meta_refresh_target(&content.value, base.as_ref()) {
+                        collector.push(XmlLink {
+                            url,
+                            location: parser.position(),
+                            kind: XmlLinkKind::MetaRefresh(content.clone()),
+                            description: None,
+                        });
+                    }
+                    let other_attributes = attributes
+                        .iter()
+                        .filter(|attribute| *attribute != content)
+                        .cloned()
+                        .collect();
+                    collector.append(&mut scrape_from_xml_start_element_attributes(
+                        name,
+                        &other_attributes,
+                        &*parser,
+                    )?);
+                    continue;
+                }
                 collector.append(&mut scrape_from_xml_start_element_attributes(
-                    &attributes,
-                    &parser,
+                    name, attributes, &*parser,
                 )?)
             }
-            XmlEvent::Comment(comment) => collector.append(
+            XmlEvent::Comment(comment) if options.comments => collector.append(
                 &mut find_urls(comment)
                     .iter()
                     .map(|link| XmlLink {
                         url: link.as_str().to_string(),
                         location: parser.position(),
                         kind: XmlLinkKind::Comment,
+                        description: None,
                     })
                     .collect(),
             ),
-            XmlEvent::Characters(chars) => collector.append(
+            XmlEvent::Characters(chars) if options.text => collector.append(
                 &mut find_urls(chars)
                     .iter()
                     .map(|link| XmlLink {
@@ -62,10 +173,11 @@ where
                         kind: XmlLinkKind::PlainText(ParentInformation {
                             parent_tag_name: current_parent.clone(),
                         }),
+                        description: None,
                     })
                     .collect(),
             ),
-            XmlEvent::CData(chars) => collector.append(
+            XmlEvent::CData(chars) if options.cdata => collector.append(
                 &mut find_urls(chars)
                     .iter()
                     .map(|link| XmlLink {
@@ -74,6 +186,7 @@ where
                         kind: XmlLinkKind::CData(ParentInformation {
                             parent_tag_name: current_parent.clone(),
                         }),
+                        description: None,
                     })
                     .collect(),
             ),
@@ -96,23 +209,89 @@ where
                 url: namespace_uri,
                 location: first_occurrence,
                 kind: XmlLinkKind::NameSpace(namespace),
+                description: None,
             })
         },
     );
 
+    if !config.include_schema_urls {
+        collector.retain(|link| !is_schema_url(&link.url));
+    }
     Ok(collector)
 }
 gen_scrape_from_file!(scrape(Read) -> Result<Vec<XmlLink>, XmlScrapingError>);
 gen_scrape_from_slice!(scrape(Read) -> Result<Vec<XmlLink>, XmlScrapingError>);
 
+/// Like [`scrape_with_config`], but also follows the `<xi:include>`-elements ([XInclude](https://www.w3.org/TR/xinclude/))
+/// up to `max_depth` documents deep.
+///
+/// `fetch` is called with the `href` of every included document and returns its content, or `None` to skip it.
+/// The `href`s are resolved against the url of the including document if that is absolute,
+/// those of the scraped document itself are passed as written.
+/// Every url is fetched at most once, so include-cycles end. Includes with `parse="text"` are not followed.
+pub fn scrape_with_xincludes<R, F>(
+    mut reader: R,
+    config: &ScrapeConfig,
+    max_depth: usize,
+    fetch: F,
+) -> Result<Vec<IncludedLink<XmlLink>>, XmlScrapingError>
+where
+    R: Read,
+    F: Fn(&str) -> Option<Vec<u8>>,
+{
+    let mut content = vec![];
+    reader.read_to_end(&mut content)?;
+    resolve_includes(&content, max_depth, &fetch, &|document| {
+        Ok((
+            scrape_with_config(document, config)?,
+            xinclude_targets(document),
+        ))
+    })
+}
+
+const XINCLUDE_NAMESPACE: &str = "http://www.w3.org/2001/XInclude";
+
+/// Collects the `href`s of the `<xi:include>`-elements including xml-documents.
+fn xinclude_targets(document: &[u8]) -> Vec<String> {
+    let mut parser = LimitedXmlReader::new(document);
+    let mut targets = vec![];
+    while let Ok(xml_event) = parser.next::<XmlScrapingError>() {
+        match xml_event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } if name.local_name == "include"
+                && name.namespace.as_deref() == Some(XINCLUDE_NAMESPACE)
+                && attribute_value(&attributes, "parse").is_none_or(|parse| parse == "xml") =>
+            {
+                targets.extend(
+                    attribute_value(&attributes, "href")
+                        .map(str::trim)
+                        .filter(|href| !href.is_empty())
+                        .map(str::to_string),
+                )
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+    targets
+}
+
 #[derive(Error, Debug)]
 pub enum XmlScrapingError {
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
+    ResourceLimitExceeded(#[from] crate::config::ResourceLimitExceededError),
+    #[error(transparent)]
     XmlReaderError(#[from] xml::reader::Error),
 }
 
+/// Android resource files (strings.xml)
+pub mod android;
+#[cfg(feature = "opml")]
+/// .opml
+pub mod opml;
 pub mod svg;
 #[cfg(feature = "xlink")]
 pub mod xlink;
@@ -145,6 +324,37 @@ pub enum XmlLinkKind {
     /// This link is a reference to a xml-namespace<br/>
     /// Example: `<root xmlns="https://link.example.com">`
     NameSpace(String),
+
+    /// The link is the redirect-target inside the `content`-attribute of a meta-refresh<br/>
+    /// Example: `<meta http-equiv="refresh" content="0;url=https://link.example.com"/>`
+    MetaRefresh(OwnedAttribute),
+}
+
+/// Which kinds of links [`scrape_with_options`] collects. All kinds are enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XmlScrapeOptions {
+    /// Links inside attributes, see [`XmlLinkKind::Attribute`] and [`XmlLinkKind::MetaRefresh`]
+    pub attributes: bool,
+    /// Links inside comments, see [`XmlLinkKind::Comment`]
+    pub comments: bool,
+    /// Links inside the text, see [`XmlLinkKind::PlainText`]
+    pub text: bool,
+    /// Links inside CDATA-sections, see [`XmlLinkKind::CData`]
+    pub cdata: bool,
+    /// Namespace-declarations, see [`XmlLinkKind::NameSpace`]
+    pub namespaces: bool,
+}
+
+impl Default for XmlScrapeOptions {
+    fn default() -> Self {
+        Self {
+            attributes: true,
+            comments: true,
+            text: true,
+            cdata: true,
+            namespaces: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -157,6 +367,23 @@ pub struct XmlLink {
     pub url: String,
     pub location: TextPosition,
     pub kind: XmlLinkKind,
+    /// The `alt`-text (images) or `title` (other elements) of the element whose attribute contains the link
+    pub description: Option<String>,
+}
+
+impl XmlLink {
+    /// The whole value of the attribute the link was found in,
+    /// e.g. the complete `Content-Security-Policy` of a `<meta>`-tag containing several urls.
+    ///
+    /// Returns `None` for links outside of attributes.
+    pub fn raw_value(&self) -> Option<&str> {
+        match &self.kind {
+            XmlLinkKind::Attribute(attribute) | XmlLinkKind::MetaRefresh(attribute) => {
+                Some(&attribute.value)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Display for XmlLink {
@@ -165,6 +392,12 @@ impl Display for XmlLink {
     }
 }
 
+impl AsUrl for XmlLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
 pub struct XmlStartElement<'a> {
     name: &'a OwnedName,
     attributes: &'a Vec<OwnedAttribute>,
@@ -192,12 +425,12 @@ pub fn scrape_from_href_tags(bytes: &[u8]) -> Result<Vec<XmlLink>, XmlScrapingEr
     while let Ok(xml_event) = &parser.next() {
         match xml_event {
             XmlEvent::StartElement {
-                name: _name,
+                name,
                 attributes,
                 namespace: _namespace,
             } => {
                 let mut list: Vec<XmlLink> =
-                    scrape_from_xml_start_element_attributes(attributes, &parser)?
+                    scrape_from_xml_start_element_attributes(name, attributes, &parser)?
                         .into_iter()
                         .filter(|link| {
                             if let XmlLinkKind::Attribute(att) = &link.kind {
@@ -218,13 +451,73 @@ pub fn scrape_from_href_tags(bytes: &[u8]) -> Result<Vec<XmlLink>, XmlScrapingEr
     Ok(collector)
 }
 
-fn scrape_from_xml_start_element_attributes<R>(
+fn attribute_value<'a>(attributes: &'a [OwnedAttribute], local_name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name.local_name.eq_ignore_ascii_case(local_name))
+        .map(|attribute| attribute.value.as_str())
+}
+
+/// Returns the `content`-attribute if the element is a `<meta http-equiv="refresh">`.
+fn meta_refresh_content<'a>(
+    name: &OwnedName,
+    attributes: &'a [OwnedAttribute],
+) -> Option<&'a OwnedAttribute> {
+    if !name.local_name.eq_ignore_ascii_case("meta")
+        || !attribute_value(attributes, "http-equiv")?
+            .trim()
+            .eq_ignore_ascii_case("refresh")
+    {
+        return None;
+    }
+    attributes
+        .iter()
+        .find(|attribute| attribute.name.local_name.eq_ignore_ascii_case("content"))
+}
+
+/// Extracts the `url=`-part of a meta-refresh `content` like `5; URL='https://link.example.com'`.
+///
+/// Relative targets are resolved against `base`. Without a base they are dropped.
+fn meta_refresh_target(content: &str, base: Option<&Url>) -> Option<String> {
+    let (_delay, rest) = content.split_once([';', ','])?;
+    let rest = rest.trim_start();
+    let rest = match rest.get(..3) {
+        Some(key) if key.eq_ignore_ascii_case("url") => rest[3..].trim_start().strip_prefix('=')?,
+        _ => rest,
+    };
+    let target = rest.trim().trim_matches(['\'', '"']);
+    if target.is_empty() {
+        return None;
+    }
+
+    if Url::parse(target).is_ok() {
+        return Some(target.to_string());
+    }
+    Some(base?.join(target).ok()?.to_string())
+}
+
+/// Returns the `alt`-text of images or the `title` of any other element (including `xlink:title`).
+fn element_description(name: &OwnedName, attributes: &[OwnedAttribute]) -> Option<String> {
+    let is_image = ["img", "image", "area"]
+        .iter()
+        .any(|image| name.local_name.eq_ignore_ascii_case(image));
+    let description = if is_image {
+        attribute_value(attributes, "alt").or_else(|| attribute_value(attributes, "title"))
+    } else {
+        attribute_value(attributes, "title")
+    };
+    description
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+        .map(str::to_string)
+}
+
+fn scrape_from_xml_start_element_attributes(
+    name: &OwnedName,
     attributes: &Vec<OwnedAttribute>,
-    parser: &EventReader<R>,
-) -> Result<Vec<XmlLink>, XmlScrapingError>
-where
-    R: Read,
-{
+    parser: &impl Position,
+) -> Result<Vec<XmlLink>, XmlScrapingError> {
+    let description = element_description(name, attributes);
     let mut ret: Vec<XmlLink> = vec![];
     for attribute in attributes {
         let mut links = find_urls(&attribute.value)
@@ -233,6 +526,7 @@ where
                 url: link.as_str().to_string(),
                 location: parser.position(),
                 kind: XmlLinkKind::Attribute(attribute.clone()),
+                description: description.clone(),
             })
             .collect();
 
@@ -246,6 +540,12 @@ mod tests {
     use super::*;
 
     const TEST_XML: &[u8] = include_bytes!("../../../test_files/xml/xml_test.xml");
+    const TEST_META_REFRESH: &[u8] =
+        include_bytes!("../../../test_files/xml/meta_refresh_test.html");
+    const TEST_MALFORMED: &[u8] = include_bytes!("../../../test_files/xml/malformed_test.xml");
+    const TEST_LATIN1: &[u8] = include_bytes!("../../../test_files/xml/latin1_test.xml");
+    const TEST_BILLION_LAUGHS: &[u8] =
+        include_bytes!("../../../test_files/xml/billion_laughs_test.xml");
 
     #[test]
     fn scrape_hrefs_test() {
@@ -257,7 +557,11 @@ mod tests {
 
     #[test]
     fn scrape_all_test() {
-        let links = scrape(TEST_XML).unwrap();
+        let config = ScrapeConfig {
+            include_schema_urls: true,
+            ..Default::default()
+        };
+        let links = scrape_with_config(TEST_XML, &config).unwrap();
         println!("{:?}", links);
         assert!(links.iter().any(|it| it.url == "https://attribute.test.com"
             && matches!(it.kind, XmlLinkKind::Attribute(_))));
@@ -271,4 +575,214 @@ mod tests {
             .any(|it| it.url == "http://www.w3.org/XML/1998/namespace"
                 && matches!(it.kind, XmlLinkKind::NameSpace(_))));
     }
+
+    #[test]
+    fn scrape_with_options_test() {
+        let config = ScrapeConfig {
+            include_schema_urls: true,
+            ..Default::default()
+        };
+        let scrape_counting_events = |options: &XmlScrapeOptions| {
+            let mut parser = LimitedXmlReader::with_comments(TEST_XML, options.comments);
+            let links = scrape_events(&mut parser, &config, options).unwrap();
+            (links, parser.events())
+        };
+        let (all_links, all_events) = scrape_counting_events(&XmlScrapeOptions::default());
+        assert!(all_links
+            .iter()
+            .any(|it| matches!(it.kind, XmlLinkKind::NameSpace(_))));
+        assert!(all_links
+            .iter()
+            .any(|it| matches!(it.kind, XmlLinkKind::Comment)));
+
+        let (links, events) = scrape_counting_events(&XmlScrapeOptions {
+            namespaces: false,
+            comments: false,
+            ..Default::default()
+        });
+        println!("{:?}", links);
+        assert!(!links
+            .iter()
+            .any(|it| matches!(it.kind, XmlLinkKind::NameSpace(_) | XmlLinkKind::Comment)));
+        assert!(links
+            .iter()
+            .any(|it| matches!(it.kind, XmlLinkKind::Attribute(_))));
+        // Comments are skipped by the parser already
+        assert!(events < all_events);
+
+        let links = scrape_with_options(
+            TEST_XML,
+            &config,
+            &XmlScrapeOptions {
+                attributes: false,
+                text: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(!links.iter().any(|it| matches!(
+            it.kind,
+            XmlLinkKind::Attribute(_) | XmlLinkKind::MetaRefresh(_) | XmlLinkKind::PlainText(_)
+        )));
+        assert!(links
+            .iter()
+            .any(|it| matches!(it.kind, XmlLinkKind::CData(_))));
+    }
+
+    #[test]
+    fn scrape_with_xincludes_test() {
+        let root =
+            br#"<book xmlns:xi="http://www.w3.org/2001/XInclude" href="https://root.test.com">
+            <xi:include href="https://xinclude.test.com/chapters/one.xml"/>
+            <xi:include href="notes.txt" parse="text"/>
+        </book>"#;
+        let fetch = |url: &str| match url {
+            "https://xinclude.test.com/chapters/one.xml" => {
+                Some(br#"<chapter href="https://chapter.test.com"/>"#.to_vec())
+            }
+            _ => None,
+        };
+        let links = scrape_with_xincludes(&root[..], &ScrapeConfig::default(), 4, fetch).unwrap();
+        println!("{:?}", links);
+        let chapter_link = links
+            .iter()
+            .find(|it| it.link.url == "https://chapter.test.com")
+            .unwrap();
+        assert_eq!(chapter_link.depth, 1);
+        assert_eq!(
+            chapter_link.chain,
+            vec!["https://xinclude.test.com/chapters/one.xml"]
+        );
+        assert!(links
+            .iter()
+            .any(|it| it.link.url == "https://root.test.com" && it.depth == 0));
+    }
+
+    #[test]
+    fn scrape_meta_refresh_test() {
+        let links = scrape(TEST_META_REFRESH).unwrap();
+        println!("{:?}", links);
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://redirect.test.com/target"
+                && matches!(it.kind, XmlLinkKind::MetaRefresh(_))));
+        assert!(!links
+            .iter()
+            .any(|it| it.url == "https://redirect.test.com/target"
+                && matches!(it.kind, XmlLinkKind::Attribute(_))));
+    }
+
+    #[test]
+    fn resolve_meta_refresh_against_base_test() {
+        let base = Url::parse("https://base.test.com/dir/").unwrap();
+        assert_eq!(
+            meta_refresh_target("5; URL='next.html'", Some(&base)).unwrap(),
+            "https://base.test.com/dir/next.html"
+        );
+        assert_eq!(meta_refresh_target("5; url=next.html", None), None);
+        assert_eq!(meta_refresh_target("5", Some(&base)), None);
+    }
+
+    #[test]
+    fn scrape_description_test() {
+        let links = scrape(
+            r#"<p><a title="Docs" href="https://docs.test.com">Docs</a><img alt="Logo" src="https://img.test.com/logo.png"/></p>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            links
+                .iter()
+                .find(|it| it.url == "https://docs.test.com")
+                .unwrap()
+                .description,
+            Some("Docs".to_string())
+        );
+        assert_eq!(
+            links
+                .iter()
+                .find(|it| it.url == "https://img.test.com/logo.png")
+                .unwrap()
+                .description,
+            Some("Logo".to_string())
+        );
+    }
+
+    #[test]
+    fn raw_value_test() {
+        let links = scrape(
+            r#"<meta http-equiv="Content-Security-Policy" content="default-src 'self' https://cdn.test.com; img-src https://img.test.com"/>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let links: Vec<_> = links
+            .iter()
+            .filter(|it| matches!(it.kind, XmlLinkKind::Attribute(_)))
+            .collect();
+        assert_eq!(links.len(), 2);
+        for link in links {
+            assert_eq!(
+                link.raw_value(),
+                Some("default-src 'self' https://cdn.test.com; img-src https://img.test.com")
+            );
+        }
+    }
+
+    #[test]
+    fn strict_test() {
+        let links = scrape(TEST_MALFORMED).unwrap();
+        assert!(links.iter().any(|it| it.url == "https://before.test.com"));
+        assert!(!links.iter().any(|it| it.url == "https://after.test.com"));
+
+        let config = ScrapeConfig {
+            strict: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            scrape_with_config(TEST_MALFORMED, &config),
+            Err(XmlScrapingError::XmlReaderError(_))
+        ));
+    }
+
+    #[test]
+    fn latin1_encoding_test() {
+        let config = ScrapeConfig {
+            strict: true,
+            ..Default::default()
+        };
+        let links = scrape_with_config(TEST_LATIN1, &config).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            [
+                "https://latin1.test.com/café",
+                "https://text.latin1.test.com/résumé"
+            ]
+        );
+
+        // Unknown 8-bit encodings are read as ISO-8859-1 instead of failing at the declaration
+        let windows_1252: &[u8] =
+            b"<?xml version=\"1.0\" encoding=\"windows-1252\"?><a href=\"https://cp1252.test.com/caf\xe9\"/>";
+        let links = scrape_with_config(windows_1252, &config).unwrap();
+        assert_eq!(links[0].url, "https://cp1252.test.com/café");
+    }
+
+    #[test]
+    fn abort_billion_laughs_test() {
+        assert!(matches!(
+            scrape(TEST_BILLION_LAUGHS),
+            Err(XmlScrapingError::ResourceLimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/xml/xml_test.xml").unwrap();
+        let from_slice = scrape_from_slice(TEST_XML).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
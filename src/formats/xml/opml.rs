@@ -0,0 +1,125 @@
+use crate::helpers::AsUrl;
+use crate::{gen_scrape_from_file, gen_scrape_from_slice};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use thiserror::Error;
+use xml::attribute::OwnedAttribute;
+use xml::reader::XmlEvent;
+use xml::EventReader;
+
+/// Scrapes the feed- and site-urls of all outlines of an OPML-file (e.g. a podcast subscription list).
+pub fn scrape<R>(reader: R) -> Result<Vec<OpmlLink>, OpmlScrapingError>
+where
+    R: Read,
+{
+    let mut collector: Vec<OpmlLink> = vec![];
+    let mut parser = EventReader::new(reader);
+    loop {
+        match parser.next()? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } if name.local_name == "outline" => {
+                let title = attribute_value(&attributes, "title")
+                    .or_else(|| attribute_value(&attributes, "text"))
+                    .map(str::to_string);
+                for (attribute, kind) in [
+                    ("xmlUrl", OpmlLinkKind::FeedUrl),
+                    ("htmlUrl", OpmlLinkKind::SiteUrl),
+                ] {
+                    if let Some(url) = attribute_value(&attributes, attribute) {
+                        collector.push(OpmlLink {
+                            url: url.to_string(),
+                            kind,
+                            title: title.clone(),
+                        })
+                    }
+                }
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+    Ok(collector)
+}
+gen_scrape_from_file!(scrape(Read) -> Result<Vec<OpmlLink>, OpmlScrapingError>);
+gen_scrape_from_slice!(scrape(Read) -> Result<Vec<OpmlLink>, OpmlScrapingError>);
+
+#[derive(Error, Debug)]
+pub enum OpmlScrapingError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    XmlReaderError(#[from] xml::reader::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpmlLink {
+    pub url: String,
+    pub kind: OpmlLinkKind,
+    /// The `title` of the outline, or its `text` if it has no title
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpmlLinkKind {
+    /// The url of the feed itself <br/>
+    /// Example: `<outline xmlUrl="https://link.example.com/rss">`
+    FeedUrl,
+    /// The url of the website belonging to the feed <br/>
+    /// Example: `<outline htmlUrl="https://link.example.com">`
+    SiteUrl,
+}
+
+impl Display for OpmlLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+impl AsUrl for OpmlLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Empty values are treated like missing attributes.
+fn attribute_value<'a>(attributes: &'a [OwnedAttribute], local_name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name.local_name == local_name)
+        .map(|attribute| attribute.value.trim())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_OPML: &[u8] = include_bytes!("../../../test_files/opml/opml_test.opml");
+
+    #[test]
+    fn scrape_opml_test() {
+        let links = scrape(TEST_OPML).unwrap();
+        println!("{:?}", links);
+        assert_eq!(
+            links,
+            vec![
+                OpmlLink {
+                    url: "https://feed.test.com/rss".to_string(),
+                    kind: OpmlLinkKind::FeedUrl,
+                    title: Some("Test Cast Title".to_string())
+                },
+                OpmlLink {
+                    url: "https://site.test.com".to_string(),
+                    kind: OpmlLinkKind::SiteUrl,
+                    title: Some("Test Cast Title".to_string())
+                },
+                OpmlLink {
+                    url: "https://untitled.test.com/feed.xml".to_string(),
+                    kind: OpmlLinkKind::FeedUrl,
+                    title: Some("Untitled Cast".to_string())
+                },
+            ]
+        );
+    }
+}
@@ -1,5 +1,7 @@
+use crate::config::ScrapeConfig;
 use crate::formats::xml::svg::SvgLinkKind::{Attribute, Comment, NameSpace, Script, Text};
 use crate::formats::xml::XmlLinkKind;
+use crate::helpers::AsUrl;
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
 use std::fmt::{Display, Formatter};
 use std::io::Read;
@@ -7,22 +9,37 @@ use thiserror::Error;
 use xml::attribute::OwnedAttribute;
 use xml::common::TextPosition;
 
+/// Scrapes links from a svg-file like [`crate::formats::xml::scrape`].
 pub fn scrape<R>(reader: R) -> Result<Vec<SvgLink>, SvgScrapingError>
 where
     R: Read,
 {
-    Ok(crate::formats::xml::scrape(reader)?
+    scrape_with_config(reader, &ScrapeConfig::default())
+}
+
+/// Like [`scrape`], but applies the given [`ScrapeConfig`] like [`crate::formats::xml::scrape_with_config`].
+pub fn scrape_with_config<R>(
+    reader: R,
+    config: &ScrapeConfig,
+) -> Result<Vec<SvgLink>, SvgScrapingError>
+where
+    R: Read,
+{
+    Ok(crate::formats::xml::scrape_with_config(reader, config)?
         .into_iter()
         .map(|link| SvgLink {
             url: link.url,
             location: link.location,
             kind: match link.kind {
-                XmlLinkKind::Attribute(attribute) => Attribute(attribute),
+                XmlLinkKind::Attribute(attribute) | XmlLinkKind::MetaRefresh(attribute) => {
+                    Attribute(attribute)
+                }
                 XmlLinkKind::Comment => Comment,
                 XmlLinkKind::PlainText(_) => Text,
                 XmlLinkKind::CData(_) => Script,
                 XmlLinkKind::NameSpace(ns) => NameSpace(ns),
             },
+            description: link.description,
         })
         .collect())
 }
@@ -42,6 +59,8 @@ pub struct SvgLink {
     pub url: String,
     pub location: TextPosition,
     pub kind: SvgLinkKind,
+    /// The `title` (e.g. `xlink:title`) of the element whose attribute contains the link
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -75,6 +94,12 @@ impl Display for SvgLink {
     }
 }
 
+impl AsUrl for SvgLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SvgLinkLocation {
     pub file: String,
@@ -94,8 +119,53 @@ mod tests {
             .iter()
             .any(|it| it.url == "https://cdata.test.com/insideACodeSnippet"
                 && matches!(it.kind, Script)));
-        assert!(links
+    }
+
+    #[test]
+    fn schema_urls_test() {
+        let xml_links = crate::formats::xml::scrape(TEST_SVG).unwrap();
+        assert!(!xml_links
+            .iter()
+            .any(|it| it.url == "http://www.w3.org/2000/svg"));
+        let svg_links = scrape(TEST_SVG).unwrap();
+        assert!(!svg_links
+            .iter()
+            .any(|it| it.url == "http://www.w3.org/2000/svg"));
+
+        let config = ScrapeConfig {
+            include_schema_urls: true,
+            ..Default::default()
+        };
+        let xml_links = crate::formats::xml::scrape_with_config(TEST_SVG, &config).unwrap();
+        assert!(xml_links
+            .iter()
+            .any(|it| it.url == "http://www.w3.org/2000/svg"));
+        let svg_links = scrape_with_config(TEST_SVG, &config).unwrap();
+        assert!(svg_links
             .iter()
             .any(|it| it.url == "http://www.w3.org/2000/svg" && matches!(it.kind, NameSpace(_))));
     }
+
+    #[test]
+    fn scrape_title_test() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+            <a xlink:title="Docs" xlink:href="https://docs.test.com"><text>Docs</text></a>
+        </svg>"#;
+        let links = scrape(svg.as_bytes()).unwrap();
+        assert!(links
+            .iter()
+            .any(|it| it.url == "https://docs.test.com"
+                && it.description == Some("Docs".to_string())));
+    }
+
+    #[test]
+    fn scrape_from_file_test() {
+        let from_file = scrape_from_file("test_files/xml/svg_test.svg").unwrap();
+        let from_slice = scrape_from_slice(TEST_SVG).unwrap();
+        assert!(!from_file.is_empty());
+        assert_eq!(
+            from_file.iter().map(|it| it.url()).collect::<Vec<_>>(),
+            from_slice.iter().map(|it| it.url()).collect::<Vec<_>>()
+        );
+    }
 }
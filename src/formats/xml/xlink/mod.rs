@@ -8,7 +8,7 @@ use crate::formats::xml::xlink::XLinkFormatError::{
     ResourceOutsideOfExtendedError, SimpleInsideOfExtendedError,
 };
 use crate::formats::xml::XmlStartElement;
-use crate::helpers::find_urls;
+use crate::helpers::{find_urls, AsUrl};
 use crate::{gen_scrape_from_file, gen_scrape_from_slice};
 use itertools::Itertools;
 use std::io::Read;
@@ -91,6 +91,12 @@ pub struct XLinkLink {
     pub kind: XLinkLinkKind,
 }
 
+impl AsUrl for XLinkLink {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum XLinkLinkKind {
     Simple,
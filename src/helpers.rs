@@ -1,9 +1,43 @@
 use linkify::LinkFinder;
-use linkify::LinkKind::Url;
+use linkify::LinkKind;
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::Range;
+use url::{ParseError, Position, Url};
 
 #[cfg(feature = "any_format")]
 pub use crate::any_format_scraper::scrape;
 
+/// Gives access to the url of any link-type, regardless of its format.
+/// # Example
+/// ```
+/// use link_scraper::any_format_scraper;
+/// use link_scraper::formats::plaintext;
+/// use link_scraper::helpers::AsUrl;
+///
+/// let text_links = plaintext::scrape_from_slice("see https://text.test.com").unwrap();
+/// let any_links = any_format_scraper::scrape_from_slice("see https://any.test.com").unwrap();
+/// let mixed: Vec<&dyn AsUrl> = text_links
+///     .iter()
+///     .map(|link| link as &dyn AsUrl)
+///     .chain(any_links.iter().map(|link| link as &dyn AsUrl))
+///     .collect();
+/// let urls: Vec<&str> = mixed.iter().map(|link| link.url()).collect();
+/// assert_eq!(urls, ["https://text.test.com", "https://any.test.com"]);
+/// ```
+pub trait AsUrl {
+    fn url(&self) -> &str;
+}
+
+impl AsUrl for String {
+    fn url(&self) -> &str {
+        self
+    }
+}
+
 /// Finds all URLs in a given string
 /// # Example
 /// ```
@@ -11,13 +45,774 @@ pub use crate::any_format_scraper::scrape;
 /// let urls = find_urls("dfjaoijewfj oijoiwfjoiwjoi j´21214https://www.google.com .äwä.f.f.wä ");
 /// assert_eq!(urls.first().unwrap().as_str(), "https://www.google.com")
 /// ```
-pub fn find_urls(content: &str) -> Vec<linkify::Link> {
-    LinkFinder::new()
+pub fn find_urls(content: &str) -> Vec<linkify::Link<'_>> {
+    DEFAULT_LINK_FINDER
         .links(content)
-        .filter(|link| link.kind().eq(&Url))
+        .filter(|link| link.kind().eq(&LinkKind::Url))
+        .collect()
+}
+
+/// Stop-characters for CSV-files, whose fields may be quoted with `"`. See [`find_urls_with_stops`].
+pub const CSV_STOPS: &[char] = &['"'];
+/// Stop-characters for source code, where statements end with `;` and arguments with `)`. See [`find_urls_with_stops`].
+pub const SOURCE_CODE_STOPS: &[char] = &[';', ')'];
+
+/// Like [`find_urls`], but also trims the given `stops` from the start and the end of every url,
+/// e.g. [`CSV_STOPS`] or [`SOURCE_CODE_STOPS`]. Urls consisting only of stop-characters are dropped.
+///
+/// Returns every url together with its byte-position inside `content`.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::{find_urls_with_stops, CSV_STOPS, SOURCE_CODE_STOPS};
+/// assert_eq!(find_urls_with_stops(r#"1,"https://x.com",2"#, CSV_STOPS), [(3, "https://x.com")]);
+/// assert_eq!(find_urls_with_stops("go(https://x.com/a);", SOURCE_CODE_STOPS), [(3, "https://x.com/a")]);
+/// ```
+pub fn find_urls_with_stops<'t>(content: &'t str, stops: &[char]) -> Vec<(usize, &'t str)> {
+    find_urls(content)
+        .iter()
+        .filter_map(|link| {
+            let url = link.as_str().trim_end_matches(stops);
+            let trimmed = url.trim_start_matches(stops);
+            let start = link.start() + url.len() - trimmed.len();
+            (!trimmed.is_empty()).then_some((start, trimmed))
+        })
+        .collect()
+}
+
+/// Whether a match of [`LinkFinder`] is an url or an email-address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchKind {
+    /// The match is an url <br/>
+    /// Example: `https://link.example.com`
+    Url,
+    /// The match is an email-address without a scheme <br/>
+    /// Example: `someone@example.com`
+    Email,
+}
+
+/// A match of an [`UrlMatcher`]: the byte-range of the url inside the searched text and its kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UrlMatch {
+    pub start: usize,
+    pub end: usize,
+    pub kind: MatchKind,
+}
+
+impl UrlMatch {
+    /// Returns the matched url from the text that was searched.
+    ///
+    /// # Panics
+    /// If the range of the match is not inside `content`.
+    pub fn as_str<'t>(&self, content: &'t str) -> &'t str {
+        &content[self.start..self.end]
+    }
+}
+
+/// Finds urls in a text, in order of their position.
+///
+/// Replaces [`LinkifyMatcher`] if set as [`crate::config::ScrapeConfig::url_matcher`],
+/// e.g. to find urls with a custom scheme or to use a regex instead of linkify.
+/// Email-matches are dropped unless [`crate::config::ScrapeConfig::include_emails`] is enabled,
+/// matches whose range is not inside the text are dropped as well.
+/// # Example
+/// ```
+/// use link_scraper::config::ScrapeConfig;
+/// use link_scraper::formats::plaintext;
+/// use link_scraper::helpers::{MatchKind, UrlMatch, UrlMatcher};
+/// use std::sync::Arc;
+///
+/// /// Matches whole words starting with `urn:`
+/// struct UrnMatcher;
+///
+/// impl UrlMatcher for UrnMatcher {
+///     fn find(&self, content: &str) -> Vec<UrlMatch> {
+///         content
+///             .match_indices("urn:")
+///             .map(|(start, _)| UrlMatch {
+///                 start,
+///                 end: content[start..].find(' ').map_or(content.len(), |end| start + end),
+///                 kind: MatchKind::Url,
+///             })
+///             .collect()
+///     }
+/// }
+///
+/// let config = ScrapeConfig {
+///     url_matcher: Some(Arc::new(UrnMatcher)),
+///     // `urn:`-urls count as schema-urls
+///     include_schema_urls: true,
+///     ..ScrapeConfig::default()
+/// };
+/// let links = plaintext::scrape_with_config("see urn:isbn:0451450523 now".as_bytes(), &config).unwrap();
+/// assert_eq!(links[0].url, "urn:isbn:0451450523");
+/// ```
+pub trait UrlMatcher: Send + Sync {
+    fn find(&self, content: &str) -> Vec<UrlMatch>;
+}
+
+impl std::fmt::Debug for dyn UrlMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UrlMatcher")
+    }
+}
+
+/// The default [`UrlMatcher`], finding urls and email-addresses with linkify like [`find_urls`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinkifyMatcher;
+
+impl UrlMatcher for LinkifyMatcher {
+    fn find(&self, content: &str) -> Vec<UrlMatch> {
+        DEFAULT_LINK_FINDER
+            .links(content)
+            .filter_map(|link| {
+                let kind = match link.kind() {
+                    LinkKind::Url => MatchKind::Url,
+                    LinkKind::Email => MatchKind::Email,
+                    _ => return None,
+                };
+                Some(UrlMatch {
+                    start: link.start(),
+                    end: link.end(),
+                    kind,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Finds the urls of a text with the [`crate::config::ScrapeConfig::url_matcher`], including email-addresses with
+/// [`crate::config::ScrapeConfig::include_emails`].
+#[cfg(any(feature = "plaintext", feature = "any_format"))]
+pub(crate) fn find_matches(content: &str, config: &crate::config::ScrapeConfig) -> Vec<UrlMatch> {
+    let matches = match &config.url_matcher {
+        Some(matcher) => matcher.find(content),
+        None => LinkifyMatcher.find(content),
+    };
+    matches
+        .into_iter()
+        .filter(|found| config.include_emails || found.kind == MatchKind::Url)
+        .filter(|found| found.start < found.end && content.get(found.start..found.end).is_some())
+        .collect()
+}
+
+/// Finds all URLs in a given string like [`find_urls`] and tries to parse each of them with [`Url::parse`].
+///
+/// Useful to sort out matches that look like urls but are not valid ones.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::parse_urls;
+/// let urls = parse_urls("valid https://example.com/ but not https://example.com:99999/");
+/// assert_eq!(urls[0].0, "https://example.com/");
+/// assert!(urls[0].1.is_ok());
+/// assert_eq!(urls[1].1, Err(url::ParseError::InvalidPort));
+/// ```
+pub fn parse_urls(content: &str) -> Vec<(String, Result<Url, ParseError>)> {
+    find_urls(content)
+        .iter()
+        .map(|link| (link.as_str().to_string(), Url::parse(link.as_str())))
         .collect()
 }
 
+/// Finds all links in a given string using a preconfigured [`LinkFinder`].
+///
+/// Unlike [`find_urls`] this returns links of every kind the `finder` is configured for (e.g. emails).
+/// # Example
+/// ```
+/// use linkify::LinkFinder;
+/// use crate::link_scraper::helpers::find_urls_with;
+/// let mut finder = LinkFinder::new();
+/// finder.url_must_have_scheme(false);
+/// assert_eq!(find_urls_with("see www.x.com", &finder).first().unwrap().as_str(), "www.x.com");
+/// finder.url_must_have_scheme(true);
+/// assert!(find_urls_with("see www.x.com", &finder).is_empty());
+/// ```
+pub fn find_urls_with<'t>(content: &'t str, finder: &LinkFinder) -> Vec<linkify::Link<'t>> {
+    finder.links(content).collect()
+}
+
+/// Counts how often each url occurs, keeping the urls in the order they were first seen.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::count_occurrences;
+/// let links = ["https://a.test.com", "https://b.test.com", "https://a.test.com", "https://a.test.com"]
+///     .map(String::from);
+/// assert_eq!(
+///     count_occurrences(&links),
+///     [("https://a.test.com".to_string(), 3), ("https://b.test.com".to_string(), 1)]
+/// );
+/// ```
+pub fn count_occurrences(links: &[impl AsUrl]) -> Vec<(String, usize)> {
+    let mut indices: HashMap<&str, usize> = HashMap::new();
+    let mut counts: Vec<(String, usize)> = vec![];
+    for link in links {
+        let url = link.url();
+        match indices.get(url) {
+            Some(&index) => counts[index].1 += 1,
+            None => {
+                indices.insert(url, counts.len());
+                counts.push((url.to_string(), 1));
+            }
+        }
+    }
+    counts
+}
+
+/// Groups links by the kind `kind_of` returns for them, keeping their order inside each group.
+///
+/// Works with the kind of any format, e.g. `group_by_kind(links, |link| link.kind)`
+/// or `group_by_kind(links, Link::kind_name)` for the links of the any-format-scraper.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::group_by_kind;
+/// let links = ["https://a.test.com", "mailto:b@test.com", "https://c.test.com"];
+/// let groups = group_by_kind(links, |link| link.split(':').next().unwrap().to_string());
+/// assert_eq!(groups["https"], ["https://a.test.com", "https://c.test.com"]);
+/// assert_eq!(groups["mailto"], ["mailto:b@test.com"]);
+/// ```
+pub fn group_by_kind<L, K, F>(links: impl IntoIterator<Item = L>, kind_of: F) -> HashMap<K, Vec<L>>
+where
+    K: Eq + Hash,
+    F: Fn(&L) -> K,
+{
+    let mut groups: HashMap<K, Vec<L>> = HashMap::new();
+    for link in links {
+        groups.entry(kind_of(&link)).or_default().push(link);
+    }
+    groups
+}
+
+/// Soft hyphens and zero-width characters, which OCR and hyphenation insert into words.
+const INVISIBLE_CHARS: [char; 6] = [
+    '\u{00AD}', '\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}',
+];
+
+/// Returns whether the given character is a soft hyphen or a zero-width character.
+pub fn is_invisible_char(c: char) -> bool {
+    INVISIBLE_CHARS.contains(&c)
+}
+
+/// Removes soft hyphens and zero-width characters, which would otherwise end up inside or break up urls.
+///
+/// Only allocates if there is something to remove.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::{find_urls, strip_invisible_chars};
+/// let text = "see https://ex\u{ad}ample.com/re\u{ad}port.pdf";
+/// assert_eq!(find_urls(text)[0].as_str(), "https://ex\u{ad}ample.com/re\u{ad}port.pdf");
+/// let cleaned = strip_invisible_chars(text);
+/// assert_eq!(find_urls(&cleaned)[0].as_str(), "https://example.com/report.pdf");
+/// ```
+pub fn strip_invisible_chars(text: &str) -> Cow<'_, str> {
+    if text.contains(INVISIBLE_CHARS) {
+        Cow::Owned(text.replace(INVISIBLE_CHARS, ""))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Characters Windows-1252 maps the bytes `0x80..=0x9F` to. Unassigned bytes keep their C1 control character.
+#[cfg(feature = "encoding")]
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Repairs an url whose UTF-8 bytes were decoded as Latin-1 or Windows-1252 (mojibake, e.g. `Ã¼` instead of `ü`).
+///
+/// Returns [`None`] if the url does not look double-encoded: it has to consist of characters of these encodings,
+/// their bytes have to form valid UTF-8 and the result has to parse as an url.
+/// Correctly encoded urls virtually never satisfy all of these.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::repair_mojibake;
+/// assert_eq!(repair_mojibake("https://Ã¼ber.example").unwrap(), "https://über.example");
+/// assert_eq!(repair_mojibake("https://über.example"), None);
+/// ```
+#[cfg(feature = "encoding")]
+pub fn repair_mojibake(url: &str) -> Option<String> {
+    if url.is_ascii() {
+        return None;
+    }
+    let bytes = url
+        .chars()
+        .map(
+            |c| match WINDOWS_1252_HIGH.iter().position(|&high| high == c) {
+                Some(index) => Some(0x80 + index as u8),
+                None => u8::try_from(c).ok(),
+            },
+        )
+        .collect::<Option<Vec<u8>>>()?;
+    let repaired = String::from_utf8(bytes).ok()?;
+    Url::parse(&repaired).ok()?;
+    Some(repaired)
+}
+
+/// Returns the sentence of `text` that contains the bytes in `range`, e.g. the range of a link.
+///
+/// Sentences end at `.`, `!` or `?` followed by whitespace and at ideographic full stops (`。`, `！`, `？`).
+/// Periods inside urls never end a sentence. The sentence is trimmed of surrounding whitespace.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::sentence_around;
+/// let url = "https://docs.test.com/v1.2/index.html";
+/// let text = format!("First sentence. Docs live at {url}. Last one!");
+/// let start = text.find(url).unwrap();
+/// assert_eq!(
+///     sentence_around(&text, start..start + url.len()),
+///     "Docs live at https://docs.test.com/v1.2/index.html."
+/// );
+/// ```
+pub fn sentence_around(text: &str, range: Range<usize>) -> &str {
+    let urls = find_urls(text)
+        .iter()
+        .map(|link| link.start()..link.end())
+        .collect::<Vec<_>>();
+    // Byte-index after the sentence-terminating character at `index`, if it ends a sentence.
+    let sentence_end = |index: usize, c: char| {
+        let after = index + c.len_utf8();
+        let ends = match c {
+            '。' | '！' | '？' => true,
+            '.' | '!' | '?' => text[after..].chars().next().is_none_or(char::is_whitespace),
+            _ => false,
+        };
+        (ends && !urls.iter().any(|url| url.contains(&index))).then_some(after)
+    };
+
+    let start = text[..range.start]
+        .char_indices()
+        .rev()
+        .find_map(|(index, c)| sentence_end(index, c))
+        .unwrap_or(0);
+    let end = text[range.end..]
+        .char_indices()
+        .find_map(|(index, c)| sentence_end(range.end + index, c))
+        .unwrap_or(text.len());
+    text[start..end].trim()
+}
+
+/// Appends `text` to `preview` until the preview holds `length` characters.
+///
+/// Runs of whitespace are collapsed into a single space and leading whitespace is dropped,
+/// so the parts of a document can be separated by appending a space. Trim the end of the finished preview.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::push_preview;
+/// let mut preview = String::new();
+/// push_preview(&mut preview, "  First\nparagraph", 20);
+/// push_preview(&mut preview, " ", 20);
+/// push_preview(&mut preview, "Second paragraph", 20);
+/// assert_eq!(preview, "First paragraph Seco");
+/// ```
+pub fn push_preview(preview: &mut String, text: &str, length: usize) {
+    let mut count = preview.chars().count();
+    for c in text.chars() {
+        if count >= length {
+            break;
+        }
+        if !c.is_whitespace() {
+            preview.push(c);
+        } else if preview.is_empty() || preview.ends_with(' ') {
+            continue;
+        } else {
+            preview.push(' ');
+        }
+        count += 1;
+    }
+}
+
+/// Returns `false` if the host of the given url is `localhost` or a private, loopback, link-local or unspecified ip-address.
+///
+/// Only the literal host is checked, no DNS-lookups are performed. The host is parsed like browsers and HTTP-clients do,
+/// so shorthand (`127.1`), decimal (`2130706433`), hexadecimal (`0x7f.0.0.1`) and percent-encoded forms are recognized.
+/// Urls without a host (e.g. `mailto:`-links) are considered public.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::is_public_url;
+/// assert!(is_public_url("https://example.com/"));
+/// assert!(!is_public_url("http://127.0.0.1/"));
+/// assert!(!is_public_url("http://localhost:8080/admin"));
+/// assert!(!is_public_url("http://[::1]/"));
+/// assert!(!is_public_url("http://127.1/"));
+/// assert!(!is_public_url("http://2130706433/"));
+/// assert!(!is_public_url("http://0x7f.0.0.1/"));
+/// assert!(!is_public_url("http://%6c%6fcalhost/"));
+/// assert!(!is_public_url("http://[::ffff:7f00:1]/"));
+/// assert!(is_public_url("mailto:someone@localhost"));
+/// ```
+pub fn is_public_url(url: &str) -> bool {
+    let host = match parse_host(url) {
+        Some(host) => host,
+        // Urls `url` cannot parse are rejected by HTTP-clients as well, their literal host is checked nevertheless
+        None => match url_host(url).and_then(|host| parse_host(host.trim_end_matches('.'))) {
+            Some(host) => host,
+            None => return true,
+        },
+    };
+    match host {
+        url::Host::Domain(domain) => domain != "localhost" && !domain.ends_with(".localhost"),
+        url::Host::Ipv4(ip) => !is_private_ipv4(&ip),
+        url::Host::Ipv6(ip) => !is_private_ipv6(&ip),
+    }
+}
+
+/// Returns `true` if the given url identifies a xml-namespace or -schema (W3C, OASIS, Microsoft, Adobe, Dublin Core, ...)
+/// instead of pointing to content.
+///
+/// URNs are always considered schema-urls. Urls on the hosts of namespaces only count if their path is that of a
+/// known namespace, so links to specifications (`https://www.w3.org/TR/...`) or documentation are kept.
+/// # Example
+/// ```
+/// use crate::link_scraper::helpers::is_schema_url;
+/// assert!(is_schema_url("http://www.w3.org/2000/svg"));
+/// assert!(is_schema_url("http://schemas.openxmlformats.org/officeDocument/2006/relationships"));
+/// assert!(is_schema_url("urn:oasis:names:tc:opendocument:xmlns:office:1.0"));
+/// assert!(is_schema_url("http://ns.adobe.com/xap/1.0/"));
+/// assert!(!is_schema_url("https://www.w3.org/TR/xml/"));
+/// assert!(!is_schema_url("https://www.w3.org/2001/sw/"));
+/// assert!(!is_schema_url("https://schemas.microsoft.com/"));
+/// assert!(!is_schema_url("https://example.com/"));
+/// ```
+pub fn is_schema_url(url: &str) -> bool {
+    if url
+        .get(..4)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("urn:"))
+    {
+        return true;
+    }
+    let Some(host) = url_host(url) else {
+        return false;
+    };
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = url
+        .split_once("://")
+        .and_then(|(_, rest)| rest.find('/').map(|start| &rest[start..]))
+        .unwrap_or_default();
+    let mut segments = path.split('/').skip(1);
+    let is_dated = segments
+        .next()
+        .is_some_and(|year| year.len() == 4 && year.bytes().all(|byte| byte.is_ascii_digit()));
+    // Namespaces like `http://ns.adobe.com/xap/1.0/` or `http://xmlns.com/foaf/0.1/` carry their version
+    let is_versioned = segments.next().is_some_and(|version| {
+        version.bytes().any(|byte| byte.is_ascii_digit())
+            && version
+                .bytes()
+                .all(|byte| byte.is_ascii_digit() || byte == b'.')
+    });
+    match host {
+        "schemas.openxmlformats.org" | "schemas.android.com" | "schemas.xmlsoap.org" => true,
+        "schemas.microsoft.com" => ["/office/", "/winfx/", "/sharepoint/"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix)),
+        "w3.org" => W3_NAMESPACES.iter().any(|prefix| path.starts_with(prefix)),
+        "openoffice.org" => is_dated,
+        "purl.org" => path.starts_with("/dc/") || path.starts_with("/rss/"),
+        "docs.oasis-open.org" => path.starts_with("/ns/"),
+        "ns.adobe.com" | "xmlns.com" => is_versioned,
+        _ => false,
+    }
+}
+
+/// The paths of the namespaces on `w3.org` used by common document-formats.
+const W3_NAMESPACES: [&str; 16] = [
+    "/1998/Math/MathML",
+    "/1999/02/22-rdf-syntax-ns",
+    "/1999/XSL/",
+    "/1999/xhtml",
+    "/1999/xlink",
+    "/2000/01/rdf-schema",
+    "/2000/09/xmldsig",
+    "/2000/svg",
+    "/2000/xmlns/",
+    "/2001/04/xmlenc",
+    "/2001/XInclude",
+    "/2001/XMLSchema",
+    "/2002/07/owl",
+    "/2005/Atom",
+    "/ns/",
+    "/XML/1998/namespace",
+];
+
+/// Returns the host of an url if it is a single label without any dot, e.g. `foo` for `http://foo/page`.
+///
+/// Such hosts only resolve inside a local network, matches like this are usually OCR-errors or loose text.
+/// Ip-addresses and urls without a host (e.g. `mailto:`-links) are never single-label.
+/// # Example
+/// ```
+/// use link_scraper::helpers::single_label_host;
+/// assert_eq!(single_label_host("http://Foo/page").as_deref(), Some("foo"));
+/// assert_eq!(single_label_host("http://localhost.:8080").as_deref(), Some("localhost"));
+/// assert_eq!(single_label_host("http://foo.com"), None);
+/// assert_eq!(single_label_host("http://[::1]/"), None);
+/// ```
+pub fn single_label_host(url: &str) -> Option<String> {
+    match parse_host(url)? {
+        url::Host::Domain(host) if !host.contains('.') => Some(host),
+        _ => None,
+    }
+}
+
+/// Normalizes an url for comparing it with others by lowercasing its scheme and host,
+/// e.g. `HTTPS://Example.COM/Path` becomes `https://example.com/Path`.
+///
+/// Only the parts that are case-insensitive are folded. Credentials, path, query and fragment are kept as written,
+/// so `https://example.com/Path` and `https://example.com/path` stay different.
+/// Urls without an authority (e.g. `mailto:`-links) only get their scheme lowercased.
+/// # Example
+/// ```
+/// use link_scraper::helpers::normalize_url;
+/// assert_eq!(normalize_url("https://Example.com/Path"), normalize_url("https://example.com/Path"));
+/// assert_ne!(normalize_url("https://example.com/Path"), normalize_url("https://example.com/path"));
+/// assert_eq!(normalize_url("HTTP://User@WWW.Example.com:8080/A?B=C#D"), "http://User@www.example.com:8080/A?B=C#D");
+/// assert_eq!(normalize_url("MAILTO:Someone@Example.com"), "mailto:Someone@Example.com");
+/// ```
+pub fn normalize_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return url.to_string();
+    };
+    let is_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !is_scheme {
+        return url.to_string();
+    }
+    let scheme = scheme.to_ascii_lowercase();
+    let Some(rest) = rest.strip_prefix("//") else {
+        return format!("{}:{}", scheme, rest);
+    };
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+    let (credentials, host) = match authority.rsplit_once('@') {
+        Some((credentials, host)) => (format!("{}@", credentials), host),
+        None => (String::new(), authority),
+    };
+    format!(
+        "{}://{}{}{}",
+        scheme,
+        credentials,
+        host.to_lowercase(),
+        path
+    )
+}
+
+/// Extracts the url-sources of a `Content-Security-Policy` together with the (lowercase) name of their directive,
+/// e.g. `("script-src", "https://js.example.com")`.
+///
+/// Directives are separated by `;`, several policies (as in a combined header) by `,`.
+/// Only directives listing sources are considered: fetch-directives (`*-src`), `base-uri`, `form-action`,
+/// `frame-ancestors`, `navigate-to` and `report-uri`. Quoted keywords (`'self'`, `'nonce-...'`), bare schemes
+/// (`https:`) and `*` allow no specific origin and are skipped. Host-sources are returned as written,
+/// so they may lack a scheme or contain wildcards like `*.example.com`.
+/// # Example
+/// ```
+/// use link_scraper::helpers::csp_sources;
+/// let policy = "default-src 'self' https://cdn.example.com; IMG-SRC data: *.img.example.com";
+/// assert_eq!(
+///     csp_sources(policy),
+///     vec![
+///         ("default-src".to_string(), "https://cdn.example.com"),
+///         ("img-src".to_string(), "*.img.example.com"),
+///     ]
+/// );
+/// ```
+pub fn csp_sources(policy: &str) -> Vec<(String, &str)> {
+    let mut sources = vec![];
+    for directive in policy.split([';', ',']) {
+        let mut tokens = directive.split_ascii_whitespace();
+        let Some(name) = tokens.next() else {
+            continue;
+        };
+        let name = name.to_ascii_lowercase();
+        if !(name.ends_with("-src") || CSP_URL_DIRECTIVES.contains(&name.as_str())) {
+            continue;
+        }
+        sources.extend(
+            tokens
+                .filter(|source| is_csp_url_source(source))
+                .map(|source| (name.clone(), source)),
+        );
+    }
+    sources
+}
+
+const CSP_URL_DIRECTIVES: [&str; 5] = [
+    "base-uri",
+    "form-action",
+    "frame-ancestors",
+    "navigate-to",
+    "report-uri",
+];
+
+fn is_csp_url_source(source: &str) -> bool {
+    let is_scheme = source.strip_suffix(':').is_some_and(|scheme| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    !(source.starts_with('\'') || source == "*" || is_scheme)
+}
+
+/// How [`redact_url`] hides the parts of an url that may contain secrets, like tokens or personal data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RedactPolicy {
+    /// Keeps the scheme, host and port, e.g. `https://x.com`
+    HostOnly,
+    /// Replaces path, query and fragment with their hash, e.g. `https://x.com/#a51daa492f9d5de1`
+    HashPath,
+    /// Drops query and fragment but keeps the path, e.g. `https://x.com/secret`
+    DropQuery,
+}
+
+/// Redacts an url according to the given [`RedactPolicy`], e.g. before it is written to a shared log.
+/// Credentials (`user:password@`) are dropped by every policy.
+///
+/// The hash of [`RedactPolicy::HashPath`] is a 64-bit FNV-1a hash, so it is the same across runs and platforms
+/// and redacted urls can still be told apart and counted. It is not a cryptographic hash, short secrets can be guessed.
+/// Urls that can not be parsed are replaced by the hash of the whole url (`redacted:#` and 16 hex-digits).
+/// # Example
+/// ```
+/// use link_scraper::helpers::{redact_url, RedactPolicy};
+/// let url = "https://user:pw@x.com/secret?token=abc";
+/// assert_eq!(redact_url(url, RedactPolicy::HostOnly), "https://x.com");
+/// assert_eq!(redact_url(url, RedactPolicy::DropQuery), "https://x.com/secret");
+/// assert_eq!(redact_url(url, RedactPolicy::HashPath), "https://x.com/#a51daa492f9d5de1");
+/// ```
+pub fn redact_url(url: &str, policy: RedactPolicy) -> String {
+    let Ok(mut url) = Url::parse(url) else {
+        return format!("redacted:#{:016x}", fnv1a_hash(url.as_bytes()));
+    };
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    // Urls without a host, like `mailto:`-links, have nothing but their scheme to keep
+    let origin = if url.has_host() {
+        url[..Position::BeforePath].to_string()
+    } else {
+        format!("{}:", url.scheme())
+    };
+    match policy {
+        RedactPolicy::HostOnly => origin,
+        RedactPolicy::HashPath => {
+            let hash = fnv1a_hash(url[Position::BeforePath..].as_bytes());
+            if url.has_host() {
+                format!("{}/#{:016x}", origin, hash)
+            } else {
+                format!("{}#{:016x}", origin, hash)
+            }
+        }
+        RedactPolicy::DropQuery => {
+            url.set_query(None);
+            url.set_fragment(None);
+            url.to_string()
+        }
+    }
+}
+
+pub(crate) fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The public suffix list from <https://publicsuffix.org/list/>, including its private domains (e.g. `github.io`).
+#[cfg(feature = "psl")]
+static PUBLIC_SUFFIX_LIST: Lazy<publicsuffix::List> = Lazy::new(|| {
+    include_str!("data/public_suffix_list.dat")
+        .parse()
+        .expect("the bundled public suffix list is valid")
+});
+
+/// Returns the registrable domain (eTLD+1) of an url, e.g. `example.co.uk` for `https://a.example.co.uk/page`.
+/// Bare hosts like `a.example.co.uk` are accepted as well.
+///
+/// Returns [`None`] for ip-addresses, for urls without a host (e.g. `mailto:`-links)
+/// and for hosts that are a public suffix themselves (e.g. `co.uk` or `localhost`).
+/// # Example
+/// ```
+/// use link_scraper::helpers::registrable_domain;
+/// assert_eq!(registrable_domain("https://a.example.co.uk/page").as_deref(), Some("example.co.uk"));
+/// assert_eq!(registrable_domain("user.github.io").as_deref(), Some("user.github.io"));
+/// assert_eq!(registrable_domain("http://192.168.0.1/"), None);
+/// ```
+#[cfg(feature = "psl")]
+pub fn registrable_domain(url: &str) -> Option<String> {
+    use publicsuffix::Psl;
+
+    let url::Host::Domain(host) = parse_host(url)? else {
+        return None;
+    };
+    let domain = PUBLIC_SUFFIX_LIST.domain(host.as_bytes())?;
+    Some(String::from_utf8_lossy(domain.as_bytes()).to_string())
+}
+
+/// Returns whether two urls belong to the same site, i.e. have the same [`registrable_domain`].
+/// Schemes and ports are ignored.
+///
+/// Ip-addresses and hosts without a registrable domain are only the same site as the identical host.
+/// # Example
+/// ```
+/// use link_scraper::helpers::same_site;
+/// assert!(same_site("a.example.co.uk", "b.example.co.uk"));
+/// assert!(same_site("https://example.co.uk", "http://b.example.co.uk:8080/page"));
+/// assert!(!same_site("https://example.com", "https://example.org"));
+/// assert!(!same_site("https://a.github.io", "https://b.github.io"));
+/// assert!(!same_site("http://10.0.0.1/", "http://10.0.0.2/"));
+/// ```
+#[cfg(feature = "psl")]
+pub fn same_site(a: &str, b: &str) -> bool {
+    match (registrable_domain(a), registrable_domain(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => parse_host(a).is_some_and(|host| Some(host) == parse_host(b)),
+    }
+}
+
+/// Parses the host of an url or of a bare host, lowercased and without a trailing dot.
+fn parse_host(url: &str) -> Option<url::Host<String>> {
+    let parsed = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(ParseError::RelativeUrlWithoutBase) => Url::parse(&format!("http://{}", url)).ok()?,
+        Err(_) => return None,
+    };
+    match parsed.host()?.to_owned() {
+        url::Host::Domain(domain) => Some(url::Host::Domain(
+            domain.trim_end_matches('.').to_ascii_lowercase(),
+        )),
+        ip => Some(ip),
+    }
+}
+
+/// Extracts the host of an url without validating it.
+pub(crate) fn url_host(url: &str) -> Option<&str> {
+    let (_scheme, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = if host_and_port.starts_with('[') {
+        host_and_port.split_inclusive(']').next()?
+    } else {
+        host_and_port.split(':').next()?
+    };
+    Some(host).filter(|host| !host.is_empty())
+}
+
+fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+    ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified()
+}
+
+fn is_private_ipv6(ip: &Ipv6Addr) -> bool {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return is_private_ipv4(&ipv4);
+    }
+    let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+    let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+    ip.is_loopback() || ip.is_unspecified() || unique_local || link_local
+}
+
+/// Constructing a [`LinkFinder`] for every call adds up when scraping large documents.
+static DEFAULT_LINK_FINDER: Lazy<LinkFinder> = Lazy::new(LinkFinder::new);
+
 #[macro_export]
 macro_rules! gen_scrape_from_slice {
     ($function_name:ident(Read) -> $output_type:ty) => {
@@ -2,12 +2,27 @@
 //!
 //! You can use those functions to extract Links and related information from a file of any supported format.
 //!
+//! All link-types (including [`any_format_scraper::Link`]) are `Send + Sync`, so scraped links can be handed
+//! to other threads.
+//!
 //! Please refer to the git-projects README.md for known issues and further information.
 
 #[cfg(feature = "any_format")]
 /// Use only if you're not sure what format your file will be.
 pub mod any_format_scraper;
+/// Options to adjust the scraping-behaviour
+pub mod config;
+/// An error-type unifying the errors of all formats
+pub mod error;
+mod format_info;
 /// Contains format-specific scrape-functions. Prefer over [`any_format_scraper`].
 pub mod formats;
 /// Helper functions
 pub mod helpers;
+/// A list of links with chainable post-processing
+pub mod links;
+#[cfg(feature = "wasm")]
+/// JavaScript-bindings for use in the browser
+pub mod wasm;
+
+pub use format_info::{supported_formats, FormatInfo};
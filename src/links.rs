@@ -0,0 +1,328 @@
+use crate::helpers::{fnv1a_hash, normalize_url, url_host, AsUrl};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// A list of links with chainable post-processing.
+///
+/// The `scrape`-functions return plain [`Vec`]s, which can be converted with [`From`] or [`Into`].
+/// `Links` dereferences to the [`Vec`], so everything that works with the [`Vec`] keeps working.
+/// # Example
+/// ```
+/// use link_scraper::formats::plaintext;
+/// use link_scraper::links::Links;
+///
+/// let links: Links<_> = plaintext::scrape_from_slice(
+///     "https://a.test.com https://b.test.com https://a.test.com",
+/// )
+/// .unwrap()
+/// .into();
+/// assert_eq!(links.len(), 3);
+/// assert_eq!(
+///     links.dedup_by_url().urls(),
+///     ["https://a.test.com", "https://b.test.com"]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Links<T>(pub Vec<T>);
+
+impl<T> Links<T>
+where
+    T: AsUrl,
+{
+    /// Returns the urls of all links.
+    pub fn urls(&self) -> Vec<&str> {
+        self.0.iter().map(|link| link.url()).collect()
+    }
+
+    /// Keeps only the first link of every url.
+    pub fn dedup_by_url(self) -> Self {
+        let mut seen: HashSet<String> = HashSet::new();
+        self.0
+            .into_iter()
+            .filter(|link| seen.insert(link.url().to_string()))
+            .collect()
+    }
+
+    /// Keeps only the first link of every url, comparing the urls after [`normalize_url`].
+    ///
+    /// Urls that only differ in the case of their scheme or host are merged,
+    /// urls that differ in the case of their path, query or fragment are not.
+    pub fn dedup_by_normalized_url(self) -> Self {
+        let mut seen: HashSet<String> = HashSet::new();
+        self.0
+            .into_iter()
+            .filter(|link| seen.insert(normalize_url(link.url())))
+            .collect()
+    }
+
+    /// Keeps only links whose host equals `host`, ignoring case.
+    ///
+    /// Links without a host (e.g. `mailto:`) are dropped.
+    pub fn filter_host(self, host: &str) -> Self {
+        self.0
+            .into_iter()
+            .filter(|link| {
+                url_host(link.url()).is_some_and(|it| {
+                    it.trim_end_matches('.')
+                        .eq_ignore_ascii_case(host.trim_end_matches('.'))
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T> Links<T>
+where
+    T: HasLocation,
+{
+    /// Sorts the links by where they were found inside the file. Links at the same location keep their order.
+    pub fn sorted_by_location(mut self) -> Self {
+        self.0.sort_by_key(|link| link.location());
+        self
+    }
+}
+
+/// Link-types that know where they were found inside the file.
+pub trait HasLocation {
+    type Location: Ord;
+
+    fn location(&self) -> Self::Location;
+}
+
+/// A deterministic id of a link, e.g. for upserting links into a database without duplicates across runs.
+///
+/// The id is a 64-bit FNV-1a hash of its parts, so the same parts give the same id across runs, platforms
+/// and versions of this crate. It is displayed as 16 hex-digits.
+/// See `any_format_scraper::Link::id` for the id of a scraped link, which is only stable within a version.
+/// # Example
+/// ```
+/// use link_scraper::links::LinkId;
+///
+/// let id = LinkId::from_parts(["https://a.test.com", "Hyperlink", "page 1"]);
+/// assert_eq!(id, LinkId::from_parts(["https://a.test.com", "Hyperlink", "page 1"]));
+/// assert_ne!(id, LinkId::from_parts(["https://a.test.com", "Hyperlink", "page 2"]));
+/// assert_eq!(id.to_string().len(), 16);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LinkId(pub u64);
+
+impl LinkId {
+    /// Hashes the given parts. Every part is prefixed with its length, so `["ab", "c"]` and `["a", "bc"]` differ.
+    pub fn from_parts<'a>(parts: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut bytes = vec![];
+        for part in parts {
+            bytes.extend_from_slice(&(part.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(part.as_bytes());
+        }
+        LinkId(fnv1a_hash(&bytes))
+    }
+}
+
+impl Display for LinkId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl<T> Default for Links<T> {
+    fn default() -> Self {
+        Links(vec![])
+    }
+}
+
+impl<T> Deref for Links<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Links<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<T>> for Links<T> {
+    fn from(links: Vec<T>) -> Self {
+        Links(links)
+    }
+}
+
+impl<T> From<Links<T>> for Vec<T> {
+    fn from(links: Links<T>) -> Self {
+        links.0
+    }
+}
+
+impl<T> FromIterator<T> for Links<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Links(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for Links<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Links<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Fails to compile if a link type stops being `Send + Sync`, see the crate documentation.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Links<String>>();
+    #[cfg(feature = "any_format")]
+    assert_send_sync::<crate::any_format_scraper::Link>();
+    #[cfg(feature = "plaintext")]
+    assert_send_sync::<crate::formats::plaintext::TextFileLink>();
+    #[cfg(feature = "pdf")]
+    assert_send_sync::<crate::formats::pdf::PdfLink>();
+    #[cfg(any(feature = "xml", feature = "xlink"))]
+    {
+        assert_send_sync::<crate::formats::xml::XmlLink>();
+        assert_send_sync::<crate::formats::xml::svg::SvgLink>();
+        assert_send_sync::<crate::formats::xml::android::AndroidStringLink>();
+    }
+    #[cfg(feature = "xlink")]
+    assert_send_sync::<crate::formats::xml::xlink::XLinkLink>();
+    #[cfg(feature = "opml")]
+    assert_send_sync::<crate::formats::xml::opml::OpmlLink>();
+    #[cfg(feature = "geo")]
+    assert_send_sync::<crate::formats::geo::GeoLink>();
+    #[cfg(feature = "ooxml")]
+    assert_send_sync::<crate::formats::ooxml::OoxmlLink>();
+    #[cfg(feature = "odf")]
+    assert_send_sync::<crate::formats::odf::OdfLink>();
+    #[cfg(feature = "iwork")]
+    assert_send_sync::<crate::formats::iwork::IworkLink>();
+    #[cfg(feature = "rtf")]
+    assert_send_sync::<crate::formats::rtf::RtfLink>();
+    #[cfg(feature = "image")]
+    assert_send_sync::<crate::formats::image::ImageLink>();
+    #[cfg(feature = "source")]
+    assert_send_sync::<crate::formats::source_code::SourceLink>();
+    #[cfg(feature = "proto")]
+    assert_send_sync::<crate::formats::proto::ProtoLink>();
+    #[cfg(feature = "fixed_width")]
+    assert_send_sync::<crate::formats::fixed_width::FixedWidthLink>();
+    #[cfg(feature = "dicom")]
+    assert_send_sync::<crate::formats::dicom::DicomLink>();
+    #[cfg(feature = "json")]
+    {
+        assert_send_sync::<crate::formats::json::JsonLink>();
+        assert_send_sync::<crate::formats::jsonl::JsonlLink>();
+    }
+    #[cfg(feature = "plist")]
+    assert_send_sync::<crate::formats::plist::PlistLink>();
+    #[cfg(feature = "lnk")]
+    assert_send_sync::<crate::formats::lnk::LnkLink>();
+    #[cfg(feature = "apk")]
+    assert_send_sync::<crate::formats::apk::ApkLink>();
+    #[cfg(feature = "shell")]
+    assert_send_sync::<crate::formats::shell::ShellLink>();
+    #[cfg(feature = "crawler")]
+    {
+        assert_send_sync::<crate::formats::robots::RobotsLink>();
+        assert_send_sync::<crate::formats::http_response::HttpLink>();
+    }
+    #[cfg(feature = "latex")]
+    assert_send_sync::<crate::formats::latex::LatexLink>();
+    #[cfg(feature = "asciidoc")]
+    assert_send_sync::<crate::formats::asciidoc::AsciidocLink>();
+    #[cfg(feature = "rst")]
+    assert_send_sync::<crate::formats::rst::RstLink>();
+    #[cfg(feature = "html")]
+    assert_send_sync::<crate::formats::html::HtmlLink>();
+    #[cfg(feature = "chm")]
+    assert_send_sync::<crate::formats::chm::ChmLink>();
+    #[cfg(feature = "clipboard")]
+    assert_send_sync::<crate::formats::clipboard::ClipboardLink>();
+    #[cfg(feature = "torrent")]
+    assert_send_sync::<crate::formats::torrent::TorrentLink>();
+    #[cfg(feature = "warc")]
+    assert_send_sync::<crate::formats::warc::WarcLink>();
+    #[cfg(feature = "mhtml")]
+    assert_send_sync::<crate::formats::mhtml::MhtmlLink>();
+    #[cfg(feature = "css")]
+    assert_send_sync::<crate::formats::css::CssLink>();
+    #[cfg(feature = "ini")]
+    assert_send_sync::<crate::formats::ini::IniLink>();
+    #[cfg(feature = "font")]
+    assert_send_sync::<crate::formats::font::FontLink>();
+    #[cfg(feature = "references")]
+    assert_send_sync::<crate::formats::references::ReferenceLink>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_host_test() {
+        let links: Links<String> = vec![
+            "https://A.test.com/x".to_string(),
+            "https://b.test.com".to_string(),
+            "mailto:someone@a.test.com".to_string(),
+            "https://a.test.com./y".to_string(),
+        ]
+        .into();
+        assert_eq!(
+            links.filter_host("a.test.com").urls(),
+            ["https://A.test.com/x", "https://a.test.com./y"]
+        );
+    }
+
+    #[test]
+    fn dedup_by_normalized_url_test() {
+        let links: Links<String> = vec![
+            "https://Example.com/Path".to_string(),
+            "HTTPS://EXAMPLE.COM/Path".to_string(),
+            "https://example.com/path".to_string(),
+            "https://example.com/Path?Q=1".to_string(),
+            "https://example.com/Path?q=1".to_string(),
+        ]
+        .into();
+        assert_eq!(
+            links.dedup_by_normalized_url().urls(),
+            [
+                "https://Example.com/Path",
+                "https://example.com/path",
+                "https://example.com/Path?Q=1",
+                "https://example.com/Path?q=1",
+            ]
+        );
+    }
+
+    #[cfg(feature = "plaintext")]
+    #[test]
+    fn sorted_by_location_test() {
+        let mut links: Links<_> = crate::formats::plaintext::scrape_from_slice(
+            "https://first.test.com https://second.test.com\nhttps://third.test.com",
+        )
+        .unwrap()
+        .into();
+        links.reverse();
+        assert_eq!(
+            links.sorted_by_location().urls(),
+            [
+                "https://first.test.com",
+                "https://second.test.com",
+                "https://third.test.com"
+            ]
+        );
+    }
+}
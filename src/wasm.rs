@@ -0,0 +1,62 @@
+use crate::any_format_scraper::{scrape_from_slice, Link};
+use crate::helpers::AsUrl;
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+/// Scrapes links from a file of any format, like [`crate::any_format_scraper::scrape`].
+///
+/// Returns the links as a JSON-array of `{ "url", "kind", "location" }`-objects (see [`links_to_json`]).
+/// Errors are thrown as JavaScript `Error`s carrying the message of the [`crate::any_format_scraper::LinkScrapingError`].
+///
+/// # Example
+/// ```js
+/// import init, { scrape_bytes } from "link_scraper";
+///
+/// await init();
+/// const links = JSON.parse(scrape_bytes(new TextEncoder().encode("See https://link.example.com")));
+/// ```
+#[wasm_bindgen]
+pub fn scrape_bytes(data: &[u8]) -> Result<JsValue, JsError> {
+    let links = scrape_from_slice(data).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(JsValue::from_str(&links_to_json(&links)))
+}
+
+/// Serializes links into the JSON returned by [`scrape_bytes`].
+///
+/// `kind` is [`Link::kind_name`], `location` is format-specific and `null` if the format has none.
+pub fn links_to_json(links: &[Link]) -> String {
+    Value::Array(
+        links
+            .iter()
+            .map(|link| {
+                let location = link.kind_and_location().and_then(|(_, location)| location);
+                json!({
+                    "url": link.url(),
+                    "kind": link.kind_name(),
+                    "location": location,
+                })
+            })
+            .collect(),
+    )
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "plaintext")]
+    #[test]
+    fn links_to_json_test() {
+        let links =
+            scrape_from_slice("See https://link.test.com and https://other.test.com").unwrap();
+        let json: Value = serde_json::from_str(&links_to_json(&links)).unwrap();
+        let urls: Vec<_> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|link| link["url"].as_str().unwrap())
+            .collect();
+        assert_eq!(urls, ["https://link.test.com", "https://other.test.com"]);
+    }
+}